@@ -0,0 +1,262 @@
+//! Benchmarks for the common serialization workloads
+//!
+//! Run with `cargo bench`, or `cargo bench --features aws-sdk-dynamodb+1` to include the
+//! conversions to and from the SDK's types. See the README for the performance targets these are
+//! held to.
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use serde_derive::{Deserialize, Serialize};
+use serde_dynamo::{from_item, from_items, to_item, Item, Items};
+use std::collections::HashMap;
+
+#[derive(Clone, Serialize, Deserialize)]
+struct User {
+    id: String,
+    name: String,
+    email: String,
+    age: u8,
+    active: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Order {
+    id: String,
+    customer: User,
+    lines: Vec<Line>,
+    notes: Option<String>,
+    metadata: HashMap<String, String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Line {
+    sku: String,
+    quantity: u32,
+    unit_price: f64,
+    tags: Vec<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Tagged {
+    id: String,
+    #[serde(with = "serde_dynamo::string_set")]
+    tags: Vec<String>,
+    #[serde(with = "serde_dynamo::number_set")]
+    scores: Vec<u64>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Attachment {
+    id: String,
+    lines: Vec<Line>,
+    #[serde(with = "serde_bytes")]
+    data: Vec<u8>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct LegacyAttachment {
+    id: String,
+    legacy_path: String,
+}
+
+/// The first variant never matches, so every value is buffered and tried twice
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum AnyAttachment {
+    Legacy(LegacyAttachment),
+    Current(Attachment),
+}
+
+fn user(i: usize) -> User {
+    User {
+        id: format!("user-{i:08}"),
+        name: String::from("Arthur Dent"),
+        email: format!("arthur{i}@example.com"),
+        age: 42,
+        active: i.is_multiple_of(2),
+    }
+}
+
+fn order() -> Order {
+    Order {
+        id: String::from("order-1"),
+        customer: user(1),
+        lines: (0..200)
+            .map(|i| Line {
+                sku: format!("sku-{i}"),
+                quantity: i,
+                unit_price: f64::from(i) * 1.25,
+                tags: vec![String::from("new"), String::from("sale")],
+            })
+            .collect(),
+        notes: Some(String::from("Leave at the door")),
+        metadata: (0..20)
+            .map(|i| (format!("key{i}"), format!("value{i}")))
+            .collect(),
+    }
+}
+
+fn tagged() -> Tagged {
+    Tagged {
+        id: String::from("tagged-1"),
+        tags: (0..1000).map(|i| format!("tag-{i}")).collect(),
+        scores: (0..1000).collect(),
+    }
+}
+
+fn small_item(c: &mut Criterion) {
+    let value = user(1);
+    let item: Item = to_item(&value).unwrap();
+
+    let mut group = c.benchmark_group("small_item");
+    group.bench_function("serialize", |b| {
+        b.iter(|| to_item::<_, Item>(black_box(&value)).unwrap())
+    });
+    group.bench_function("deserialize", |b| {
+        b.iter_batched(
+            || item.clone(),
+            |item| from_item::<_, User>(item).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+fn large_nested_item(c: &mut Criterion) {
+    let value = order();
+    let item: Item = to_item(&value).unwrap();
+
+    let mut group = c.benchmark_group("large_nested_item");
+    group.bench_function("serialize", |b| {
+        b.iter(|| to_item::<_, Item>(black_box(&value)).unwrap())
+    });
+    group.bench_function("deserialize", |b| {
+        b.iter_batched(
+            || item.clone(),
+            |item| from_item::<_, Order>(item).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+fn batch(c: &mut Criterion) {
+    let values: Vec<User> = (0..10_000).map(user).collect();
+    let items: Vec<Item> = values.iter().map(|value| to_item(value).unwrap()).collect();
+
+    let mut group = c.benchmark_group("batch_10k");
+    group.throughput(Throughput::Elements(values.len() as u64));
+    group.bench_function("serialize", |b| {
+        b.iter(|| {
+            black_box(&values)
+                .iter()
+                .map(|value| to_item(value).unwrap())
+                .collect::<Vec<Item>>()
+        })
+    });
+    group.bench_function("deserialize", |b| {
+        b.iter_batched(
+            || Items::from(items.clone()),
+            |items| from_items::<_, User>(items).unwrap(),
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+fn set_heavy_item(c: &mut Criterion) {
+    let value = tagged();
+    let item: Item = to_item(&value).unwrap();
+
+    let mut group = c.benchmark_group("set_heavy_item");
+    group.bench_function("serialize", |b| {
+        b.iter(|| to_item::<_, Item>(black_box(&value)).unwrap())
+    });
+    group.bench_function("deserialize", |b| {
+        b.iter_batched(
+            || item.clone(),
+            |item| from_item::<_, Tagged>(item).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+fn json_value(c: &mut Criterion) {
+    let value = serde_json::to_value(order()).unwrap();
+    let item: Item = to_item(&value).unwrap();
+
+    let mut group = c.benchmark_group("json_value");
+    group.bench_function("serialize", |b| {
+        b.iter(|| to_item::<_, Item>(black_box(&value)).unwrap())
+    });
+    group.bench_function("deserialize", |b| {
+        b.iter_batched(
+            || item.clone(),
+            |item| from_item::<_, serde_json::Value>(item).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+fn untagged(c: &mut Criterion) {
+    let value = Attachment {
+        id: String::from("attachment-1"),
+        lines: order().lines,
+        data: vec![7; 64 * 1024],
+    };
+    let item: Item = to_item(&value).unwrap();
+
+    let mut group = c.benchmark_group("untagged");
+    group.bench_function("deserialize_struct", |b| {
+        b.iter_batched(
+            || item.clone(),
+            |item| from_item::<_, Attachment>(item).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("deserialize_enum", |b| {
+        b.iter_batched(
+            || item.clone(),
+            |item| from_item::<_, AnyAttachment>(item).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+#[cfg(feature = "aws-sdk-dynamodb+1")]
+fn aws_sdk(c: &mut Criterion) {
+    use __aws_sdk_dynamodb_1::types::AttributeValue;
+
+    let value = order();
+    let item: HashMap<String, AttributeValue> = to_item(&value).unwrap();
+
+    let mut group = c.benchmark_group("aws_sdk");
+    group.bench_function("serialize", |b| {
+        b.iter(|| to_item::<_, HashMap<String, AttributeValue>>(black_box(&value)).unwrap())
+    });
+    group.bench_function("deserialize", |b| {
+        b.iter_batched(
+            || item.clone(),
+            |item| from_item::<_, Order>(item).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+#[cfg(not(feature = "aws-sdk-dynamodb+1"))]
+fn aws_sdk(_: &mut Criterion) {}
+
+criterion_group!(
+    benches,
+    small_item,
+    large_nested_item,
+    batch,
+    set_heavy_item,
+    json_value,
+    untagged,
+    aws_sdk
+);
+criterion_main!(benches);
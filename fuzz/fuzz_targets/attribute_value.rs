@@ -0,0 +1,102 @@
+//! Deserialize arbitrary attribute value trees into `serde_json::Value` and a few scalar types
+//!
+//! The trees include what DynamoDB never returns, such as malformed numbers, `NULL: false`, and
+//! duplicate set elements. Deserializing may fail, but must not panic.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use serde_dynamo::{AttributeValue, BinaryPolicy, DeserializerConfig, Item};
+
+/// Containers nested deeper than this become `NULL`, so that deep inputs test the code rather
+/// than the stack size
+const MAX_DEPTH: usize = 32;
+
+#[derive(Debug, Arbitrary)]
+enum Value {
+    N(String),
+    S(String),
+    Bool(bool),
+    B(Vec<u8>),
+    Null(bool),
+    M(Vec<(String, Value)>),
+    L(Vec<Value>),
+    Ss(Vec<String>),
+    Ns(Vec<String>),
+    Bs(Vec<Vec<u8>>),
+}
+
+impl Value {
+    fn into_attribute_value(self, depth: usize) -> AttributeValue {
+        match self {
+            Value::N(n) => AttributeValue::N(n),
+            Value::S(s) => AttributeValue::S(s),
+            Value::Bool(b) => AttributeValue::Bool(b),
+            Value::B(b) => AttributeValue::B(b),
+            Value::Null(null) => AttributeValue::Null(null),
+            Value::M(_) | Value::L(_) if depth >= MAX_DEPTH => AttributeValue::Null(true),
+            Value::M(m) => AttributeValue::M(
+                m.into_iter()
+                    .map(|(key, value)| (key, value.into_attribute_value(depth + 1)))
+                    .collect(),
+            ),
+            Value::L(l) => AttributeValue::L(
+                l.into_iter()
+                    .map(|value| value.into_attribute_value(depth + 1))
+                    .collect(),
+            ),
+            Value::Ss(ss) => AttributeValue::Ss(ss),
+            Value::Ns(ns) => AttributeValue::Ns(ns),
+            Value::Bs(bs) => AttributeValue::Bs(bs),
+        }
+    }
+}
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    value: Value,
+    coerce: bool,
+    lenient_numbers: bool,
+    base64: bool,
+    path: String,
+}
+
+fuzz_target!(|input: Input| {
+    let value = input.value.into_attribute_value(0);
+    let config = DeserializerConfig::new()
+        .coerce_bools(input.coerce)
+        .coerce_strings(input.coerce)
+        .coerce_base64(input.base64)
+        .coerce_singletons(input.coerce)
+        .lenient_numbers(input.lenient_numbers)
+        .binary_policy(if input.base64 {
+            BinaryPolicy::Base64
+        } else {
+            BinaryPolicy::ByteArray
+        });
+
+    if let Ok(json) = serde_dynamo::from_attribute_value_with_config::<_, serde_json::Value>(
+        value.clone(),
+        config.clone(),
+    ) {
+        let _ = serde_dynamo::to_attribute_value::<_, AttributeValue>(json);
+    }
+    let _ =
+        serde_dynamo::from_attribute_value_with_config::<_, String>(value.clone(), config.clone());
+    let _ = serde_dynamo::from_attribute_value_with_config::<_, i64>(value.clone(), config.clone());
+    let _ = serde_dynamo::from_attribute_value_with_config::<_, u8>(value.clone(), config.clone());
+    let _ = serde_dynamo::from_attribute_value_with_config::<_, f64>(value.clone(), config.clone());
+    let _ =
+        serde_dynamo::from_attribute_value_with_config::<_, bool>(value.clone(), config.clone());
+    let _ = value.size();
+    let _ = value.numeric_eq(&value);
+    let _ = value.key_cmp(&value);
+
+    if let AttributeValue::M(map) = value {
+        let mut item = Item::from(map);
+        let _ = serde_dynamo::from_item_ref_with_config::<serde_json::Value>(&item, config);
+        let _ = item.get_path_as::<serde_json::Value>(&input.path);
+        let _ = item.set_path(&input.path, AttributeValue::Null(true));
+    }
+});
@@ -0,0 +1,22 @@
+//! Parse arbitrary bytes as DynamoDB JSON, as found in exports and the HTTP API
+//!
+//! Parsing may fail, but must not panic, and whatever parses must survive a round trip.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use serde_dynamo::{AttributeValue, Item};
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(value) = serde_json::from_slice::<AttributeValue>(data) {
+        let json = serde_json::to_vec(&value).unwrap();
+        let reparsed: AttributeValue = serde_json::from_slice(&json).unwrap();
+        assert_eq!(reparsed, value);
+        let _ = serde_dynamo::from_attribute_value::<_, serde_json::Value>(value);
+    }
+
+    if let Ok(item) = serde_json::from_slice::<Item>(data) {
+        let _ = serde_dynamo::from_item_ref::<serde_json::Value>(&item);
+        let _ = item.size();
+    }
+});
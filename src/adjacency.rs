@@ -0,0 +1,307 @@
+//! Items for the single-table adjacency list pattern
+//!
+//! In the [adjacency list] pattern, every entity and every relationship between two entities is
+//! an item in one table. An entity's own item has the same partition and sort key, such as
+//! `USER#u-1`, and a relationship has the source's key as its partition key and the target's as
+//! its sort key. A query on `USER#u-1` then returns the user together with everything it links
+//! to, and a global secondary index with the two keys swapped returns the links pointing at it.
+//! Many-to-many relationships need no join table.
+//!
+//! [`Edge`] is such an item: the `pk` and `sk` keys, an `entity` attribute naming the kind of item,
+//! and a payload whose fields are stored alongside them. Build keys with [`key`], so that every
+//! sort key is written as `{PREFIX}#{id}` and a `begins_with` condition on [`key_prefix`] finds
+//! all the links to one kind of entity.
+//!
+//! # Examples
+//!
+//! ```
+//! use serde_derive::{Serialize, Deserialize};
+//! use serde_dynamo::adjacency::{self, Edge};
+//! use serde_dynamo::{dynamo_item, Item};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Membership {
+//!     role: String,
+//! }
+//!
+//! let membership = Edge::new(
+//!     "membership",
+//!     adjacency::key("USER", "u-1"),
+//!     adjacency::key("GROUP", "admins"),
+//!     Membership { role: String::from("owner") },
+//! );
+//!
+//! let item: Item = serde_dynamo::to_item(&membership).unwrap();
+//! assert_eq!(
+//!     item,
+//!     dynamo_item! {
+//!         "pk": "USER#u-1",
+//!         "sk": "GROUP#admins",
+//!         "entity": "membership",
+//!         "role": "owner",
+//!     }
+//! );
+//!
+//! let edge: Edge<Membership> = serde_dynamo::from_item(item).unwrap();
+//! assert_eq!(edge.target(), Some(("GROUP", "admins")));
+//! assert!(edge.sk.starts_with(&adjacency::key_prefix("GROUP")));
+//! ```
+//!
+//! [adjacency list]: https://docs.aws.amazon.com/amazondynamodb/latest/developerguide/bp-adjacency-graphs.html
+
+use crate::flatten::{FlatMapSerializer, FlattenedMap};
+use serde::de::{self, Deserialize, Deserializer, MapAccess, Visitor};
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+
+/// The character between a key's prefix and its id
+pub const SEPARATOR: char = '#';
+
+/// The key `{prefix}#{id}` of an entity
+///
+/// The prefix names the kind of entity, and ends at the first [`SEPARATOR`], so it must not
+/// contain one. The id may.
+///
+/// ```
+/// assert_eq!(serde_dynamo::adjacency::key("ORDER", "o#1"), "ORDER#o#1");
+/// ```
+pub fn key(prefix: &str, id: &str) -> String {
+    let mut key = key_prefix(prefix);
+    key.push_str(id);
+    key
+}
+
+/// The start of every key with `prefix`, for `begins_with` key conditions
+///
+/// ```
+/// assert_eq!(serde_dynamo::adjacency::key_prefix("ORDER"), "ORDER#");
+/// ```
+pub fn key_prefix(prefix: &str) -> String {
+    let mut key = String::with_capacity(prefix.len() + 1);
+    key.push_str(prefix);
+    key.push(SEPARATOR);
+    key
+}
+
+/// Split a key built with [`key`] into its prefix and id
+///
+/// Returns `None` if the key has no [`SEPARATOR`].
+///
+/// ```
+/// use serde_dynamo::adjacency::split_key;
+///
+/// assert_eq!(split_key("ORDER#o#1"), Some(("ORDER", "o#1")));
+/// assert_eq!(split_key("ORDER"), None);
+/// ```
+pub fn split_key(key: &str) -> Option<(&str, &str)> {
+    key.split_once(SEPARATOR)
+}
+
+/// An entity or a relationship between two entities, stored as one item
+///
+/// The payload is [flattened] into the item, so its fields must not collide with the edge's
+/// `pk`, `sk`, and `entity` attributes. The payload must serialize as a struct or map.
+///
+/// See the [module documentation][crate::adjacency] for more information.
+///
+/// [flattened]: https://serde.rs/attr-flatten.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edge<T> {
+    /// The partition key: the key of the source entity
+    pub pk: String,
+    /// The sort key: the key of the target entity, or the source's own key for an entity item
+    pub sk: String,
+    /// The kind of item, such as the name of the relationship
+    pub entity: String,
+    /// The wrapped value
+    pub payload: T,
+}
+
+impl<T> Edge<T> {
+    /// A relationship from the entity with key `pk` to the entity with key `sk`
+    pub fn new<E, P, S>(entity: E, pk: P, sk: S, payload: T) -> Self
+    where
+        E: Into<String>,
+        P: Into<String>,
+        S: Into<String>,
+    {
+        Edge {
+            pk: pk.into(),
+            sk: sk.into(),
+            entity: entity.into(),
+            payload,
+        }
+    }
+
+    /// The item of the entity with key `key` itself, with the same partition and sort key
+    ///
+    /// ```
+    /// use serde_dynamo::adjacency::{self, Edge};
+    ///
+    /// let user = Edge::node("user", adjacency::key("USER", "u-1"), ());
+    /// assert_eq!(user.pk, user.sk);
+    /// assert!(user.is_node());
+    /// ```
+    pub fn node<E, K>(entity: E, key: K, payload: T) -> Self
+    where
+        E: Into<String>,
+        K: Into<String>,
+    {
+        let key = key.into();
+        Edge {
+            pk: key.clone(),
+            sk: key,
+            entity: entity.into(),
+            payload,
+        }
+    }
+
+    /// Whether this is an entity's own item rather than a relationship
+    pub fn is_node(&self) -> bool {
+        self.pk == self.sk
+    }
+
+    /// The prefix and id of the source entity, if `pk` was built with [`key`]
+    pub fn source(&self) -> Option<(&str, &str)> {
+        split_key(&self.pk)
+    }
+
+    /// The prefix and id of the target entity, if `sk` was built with [`key`]
+    pub fn target(&self) -> Option<(&str, &str)> {
+        split_key(&self.sk)
+    }
+
+    /// The same relationship seen from its target, with the keys swapped
+    ///
+    /// Write both directions when a many-to-many relationship must be queried from either side
+    /// without a global secondary index.
+    pub fn inverted(self) -> Self {
+        Edge {
+            pk: self.sk,
+            sk: self.pk,
+            ..self
+        }
+    }
+
+    /// Unwrap the payload, discarding the keys
+    pub fn into_payload(self) -> T {
+        self.payload
+    }
+}
+
+impl<T> Serialize for Edge<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("pk", &self.pk)?;
+        map.serialize_entry("sk", &self.sk)?;
+        map.serialize_entry("entity", &self.entity)?;
+        self.payload.serialize(FlatMapSerializer(&mut map))?;
+        map.end()
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Edge<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(EdgeVisitor(PhantomData))
+    }
+}
+
+struct EdgeVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for EdgeVisitor<T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = Edge<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an adjacency list item")
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut pk = None;
+        let mut sk = None;
+        let mut entity = None;
+
+        let payload = FlattenedMap::new(map, |key, map: &mut A| {
+            match key {
+                "pk" => pk = Some(map.next_value()?),
+                "sk" => sk = Some(map.next_value()?),
+                "entity" => entity = Some(map.next_value()?),
+                _ => return Ok(false),
+            }
+            Ok(true)
+        })
+        .deserialize_payload()?;
+
+        Ok(Edge {
+            pk: pk.ok_or_else(|| de::Error::missing_field("pk"))?,
+            sk: sk.ok_or_else(|| de::Error::missing_field("sk"))?,
+            entity: entity.ok_or_else(|| de::Error::missing_field("entity"))?,
+            payload,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{key, Edge};
+    use crate::{dynamo_item, from_items, to_item, Item};
+    use serde_derive::{Deserialize, Serialize};
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[serde(untagged)]
+    enum Payload {
+        Order { total: u32 },
+        Empty {},
+    }
+
+    #[test]
+    fn round_trip() {
+        let edge = Edge::new(
+            "placed",
+            key("USER", "u-1"),
+            key("ORDER", "o#7"),
+            Payload::Order { total: 30 },
+        );
+        let item: Item = to_item(&edge).unwrap();
+        assert_eq!(
+            item,
+            dynamo_item! { "pk": "USER#u-1", "sk": "ORDER#o#7", "entity": "placed", "total": 30 }
+        );
+
+        let node = Edge::node("user", key("USER", "u-1"), Payload::Empty {});
+        let items = vec![to_item::<_, Item>(&node).unwrap(), item];
+        let edges: Vec<Edge<Payload>> = from_items(items).unwrap();
+        assert_eq!(edges, vec![node, edge]);
+        assert!(edges[0].is_node());
+        assert_eq!(edges[1].source(), Some(("USER", "u-1")));
+        assert_eq!(edges[1].target(), Some(("ORDER", "o#7")));
+    }
+
+    #[test]
+    fn inverted() {
+        let edge = Edge::new("member", key("USER", "u-1"), key("GROUP", "g-1"), 5u8).inverted();
+        assert_eq!(edge.pk, "GROUP#g-1");
+        assert_eq!(edge.sk, "USER#u-1");
+        assert_eq!(edge.entity, "member");
+        assert!(!edge.is_node());
+        assert_eq!(edge.into_payload(), 5);
+    }
+}
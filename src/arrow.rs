@@ -0,0 +1,300 @@
+//! Convert items to [Apache Arrow] record batches
+//!
+//! Scan results can be handed to Arrow-based tooling, such as a Parquet writer, without a JSON
+//! round trip for every row. Each column of the target schema is filled from the attribute of
+//! the same name:
+//!
+//! | Arrow type | Attribute types |
+//! |------------|-----------------|
+//! | `Utf8`, `LargeUtf8` | `S` as-is; any other type as JSON, with binary as base64 |
+//! | `Boolean` | `BOOL` |
+//! | `Int8` to `Int64`, `UInt8` to `UInt64` | `N` holding an integer |
+//! | `Float32`, `Float64` | `N` |
+//! | `Binary`, `LargeBinary` | `B` |
+//!
+//! Missing and `NULL` attributes become nulls, which the column must allow. Attributes without a
+//! column are ignored. Nested maps and lists are stored as JSON in text columns.
+//!
+//! ```
+//! use __arrow_array::{cast::AsArray, types::Int64Type, Array};
+//! use __arrow_schema::{DataType, Field, Schema};
+//! use serde_dynamo::{arrow::items_to_record_batch, dynamo_item, Items};
+//! use std::sync::Arc;
+//!
+//! let items = Items::from(vec![
+//!     dynamo_item! { "id": "a", "age": 37, "address": { "city": "Springfield" } }.into_inner(),
+//!     dynamo_item! { "id": "b" }.into_inner(),
+//! ]);
+//! let schema = Arc::new(Schema::new(vec![
+//!     Field::new("id", DataType::Utf8, false),
+//!     Field::new("age", DataType::Int64, true),
+//!     Field::new("address", DataType::Utf8, true),
+//! ]));
+//!
+//! let batch = items_to_record_batch(&items, schema)?;
+//!
+//! assert_eq!(batch.num_rows(), 2);
+//! let ages = batch.column(1).as_primitive::<Int64Type>();
+//! assert_eq!(ages.value(0), 37);
+//! assert!(ages.is_null(1));
+//! assert_eq!(batch.column(2).as_string::<i32>().value(0), r#"{"city":"Springfield"}"#);
+//! # Ok::<(), serde_dynamo::Error>(())
+//! ```
+//!
+//! [Apache Arrow]: https://arrow.apache.org
+
+use crate::{error::ErrorImpl, ser::to_json, AttributeValue, Item, Result};
+use __arrow_array::{
+    builder::{
+        ArrayBuilder, BinaryBuilder, BooleanBuilder, LargeBinaryBuilder, LargeStringBuilder,
+        PrimitiveBuilder, StringBuilder,
+    },
+    types::{
+        ArrowPrimitiveType, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type, Int8Type,
+        UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+    },
+    ArrayRef, RecordBatch,
+};
+use __arrow_schema::{DataType, Field, SchemaRef};
+use std::str::FromStr;
+
+/// Convert items into a record batch with the given schema
+///
+/// See the [module documentation](self) for how attributes map to columns.
+pub fn items_to_record_batch(items: &[Item], schema: SchemaRef) -> Result<RecordBatch> {
+    let columns = schema
+        .fields()
+        .iter()
+        .map(|field| column(items, field))
+        .collect::<Result<Vec<_>>>()?;
+    RecordBatch::try_new(schema, columns).map_err(|err| ErrorImpl::Arrow(err.to_string()).into())
+}
+
+fn column(items: &[Item], field: &Field) -> Result<ArrayRef> {
+    let values = items.iter().enumerate().map(|(row, item)| {
+        let value = match item.get(field.name()) {
+            None | Some(AttributeValue::Null(_)) if field.is_nullable() => None,
+            None | Some(AttributeValue::Null(_)) => {
+                return Err(problem(
+                    field,
+                    row,
+                    "is missing or NULL in a non-nullable column",
+                ))
+            }
+            Some(value) => Some(value),
+        };
+        Ok((row, value))
+    });
+
+    match field.data_type() {
+        DataType::Utf8 => build(StringBuilder::new(), values, |_, value| Ok(text(value))),
+        DataType::LargeUtf8 => build(LargeStringBuilder::new(), values, |_, value| {
+            Ok(text(value))
+        }),
+        DataType::Boolean => build(BooleanBuilder::new(), values, |row, value| match value {
+            AttributeValue::Bool(b) => Ok(*b),
+            other => Err(mismatch(field, row, "BOOL", other)),
+        }),
+        DataType::Binary => build(BinaryBuilder::new(), values, |row, value| {
+            bytes(field, row, value)
+        }),
+        DataType::LargeBinary => build(LargeBinaryBuilder::new(), values, |row, value| {
+            bytes(field, row, value)
+        }),
+        DataType::Int8 => number::<Int8Type>(field, values),
+        DataType::Int16 => number::<Int16Type>(field, values),
+        DataType::Int32 => number::<Int32Type>(field, values),
+        DataType::Int64 => number::<Int64Type>(field, values),
+        DataType::UInt8 => number::<UInt8Type>(field, values),
+        DataType::UInt16 => number::<UInt16Type>(field, values),
+        DataType::UInt32 => number::<UInt32Type>(field, values),
+        DataType::UInt64 => number::<UInt64Type>(field, values),
+        DataType::Float32 => number::<Float32Type>(field, values),
+        DataType::Float64 => number::<Float64Type>(field, values),
+        other => Err(ErrorImpl::Arrow(format!(
+            "column `{}` has unsupported type {other}",
+            field.name()
+        ))
+        .into()),
+    }
+}
+
+/// Something that can append optional values and finish into an array
+trait Append<T>: ArrayBuilder {
+    fn append(&mut self, value: Option<T>);
+}
+
+macro_rules! impl_append {
+    ($($builder:ty => $value:ty),* $(,)?) => {
+        $(
+            impl<'a> Append<$value> for $builder {
+                fn append(&mut self, value: Option<$value>) {
+                    self.append_option(value)
+                }
+            }
+        )*
+    };
+}
+
+impl_append! {
+    StringBuilder => String,
+    LargeStringBuilder => String,
+    BooleanBuilder => bool,
+    BinaryBuilder => &'a [u8],
+    LargeBinaryBuilder => &'a [u8],
+}
+
+impl<P> Append<P::Native> for PrimitiveBuilder<P>
+where
+    P: ArrowPrimitiveType,
+{
+    fn append(&mut self, value: Option<P::Native>) {
+        self.append_option(value)
+    }
+}
+
+fn build<'a, B, T>(
+    mut builder: B,
+    values: impl Iterator<Item = Result<(usize, Option<&'a AttributeValue>)>>,
+    convert: impl Fn(usize, &'a AttributeValue) -> Result<T>,
+) -> Result<ArrayRef>
+where
+    B: Append<T>,
+{
+    for value in values {
+        let (row, value) = value?;
+        builder.append(value.map(|value| convert(row, value)).transpose()?);
+    }
+    Ok(builder.finish())
+}
+
+fn number<'a, P>(
+    field: &Field,
+    values: impl Iterator<Item = Result<(usize, Option<&'a AttributeValue>)>>,
+) -> Result<ArrayRef>
+where
+    P: ArrowPrimitiveType,
+    P::Native: FromStr,
+{
+    build(
+        PrimitiveBuilder::<P>::new(),
+        values,
+        |row, value| match value {
+            AttributeValue::N(n) => n.parse().map_err(|_| {
+                problem(
+                    field,
+                    row,
+                    &format!("`{n}` does not fit in a {} column", field.data_type()),
+                )
+            }),
+            other => Err(mismatch(field, row, "N", other)),
+        },
+    )
+}
+
+fn text(value: &AttributeValue) -> String {
+    match value {
+        AttributeValue::S(s) => s.clone(),
+        other => to_json(other),
+    }
+}
+
+fn bytes<'a>(field: &Field, row: usize, value: &'a AttributeValue) -> Result<&'a [u8]> {
+    match value {
+        AttributeValue::B(b) => Ok(b),
+        other => Err(mismatch(field, row, "B", other)),
+    }
+}
+
+fn mismatch(field: &Field, row: usize, expected: &str, found: &AttributeValue) -> crate::Error {
+    problem(
+        field,
+        row,
+        &format!("expected {expected}, found {}", found.type_descriptor()),
+    )
+}
+
+fn problem(field: &Field, row: usize, problem: &str) -> crate::Error {
+    ErrorImpl::Arrow(format!("column `{}`, row {row}: {problem}", field.name())).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dynamo_item;
+    use __arrow_array::{cast::AsArray, Array};
+    use __arrow_schema::Schema;
+    use std::sync::Arc;
+
+    #[test]
+    fn column_types() {
+        let items = vec![
+            dynamo_item! {
+                "s": "text",
+                "n": 7,
+                "f": 2.5,
+                "b": b([1u8, 2]),
+                "bool": true,
+                "nested": [1, { "a": ss["x"] }],
+                "ignored": "x",
+            },
+            dynamo_item! { "s": null, "n": 8, "f": 1, "bool": false },
+        ];
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("s", DataType::LargeUtf8, true),
+            Field::new("n", DataType::UInt8, false),
+            Field::new("f", DataType::Float32, false),
+            Field::new("b", DataType::Binary, true),
+            Field::new("bool", DataType::Boolean, false),
+            Field::new("nested", DataType::Utf8, true),
+        ]));
+
+        let batch = items_to_record_batch(&items, schema.clone()).unwrap();
+
+        let s = batch.column(0).as_string::<i64>();
+        assert_eq!(s.value(0), "text");
+        assert!(s.is_null(1));
+        let n = batch.column(1).as_primitive::<UInt8Type>();
+        assert_eq!(n.values(), &[7, 8]);
+        let f = batch.column(2).as_primitive::<Float32Type>();
+        assert_eq!(f.values(), &[2.5, 1.0]);
+        assert_eq!(batch.column(3).as_binary::<i32>().value(0), &[1, 2]);
+        assert!(batch.column(4).as_boolean().value(0));
+        assert_eq!(
+            batch.column(5).as_string::<i32>().value(0),
+            r#"[1,{"a":["x"]}]"#
+        );
+
+        let items = vec![dynamo_item! { "n": 300, "f": 1, "bool": "yes" }];
+        assert_eq!(
+            items_to_record_batch(&items, schema).unwrap_err().to_string(),
+            "Failed to build Arrow record batch: column `n`, row 0: `300` does not fit in a UInt8 column"
+        );
+    }
+
+    #[test]
+    fn errors_name_the_column_and_row() {
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "flag",
+            DataType::Boolean,
+            false,
+        )]));
+
+        let items = vec![
+            dynamo_item! { "flag": true },
+            dynamo_item! { "flag": "yes" },
+        ];
+        assert_eq!(
+            items_to_record_batch(&items, schema.clone())
+                .unwrap_err()
+                .to_string(),
+            "Failed to build Arrow record batch: column `flag`, row 1: expected BOOL, found S"
+        );
+
+        let items = vec![dynamo_item! {}];
+        assert_eq!(
+            items_to_record_batch(&items, schema).unwrap_err().to_string(),
+            "Failed to build Arrow record batch: column `flag`, row 0: is missing or NULL in a non-nullable column"
+        );
+    }
+}
@@ -0,0 +1,128 @@
+/// Declare `&'static str` constants for a struct's attribute names.
+///
+/// Expression strings and key maps refer to attributes by name, which makes them easy to break
+/// when a field is renamed. This macro declares a module with one constant per attribute, so the
+/// names live in one place next to the struct.
+///
+/// Each constant defaults to the field name. When serde renames the field (including with
+/// `rename_all`), give the serialized name explicitly with `field = "name"`.
+///
+/// When the module is declared `for` a type, a test is generated that checks every constant
+/// against the attribute names serde actually uses for that type, so drift is caught by
+/// `cargo test`. The type must implement [`Deserialize`](serde::Deserialize) for the check.
+///
+/// ```
+/// # use serde_derive::{Serialize, Deserialize};
+/// use serde_dynamo::attr_names;
+///
+/// #[derive(Serialize, Deserialize)]
+/// #[serde(rename_all = "camelCase")]
+/// struct User {
+///     id: String,
+///     display_name: String,
+///     age: u8,
+/// }
+///
+/// attr_names! {
+///     /// Attribute names for [`User`]
+///     pub mod user_attrs for User {
+///         id,
+///         display_name = "displayName",
+///     }
+/// }
+///
+/// assert_eq!(user_attrs::id, "id");
+/// assert_eq!(user_attrs::display_name, "displayName");
+/// assert_eq!(user_attrs::ALL, &["id", "displayName"]);
+///
+/// let expression = format!("{} = :name", user_attrs::display_name);
+/// ```
+#[macro_export]
+macro_rules! attr_names {
+    (
+        $(#[$meta:meta])*
+        $vis:vis mod $module:ident $(for $ty:ty)? {
+            $($field:ident $(= $name:literal)?),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[allow(dead_code, non_upper_case_globals)]
+        $vis mod $module {
+            $(
+                #[doc = concat!("The `", $crate::__attr_name!($field $(= $name)?), "` attribute")]
+                pub const $field: &str = $crate::__attr_name!($field $(= $name)?);
+            )*
+
+            /// All of the attribute names, in declaration order
+            pub const ALL: &[&str] = &[$($field),*];
+
+            $(
+                #[cfg(test)]
+                mod __attr_names_check {
+                    #[allow(unused_imports)]
+                    use super::super::*;
+
+                    #[test]
+                    fn attribute_names_match_serde() {
+                        let fields = $crate::expression::field_names::<$ty>();
+                        for name in super::ALL {
+                            assert!(
+                                fields.contains(name),
+                                "`{}` is not an attribute of `{}`; serde uses {:?}",
+                                name,
+                                stringify!($ty),
+                                fields,
+                            );
+                        }
+                    }
+                }
+            )?
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __attr_name {
+    ($field:ident) => {
+        stringify!($field)
+    };
+    ($field:ident = $name:literal) => {
+        $name
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_derive::Deserialize;
+
+    #[derive(Deserialize)]
+    #[allow(dead_code)]
+    #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+    struct Order {
+        order_id: String,
+        #[serde(rename = "ts")]
+        created: u64,
+    }
+
+    attr_names! {
+        mod order_attrs for Order {
+            order_id = "ORDER_ID",
+            created = "ts",
+        }
+    }
+
+    attr_names! {
+        mod loose {
+            pk,
+            sk = "SK",
+        }
+    }
+
+    #[test]
+    fn constants() {
+        assert_eq!(order_attrs::order_id, "ORDER_ID");
+        assert_eq!(order_attrs::created, "ts");
+        assert_eq!(loose::ALL, &["pk", "SK"]);
+    }
+}
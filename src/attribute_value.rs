@@ -1,9 +1,17 @@
+use crate::{de::from_attribute_value_ref, error::ErrorImpl, number::Decimal, path, Error, Result};
 use base64::Engine;
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 
 const BASE64_ENGINE: base64::engine::GeneralPurpose = base64::engine::general_purpose::STANDARD;
 
 /// The value for an attribute that comes from DynamoDb.
+///
+/// `==` and [`Hash`](std::hash::Hash) are strict: numbers are compared as written and sets in
+/// order. See [`numeric_eq`](AttributeValue::numeric_eq) for comparing what values mean.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum AttributeValue {
     /// An attribute of type Number. For example:
@@ -245,11 +253,246 @@ impl serde::Serialize for Item {
     }
 }
 
+impl AttributeValue {
+    /// The DynamoDB data type descriptor, such as `S` or `BOOL`
+    pub(crate) fn type_descriptor(&self) -> &'static str {
+        match self {
+            AttributeValue::N(_) => "N",
+            AttributeValue::S(_) => "S",
+            AttributeValue::Bool(_) => "BOOL",
+            AttributeValue::B(_) => "B",
+            AttributeValue::Null(_) => "NULL",
+            AttributeValue::M(_) => "M",
+            AttributeValue::L(_) => "L",
+            AttributeValue::Ss(_) => "SS",
+            AttributeValue::Ns(_) => "NS",
+            AttributeValue::Bs(_) => "BS",
+        }
+    }
+
+    /// An estimate of the bytes DynamoDB counts toward the item size limit for this value
+    ///
+    /// Follows DynamoDB's published rules: strings and binary count their length in bytes, numbers
+    /// about one byte per two significant digits plus one, booleans and nulls one byte, and maps
+    /// and lists three bytes plus one byte per element on top of their contents. The estimate
+    /// doesn't include the attribute name.
+    pub fn size(&self) -> usize {
+        match self {
+            AttributeValue::N(n) => number_size(n),
+            AttributeValue::S(s) => s.len(),
+            AttributeValue::Bool(_) | AttributeValue::Null(_) => 1,
+            AttributeValue::B(b) => b.len(),
+            AttributeValue::M(m) => {
+                3 + m
+                    .iter()
+                    .map(|(name, value)| 1 + name.len() + value.size())
+                    .sum::<usize>()
+            }
+            AttributeValue::L(l) => 3 + l.iter().map(|value| 1 + value.size()).sum::<usize>(),
+            AttributeValue::Ss(ss) => ss.iter().map(String::len).sum(),
+            AttributeValue::Ns(ns) => ns.iter().map(|n| number_size(n)).sum(),
+            AttributeValue::Bs(bs) => bs.iter().map(Vec::len).sum(),
+        }
+    }
+
+    /// Whether this and `other` hold the same data
+    ///
+    /// Unlike `==`, which compares numbers as strings and sets in order, this compares `N` and
+    /// `NS` values by their numeric value, so `1.50` equals `1.5E0`, and every set as a set.
+    /// Maps and lists are compared recursively. Numbers that don't parse are compared as strings.
+    ///
+    /// ```
+    /// use serde_dynamo::AttributeValue;
+    ///
+    /// let a = AttributeValue::Ns(vec![String::from("1.50"), String::from("20")]);
+    /// let b = AttributeValue::Ns(vec![String::from("2E1"), String::from("1.5")]);
+    /// assert_ne!(a, b);
+    /// assert!(a.numeric_eq(&b));
+    /// ```
+    pub fn numeric_eq(&self, other: &Self) -> bool {
+        fn same_members<'a, T, K>(a: &'a [T], b: &'a [T], key: impl Fn(&'a T) -> K) -> bool
+        where
+            K: Ord,
+        {
+            let mut a: Vec<K> = a.iter().map(&key).collect();
+            let mut b: Vec<K> = b.iter().map(&key).collect();
+            a.sort_unstable();
+            b.sort_unstable();
+            a == b
+        }
+
+        match (self, other) {
+            (AttributeValue::N(a), AttributeValue::N(b)) => {
+                canonical_number(a) == canonical_number(b)
+            }
+            (AttributeValue::M(a), AttributeValue::M(b)) => maps_numeric_eq(a, b),
+            (AttributeValue::L(a), AttributeValue::L(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.numeric_eq(b))
+            }
+            (AttributeValue::Ss(a), AttributeValue::Ss(b)) => same_members(a, b, String::as_str),
+            (AttributeValue::Ns(a), AttributeValue::Ns(b)) => {
+                same_members(a, b, |n| canonical_number(n))
+            }
+            (AttributeValue::Bs(a), AttributeValue::Bs(b)) => same_members(a, b, Vec::as_slice),
+            _ => self == other,
+        }
+    }
+
+    /// Order this and `other` the way DynamoDB orders sort keys
+    ///
+    /// Strings and binary are ordered by their bytes, and numbers by their value, so results can
+    /// be merged client-side in the order a query returns them. Fails if the values aren't both
+    /// `S`, both `N` or both `B`, or if a number doesn't parse.
+    ///
+    /// ```
+    /// use serde_dynamo::AttributeValue;
+    /// use std::cmp::Ordering;
+    ///
+    /// let n = |n: &str| AttributeValue::N(String::from(n));
+    /// assert_eq!(n("9").key_cmp(&n("10")).unwrap(), Ordering::Less);
+    /// assert_eq!(n("1.50").key_cmp(&n("1.5")).unwrap(), Ordering::Equal);
+    ///
+    /// let s = |s: &str| AttributeValue::S(String::from(s));
+    /// assert_eq!(s("Z").key_cmp(&s("a")).unwrap(), Ordering::Less);
+    ///
+    /// assert!(s("1").key_cmp(&n("1")).is_err());
+    /// ```
+    pub fn key_cmp(&self, other: &Self) -> Result<Ordering> {
+        let decimal = |n: &str| {
+            Decimal::parse(n).ok_or_else(|| {
+                ErrorImpl::UnrepresentableNumber(n.to_string(), "it is not a finite decimal number")
+                    .into()
+            })
+        };
+        match (self, other) {
+            (AttributeValue::S(a), AttributeValue::S(b)) => Ok(a.as_bytes().cmp(b.as_bytes())),
+            (AttributeValue::N(a), AttributeValue::N(b)) => Ok(decimal(a)?.cmp_value(&decimal(b)?)),
+            (AttributeValue::B(a), AttributeValue::B(b)) => Ok(a.cmp(b)),
+            _ => Err(
+                ErrorImpl::IncomparableKeys(self.type_descriptor(), other.type_descriptor()).into(),
+            ),
+        }
+    }
+
+    /// Fail if this is a number or set that DynamoDB rejects
+    ///
+    /// Only builds with debug assertions check, so that values built wrongly, such as an empty set
+    /// from a custom `Serialize` impl, fail where they are created rather than when a request is
+    /// sent, without release builds paying for the check. Values nested in maps and lists aren't
+    /// checked.
+    pub(crate) fn debug_check_valid(&self) -> Result<()> {
+        if !cfg!(debug_assertions) {
+            return Ok(());
+        }
+
+        fn check_set<'a, T, K>(
+            set: &'a [T],
+            type_descriptor: &'static str,
+            key: impl Fn(&'a T) -> Option<K>,
+        ) -> Result<()>
+        where
+            K: std::hash::Hash + Eq,
+        {
+            let invalid = |problem| Err(ErrorImpl::InvalidSet(type_descriptor, problem).into());
+            if set.is_empty() {
+                return invalid(String::from("the set is empty"));
+            }
+            let mut seen = std::collections::HashSet::new();
+            for (index, member) in set.iter().enumerate() {
+                let Some(key) = key(member) else {
+                    return invalid(format!("member {index} is not a valid number"));
+                };
+                if !seen.insert(key) {
+                    return invalid(format!("member {index} is a duplicate"));
+                }
+            }
+            Ok(())
+        }
+        let number = |n: &String| {
+            crate::number::check(n.clone(), crate::NumberPrecision::Reject)
+                .ok()
+                .and_then(|n| crate::number::Decimal::parse(&n))
+        };
+
+        match self {
+            AttributeValue::N(n) => {
+                crate::number::check(n.clone(), crate::NumberPrecision::Reject).map(drop)
+            }
+            AttributeValue::Ss(ss) => check_set(ss, "SS", |s| Some(s.as_str())),
+            AttributeValue::Ns(ns) => check_set(ns, "NS", number),
+            AttributeValue::Bs(bs) => check_set(bs, "BS", |b| Some(b.as_slice())),
+            _ => Ok(()),
+        }
+    }
+
+    /// Replace every value with a placeholder of the same type
+    fn scrub(&mut self) {
+        match self {
+            AttributeValue::N(n) => *n = String::from("0"),
+            AttributeValue::S(s) => *s = "x".repeat(s.chars().count()),
+            AttributeValue::Bool(b) => *b = false,
+            AttributeValue::B(b) => b.fill(0),
+            AttributeValue::Null(_) => {}
+            AttributeValue::M(m) => m.values_mut().for_each(AttributeValue::scrub),
+            AttributeValue::L(l) => l.iter_mut().for_each(AttributeValue::scrub),
+            AttributeValue::Ss(ss) => ss.clear(),
+            AttributeValue::Ns(ns) => ns.clear(),
+            AttributeValue::Bs(bs) => bs.clear(),
+        }
+    }
+
+    /// Scrub the values at `path`, looking through lists
+    fn scrub_path(&mut self, path: &[&str]) {
+        match (self, path) {
+            (value, []) => value.scrub(),
+            (AttributeValue::M(m), [key, rest @ ..]) => {
+                if let Some(value) = m.get_mut(*key) {
+                    value.scrub_path(rest);
+                }
+            }
+            (AttributeValue::L(l), path) => l.iter_mut().for_each(|value| value.scrub_path(path)),
+            _ => {}
+        }
+    }
+}
+
+/// The attributes to replace when [scrubbing](Item::scrub) an item
+///
+/// Each path is a dot-separated list of map keys. Lists along the way are looked through, so
+/// `orders.address` covers the `address` of every element of the `orders` list.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScrubPolicy {
+    paths: Vec<String>,
+}
+
+impl ScrubPolicy {
+    /// Create a policy that scrubs nothing
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also scrub the value at `path`, and everything nested inside it
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.paths.push(path.into());
+        self
+    }
+}
+
 /// An item that comes from DynamoDb.
 #[derive(Debug, Clone, Eq, PartialEq, Default)]
 pub struct Item(HashMap<String, AttributeValue>);
 
 impl Item {
+    /// Create an empty item
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty item with room for at least `capacity` attributes
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self(HashMap::with_capacity(capacity))
+    }
+
     /// Get a reference to the inner HashMap
     pub fn inner(&self) -> &HashMap<String, AttributeValue> {
         &self.0
@@ -264,6 +507,443 @@ impl Item {
     pub fn into_inner(self) -> HashMap<String, AttributeValue> {
         self.0
     }
+
+    /// An estimate of the bytes DynamoDB counts toward its 400 KB item size limit
+    ///
+    /// This is the length of every attribute name plus the [size](AttributeValue::size) of its
+    /// value.
+    ///
+    /// ```
+    /// use serde_dynamo::dynamo_item;
+    ///
+    /// let item = dynamo_item! { "id": "abc", "count": 1234 };
+    /// assert_eq!(item.size(), (2 + 3) + (5 + 3));
+    /// ```
+    pub fn size(&self) -> usize {
+        self.0
+            .iter()
+            .map(|(name, value)| name.len() + value.size())
+            .sum()
+    }
+
+    /// Whether this and `other` have the same attributes holding the same data
+    ///
+    /// Values are compared with [`AttributeValue::numeric_eq`], so items written by producers
+    /// that format numbers or order sets differently compare equal.
+    ///
+    /// ```
+    /// use serde_dynamo::dynamo_item;
+    ///
+    /// let a = dynamo_item! { "id": "abc", "sizes": ns["10", "2.5"], "tags": ss["a", "b"] };
+    /// let b = dynamo_item! { "id": "abc", "sizes": ns["2.50", "1E1"], "tags": ss["b", "a"] };
+    /// assert_ne!(a, b);
+    /// assert!(a.semantically_eq(&b));
+    /// ```
+    pub fn semantically_eq(&self, other: &Self) -> bool {
+        maps_numeric_eq(&self.0, &other.0)
+    }
+
+    /// Deserialize the attribute `name` as a `T`.
+    ///
+    /// This extracts a field or two from a large item without defining a struct for it. Errors
+    /// name the attribute, including when it is missing.
+    ///
+    /// ```
+    /// use serde_dynamo::{AttributeValue, Item};
+    /// use std::collections::HashMap;
+    ///
+    /// let item = Item::from(HashMap::from([
+    ///     (String::from("age"), AttributeValue::N(String::from("37"))),
+    ///     (String::from("name"), AttributeValue::N(String::from("1"))),
+    /// ]));
+    ///
+    /// assert_eq!(item.get_as::<u64>("age").unwrap(), 37);
+    /// assert_eq!(
+    ///     item.get_as::<String>("name").unwrap_err().to_string(),
+    ///     "Attribute `name`: Expected string",
+    /// );
+    /// assert_eq!(
+    ///     item.get_as::<u64>("height").unwrap_err().to_string(),
+    ///     "Attribute `height` is missing",
+    /// );
+    /// ```
+    pub fn get_as<'de, T>(&self, name: &str) -> Result<T>
+    where
+        T: Deserialize<'de>,
+    {
+        match self.0.get(name) {
+            Some(value) => from_attribute_value_ref(value).map_err(|err| err.in_attribute(name)),
+            None => Err(ErrorImpl::MissingAttribute(name.to_string()).into()),
+        }
+    }
+
+    /// Deserialize the attribute `name` as a `T`, if it is present and not `NULL`.
+    ///
+    /// See [`get_as`](Self::get_as).
+    pub fn get_as_opt<'de, T>(&self, name: &str) -> Result<Option<T>>
+    where
+        T: Deserialize<'de>,
+    {
+        match self.0.get(name) {
+            Some(value) => from_attribute_value_ref(value).map_err(|err| err.in_attribute(name)),
+            None => Ok(None),
+        }
+    }
+
+    /// Deserialize the value at a document path, such as `address.lines[0]`, as a `T`.
+    ///
+    /// A path is a list of map keys separated by `.`, where each key may be followed by list
+    /// indexes in brackets; see the [`path`](crate::path) module for escaping keys. Errors name
+    /// the path. See [`get_as`](Self::get_as).
+    ///
+    /// ```
+    /// use serde_dynamo::{dynamo_item, Item};
+    ///
+    /// let item: Item = dynamo_item! {
+    ///     "address": { "lines": ["1 Main St", "Springfield"] },
+    /// };
+    ///
+    /// let city: String = item.get_path_as("address.lines[1]").unwrap();
+    /// assert_eq!(city, "Springfield");
+    /// ```
+    pub fn get_path_as<'de, T>(&self, path: &str) -> Result<T>
+    where
+        T: Deserialize<'de>,
+    {
+        let segments = path::parse(path)?;
+        match path::lookup(&self.0, &segments) {
+            Some(value) => from_attribute_value_ref(value).map_err(|err| err.in_path(path)),
+            None => Err(ErrorImpl::MissingAttribute(path.to_string()).into()),
+        }
+    }
+
+    /// Store `value` at a document path, such as `address.lines[0]`, returning the value it
+    /// replaced.
+    ///
+    /// Missing maps along the path are created, and lists are extended with `NULL`s up to the
+    /// index. This patches items loaded as a raw [`Item`] before they are written back. Use
+    /// [`set_path_strict`](Self::set_path_strict) to fail instead. See
+    /// [`get_path_as`](Self::get_path_as) for the path syntax.
+    ///
+    /// ```
+    /// use serde_dynamo::{dynamo_item, dynamo_value, AttributeValue, Item};
+    ///
+    /// let mut item: Item = dynamo_item! {
+    ///     "address": { "lines": ["1 Main St"] },
+    /// };
+    ///
+    /// let previous = item
+    ///     .set_path("address.lines[0]", AttributeValue::S(String::from("2 Main St")))
+    ///     .unwrap();
+    /// assert_eq!(previous, Some(AttributeValue::S(String::from("1 Main St"))));
+    ///
+    /// item.set_path("billing.lines[1]", AttributeValue::S(String::from("Springfield")))
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     item["billing"],
+    ///     dynamo_value!({ "lines": [null, "Springfield"] }),
+    /// );
+    /// ```
+    pub fn set_path(
+        &mut self,
+        path: &str,
+        value: AttributeValue,
+    ) -> Result<Option<AttributeValue>> {
+        let segments = path::parse(path)?;
+        path::insert(&mut self.0, &segments, value, true)
+            .map_err(|err| err.in_path_unless_missing(path))
+    }
+
+    /// Store `value` at a document path, returning the value it replaced, without creating
+    /// anything along the way.
+    ///
+    /// Every map and list on the path, and the list element being replaced, must already exist.
+    /// The last map key may be new. See [`set_path`](Self::set_path).
+    ///
+    /// ```
+    /// use serde_dynamo::{dynamo_item, AttributeValue, Item};
+    ///
+    /// let mut item: Item = dynamo_item! {
+    ///     "address": { "lines": ["1 Main St"] },
+    /// };
+    ///
+    /// let err = item
+    ///     .set_path_strict("address.lines[1]", AttributeValue::Null(true))
+    ///     .unwrap_err();
+    /// assert_eq!(
+    ///     err.to_string(),
+    ///     "Attribute `address.lines[1]` is missing",
+    /// );
+    /// ```
+    pub fn set_path_strict(
+        &mut self,
+        path: &str,
+        value: AttributeValue,
+    ) -> Result<Option<AttributeValue>> {
+        let segments = path::parse(path)?;
+        path::insert(&mut self.0, &segments, value, false)
+            .map_err(|err| err.in_path_unless_missing(path))
+    }
+
+    /// Replace the values covered by `policy` with placeholders of the same type.
+    ///
+    /// This turns production items into fixtures that can be shared in bug reports while keeping
+    /// the shape that triggered the bug. Strings are replaced by `x`s of the same length, numbers
+    /// by `0`, binary values by zeros of the same length, booleans by `false`, and sets are
+    /// emptied. Maps and lists are scrubbed element by element. Paths that aren't in the item are
+    /// ignored.
+    ///
+    /// ```
+    /// use serde_dynamo::{AttributeValue, Item, ScrubPolicy};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut item = Item::from(HashMap::from([
+    ///     (String::from("id"), AttributeValue::S(String::from("u-42"))),
+    ///     (String::from("email"), AttributeValue::S(String::from("ann@example.com"))),
+    ///     (String::from("age"), AttributeValue::N(String::from("37"))),
+    /// ]));
+    ///
+    /// item.scrub(&ScrubPolicy::new().path("email").path("age"));
+    ///
+    /// assert_eq!(item["id"], AttributeValue::S(String::from("u-42")));
+    /// assert_eq!(item["email"], AttributeValue::S(String::from("xxxxxxxxxxxxxxx")));
+    /// assert_eq!(item["age"], AttributeValue::N(String::from("0")));
+    /// ```
+    pub fn scrub(&mut self, policy: &ScrubPolicy) {
+        for path in &policy.paths {
+            let path: Vec<&str> = path.split('.').collect();
+            if let [key, rest @ ..] = path.as_slice() {
+                if let Some(value) = self.0.get_mut(*key) {
+                    value.scrub_path(rest);
+                }
+            }
+        }
+    }
+
+    /// The names of the attributes that match the glob `pattern`.
+    ///
+    /// In the pattern, `*` matches any run of characters, including none, and `?` matches any one
+    /// character. Every other character matches itself, so a pattern without wildcards matches
+    /// only that name, and `gsi1*` matches every name starting with `gsi1`. Names are returned in
+    /// no particular order.
+    ///
+    /// ```
+    /// use serde_dynamo::dynamo_item;
+    ///
+    /// let item = dynamo_item! { "id": "u-42", "gsi1pk": "ORG#7", "gsi1sk": "USER#42" };
+    ///
+    /// let mut keys: Vec<&str> = item.keys_matching("gsi?*").collect();
+    /// keys.sort();
+    /// assert_eq!(keys, ["gsi1pk", "gsi1sk"]);
+    /// ```
+    pub fn keys_matching<'a>(&'a self, pattern: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.0
+            .keys()
+            .map(String::as_str)
+            .filter(move |name| glob_matches(pattern, name))
+    }
+
+    /// A copy of the attributes whose names match the glob `pattern`.
+    ///
+    /// See [`keys_matching`](Self::keys_matching) for the pattern syntax.
+    pub fn select_matching(&self, pattern: &str) -> Item {
+        Item(
+            self.0
+                .iter()
+                .filter(|(name, _)| glob_matches(pattern, name))
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect(),
+        )
+    }
+
+    /// Remove the attributes whose names match the glob `pattern`, and return them.
+    ///
+    /// Useful for stripping computed attributes, such as index keys, before an item leaves the
+    /// service. See [`keys_matching`](Self::keys_matching) for the pattern syntax.
+    ///
+    /// ```
+    /// use serde_dynamo::dynamo_item;
+    ///
+    /// let mut item = dynamo_item! { "id": "u-42", "gsi1pk": "ORG#7", "gsi1sk": "USER#42" };
+    ///
+    /// let removed = item.remove_matching("gsi1*");
+    /// assert_eq!(item, dynamo_item! { "id": "u-42" });
+    /// assert_eq!(removed, dynamo_item! { "gsi1pk": "ORG#7", "gsi1sk": "USER#42" });
+    /// ```
+    pub fn remove_matching(&mut self, pattern: &str) -> Item {
+        let (removed, kept) = std::mem::take(&mut self.0)
+            .into_iter()
+            .partition(|(name, _)| glob_matches(pattern, name));
+        self.0 = kept;
+        Item(removed)
+    }
+
+    /// Check that the item has usable values for the table's key attributes before writing it.
+    ///
+    /// Key attributes must be present, must be a string, number, or binary, and must not be
+    /// empty. DynamoDB otherwise rejects the write with errors such as "One of the required keys
+    /// was not given a value". The returned error lists every problem found.
+    ///
+    /// ```
+    /// use serde_dynamo::{AttributeValue, Item};
+    /// use std::collections::HashMap;
+    ///
+    /// let item = Item::from(HashMap::from([
+    ///     (String::from("pk"), AttributeValue::S(String::new())),
+    /// ]));
+    ///
+    /// let err = item.validate_key_schema("pk", Some("sk")).unwrap_err();
+    /// assert_eq!(
+    ///     err.to_string(),
+    ///     "Invalid key attributes: `pk` is an empty string, `sk` is missing",
+    /// );
+    /// ```
+    pub fn validate_key_schema(&self, partition_key: &str, sort_key: Option<&str>) -> Result<()> {
+        let problems: Vec<String> = std::iter::once(partition_key)
+            .chain(sort_key)
+            .filter_map(|name| {
+                let problem = match self.0.get(name) {
+                    None => String::from("is missing"),
+                    Some(AttributeValue::S(s)) if s.is_empty() => {
+                        String::from("is an empty string")
+                    }
+                    Some(AttributeValue::B(b)) if b.is_empty() => String::from("is empty binary"),
+                    Some(AttributeValue::S(_) | AttributeValue::N(_) | AttributeValue::B(_)) => {
+                        return None
+                    }
+                    Some(other) => format!(
+                        "is of type {}, but keys must be of type S, N, or B",
+                        other.type_descriptor()
+                    ),
+                };
+                Some(format!("`{name}` {problem}"))
+            })
+            .collect();
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ErrorImpl::InvalidKeyAttributes(problems).into())
+        }
+    }
+}
+
+/// Whether `a` and `b` have the same keys, with [numerically equal](AttributeValue::numeric_eq)
+/// values
+fn maps_numeric_eq(
+    a: &HashMap<String, AttributeValue>,
+    b: &HashMap<String, AttributeValue>,
+) -> bool {
+    a.len() == b.len()
+        && a.iter()
+            .all(|(name, value)| b.get(name).is_some_and(|other| value.numeric_eq(other)))
+}
+
+/// A form of the number `n` that is the same for every way of writing its value
+///
+/// Numbers that don't parse are left as they are.
+fn canonical_number(n: &str) -> Cow<'_, str> {
+    match Decimal::parse(n) {
+        Some(decimal) if decimal.digits.is_empty() => Cow::Borrowed("0"),
+        Some(decimal) => {
+            let sign = if decimal.negative { "-" } else { "" };
+            Cow::Owned(format!("{sign}{}E{}", decimal.digits, decimal.exponent))
+        }
+        None => Cow::Borrowed(n),
+    }
+}
+
+/// The size of the number `n`: one byte per two significant digits, plus one
+fn number_size(n: &str) -> usize {
+    let mantissa = n.split(['e', 'E']).next().unwrap_or_default();
+    let digits: String = mantissa.chars().filter(char::is_ascii_digit).collect();
+    let significant = digits.trim_start_matches('0').trim_end_matches('0').len();
+    significant.max(1).div_ceil(2) + 1
+}
+
+/// Whether `name` matches the glob `pattern`, where `*` matches any run of characters and `?`
+/// matches any one character
+fn glob_matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // The position of the last `*`, and the name position it was tried at
+    let mut star = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                // Let the last `*` match one more character and try again
+                Some((star_p, star_n)) => {
+                    star = Some((star_p, star_n + 1));
+                    p = star_p + 1;
+                    n = star_n + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Hashes consistently with `==`
+///
+/// Map entries are hashed in key order, so equal maps hash equally whatever order their entries
+/// are stored in. The hash of a value is stable for a given version of this crate and of the
+/// hasher, but isn't part of the crate's compatibility guarantees, so don't persist it.
+impl Hash for AttributeValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            AttributeValue::N(n) => n.hash(state),
+            AttributeValue::S(s) => s.hash(state),
+            AttributeValue::Bool(b) => b.hash(state),
+            AttributeValue::B(b) => b.hash(state),
+            AttributeValue::Null(null) => null.hash(state),
+            AttributeValue::M(m) => hash_map(m, state),
+            AttributeValue::L(l) => l.hash(state),
+            AttributeValue::Ss(ss) => ss.hash(state),
+            AttributeValue::Ns(ns) => ns.hash(state),
+            AttributeValue::Bs(bs) => bs.hash(state),
+        }
+    }
+}
+
+/// Hashes consistently with `==`, hashing attributes in name order
+///
+/// This makes it possible to deduplicate items, such as stream records, with a `HashSet<Item>`.
+/// The hash has the same stability as that of [`AttributeValue`].
+///
+/// ```
+/// use serde_dynamo::dynamo_item;
+/// use std::collections::HashSet;
+///
+/// let records = [
+///     dynamo_item! { "id": "a", "n": 1 },
+///     dynamo_item! { "id": "b", "n": 1 },
+///     dynamo_item! { "n": 1, "id": "a" },
+/// ];
+/// let unique: HashSet<_> = records.into_iter().collect();
+/// assert_eq!(unique.len(), 2);
+/// ```
+impl Hash for Item {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_map(&self.0, state);
+    }
+}
+
+/// Hash the entries of `m` in key order
+fn hash_map<H: Hasher>(m: &HashMap<String, AttributeValue>, state: &mut H) {
+    let mut entries: Vec<_> = m.iter().collect();
+    entries.sort_unstable_by_key(|(name, _)| *name);
+    entries.hash(state);
 }
 
 impl AsRef<HashMap<String, AttributeValue>> for Item {
@@ -316,10 +996,165 @@ where
     }
 }
 
+impl<T> From<&HashMap<String, T>> for Item
+where
+    T: Clone,
+    AttributeValue: From<T>,
+{
+    fn from(m: &HashMap<String, T>) -> Self {
+        Item(
+            m.iter()
+                .map(|(key, value)| (key.clone(), AttributeValue::from(value.clone())))
+                .collect(),
+        )
+    }
+}
+
+impl From<String> for AttributeValue {
+    fn from(s: String) -> Self {
+        AttributeValue::S(s)
+    }
+}
+
+impl From<&str> for AttributeValue {
+    fn from(s: &str) -> Self {
+        AttributeValue::S(s.to_string())
+    }
+}
+
+impl From<bool> for AttributeValue {
+    fn from(b: bool) -> Self {
+        AttributeValue::Bool(b)
+    }
+}
+
+impl From<Vec<u8>> for AttributeValue {
+    fn from(b: Vec<u8>) -> Self {
+        AttributeValue::B(b)
+    }
+}
+
+macro_rules! impl_from_integer {
+    ($($ty:ty)*) => {
+        $(
+            impl From<$ty> for AttributeValue {
+                fn from(n: $ty) -> Self {
+                    AttributeValue::N(n.to_string())
+                }
+            }
+        )*
+    };
+}
+
+// Every one of these fits in DynamoDB's 38 significant digits, unlike `i128` and `u128`
+impl_from_integer! { i8 i16 i32 i64 isize u8 u16 u32 u64 usize }
+
 /// Multiple items that come from DynamoDb.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Items(Vec<Item>);
 
+impl std::ops::Deref for Items {
+    type Target = [Item];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Items {
+    /// Deserialize the first item as a `T`, failing if there are no items
+    ///
+    /// Any further items are ignored. Use [`single_typed`](Self::single_typed) when more than one
+    /// item means something went wrong.
+    pub fn first_typed<'de, T>(&self) -> Result<T>
+    where
+        T: Deserialize<'de>,
+    {
+        match self.0.first() {
+            Some(item) => crate::from_item_ref(item),
+            None => Err(ErrorImpl::UnexpectedItemCount("at least one item", 0).into()),
+        }
+    }
+
+    /// Deserialize the only item as a `T`, failing unless there is exactly one item
+    ///
+    /// This suits a query that should match a single item, such as a lookup through a unique
+    /// secondary index.
+    ///
+    /// ```
+    /// use serde_dynamo::{dynamo_item, Items};
+    /// # use serde_derive::Deserialize;
+    ///
+    /// #[derive(Debug, Deserialize)]
+    /// struct User {
+    ///     email: String,
+    /// }
+    ///
+    /// let items = Items::from(vec![dynamo_item! { "email": "a@example.com" }]);
+    /// let user: User = items.single_typed().unwrap();
+    /// assert_eq!(user.email, "a@example.com");
+    ///
+    /// let items = Items::from(vec![
+    ///     dynamo_item! { "email": "a@example.com" },
+    ///     dynamo_item! { "email": "b@example.com" },
+    /// ]);
+    /// assert_eq!(
+    ///     items.single_typed::<User>().unwrap_err().to_string(),
+    ///     "Expected exactly one item, found 2 items",
+    /// );
+    /// ```
+    pub fn single_typed<'de, T>(&self) -> Result<T>
+    where
+        T: Deserialize<'de>,
+    {
+        match self.0.as_slice() {
+            [item] => crate::from_item_ref(item),
+            items => Err(ErrorImpl::UnexpectedItemCount("exactly one item", items.len()).into()),
+        }
+    }
+
+    /// Deserialize every item as a `T`, keeping the items that fail beside their errors
+    ///
+    /// Unlike [`from_items`](crate::from_items), one bad item doesn't fail the whole batch. The
+    /// successes keep their order, and so do the failures, each with the raw item it came from so
+    /// that it can be retried or sent to a dead-letter queue instead of being dropped.
+    ///
+    /// ```
+    /// use serde_dynamo::{dynamo_item, Items};
+    /// # use serde_derive::Deserialize;
+    ///
+    /// #[derive(Debug, Deserialize)]
+    /// struct Event {
+    ///     id: u32,
+    /// }
+    ///
+    /// let items = Items::from(vec![
+    ///     dynamo_item! { "id": 1 },
+    ///     dynamo_item! { "id": "two" },
+    ///     dynamo_item! { "id": 3 },
+    /// ]);
+    ///
+    /// let (events, failures) = items.partition_typed::<Event>();
+    /// assert_eq!(events.iter().map(|event| event.id).collect::<Vec<_>>(), vec![1, 3]);
+    /// assert_eq!(failures.len(), 1);
+    /// assert_eq!(failures[0].0, dynamo_item! { "id": "two" });
+    /// ```
+    pub fn partition_typed<'de, T>(self) -> (Vec<T>, Vec<(Item, Error)>)
+    where
+        T: Deserialize<'de>,
+    {
+        let mut parsed = Vec::with_capacity(self.0.len());
+        let mut failures = Vec::new();
+        for item in self.0 {
+            match crate::from_item_ref(&item) {
+                Ok(value) => parsed.push(value),
+                Err(err) => failures.push((item, err)),
+            }
+        }
+        (parsed, failures)
+    }
+}
+
 impl<T> From<Items> for Vec<HashMap<String, T>>
 where
     HashMap<String, T>: From<Item>,
@@ -338,11 +1173,248 @@ where
     }
 }
 
+impl<T> From<&[HashMap<String, T>]> for Items
+where
+    T: Clone,
+    AttributeValue: From<T>,
+{
+    fn from(items: &[HashMap<String, T>]) -> Self {
+        Items(items.iter().map(Into::into).collect())
+    }
+}
+
+impl From<Vec<Item>> for Items {
+    fn from(items: Vec<Item>) -> Self {
+        Items(items)
+    }
+}
+
+impl From<Items> for Vec<Item> {
+    fn from(Items(items): Items) -> Self {
+        items
+    }
+}
+
+impl IntoIterator for Items {
+    type Item = Item;
+    type IntoIter = std::vec::IntoIter<Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn items_with_expected_counts() {
+        use serde_derive::Deserialize;
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Row {
+            id: u8,
+        }
+
+        let none = Items::from(Vec::<Item>::new());
+        let one = Items::from(vec![crate::dynamo_item! { "id": 1 }]);
+        let two = Items::from(vec![
+            crate::dynamo_item! { "id": 1 },
+            crate::dynamo_item! { "id": 2 },
+        ]);
+
+        assert_eq!(one.first_typed::<Row>().unwrap(), Row { id: 1 });
+        assert_eq!(two.first_typed::<Row>().unwrap(), Row { id: 1 });
+        assert_eq!(one.single_typed::<Row>().unwrap(), Row { id: 1 });
+        assert_eq!(
+            none.first_typed::<Row>().unwrap_err().to_string(),
+            "Expected at least one item, found 0 items"
+        );
+        assert_eq!(
+            none.single_typed::<Row>().unwrap_err().to_string(),
+            "Expected exactly one item, found 0 items"
+        );
+        assert_eq!(
+            two.single_typed::<Row>().unwrap_err().to_string(),
+            "Expected exactly one item, found 2 items"
+        );
+    }
+
+    #[test]
+    fn partition_typed_items() {
+        use serde_derive::Deserialize;
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Row {
+            id: u8,
+        }
+
+        let items = Items::from(vec![
+            crate::dynamo_item! { "id": 1 },
+            crate::dynamo_item! { "id": 300 },
+            crate::dynamo_item! { "id": 2 },
+            crate::dynamo_item! { "name": "x" },
+        ]);
+        let (rows, failures) = items.partition_typed::<Row>();
+        assert_eq!(rows, vec![Row { id: 1 }, Row { id: 2 }]);
+        let failed: Vec<&Item> = failures.iter().map(|(item, _)| item).collect();
+        assert_eq!(
+            failed,
+            vec![
+                &crate::dynamo_item! { "id": 300 },
+                &crate::dynamo_item! { "name": "x" }
+            ]
+        );
+        assert_eq!(failures[1].1.to_string(), "missing field `id`");
+
+        let (rows, failures) = Items::from(Vec::<Item>::new()).partition_typed::<Row>();
+        assert!(rows.is_empty() && failures.is_empty());
+    }
+
+    #[test]
+    fn get_typed_attributes() {
+        let item: Item = crate::dynamo_item! {
+            "null": null,
+            "tags": ["a", 1],
+        };
+
+        assert_eq!(item.get_as_opt::<u8>("null").unwrap(), None);
+        assert_eq!(item.get_as_opt::<u8>("missing").unwrap(), None);
+        assert_eq!(item.get_path_as::<String>("tags[0]").unwrap(), "a");
+        assert_eq!(
+            item.get_path_as::<String>("tags[1]")
+                .unwrap_err()
+                .to_string(),
+            "Attribute `tags[1]`: Expected string"
+        );
+        assert_eq!(
+            item.get_path_as::<String>("tags[2]")
+                .unwrap_err()
+                .to_string(),
+            "Attribute `tags[2]` is missing"
+        );
+        assert_eq!(
+            item.get_path_as::<String>("tags[x]")
+                .unwrap_err()
+                .to_string(),
+            "Invalid attribute path `tags[x]`"
+        );
+    }
+
+    #[test]
+    fn set_values_at_paths() {
+        let mut item: Item = crate::dynamo_item! {
+            "name": "a",
+            "tags": ["x"],
+        };
+
+        assert_eq!(
+            item.set_path("tags[2].k", AttributeValue::Bool(true))
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            item["tags"],
+            crate::dynamo_value!(["x", null, { "k": true }])
+        );
+        assert_eq!(
+            item.set_path_strict("tags[2].k", AttributeValue::Bool(false))
+                .unwrap(),
+            Some(AttributeValue::Bool(true))
+        );
+        assert_eq!(
+            item.set_path_strict("tags[2].new", AttributeValue::Bool(false))
+                .unwrap(),
+            None
+        );
+
+        assert_eq!(
+            item.set_path_strict("other.k", AttributeValue::Null(true))
+                .unwrap_err()
+                .to_string(),
+            "Attribute `other` is missing"
+        );
+        assert_eq!(
+            item.set_path_strict("tags[5][0]", AttributeValue::Null(true))
+                .unwrap_err()
+                .to_string(),
+            "Attribute `tags[5]` is missing"
+        );
+        assert_eq!(
+            item.set_path("name.k", AttributeValue::Null(true))
+                .unwrap_err()
+                .to_string(),
+            "Attribute `name.k`: Attribute path does not match the structure of the item at a S value"
+        );
+
+        // Padding a list this far could never be stored, and must not allocate
+        for path in ["tags[409600]", "huge[18446744073709551615].k"] {
+            let err = item.set_path(path, AttributeValue::Null(true)).unwrap_err();
+            assert_eq!(err.code(), crate::ErrorCode::ItemTooLarge, "{path}");
+        }
+        assert_eq!(
+            item["tags"],
+            crate::dynamo_value!(["x", null, { "k": false, "new": false }])
+        );
+    }
+
+    #[test]
+    fn scrub_nested_values() {
+        let order = |street: &str, total: &str| {
+            AttributeValue::M(HashMap::from([
+                (
+                    String::from("street"),
+                    AttributeValue::S(String::from(street)),
+                ),
+                (
+                    String::from("total"),
+                    AttributeValue::N(String::from(total)),
+                ),
+            ]))
+        };
+        let mut item = Item::from(HashMap::from([
+            (
+                String::from("orders"),
+                AttributeValue::L(vec![order("1 Main St", "9.99"), order("Élm", "1")]),
+            ),
+            (
+                String::from("profile"),
+                AttributeValue::M(HashMap::from([
+                    (String::from("avatar"), AttributeValue::B(vec![7, 8, 9])),
+                    (String::from("verified"), AttributeValue::Bool(true)),
+                    (
+                        String::from("tags"),
+                        AttributeValue::Ss(vec![String::from("vip")]),
+                    ),
+                    (String::from("nickname"), AttributeValue::Null(true)),
+                ])),
+            ),
+        ]));
+
+        item.scrub(
+            &ScrubPolicy::new()
+                .path("orders.street")
+                .path("profile")
+                .path("missing.path"),
+        );
+
+        assert_eq!(
+            item["orders"],
+            AttributeValue::L(vec![order("xxxxxxxxx", "9.99"), order("xxx", "1")])
+        );
+        assert_eq!(
+            item["profile"],
+            AttributeValue::M(HashMap::from([
+                (String::from("avatar"), AttributeValue::B(vec![0, 0, 0])),
+                (String::from("verified"), AttributeValue::Bool(false)),
+                (String::from("tags"), AttributeValue::Ss(vec![])),
+                (String::from("nickname"), AttributeValue::Null(true)),
+            ]))
+        );
+    }
+
     #[test]
     fn deserialize_from_example() {
         // Example from https://docs.aws.amazon.com/amazondynamodb/latest/developerguide/S3DataExport.Output.html
@@ -616,4 +1688,307 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn validate_key_schema() {
+        let item = Item::from(HashMap::from([
+            (
+                String::from("pk"),
+                AttributeValue::S(String::from("user#1")),
+            ),
+            (String::from("sk"), AttributeValue::N(String::from("1"))),
+            (String::from("empty"), AttributeValue::B(vec![])),
+            (String::from("list"), AttributeValue::L(vec![])),
+        ]));
+
+        assert!(item.validate_key_schema("pk", None).is_ok());
+        assert!(item.validate_key_schema("pk", Some("sk")).is_ok());
+        assert_eq!(
+            item.validate_key_schema("empty", Some("list"))
+                .unwrap_err()
+                .to_string(),
+            "Invalid key attributes: `empty` is empty binary, `list` is of type L, but keys must be of type S, N, or B"
+        );
+    }
+
+    #[test]
+    fn convert_items() {
+        let map = HashMap::from([(String::from("id"), AttributeValue::S(String::from("a")))]);
+        let item = Item::from(&map);
+        assert_eq!(HashMap::<String, AttributeValue>::from(item.clone()), map);
+
+        let items = Items::from(&[map.clone(), map.clone()][..]);
+        assert_eq!(items.len(), 2);
+        assert_eq!(
+            Vec::<Item>::from(items.clone()),
+            vec![item.clone(), item.clone()]
+        );
+        assert_eq!(
+            Items::from(vec![item.clone()]).into_iter().next(),
+            Some(item)
+        );
+    }
+
+    #[cfg(feature = "aws-sdk-dynamodb+1")]
+    #[test]
+    fn convert_sdk_items() {
+        use __aws_sdk_dynamodb_1::types::AttributeValue as SdkAttributeValue;
+
+        let sdk = HashMap::from([(String::from("id"), SdkAttributeValue::S(String::from("a")))]);
+        let item = Item::from(&sdk);
+        assert_eq!(item["id"], AttributeValue::S(String::from("a")));
+        assert_eq!(HashMap::<String, SdkAttributeValue>::from(item), sdk);
+
+        let items = Items::from(std::slice::from_ref(&sdk));
+        assert_eq!(
+            Vec::<HashMap<String, SdkAttributeValue>>::from(items),
+            vec![sdk]
+        );
+    }
+
+    #[cfg(all(feature = "aws-sdk-dynamodb+1", feature = "rusoto_dynamodb+0_48"))]
+    #[test]
+    fn sdk_conversions_move_binary() {
+        use __aws_sdk_dynamodb_1::types::AttributeValue as SdkAttributeValue;
+        use __rusoto_dynamodb_0_48::AttributeValue as RusotoAttributeValue;
+
+        let bytes = vec![7; 1024];
+        let ptr = bytes.as_ptr();
+
+        let sdk = SdkAttributeValue::from(AttributeValue::B(bytes));
+        let AttributeValue::B(bytes) = AttributeValue::from(sdk) else {
+            panic!("expected binary");
+        };
+        assert_eq!(bytes.as_ptr(), ptr);
+
+        let rusoto = RusotoAttributeValue::from(AttributeValue::Bs(vec![bytes]));
+        let AttributeValue::Bs(bs) = AttributeValue::from(rusoto) else {
+            panic!("expected binary set");
+        };
+        assert_eq!(bs[0].as_ptr(), ptr);
+    }
+
+    #[test]
+    fn estimated_sizes() {
+        for (n, size) in [
+            ("0", 2),
+            ("7", 2),
+            ("12", 2),
+            ("123", 3),
+            ("-1.50", 2),
+            ("1e100", 2),
+        ] {
+            assert_eq!(AttributeValue::N(String::from(n)).size(), size, "{n}");
+        }
+
+        let item: Item = crate::dynamo_item! {
+            "s": "héllo",
+            "b": true,
+            "m": { "k": null },
+            "l": [1, "ab"],
+        };
+        assert_eq!(
+            item.size(),
+            (1 + 6) + (1 + 1) + (1 + 3 + (1 + 1 + 1)) + (1 + 3 + (1 + 2) + (1 + 2))
+        );
+    }
+
+    #[test]
+    fn numeric_equality() {
+        for (a, b, equal) in [
+            ("1.50", "1.5", true),
+            ("150", "1.5E2", true),
+            ("-0", "0.000", true),
+            ("0.1", "1e-1", true),
+            ("-2", "2", false),
+            ("10", "1", false),
+            ("abc", "abc", true),
+        ] {
+            let (a, b) = (
+                AttributeValue::N(String::from(a)),
+                AttributeValue::N(String::from(b)),
+            );
+            assert_eq!(a.numeric_eq(&b), equal, "{a:?} {b:?}");
+        }
+
+        let a: Item = crate::dynamo_item! {
+            "n": ns["1", "2.0"],
+            "s": ss["x", "y"],
+            "b": bs[&[1][..], &[2][..]],
+            "m": { "price": ns["9.90"], "list": [ns["3"], "a"] },
+        };
+        let b: Item = crate::dynamo_item! {
+            "n": ns["2", "1.0"],
+            "s": ss["y", "x"],
+            "b": bs[&[2][..], &[1][..]],
+            "m": { "list": [ns["3.0"], "a"], "price": ns["9.9"] },
+        };
+        assert!(a.semantically_eq(&b));
+
+        let mut c = b.clone();
+        c.insert(String::from("extra"), AttributeValue::Null(true));
+        assert!(!a.semantically_eq(&c));
+        assert!(!c.semantically_eq(&a));
+
+        let d: Item = crate::dynamo_item! { "l": [1, 2] };
+        let e: Item = crate::dynamo_item! { "l": [2, 1] };
+        assert!(!d.semantically_eq(&e));
+        assert!(
+            !AttributeValue::S(String::from("1")).numeric_eq(&AttributeValue::N(String::from("1")))
+        );
+    }
+
+    #[test]
+    fn hashes_match_equality() {
+        use std::collections::hash_map::DefaultHasher;
+
+        fn hash<T: Hash>(value: &T) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        // Built in different orders, so the maps are likely to iterate differently
+        let names: Vec<String> = (0..32).map(|i| format!("attr{i}")).collect();
+        let a: Item = names
+            .iter()
+            .map(|name| {
+                (
+                    name.clone(),
+                    AttributeValue::M(HashMap::from([(name.clone(), AttributeValue::Null(true))])),
+                )
+            })
+            .collect::<HashMap<_, _>>()
+            .into();
+        let b: Item = names
+            .iter()
+            .rev()
+            .map(|name| {
+                (
+                    name.clone(),
+                    AttributeValue::M(HashMap::from([(name.clone(), AttributeValue::Null(true))])),
+                )
+            })
+            .collect::<HashMap<_, _>>()
+            .into();
+        assert_eq!(a, b);
+        assert_eq!(hash(&a), hash(&b));
+
+        let values = [
+            AttributeValue::N(String::from("1")),
+            AttributeValue::S(String::from("1")),
+            AttributeValue::Ss(vec![String::from("1")]),
+            AttributeValue::Ns(vec![String::from("1")]),
+            AttributeValue::Bool(true),
+            AttributeValue::Null(true),
+        ];
+        let hashes: std::collections::HashSet<u64> = values.iter().map(hash).collect();
+        assert_eq!(hashes.len(), values.len());
+    }
+
+    #[test]
+    fn orders_keys_like_dynamodb() {
+        let n = |n: &str| AttributeValue::N(String::from(n));
+        let mut numbers = [
+            "10", "-1E2", "0.5", "-0.25", "0", "2", "-3", "1e-130", "-0", "9.99", "1E+125",
+        ]
+        .map(n);
+        numbers.sort_by(|a, b| a.key_cmp(b).unwrap());
+        assert_eq!(
+            numbers,
+            ["-1E2", "-3", "-0.25", "0", "-0", "1e-130", "0.5", "2", "9.99", "10", "1E+125"].map(n)
+        );
+
+        let s = |s: &str| AttributeValue::S(String::from(s));
+        let mut strings = ["b", "aa", "B", "é", "a", ""].map(s);
+        strings.sort_by(|a, b| a.key_cmp(b).unwrap());
+        assert_eq!(strings, ["", "B", "a", "aa", "b", "é"].map(s));
+
+        let b = |b: &[u8]| AttributeValue::B(b.to_vec());
+        assert_eq!(b(&[1, 255]).key_cmp(&b(&[2])).unwrap(), Ordering::Less);
+        assert_eq!(b(&[1]).key_cmp(&b(&[1, 0])).unwrap(), Ordering::Less);
+
+        assert_eq!(
+            s("1").key_cmp(&n("1")).unwrap_err().to_string(),
+            "Can't order key values of type S and N, both must be S, both N, or both B"
+        );
+        assert!(AttributeValue::Bool(true)
+            .key_cmp(&AttributeValue::Bool(true))
+            .is_err());
+        assert!(n("x").key_cmp(&n("1")).is_err());
+    }
+
+    #[test]
+    fn glob_patterns() {
+        for (pattern, name, matches) in [
+            ("gsi1*", "gsi1pk", true),
+            ("gsi1*", "gsi1", true),
+            ("gsi1*", "gsi2pk", false),
+            ("*sk", "gsi1sk", true),
+            ("*sk", "gsi1pk", false),
+            ("g?i*k", "gsi1sk", true),
+            ("a*b*c", "aXbYbZc", true),
+            ("a*b*c", "aXbYc!", false),
+            ("id", "id", true),
+            ("id", "ids", false),
+            ("?", "é", true),
+            ("*", "", true),
+            ("", "", true),
+            ("", "a", false),
+        ] {
+            assert_eq!(glob_matches(pattern, name), matches, "{pattern} {name}");
+        }
+    }
+
+    #[test]
+    fn matching_attributes() {
+        let mut item = crate::dynamo_item! { "pk": "a", "gsi1pk": "b", "gsi2pk": "c" };
+
+        let mut keys: Vec<&str> = item.keys_matching("*pk").collect();
+        keys.sort();
+        assert_eq!(keys, ["gsi1pk", "gsi2pk", "pk"]);
+        assert_eq!(item.keys_matching("lsi*").count(), 0);
+
+        assert_eq!(
+            item.select_matching("gsi2*"),
+            crate::dynamo_item! { "gsi2pk": "c" }
+        );
+        assert_eq!(item.len(), 3);
+
+        assert_eq!(
+            item.remove_matching("gsi*"),
+            crate::dynamo_item! { "gsi1pk": "b", "gsi2pk": "c" }
+        );
+        assert_eq!(item, crate::dynamo_item! { "pk": "a" });
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn invalid_values() {
+        let n = |n: &str| String::from(n);
+        for (value, problem) in [
+            (
+                AttributeValue::N(n("1e-131")),
+                Some("Number `1e-131` can't be stored in DynamoDB: its magnitude is outside 1E-130 to 1E+126"),
+            ),
+            (AttributeValue::N(n("1.5")), None),
+            (
+                AttributeValue::Ns(vec![]),
+                Some("Invalid NS attribute value: the set is empty"),
+            ),
+            (
+                AttributeValue::Ns(vec![n("1"), n("1.0")]),
+                Some("Invalid NS attribute value: member 1 is a duplicate"),
+            ),
+            (
+                AttributeValue::Ns(vec![n("1"), n("x")]),
+                Some("Invalid NS attribute value: member 1 is not a valid number"),
+            ),
+            (AttributeValue::Ss(vec![n("a"), n("A")]), None),
+            (AttributeValue::L(vec![]), None),
+        ] {
+            let err = value.debug_check_valid().err().map(|err| err.to_string());
+            assert_eq!(err.as_deref(), problem, "{value:?}");
+        }
+    }
 }
@@ -0,0 +1,178 @@
+//! Grouping items into `BatchWriteItem` requests
+//!
+//! A `BatchWriteItem` request holds at most 25 writes and 16 MB. Splitting items into groups of
+//! 25 still trips the size limit when items are large. [`Batching::batch`] groups items by both
+//! limits, using the estimated size of each item, and reports the size of each group.
+//!
+//! ```
+//! use serde_dynamo::batch::Batching;
+//! # use serde_derive::Serialize;
+//!
+//! #[derive(Serialize)]
+//! struct Upload {
+//!     id: u32,
+//!     data: String,
+//! }
+//!
+//! let uploads = (0..30).map(|id| Upload { id, data: "x".repeat(1000) });
+//!
+//! let batches = Batching::new().max_bytes(10_000).batch_values(uploads)?;
+//! assert_eq!(batches.len(), 4);
+//! assert!(batches.iter().all(|batch| batch.items.len() <= 25 && batch.size <= 10_000));
+//! assert_eq!(batches.iter().map(|batch| batch.items.len()).sum::<usize>(), 30);
+//! # Ok::<(), serde_dynamo::Error>(())
+//! ```
+
+use crate::{chunking::MAX_ITEM_SIZE, error::ErrorImpl, Item, Result};
+use serde::Serialize;
+
+/// The most writes DynamoDB accepts in one `BatchWriteItem` request
+pub const MAX_BATCH_ITEMS: usize = 25;
+
+/// The largest `BatchWriteItem` request DynamoDB accepts, in bytes
+pub const MAX_BATCH_SIZE: usize = 16 * 1024 * 1024;
+
+/// Groups items into batches that fit in one `BatchWriteItem` request each
+///
+/// See the [module documentation][crate::batch] for more information.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Batching {
+    max_items: usize,
+    max_bytes: usize,
+}
+
+impl Default for Batching {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Batching {
+    /// Batch up to [`MAX_BATCH_ITEMS`] items and [`MAX_BATCH_SIZE`] bytes
+    pub fn new() -> Self {
+        Self {
+            max_items: MAX_BATCH_ITEMS,
+            max_bytes: MAX_BATCH_SIZE,
+        }
+    }
+
+    /// Put at most `max_items` items in each batch
+    pub fn max_items(mut self, max_items: usize) -> Self {
+        self.max_items = max_items;
+        self
+    }
+
+    /// Keep each batch at or under `bytes`, as estimated by [`Item::size`]
+    ///
+    /// The estimate doesn't include the table names and request structure, so leave some
+    /// headroom below [`MAX_BATCH_SIZE`] for them.
+    pub fn max_bytes(mut self, bytes: usize) -> Self {
+        self.max_bytes = bytes;
+        self
+    }
+
+    /// Group `items` into batches, keeping their order
+    ///
+    /// Fails if an item is bigger than [`MAX_ITEM_SIZE`] or than a whole batch may be, since
+    /// DynamoDB would reject it however it is batched.
+    pub fn batch<I>(&self, items: I) -> Result<Vec<Batch>>
+    where
+        I: IntoIterator<Item = Item>,
+    {
+        let max_item_size = MAX_ITEM_SIZE.min(self.max_bytes);
+        let mut batches = Vec::new();
+        let mut current = Batch::default();
+        for item in items {
+            let size = item.size();
+            if size > max_item_size {
+                return Err(ErrorImpl::ItemTooLarge(size, max_item_size).into());
+            }
+            let full =
+                current.items.len() >= self.max_items || current.size + size > self.max_bytes;
+            if full && !current.items.is_empty() {
+                batches.push(std::mem::take(&mut current));
+            }
+            current.size += size;
+            current.items.push(item);
+        }
+        if !current.items.is_empty() {
+            batches.push(current);
+        }
+        Ok(batches)
+    }
+
+    /// Serialize `values` into items and group them into batches
+    ///
+    /// See [`batch`](Self::batch).
+    pub fn batch_values<I>(&self, values: I) -> Result<Vec<Batch>>
+    where
+        I: IntoIterator,
+        I::Item: Serialize,
+    {
+        let items = values
+            .into_iter()
+            .map(crate::to_item)
+            .collect::<Result<Vec<Item>>>()?;
+        self.batch(items)
+    }
+}
+
+/// Items that fit in one `BatchWriteItem` request
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Batch {
+    /// The items, in their original order
+    pub items: Vec<Item>,
+    /// The estimated size of the items, in bytes
+    pub size: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Batching, MAX_BATCH_ITEMS};
+    use crate::{dynamo_item, Item};
+
+    fn item_of_size(size: usize) -> Item {
+        // "d" plus the string
+        dynamo_item! { "d": "x".repeat(size - 1) }
+    }
+
+    #[test]
+    fn respects_both_limits() {
+        let small = (0..60).map(|_| item_of_size(10));
+        let batches = Batching::new().batch(small).unwrap();
+        assert_eq!(
+            batches
+                .iter()
+                .map(|batch| batch.items.len())
+                .collect::<Vec<_>>(),
+            [MAX_BATCH_ITEMS, MAX_BATCH_ITEMS, 10]
+        );
+        assert!(batches
+            .iter()
+            .all(|batch| batch.size == batch.items.len() * 10));
+
+        let sizes = [400, 300, 300, 100, 1000, 1];
+        let batches = Batching::new()
+            .max_bytes(1000)
+            .batch(sizes.map(item_of_size))
+            .unwrap();
+        assert_eq!(
+            batches.iter().map(|batch| batch.size).collect::<Vec<_>>(),
+            [1000, 100, 1000, 1]
+        );
+    }
+
+    #[test]
+    fn rejects_items_that_never_fit() {
+        assert!(Batching::new().batch(Vec::new()).unwrap().is_empty());
+
+        let err = Batching::new()
+            .max_bytes(100)
+            .batch([item_of_size(50), item_of_size(101)])
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Item of 101 bytes is larger than the maximum of 100 bytes"
+        );
+    }
+}
@@ -8,10 +8,28 @@
 //! be beneficial to additionally annotate the field with `#[serde(default)]`
 //! and `#[serde(skip_serializing_if = "<empty check>")]`. This will make sure
 //! that the field is omitted when empty.
+//! For an `Option` field, the [`optional`] codec does both: it leaves out a
+//! set that is `None` or empty.
 //!
-//! This serializer does not check for duplicate values or an empty set.
-//! If the set contains duplicate values or is empty, DynamoDB will return a
-//! validation error when the attribute value is used.
+//! In release builds, this serializer does not check for duplicate values or
+//! an empty set. If the set contains duplicate values or is empty, DynamoDB
+//! will return a validation error when the attribute value is used. Builds
+//! with debug assertions fail with
+//! [`ErrorCode::InvalidSet`](crate::ErrorCode::InvalidSet) instead, where the
+//! set is serialized.
+//!
+//! # Containers
+//!
+//! The element order and membership are taken from whatever the value serializes as a sequence:
+//! `Vec`, `VecDeque`, `BinaryHeap`, `HashSet`, `BTreeSet`, arrays, and slices all work, for the
+//! field codec as well as the [`BinarySet`] wrapper. An iterator can be collected straight into a
+//! wrapper, as in `iter.collect::<BinarySet<Vec<_>>>()`.
+//!
+//! Nothing here deduplicates. A `HashSet<ByteBuf>` or `BTreeSet<ByteBuf>` can't hold duplicates in
+//! the first place, so it's the simplest way to always produce a valid set; a `Vec` or `VecDeque`
+//! must be kept unique by the caller. Only `serde_bytes` types and other types that serialize as
+//! bytes are binaries; a plain `Vec<u8>` serializes as a list of numbers. Reading a set back into a
+//! `HashSet` or `BTreeSet` can't produce duplicates either.
 //!
 //! # Errors
 //!
@@ -52,7 +70,7 @@ pub(super) static NEWTYPE_SYMBOL: &str = "\u{037E}BYTESSET\u{037E}";
 
 #[inline]
 pub(crate) fn should_serialize_as_binary_set(name: &str) -> bool {
-    std::ptr::eq(name, NEWTYPE_SYMBOL)
+    std::ptr::eq(name, NEWTYPE_SYMBOL) || optional::should_serialize_as_optional_set(name)
 }
 
 /// Serializes the given value as a binary set
@@ -119,6 +137,12 @@ where
     }
 }
 
+impl<A> FromIterator<A> for BinarySet<Vec<A>> {
+    fn from_iter<I: IntoIterator<Item = A>>(iter: I) -> Self {
+        BinarySet(iter.into_iter().collect())
+    }
+}
+
 #[inline(never)]
 pub(crate) fn convert_to_set(value: crate::AttributeValue) -> crate::Result<crate::AttributeValue> {
     let vals = match value {
@@ -137,7 +161,9 @@ pub(crate) fn convert_to_set(value: crate::AttributeValue) -> crate::Result<crat
         })
         .collect::<Result<_, _>>()?;
 
-    Ok(crate::AttributeValue::Bs(set))
+    let set = crate::AttributeValue::Bs(set);
+    set.debug_check_valid()?;
+    Ok(set)
 }
 
 #[cfg(test)]
@@ -180,4 +206,117 @@ mod tests {
             crate::AttributeValue::Bs(vec![b"test".to_vec(), b"test2".to_vec(),])
         );
     }
+
+    #[test]
+    fn std_containers_and_iterators() {
+        use serde_bytes::ByteBuf;
+        use std::collections::{BTreeSet, BinaryHeap, HashSet, VecDeque};
+
+        fn members<T: serde::Serialize>(set: BinarySet<T>) -> Vec<Vec<u8>> {
+            match crate::to_attribute_value(set).unwrap() {
+                crate::AttributeValue::Bs(mut members) => {
+                    members.sort();
+                    members
+                }
+                other => panic!("expected a binary set, got {other:?}"),
+            }
+        }
+
+        let a = ByteBuf::from(b"a".to_vec());
+        let b = ByteBuf::from(b"b".to_vec());
+        let expected = vec![b"a".to_vec(), b"b".to_vec()];
+        assert_eq!(
+            members(BinarySet(VecDeque::from([b.clone(), a.clone()]))),
+            expected
+        );
+        assert_eq!(
+            members(BinarySet(BinaryHeap::from([b.clone(), a.clone()]))),
+            expected
+        );
+        assert_eq!(
+            members(BinarySet(HashSet::from([b.clone(), a.clone()]))),
+            expected
+        );
+        assert_eq!(
+            members(BinarySet(BTreeSet::from([b.clone(), a.clone()]))),
+            expected
+        );
+        assert_eq!(members(BinarySet([b.clone(), a.clone()])), expected);
+
+        let collected: BinarySet<Vec<_>> = [b, a].into_iter().collect();
+        assert_eq!(members(collected), expected);
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Struct {
+            #[serde(with = "crate::binary_set")]
+            set: BTreeSet<ByteBuf>,
+        }
+
+        let value = Struct {
+            set: expected.iter().cloned().map(ByteBuf::from).collect(),
+        };
+        let item: crate::Item = crate::to_item(&value).unwrap();
+        assert_eq!(item["set"], crate::AttributeValue::Bs(expected));
+        assert_eq!(crate::from_item::<_, Struct>(item).unwrap(), value);
+    }
+}
+
+/// Serializer codec for an optional binary set that may be empty
+///
+/// To use, annotate an `Option` field with
+/// `#[serde(default, with = "serde_dynamo::binary_set::optional")]`.
+///
+/// DynamoDB rejects empty sets, so `None` and an empty set are both left out of the item, or
+/// written as `NULL` with [`EmptySetPolicy::Null`](crate::EmptySetPolicy::Null). Both read back as
+/// `None`, as does a missing attribute thanks to `#[serde(default)]`. Other serializers see `None`
+/// as an empty sequence.
+///
+/// ```
+/// use serde_derive::{Serialize, Deserialize};
+/// use serde_dynamo::{AttributeValue, Item};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct MyStruct {
+///     #[serde(default, with = "serde_dynamo::binary_set::optional")]
+///     hashes: Option<Vec<serde_bytes::ByteBuf>>,
+/// }
+///
+/// let item: Item = serde_dynamo::to_item(MyStruct { hashes: None }).unwrap();
+/// assert!(item.is_empty());
+/// let my_struct: MyStruct = serde_dynamo::from_item(item).unwrap();
+/// assert_eq!(my_struct, MyStruct { hashes: None });
+///
+/// let item: Item = serde_dynamo::to_item(MyStruct { hashes: Some(vec![serde_bytes::ByteBuf::from(b"a".to_vec())]) }).unwrap();
+/// assert_eq!(item["hashes"], AttributeValue::Bs(vec![b"a".to_vec()]));
+/// ```
+pub mod optional {
+    pub(super) static NEWTYPE_SYMBOL: &str = "\u{037E}OPTIONALBYTESSET\u{037E}";
+
+    #[inline]
+    pub(crate) fn should_serialize_as_optional_set(name: &str) -> bool {
+        std::ptr::eq(name, NEWTYPE_SYMBOL)
+    }
+
+    /// Serializes the given value as a binary set, or leaves it out if it is `None` or empty
+    ///
+    /// See the [module documentation][crate::binary_set::optional] for additional usage information.
+    pub fn serialize<T, S>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: serde::Serialize,
+        S: serde::Serializer,
+    {
+        match value {
+            Some(value) => serializer.serialize_newtype_struct(NEWTYPE_SYMBOL, value),
+            None => serializer.serialize_newtype_struct(NEWTYPE_SYMBOL, &[(); 0]),
+        }
+    }
+
+    /// Deserializes the given value as an optional set
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        T: serde::Deserialize<'de>,
+        D: serde::Deserializer<'de>,
+    {
+        <Option<T> as serde::Deserialize>::deserialize(deserializer)
+    }
 }
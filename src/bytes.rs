@@ -0,0 +1,67 @@
+//! Support for the `bytes` crate
+//!
+//! `Bytes` and `BytesMut` are stored as `B` attributes. Reading them hands the decoded buffer
+//! over instead of copying it, and converting a uniquely owned `Bytes` into an [`AttributeValue`]
+//! reuses its allocation.
+
+use crate::AttributeValue;
+use __bytes::{Bytes, BytesMut};
+
+impl From<Bytes> for AttributeValue {
+    fn from(bytes: Bytes) -> Self {
+        AttributeValue::B(bytes.into())
+    }
+}
+
+impl From<BytesMut> for AttributeValue {
+    fn from(bytes: BytesMut) -> Self {
+        AttributeValue::B(bytes.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Bytes, BytesMut};
+    use crate::{from_item, to_item, AttributeValue, Item};
+    use serde_derive::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Payload {
+        id: String,
+        body: Bytes,
+        scratch: BytesMut,
+        preview: Option<Bytes>,
+    }
+
+    #[test]
+    fn round_trips_bytes_fields() {
+        let payload = Payload {
+            id: String::from("p-1"),
+            body: Bytes::from_static(&[0, 1, 2, 255]),
+            scratch: BytesMut::from(&b"abc"[..]),
+            preview: None,
+        };
+        let item: Item = to_item(&payload).unwrap();
+        assert_eq!(item["body"], AttributeValue::B(vec![0, 1, 2, 255]));
+        assert_eq!(item["scratch"], AttributeValue::B(b"abc".to_vec()));
+
+        let read: Payload = from_item(item).unwrap();
+        assert_eq!(read, payload);
+    }
+
+    #[test]
+    fn converts_into_attribute_values() {
+        let bytes = Bytes::from(vec![1, 2, 3]);
+        let pointer = bytes.as_ptr();
+        let AttributeValue::B(vec) = AttributeValue::from(bytes) else {
+            panic!("expected binary");
+        };
+        assert_eq!(vec, [1, 2, 3]);
+        assert_eq!(vec.as_ptr(), pointer);
+
+        assert_eq!(
+            AttributeValue::from(BytesMut::from(&b"xy"[..])),
+            AttributeValue::B(b"xy".to_vec())
+        );
+    }
+}
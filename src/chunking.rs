@@ -0,0 +1,656 @@
+//! Storing values too big for a single item
+//!
+//! DynamoDB rejects items over 400 KB. [`Chunking::split`] serializes a value, and if the item
+//! would be too big, encodes it as binary and spreads it over continuation items that share its
+//! partition key. [`Chunking::join`] reassembles the original value from those items.
+//!
+//! # Layout
+//!
+//! A value that fits is stored as a single ordinary item with the given sort key, so small values
+//! cost nothing extra. Otherwise the item with the given sort key is a head item holding only the
+//! keys and the attributes `total_parts` and `generation`. Each continuation item has the sort key
+//! `{sort_key}#part-{N}`, counting from 1, the attributes `part`, `total_parts`, and
+//! `generation`, and a slice of the encoded value in the binary `payload` attribute.
+//!
+//! The generation is a fresh id for every call to `split`, so parts left over from an earlier
+//! split are never mistaken for the current value: `join` only uses the parts whose generation
+//! matches the head, and when the head is a whole item it ignores parts altogether.
+//!
+//! The concatenated payload starts with a format version byte, currently `1`, followed by the
+//! encoded map of attributes. Each value in it is a type byte (`0` N, `1` S, `2` BOOL, `3` B,
+//! `4` NULL, `5` M, `6` L, `7` SS, `8` NS, `9` BS) followed by its contents. BOOL and NULL are one
+//! byte; strings, numbers, and binary are a length and their bytes; maps are a length and
+//! name/value pairs, with names encoded like strings; lists and sets are a length and their
+//! members. All lengths are little-endian `u32`s. `join` rejects versions it doesn't know, so a
+//! future change to the encoding gets a new version rather than misreading stored data.
+//!
+//! # Reading and writing
+//!
+//! [`split`](Chunking::split) returns the head item last. Write the items in order, or in a
+//! transaction when there are few enough, so that the head only points to the new generation
+//! once all of its parts are stored. To read, get the head item and query the partition key for
+//! sort keys beginning with `{sort_key}#part-`, and pass all of them to `join`.
+//!
+//! # Cleaning up
+//!
+//! Rewriting a value into fewer parts, or into a single item, leaves the old parts behind, as
+//! does a split whose head was never written. `join` ignores them, but they still take up
+//! storage. After writing, read the items back as above and delete the keys
+//! [`stale_parts`](Chunking::stale_parts) returns.
+//!
+//! ```
+//! # use serde_derive::{Deserialize, Serialize};
+//! use serde_dynamo::chunking::Chunking;
+//! use serde_dynamo::AttributeValue;
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Document {
+//!     title: String,
+//!     body: String,
+//! }
+//!
+//! let document = Document {
+//!     title: String::from("Mostly Harmless"),
+//!     body: "Don't panic. ".repeat(200),
+//! };
+//!
+//! let chunking = Chunking::new("pk", "sk").max_size(1024);
+//! let pk = AttributeValue::S(String::from("doc-1"));
+//! let items = chunking.split(&document, pk, "v1")?;
+//! assert_eq!(items.len(), 4);
+//! assert_eq!(items[0]["sk"], AttributeValue::S(String::from("v1#part-1")));
+//! assert_eq!(items[3]["sk"], AttributeValue::S(String::from("v1")));
+//! assert!(items.iter().all(|item| item.size() <= 1024));
+//!
+//! let joined: Document = chunking.join(items)?;
+//! assert_eq!(joined, document);
+//! # Ok::<(), serde_dynamo::Error>(())
+//! ```
+
+use crate::{error::ErrorImpl, AttributeValue, Item, Result};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The largest item DynamoDB stores, in bytes
+pub const MAX_ITEM_SIZE: usize = 400 * 1024;
+
+const PART: &str = "part";
+const TOTAL_PARTS: &str = "total_parts";
+const GENERATION: &str = "generation";
+const PAYLOAD: &str = "payload";
+
+/// The version of the payload encoding, written as its first byte
+const FORMAT_VERSION: u8 = 1;
+
+/// Splits values over several items, and joins them back together
+///
+/// See the [module documentation][crate::chunking] for more information.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chunking {
+    partition_key: String,
+    sort_key: String,
+    max_size: usize,
+}
+
+impl Chunking {
+    /// Chunk items of a table with the given partition and sort key attributes, keeping each item
+    /// under [`MAX_ITEM_SIZE`]
+    pub fn new(partition_key: impl Into<String>, sort_key: impl Into<String>) -> Self {
+        Self {
+            partition_key: partition_key.into(),
+            sort_key: sort_key.into(),
+            max_size: MAX_ITEM_SIZE,
+        }
+    }
+
+    /// Keep each item at or under `bytes`, as estimated by [`Item::size`]
+    ///
+    /// Leave headroom below [`MAX_ITEM_SIZE`] for attributes added to the items after splitting.
+    pub fn max_size(mut self, bytes: usize) -> Self {
+        self.max_size = bytes;
+        self
+    }
+
+    /// Serialize `value` into one item, or into continuation items and a head item if it doesn't
+    /// fit
+    ///
+    /// Every item returned has the partition key `partition_key`. The last item is the whole
+    /// value or the head, with the sort key `sort_key`, and any items before it are continuation
+    /// items with the sort keys `{sort_key}#part-{N}`.
+    pub fn split<T>(
+        &self,
+        value: &T,
+        partition_key: AttributeValue,
+        sort_key: &str,
+    ) -> Result<Vec<Item>>
+    where
+        T: Serialize + ?Sized,
+    {
+        let mut item: Item = crate::to_item(value)?;
+        let mut whole = item.clone();
+        whole.insert(self.partition_key.clone(), partition_key.clone());
+        whole.insert(
+            self.sort_key.clone(),
+            AttributeValue::S(sort_key.to_string()),
+        );
+        if whole.size() <= self.max_size {
+            return Ok(vec![whole]);
+        }
+
+        let mut payload = vec![FORMAT_VERSION];
+        encode_map(std::mem::take(item.inner_mut()), &mut payload);
+
+        let generation = AttributeValue::S(generation());
+        let key = |sort_key: String| {
+            Item::from(HashMap::from([
+                (self.partition_key.clone(), partition_key.clone()),
+                (self.sort_key.clone(), AttributeValue::S(sort_key)),
+                (String::from(GENERATION), generation.clone()),
+            ]))
+        };
+
+        // The size of a part without its payload, with room for the longest part numbers
+        let part = |n: String, payload: Vec<u8>| {
+            let mut item = key(format!("{sort_key}#part-{n}"));
+            item.insert(String::from(PART), AttributeValue::N(n.clone()));
+            item.insert(String::from(TOTAL_PARTS), AttributeValue::N(n));
+            item.insert(String::from(PAYLOAD), AttributeValue::B(payload));
+            item
+        };
+        let longest = "9".repeat(payload.len().to_string().len());
+        let overhead = part(longest, Vec::new()).size();
+        let capacity = self.max_size.saturating_sub(overhead);
+        if capacity == 0 {
+            return Err(ErrorImpl::ChunkSizeTooSmall(self.max_size).into());
+        }
+
+        let total = AttributeValue::N(payload.len().div_ceil(capacity).to_string());
+        let mut items: Vec<Item> = payload
+            .chunks(capacity)
+            .enumerate()
+            .map(|(index, chunk)| {
+                let mut item = part((index + 1).to_string(), chunk.to_vec());
+                item.insert(String::from(TOTAL_PARTS), total.clone());
+                item
+            })
+            .collect();
+
+        let mut head = key(sort_key.to_string());
+        head.insert(String::from(TOTAL_PARTS), total);
+        items.push(head);
+        Ok(items)
+    }
+
+    /// Deserialize a value from the head item and continuation items [`split`](Self::split)
+    /// produced, in any order
+    ///
+    /// Parts from other generations are ignored. Fails if the head is missing, or the parts of
+    /// its generation are missing or duplicated.
+    pub fn join<I, T>(&self, items: I) -> Result<T>
+    where
+        I: IntoIterator<Item = Item>,
+        T: DeserializeOwned,
+    {
+        let (head, parts) = self.head_and_parts(items)?;
+        let Some((generation, total)) = chunked(&head)? else {
+            return crate::from_item(head);
+        };
+
+        let mut parts: Vec<(usize, &[u8])> = parts
+            .iter()
+            .filter(|part| part.get(GENERATION) == Some(generation))
+            .map(|part| {
+                let number: usize = part.get_as(PART)?;
+                if part.get_as::<usize>(TOTAL_PARTS)? != total {
+                    return Err(invalid("parts disagree on the number of parts"));
+                }
+                let Some(AttributeValue::B(payload)) = part.get(PAYLOAD) else {
+                    return Err(invalid(format!("part {number} has no binary `{PAYLOAD}`")));
+                };
+                Ok((number, payload.as_slice()))
+            })
+            .collect::<Result<_>>()?;
+        parts.sort_unstable_by_key(|&(part, _)| part);
+
+        if !parts.iter().map(|&(part, _)| part).eq(1..=total) {
+            return Err(invalid(format!(
+                "expected parts 1 to {total}, found {:?}",
+                parts.iter().map(|&(part, _)| part).collect::<Vec<_>>()
+            )));
+        }
+
+        let payload: Vec<u8> = parts
+            .iter()
+            .flat_map(|&(_, payload)| payload)
+            .copied()
+            .collect();
+        let Some((&version, mut input)) = payload.split_first() else {
+            return Err(invalid("the payload is empty"));
+        };
+        if version != FORMAT_VERSION {
+            return Err(invalid(format!(
+                "unsupported payload format version {version}"
+            )));
+        }
+        let map = decode_map(&mut input)
+            .filter(|_| input.is_empty())
+            .ok_or_else(|| invalid("the payload is corrupt"))?;
+        crate::from_item(Item::from(map))
+    }
+
+    /// The keys of the continuation items that [`join`](Self::join) would ignore
+    ///
+    /// Takes the same items as `join`. Delete the returned keys to clean up parts left over from
+    /// earlier or unfinished splits.
+    pub fn stale_parts<I>(&self, items: I) -> Result<Vec<Item>>
+    where
+        I: IntoIterator<Item = Item>,
+    {
+        let (head, parts) = self.head_and_parts(items)?;
+        let generation = chunked(&head)?.map(|(generation, _)| generation);
+        Ok(parts
+            .into_iter()
+            .filter(|part| generation.is_none() || part.get(GENERATION) != generation)
+            .map(|mut part| {
+                part.retain(|name, _| *name == self.partition_key || *name == self.sort_key);
+                part
+            })
+            .collect())
+    }
+
+    /// Separate the head item, which has the shortest sort key, from the continuation items
+    fn head_and_parts<I>(&self, items: I) -> Result<(Item, Vec<Item>)>
+    where
+        I: IntoIterator<Item = Item>,
+    {
+        let mut items = items
+            .into_iter()
+            .map(|item| Ok((item.get_as::<String>(&self.sort_key)?, item)))
+            .collect::<Result<Vec<_>>>()?;
+        items.sort_unstable_by_key(|(sort_key, _)| sort_key.len());
+
+        let mut items = items.into_iter();
+        let Some((head_key, head)) = items.next() else {
+            return Err(invalid("there are no items"));
+        };
+        if head.contains_key(PART) && head.contains_key(PAYLOAD) {
+            return Err(invalid("the head item is missing"));
+        }
+        let prefix = format!("{head_key}#part-");
+        let parts = items
+            .map(|(sort_key, part)| {
+                if sort_key.starts_with(&prefix) {
+                    Ok(part)
+                } else {
+                    Err(invalid(format!(
+                        "`{sort_key}` is not a part of `{head_key}`"
+                    )))
+                }
+            })
+            .collect::<Result<_>>()?;
+        Ok((head, parts))
+    }
+}
+
+/// The generation and number of parts of a head item, or `None` for a whole item
+fn chunked(head: &Item) -> Result<Option<(&AttributeValue, usize)>> {
+    match head.get(GENERATION) {
+        Some(generation) if head.len() == 4 && head.contains_key(TOTAL_PARTS) => {
+            Ok(Some((generation, head.get_as(TOTAL_PARTS)?)))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// A new generation id, unique to this process and ordered by time
+fn generation() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos());
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+    format!("{nanos:x}-{count:x}")
+}
+
+fn invalid(reason: impl Into<String>) -> crate::Error {
+    ErrorImpl::InvalidChunks(reason.into()).into()
+}
+
+// The payload encoding is described in the module documentation.
+
+fn encode_len(len: usize, out: &mut Vec<u8>) {
+    out.extend_from_slice(&(len as u32).to_le_bytes());
+}
+
+fn encode_bytes(bytes: &[u8], out: &mut Vec<u8>) {
+    encode_len(bytes.len(), out);
+    out.extend_from_slice(bytes);
+}
+
+fn encode_map(map: HashMap<String, AttributeValue>, out: &mut Vec<u8>) {
+    encode_len(map.len(), out);
+    for (name, value) in map {
+        encode_bytes(name.as_bytes(), out);
+        encode(value, out);
+    }
+}
+
+fn encode(value: AttributeValue, out: &mut Vec<u8>) {
+    match value {
+        AttributeValue::N(n) => {
+            out.push(0);
+            encode_bytes(n.as_bytes(), out);
+        }
+        AttributeValue::S(s) => {
+            out.push(1);
+            encode_bytes(s.as_bytes(), out);
+        }
+        AttributeValue::Bool(b) => out.extend_from_slice(&[2, u8::from(b)]),
+        AttributeValue::B(b) => {
+            out.push(3);
+            encode_bytes(&b, out);
+        }
+        AttributeValue::Null(null) => out.extend_from_slice(&[4, u8::from(null)]),
+        AttributeValue::M(m) => {
+            out.push(5);
+            encode_map(m, out);
+        }
+        AttributeValue::L(l) => {
+            out.push(6);
+            encode_len(l.len(), out);
+            l.into_iter().for_each(|value| encode(value, out));
+        }
+        AttributeValue::Ss(ss) => {
+            out.push(7);
+            encode_len(ss.len(), out);
+            ss.iter().for_each(|s| encode_bytes(s.as_bytes(), out));
+        }
+        AttributeValue::Ns(ns) => {
+            out.push(8);
+            encode_len(ns.len(), out);
+            ns.iter().for_each(|n| encode_bytes(n.as_bytes(), out));
+        }
+        AttributeValue::Bs(bs) => {
+            out.push(9);
+            encode_len(bs.len(), out);
+            bs.iter().for_each(|b| encode_bytes(b, out));
+        }
+    }
+}
+
+fn take<'a>(input: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+    if input.len() < len {
+        return None;
+    }
+    let (taken, rest) = input.split_at(len);
+    *input = rest;
+    Some(taken)
+}
+
+fn decode_len(input: &mut &[u8]) -> Option<usize> {
+    let bytes = take(input, 4)?.try_into().ok()?;
+    Some(u32::from_le_bytes(bytes) as usize)
+}
+
+fn decode_bytes(input: &mut &[u8]) -> Option<Vec<u8>> {
+    let len = decode_len(input)?;
+    take(input, len).map(<[u8]>::to_vec)
+}
+
+fn decode_string(input: &mut &[u8]) -> Option<String> {
+    String::from_utf8(decode_bytes(input)?).ok()
+}
+
+/// Decode `len` members, without trusting `len` for the initial allocation
+fn decode_members<T>(
+    input: &mut &[u8],
+    decode: impl Fn(&mut &[u8]) -> Option<T>,
+) -> Option<Vec<T>> {
+    let len = decode_len(input)?;
+    let mut members = Vec::with_capacity(len.min(input.len()));
+    for _ in 0..len {
+        members.push(decode(input)?);
+    }
+    Some(members)
+}
+
+fn decode_map(input: &mut &[u8]) -> Option<HashMap<String, AttributeValue>> {
+    let entries = decode_members(input, |input| Some((decode_string(input)?, decode(input)?)))?;
+    Some(entries.into_iter().collect())
+}
+
+fn decode(input: &mut &[u8]) -> Option<AttributeValue> {
+    let value = match take(input, 1)?[0] {
+        0 => AttributeValue::N(decode_string(input)?),
+        1 => AttributeValue::S(decode_string(input)?),
+        2 => AttributeValue::Bool(take(input, 1)?[0] != 0),
+        3 => AttributeValue::B(decode_bytes(input)?),
+        4 => AttributeValue::Null(take(input, 1)?[0] != 0),
+        5 => AttributeValue::M(decode_map(input)?),
+        6 => AttributeValue::L(decode_members(input, decode)?),
+        7 => AttributeValue::Ss(decode_members(input, decode_string)?),
+        8 => AttributeValue::Ns(decode_members(input, decode_string)?),
+        9 => AttributeValue::Bs(decode_members(input, decode_bytes)?),
+        _ => return None,
+    };
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dynamo_item;
+    use serde_derive::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Document {
+        id: String,
+        body: String,
+        tags: Vec<String>,
+    }
+
+    fn document() -> Document {
+        Document {
+            id: String::from("doc-1"),
+            body: "x".repeat(5000),
+            tags: vec![String::from("a"), String::from("b")],
+        }
+    }
+
+    #[test]
+    fn encodes_every_type() {
+        let item = dynamo_item! {
+            "count": 42,
+            "flags": { "draft": true, "deleted": null },
+            "tags": ["a", 1, [2.5]],
+            "bytes": b(&[0, 1, 2, 255]),
+            "ss": ss["a", "b"],
+            "ns": ns[1, -2.5],
+            "bs": bs[&[1][..], &[2, 3][..]],
+        };
+        let mut payload = Vec::new();
+        encode_map(item.clone().into_inner(), &mut payload);
+
+        let mut input = payload.as_slice();
+        assert_eq!(decode_map(&mut input).map(Item::from), Some(item));
+        assert!(input.is_empty());
+
+        for len in 0..payload.len() {
+            assert_eq!(decode_map(&mut &payload[..len]), None);
+        }
+    }
+
+    #[test]
+    fn round_trips_in_any_order() {
+        let chunking = Chunking::new("pk", "sk").max_size(1000);
+        let document = document();
+
+        let mut items = chunking
+            .split(&document, AttributeValue::S(String::from("p")), "doc")
+            .unwrap();
+        let head = items.pop().unwrap();
+        let total = items.len();
+        assert!(total > 1);
+        assert_eq!(head.len(), 4);
+        assert_eq!(head["sk"], AttributeValue::S(String::from("doc")));
+        assert_eq!(head.get_as::<usize>("total_parts").unwrap(), total);
+        for (index, part) in items.iter().enumerate() {
+            assert!(part.size() <= 1000);
+            assert_eq!(part["pk"], AttributeValue::S(String::from("p")));
+            assert_eq!(part["generation"], head["generation"]);
+            assert_eq!(part.get_as::<usize>("part").unwrap(), index + 1);
+            assert_eq!(part.get_as::<usize>("total_parts").unwrap(), total);
+            assert_eq!(
+                part.get_as::<String>("sk").unwrap(),
+                format!("doc#part-{}", index + 1)
+            );
+        }
+        let AttributeValue::B(payload) = &items[0]["payload"] else {
+            panic!("the payload isn't binary");
+        };
+        assert_eq!(payload[0], FORMAT_VERSION);
+
+        items.reverse();
+        items.insert(1, head);
+        let joined: Document = chunking.join(items).unwrap();
+        assert_eq!(joined, document);
+    }
+
+    #[test]
+    fn small_values_stay_whole() {
+        let chunking = Chunking::new("pk", "sk");
+        let document = document();
+        let items = chunking
+            .split(&document, AttributeValue::N(String::from("7")), "s")
+            .unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0]["sk"], AttributeValue::S(String::from("s")));
+        assert!(!items[0].contains_key("part"));
+        assert!(!items[0].contains_key("generation"));
+
+        let joined: Document = chunking.join(items).unwrap();
+        assert_eq!(joined, document);
+    }
+
+    #[test]
+    fn ignores_stale_parts() {
+        let chunking = Chunking::new("pk", "sk").max_size(1000);
+        let pk = AttributeValue::S(String::from("p"));
+        let old = chunking.split(&document(), pk.clone(), "doc").unwrap();
+        let old_parts = old.len() - 1;
+
+        // Rewritten into fewer parts, the head and overlapping part numbers are replaced
+        let shorter = Document {
+            body: "y".repeat(2000),
+            ..document()
+        };
+        let new = chunking.split(&shorter, pk.clone(), "doc").unwrap();
+        let new_parts = new.len() - 1;
+        assert!(new_parts < old_parts);
+        let table: Vec<Item> = new
+            .iter()
+            .chain(&old[new_parts..old_parts])
+            .cloned()
+            .collect();
+        assert_eq!(
+            chunking.join::<_, Document>(table.clone()).unwrap(),
+            shorter
+        );
+        let stale: Vec<String> = chunking
+            .stale_parts(table)
+            .unwrap()
+            .iter()
+            .map(|key| {
+                assert_eq!(key.len(), 2);
+                key.get_as("sk").unwrap()
+            })
+            .collect();
+        assert_eq!(
+            stale,
+            (new_parts + 1..=old_parts)
+                .map(|n| format!("doc#part-{n}"))
+                .collect::<Vec<_>>()
+        );
+
+        // Rewritten into a single item, every old part is stale
+        let small = Document {
+            body: String::from("z"),
+            ..document()
+        };
+        let mut table = chunking.split(&small, pk, "doc").unwrap();
+        table.extend_from_slice(&old[..old_parts]);
+        assert_eq!(chunking.join::<_, Document>(table.clone()).unwrap(), small);
+        assert_eq!(chunking.stale_parts(table).unwrap().len(), old_parts);
+    }
+
+    #[test]
+    fn rejects_bad_parts() {
+        let chunking = Chunking::new("pk", "sk").max_size(1000);
+        let items = chunking
+            .split(&document(), AttributeValue::S(String::from("p")), "doc")
+            .unwrap();
+        let total = items.len() - 1;
+
+        let err = chunking
+            .join::<_, Document>(items[1..].to_vec())
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Invalid chunked items: expected parts 1 to {total}, found {:?}",
+                (2..=total).collect::<Vec<_>>()
+            )
+        );
+
+        let err = chunking
+            .join::<_, Document>(items[..total].to_vec())
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid chunked items: the head item is missing"
+        );
+
+        let mut other = items.clone();
+        other[0].insert(String::from("sk"), AttributeValue::S(String::from("doc2")));
+        let err = chunking.join::<_, Document>(other).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid chunked items: `doc2` is not a part of `doc`"
+        );
+
+        let mut corrupt = items.clone();
+        corrupt[total - 1].insert(String::from("payload"), AttributeValue::B(vec![9]));
+        assert!(chunking.join::<_, Document>(corrupt).is_err());
+
+        let mut future = items.clone();
+        let Some(AttributeValue::B(payload)) = future[0].get_mut("payload") else {
+            panic!("the payload isn't binary");
+        };
+        payload[0] = 2;
+        let err = chunking.join::<_, Document>(future).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid chunked items: unsupported payload format version 2"
+        );
+
+        assert!(chunking.join::<_, Document>(Vec::new()).is_err());
+
+        let err = Chunking::new("pk", "sk")
+            .max_size(10)
+            .split(&document(), AttributeValue::S(String::from("p")), "doc")
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "A maximum item size of 10 bytes leaves no room for the chunk payload"
+        );
+    }
+
+    #[test]
+    fn generations_are_unique() {
+        assert_ne!(generation(), generation());
+    }
+}
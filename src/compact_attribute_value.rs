@@ -0,0 +1,154 @@
+use crate::AttributeValue;
+use __compact_str::CompactString;
+use std::collections::HashMap;
+
+/// An [`AttributeValue`] that stores strings inline when they are short
+///
+/// Keys and enum-like values are usually only a few bytes long, but every `S` and `N` in an
+/// [`AttributeValue`] is a separate heap allocation. `CompactAttributeValue` stores strings of
+/// up to 24 bytes (12 on 32-bit targets) inline instead, which shrinks large caches of items.
+///
+/// Converting from an [`AttributeValue`] moves long strings without copying them, and
+/// converting back does the same, so a round trip through the SDK types via `AttributeValue`
+/// only copies the short strings.
+///
+/// ```
+/// use serde_dynamo::{AttributeValue, CompactAttributeValue};
+///
+/// let value: CompactAttributeValue = serde_dynamo::to_attribute_value("open")?;
+/// assert!(matches!(&value, CompactAttributeValue::S(s) if !s.is_heap_allocated()));
+///
+/// let status: String = serde_dynamo::from_attribute_value(value)?;
+/// assert_eq!(status, "open");
+/// # Ok::<(), serde_dynamo::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompactAttributeValue {
+    /// An attribute of type Number. For example:
+    ///
+    /// ```text
+    /// "N": "123.45"
+    /// ```
+    N(CompactString),
+    /// An attribute of type String. For example:
+    ///
+    /// ```text
+    /// "S": "Hello"
+    /// ```
+    S(CompactString),
+    /// An attribute of type Boolean. For example:
+    ///
+    /// ```text
+    /// "BOOL": true
+    /// ```
+    Bool(bool),
+    /// An attribute of type Binary.
+    B(Vec<u8>),
+    /// An attribute of type Null. For example:
+    ///
+    /// ```text
+    /// "NULL": true
+    /// ```
+    Null(bool),
+    /// An attribute of type Map.
+    M(HashMap<CompactString, CompactAttributeValue>),
+    /// An attribute of type List.
+    L(Vec<CompactAttributeValue>),
+    /// An attribute of type String Set.
+    Ss(Vec<CompactString>),
+    /// An attribute of type Number Set.
+    Ns(Vec<CompactString>),
+    /// An attribute of type Binary Set.
+    Bs(Vec<Vec<u8>>),
+}
+
+impl From<AttributeValue> for CompactAttributeValue {
+    fn from(value: AttributeValue) -> Self {
+        match value {
+            AttributeValue::N(n) => CompactAttributeValue::N(n.into()),
+            AttributeValue::S(s) => CompactAttributeValue::S(s.into()),
+            AttributeValue::Bool(b) => CompactAttributeValue::Bool(b),
+            AttributeValue::B(b) => CompactAttributeValue::B(b),
+            AttributeValue::Null(null) => CompactAttributeValue::Null(null),
+            AttributeValue::M(m) => CompactAttributeValue::M(
+                m.into_iter()
+                    .map(|(key, value)| (key.into(), value.into()))
+                    .collect(),
+            ),
+            AttributeValue::L(l) => {
+                CompactAttributeValue::L(l.into_iter().map(Into::into).collect())
+            }
+            AttributeValue::Ss(ss) => {
+                CompactAttributeValue::Ss(ss.into_iter().map(Into::into).collect())
+            }
+            AttributeValue::Ns(ns) => {
+                CompactAttributeValue::Ns(ns.into_iter().map(Into::into).collect())
+            }
+            AttributeValue::Bs(bs) => CompactAttributeValue::Bs(bs),
+        }
+    }
+}
+
+impl From<CompactAttributeValue> for AttributeValue {
+    fn from(value: CompactAttributeValue) -> Self {
+        match value {
+            CompactAttributeValue::N(n) => AttributeValue::N(n.into_string()),
+            CompactAttributeValue::S(s) => AttributeValue::S(s.into_string()),
+            CompactAttributeValue::Bool(b) => AttributeValue::Bool(b),
+            CompactAttributeValue::B(b) => AttributeValue::B(b),
+            CompactAttributeValue::Null(null) => AttributeValue::Null(null),
+            CompactAttributeValue::M(m) => AttributeValue::M(
+                m.into_iter()
+                    .map(|(key, value)| (key.into_string(), value.into()))
+                    .collect(),
+            ),
+            CompactAttributeValue::L(l) => {
+                AttributeValue::L(l.into_iter().map(Into::into).collect())
+            }
+            CompactAttributeValue::Ss(ss) => {
+                AttributeValue::Ss(ss.into_iter().map(CompactString::into_string).collect())
+            }
+            CompactAttributeValue::Ns(ns) => {
+                AttributeValue::Ns(ns.into_iter().map(CompactString::into_string).collect())
+            }
+            CompactAttributeValue::Bs(bs) => AttributeValue::Bs(bs),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_keeps_values() {
+        let long = String::from("a string that is too long to be stored inline");
+        let value = AttributeValue::M(HashMap::from([
+            (String::from("id"), AttributeValue::S(String::from("u-1"))),
+            (String::from("bio"), AttributeValue::S(long.clone())),
+            (
+                String::from("scores"),
+                AttributeValue::Ns(vec![String::from("1"), String::from("2.5")]),
+            ),
+            (
+                String::from("items"),
+                AttributeValue::L(vec![
+                    AttributeValue::Bool(true),
+                    AttributeValue::Null(true),
+                    AttributeValue::B(vec![1, 2]),
+                    AttributeValue::Ss(vec![String::from("x")]),
+                    AttributeValue::Bs(vec![vec![3]]),
+                ]),
+            ),
+        ]));
+
+        let compact = CompactAttributeValue::from(value.clone());
+        let CompactAttributeValue::M(m) = &compact else {
+            panic!("expected a map, found {compact:?}");
+        };
+        assert!(matches!(&m["id"], CompactAttributeValue::S(s) if !s.is_heap_allocated()));
+        assert!(matches!(&m["bio"], CompactAttributeValue::S(s) if s.is_heap_allocated()));
+
+        assert_eq!(AttributeValue::from(compact), value);
+    }
+}
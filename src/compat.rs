@@ -0,0 +1,319 @@
+//! The wire format compatibility contract
+//!
+//! Items written by one version of **serde_dynamo** must be readable by every later version, and
+//! the same Rust value must keep serializing to the same item. Data in DynamoDB routinely
+//! outlives the code that wrote it, and a changed representation breaks key lookups, condition
+//! expressions, and secondary indexes, not just deserialization.
+//!
+//! The representations below are fixed for format version [`FORMAT_VERSION`]. They will only
+//! change in a new major version of the crate, and are checked by a corpus of value/item pairs
+//! in this module's tests. New corpus entries may be added in minor versions; existing entries
+//! are never edited.
+//!
+//! | Rust | DynamoDB |
+//! |------|----------|
+//! | `bool` | `BOOL` |
+//! | integers and floats | `N`, written with [`ToString`], except `-0.0`, which is written as `0` |
+//! | `char`, `String`, `&str` | `S` |
+//! | `()`, unit structs, `None` | `NULL` (`true`) |
+//! | `Some(value)`, newtype structs | the wrapped value |
+//! | `Vec<T>`, slices, tuples, tuple structs | `L` |
+//! | maps with string or integer keys | `M`, integer keys written as decimal strings |
+//! | structs | `M`, one entry per serialized field |
+//! | unit enum variants | `S` holding the variant name |
+//! | newtype, tuple and struct variants | `M` with a single entry from the variant name to the content |
+//! | [`serde_bytes`](https://docs.rs/serde_bytes) values | `B` |
+//! | [`string_set`](crate::string_set), [`number_set`](crate::number_set), [`binary_set`](crate::binary_set) | `SS`, `NS`, `BS` |
+//!
+//! Numbers that DynamoDB can't store, with more than 38 significant digits or a magnitude outside
+//! `1E-130` to `1E+126`, fail to serialize with
+//! [`ErrorCode::UnrepresentableNumber`](crate::ErrorCode::UnrepresentableNumber) instead of being
+//! written as an `N` that DynamoDB rejects. That includes `u128::MAX`, `f64::MAX`, subnormal
+//! floats such as `5e-324`, and non-finite floats. Setting
+//! [`NumberPrecision::Truncate`](crate::NumberPrecision::Truncate) writes the first 38 digits of
+//! long numbers instead.
+//!
+//! Sets must not be empty or hold duplicates. Release builds write such sets as given, and
+//! DynamoDB rejects them when they are used. Builds with debug assertions fail to serialize them
+//! with [`ErrorCode::InvalidSet`](crate::ErrorCode::InvalidSet).
+//!
+//! Options that change the output, such as those on
+//! [`SerializerConfig`](crate::SerializerConfig), are outside this contract until they are
+//! enabled, and each documents its own representation.
+//!
+//! # Items written by other libraries
+//!
+//! Tables are often shared with services in other languages, whose document mappers make
+//! different choices. [`java_mapper`] and [`boto3`] return a
+//! [`DeserializerConfig`] with the leniency options their data needs, which can be adjusted
+//! further with its builder methods. Sets are read as sequences, and lists are read by the
+//! [`string_set`](crate::string_set) and [`number_set`](crate::number_set) codecs, with any
+//! configuration, so a Rust `Vec` can read a Java `Set` or a Python `set`.
+
+use crate::DeserializerConfig;
+
+/// The version of the wire format described in the [module documentation](self)
+pub const FORMAT_VERSION: u32 = 1;
+
+/// A configuration for reading items written by the AWS SDK for Java's `DynamoDBMapper`
+///
+/// Older versions of the mapper store `boolean` fields as `N("1")` and `N("0")`, and tables
+/// shared with Java code commonly hold identifiers and `BigDecimal` values as numbers where Rust
+/// expects strings. This enables [`coerce_bools`](DeserializerConfig::coerce_bools),
+/// [`coerce_strings`](DeserializerConfig::coerce_strings) and
+/// [`lenient_numbers`](DeserializerConfig::lenient_numbers).
+///
+/// ```
+/// use serde_dynamo::{compat, dynamo_item};
+/// # use serde_derive::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Account {
+///     id: String,
+///     active: bool,
+///     roles: Vec<String>,
+/// }
+///
+/// let item = dynamo_item! { "id": 1042, "active": 1, "roles": ss["admin"] };
+///
+/// let account: Account = serde_dynamo::from_item_with_config(item, compat::java_mapper()).unwrap();
+/// assert_eq!(account.id, "1042");
+/// assert!(account.active);
+/// assert_eq!(account.roles, vec!["admin"]);
+/// ```
+pub fn java_mapper() -> DeserializerConfig {
+    DeserializerConfig::new()
+        .coerce_bools(true)
+        .coerce_strings(true)
+        .lenient_numbers(true)
+}
+
+/// A configuration for reading items written by boto3's `TypeSerializer`, as used by its
+/// `Table` resource
+///
+/// Python code tends to store identifiers as numbers where Rust expects strings, so this enables
+/// [`coerce_strings`](DeserializerConfig::coerce_strings). Booleans are written as `BOOL`, so
+/// [`coerce_bools`](DeserializerConfig::coerce_bools) is left disabled. boto3 writes `Decimal`s
+/// as `N`, which DynamoDB normalizes on write, so
+/// [`lenient_numbers`](DeserializerConfig::lenient_numbers) is left disabled too.
+pub fn boto3() -> DeserializerConfig {
+    DeserializerConfig::new().coerce_strings(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        dynamo_item, dynamo_value, from_attribute_value, from_item_with_config, to_attribute_value,
+        AttributeValue,
+    };
+    use serde::de::DeserializeOwned;
+    use serde_derive::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+    use std::fmt::Debug;
+
+    /// Assert that `value` serializes to `expected`, and that `expected` deserializes to `value`
+    #[track_caller]
+    fn assert_wire<T>(value: T, expected: AttributeValue)
+    where
+        T: serde::Serialize + DeserializeOwned + PartialEq + Debug,
+    {
+        let serialized: AttributeValue = to_attribute_value(&value).unwrap();
+        assert_eq!(serialized, expected, "serializing {value:?}");
+        let deserialized: T = from_attribute_value(expected).unwrap();
+        assert_eq!(deserialized, value);
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Unit;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Newtype(u8);
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Tuple(u8, String);
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Struct {
+        id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        skipped: Option<u8>,
+        null: Option<u8>,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Enum {
+        Unit,
+        Newtype(u8),
+        Tuple(u8, bool),
+        Struct { id: u8 },
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Sets {
+        #[serde(with = "crate::string_set")]
+        ss: Vec<String>,
+        #[serde(with = "crate::number_set")]
+        ns: Vec<i32>,
+        #[serde(with = "crate::binary_set")]
+        bs: Vec<serde_bytes::ByteBuf>,
+    }
+
+    #[test]
+    fn format_version_1() {
+        assert_eq!(super::FORMAT_VERSION, 1);
+
+        assert_wire(true, dynamo_value!(true));
+        assert_wire(-7i8, dynamo_value!(-7));
+        assert_wire(
+            u64::MAX,
+            AttributeValue::N(String::from("18446744073709551615")),
+        );
+        assert_wire(
+            i64::MIN,
+            AttributeValue::N(String::from("-9223372036854775808")),
+        );
+        assert_wire(1.5f32, AttributeValue::N(String::from("1.5")));
+        assert_wire(0.1f64, AttributeValue::N(String::from("0.1")));
+        assert_wire(3.0f64, AttributeValue::N(String::from("3")));
+        assert_wire(-0.0f64, AttributeValue::N(String::from("0")));
+        assert_wire(-0.0f32, AttributeValue::N(String::from("0")));
+        assert_wire('x', dynamo_value!("x"));
+        assert_wire(String::from("text"), dynamo_value!("text"));
+        assert_wire((), dynamo_value!(null));
+        assert_wire(Unit, dynamo_value!(null));
+        assert_wire(None::<u8>, dynamo_value!(null));
+        assert_wire(Some(1u8), dynamo_value!(1));
+        assert_wire(Newtype(2), dynamo_value!(2));
+        assert_wire(vec![1u8, 2], dynamo_value!([1, 2]));
+        assert_wire((1u8, String::from("a")), dynamo_value!([1, "a"]));
+        assert_wire(Tuple(1, String::from("a")), dynamo_value!([1, "a"]));
+        assert_wire(
+            BTreeMap::from([(String::from("k"), 1u8)]),
+            dynamo_value!({ "k": 1 }),
+        );
+        assert_wire(
+            BTreeMap::from([(10u32, true)]),
+            dynamo_value!({ "10": true }),
+        );
+        assert_wire(
+            Struct {
+                id: String::from("a"),
+                skipped: None,
+                null: None,
+            },
+            dynamo_value!({ "id": "a", "null": null }),
+        );
+        assert_wire(Enum::Unit, dynamo_value!("Unit"));
+        assert_wire(Enum::Newtype(1), dynamo_value!({ "Newtype": 1 }));
+        assert_wire(Enum::Tuple(1, true), dynamo_value!({ "Tuple": [1, true] }));
+        assert_wire(
+            Enum::Struct { id: 1 },
+            dynamo_value!({ "Struct": { "id": 1 } }),
+        );
+        assert_wire(
+            serde_bytes::ByteBuf::from(vec![0, 255]),
+            dynamo_value!(b([0u8, 255])),
+        );
+        assert_wire(
+            Sets {
+                ss: vec![String::from("a")],
+                ns: vec![-1, 2],
+                bs: vec![serde_bytes::ByteBuf::from(vec![1])],
+            },
+            dynamo_value!({ "ss": ss["a"], "ns": ns[-1, 2], "bs": bs[[1u8]] }),
+        );
+    }
+
+    #[test]
+    fn unrepresentable_numbers() {
+        use crate::ErrorCode;
+
+        #[track_caller]
+        fn assert_unrepresentable<T: serde::Serialize>(value: T) {
+            let err = to_attribute_value::<_, AttributeValue>(value).unwrap_err();
+            assert_eq!(err.code(), ErrorCode::UnrepresentableNumber);
+        }
+
+        assert_unrepresentable(u128::MAX);
+        assert_unrepresentable(i128::MIN);
+        assert_unrepresentable(f64::MAX);
+        assert_unrepresentable(5e-324f64);
+        assert_unrepresentable(f64::NAN);
+        assert_unrepresentable(f32::INFINITY);
+
+        assert_eq!(
+            to_attribute_value::<_, AttributeValue>(10u128.pow(37)).unwrap(),
+            AttributeValue::N(String::from("10000000000000000000000000000000000000")),
+        );
+        assert_wire(f32::MAX, AttributeValue::N(f32::MAX.to_string()));
+    }
+
+    #[test]
+    fn invalid_sets() {
+        let empty = Sets {
+            ss: vec![],
+            ns: vec![1],
+            bs: vec![serde_bytes::ByteBuf::from(vec![1])],
+        };
+        let duplicates = Sets {
+            ss: vec![String::from("a")],
+            ns: vec![1, 1],
+            bs: vec![serde_bytes::ByteBuf::from(vec![1])],
+        };
+
+        if cfg!(debug_assertions) {
+            for sets in [empty, duplicates] {
+                let err = to_attribute_value::<_, AttributeValue>(&sets).unwrap_err();
+                assert_eq!(err.code(), crate::ErrorCode::InvalidSet, "{sets:?}");
+            }
+        } else {
+            assert_eq!(
+                to_attribute_value::<_, AttributeValue>(&empty).unwrap(),
+                dynamo_value!({ "ss": ss[], "ns": ns[1], "bs": bs[[1u8]] })
+            );
+            assert_eq!(
+                to_attribute_value::<_, AttributeValue>(&duplicates).unwrap(),
+                dynamo_value!({ "ss": ss["a"], "ns": ns[1, 1], "bs": bs[[1u8]] })
+            );
+        }
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Shared {
+        id: String,
+        active: bool,
+        qty: u32,
+        tags: Vec<String>,
+        #[serde(with = "crate::string_set")]
+        aliases: Vec<String>,
+    }
+
+    #[test]
+    fn other_library_presets() {
+        let mut item = dynamo_item! { "id": 7, "active": 1, "tags": ss["a"], "aliases": ["b"] };
+        item.insert(String::from("qty"), AttributeValue::N(String::from("007")));
+        let expected = Shared {
+            id: String::from("7"),
+            active: true,
+            qty: 7,
+            tags: vec![String::from("a")],
+            aliases: vec![String::from("b")],
+        };
+        assert_eq!(
+            from_item_with_config::<_, Shared>(item.clone(), super::java_mapper()).unwrap(),
+            expected
+        );
+        // boto3 writes `BOOL`, so numeric booleans stay an error
+        item.insert(String::from("qty"), AttributeValue::N(String::from("7")));
+        assert!(from_item_with_config::<_, Shared>(item.clone(), super::boto3()).is_err());
+        item.insert(String::from("active"), AttributeValue::Bool(true));
+        // DynamoDB normalizes the numbers boto3 writes, so padded numbers stay an error too
+        let mut padded = item.clone();
+        padded.insert(String::from("qty"), AttributeValue::N(String::from(" 7")));
+        assert!(from_item_with_config::<_, Shared>(padded, super::boto3()).is_err());
+        assert_eq!(
+            from_item_with_config::<_, Shared>(item, super::boto3()).unwrap(),
+            expected
+        );
+    }
+}
@@ -0,0 +1,179 @@
+//! Helpers for atomic counter updates
+//!
+//! The usual way to count in DynamoDB is an `UpdateItem` call with the update expression
+//! `ADD #views :by`, which creates the attribute if it is missing. Asking for
+//! `ReturnValues::UpdatedNew` then returns the new count, without a separate read.
+//!
+//! [`increment_expression`] builds that expression, and [`updated_value`] reads the new count
+//! from the returned attributes.
+//!
+//! ```no_run
+//! # use __aws_sdk_dynamodb_1::client::Client;
+//! # use __aws_sdk_dynamodb_1::types::{AttributeValue, ReturnValue};
+//! use serde_dynamo::counter;
+//!
+//! # async fn count_view(client: &Client) -> Result<(), Box<dyn std::error::Error>> {
+//! let (expression, names, values) = counter::increment_expression("views", 1)?
+//!     .into_parts::<AttributeValue>();
+//!
+//! let output = client
+//!     .update_item()
+//!     .table_name("pages")
+//!     .set_key(Some(serde_dynamo::to_item(serde_json::json!({ "id": "home" }))?))
+//!     .update_expression(expression)
+//!     .set_expression_attribute_names(names)
+//!     .set_expression_attribute_values(values)
+//!     .return_values(ReturnValue::UpdatedNew)
+//!     .send()
+//!     .await?;
+//!
+//! let views: u64 = counter::updated_value(output.attributes.unwrap_or_default(), "views")?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{error::ErrorImpl, expression::Expression, AttributeValue, Item, Result};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Build the update expression `ADD #field :by`, which adds `by` to the number attribute
+/// `field`
+///
+/// The attribute name is always given as a placeholder, so `field` may be a reserved word. A
+/// missing attribute is treated as zero. `by` may be negative, and must serialize to a number.
+///
+/// The name placeholder is the field's [`placeholder`](crate::expression::placeholder), and the
+/// value placeholder is `:__sd_add_` followed by the same escaped name. Increments of different
+/// fields, and a condition from [`item_unchanged`](crate::condition::item_unchanged), can
+/// therefore share one set of names and values: join the increments as
+/// `ADD #a :by_a, #b :by_b` and merge their maps.
+///
+/// ```
+/// use serde_dynamo::{counter, AttributeValue};
+///
+/// let expression = counter::increment_expression("count", -2)?;
+///
+/// assert_eq!(expression.expression(), "ADD #__sd_count :__sd_add_count");
+/// assert_eq!(expression.names()["#__sd_count"], "count");
+/// assert_eq!(
+///     expression.values()[":__sd_add_count"],
+///     AttributeValue::N(String::from("-2")),
+/// );
+/// # Ok::<(), serde_dynamo::Error>(())
+/// ```
+pub fn increment_expression<T>(field: &str, by: T) -> Result<Expression>
+where
+    T: Serialize,
+{
+    let name = crate::expression::placeholder(field);
+    let value = crate::expression::value_placeholder("add", field);
+    let expression = Expression::raw(format!("ADD {name} {value}"))
+        .with_name(name, field)
+        .with_value(value.clone(), by)?;
+    if !matches!(expression.values()[&value], AttributeValue::N(_)) {
+        return Err(ErrorImpl::ExpectedNum.into());
+    }
+    Ok(expression)
+}
+
+/// Read the new value of the counter `field` from the attributes an update returned
+///
+/// The update must ask for `ReturnValues` of `UPDATED_NEW` or `ALL_NEW`. Errors name the
+/// attribute, including when it is missing.
+///
+/// ```
+/// use serde_dynamo::{counter, AttributeValue};
+/// use std::collections::HashMap;
+///
+/// let attributes = HashMap::from([
+///     (String::from("views"), AttributeValue::N(String::from("43"))),
+/// ]);
+///
+/// let views: u64 = counter::updated_value(attributes, "views")?;
+/// assert_eq!(views, 43);
+/// # Ok::<(), serde_dynamo::Error>(())
+/// ```
+pub fn updated_value<I, T>(attributes: I, field: &str) -> Result<T>
+where
+    I: Into<Item>,
+    T: DeserializeOwned,
+{
+    attributes.into().get_as(field)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn increments() {
+        let (expression, names, values) = increment_expression("total", 5u8)
+            .unwrap()
+            .into_parts::<AttributeValue>();
+        assert_eq!(expression, "ADD #__sd_total :__sd_add_total");
+        assert_eq!(
+            names,
+            Some(HashMap::from([(
+                String::from("#__sd_total"),
+                String::from("total")
+            )]))
+        );
+        assert_eq!(
+            values,
+            Some(HashMap::from([(
+                String::from(":__sd_add_total"),
+                AttributeValue::N(String::from("5"))
+            )]))
+        );
+
+        assert_eq!(
+            increment_expression("total", "5").unwrap_err().to_string(),
+            "Expected num"
+        );
+    }
+
+    #[test]
+    fn combines_increments_and_conditions() {
+        let views = increment_expression("views", 1).unwrap();
+        let likes = increment_expression("likes", 2).unwrap();
+        let read = crate::dynamo_item! { "views": 41, "likes": 7 };
+        let condition = crate::condition::item_unchanged(&read).unwrap();
+
+        let mut names = HashMap::new();
+        let mut values = HashMap::new();
+        for expression in [&views, &likes, &condition] {
+            for (placeholder, name) in expression.names() {
+                let previous = names.insert(placeholder.clone(), name.clone());
+                assert!(previous.is_none() || previous.as_ref() == Some(name));
+            }
+            for (placeholder, value) in expression.values() {
+                assert!(values.insert(placeholder.clone(), value.clone()).is_none());
+            }
+        }
+        assert_eq!(names.len(), 2);
+        assert_eq!(values.len(), 4);
+        assert_eq!(
+            values[":__sd_add_likes"],
+            AttributeValue::N(String::from("2"))
+        );
+        assert_eq!(
+            values[":__sd_read_likes"],
+            AttributeValue::N(String::from("7"))
+        );
+    }
+
+    #[test]
+    fn reads_updated_value() {
+        let attributes = Item::from(HashMap::from([(
+            String::from("total"),
+            AttributeValue::N(String::from("12")),
+        )]));
+        assert_eq!(updated_value::<_, i32>(attributes.clone(), "total"), Ok(12));
+        assert_eq!(
+            updated_value::<_, i32>(attributes, "views")
+                .unwrap_err()
+                .to_string(),
+            "Attribute `views` is missing"
+        );
+    }
+}
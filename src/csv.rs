@@ -0,0 +1,373 @@
+//! Export and import flat items as CSV
+//!
+//! Useful for quick data fixes and ops tooling: export a scan, edit it in a spreadsheet, and
+//! import it again. The format follows the DynamoDB console's CSV export, where each header names
+//! an attribute and its type, such as `age (N)`:
+//!
+//! | Type | Cell |
+//! |------|------|
+//! | `S`, `N` | the value |
+//! | `BOOL` | `true` or `false` |
+//! | `NULL` | `true` |
+//! | `B` | base64 |
+//! | `SS`, `NS`, `BS` | a JSON array of strings, with binary as base64 |
+//!
+//! An attribute stored with different types in different items gets one column per type. Empty
+//! cells are missing attributes, so empty strings are not preserved.
+//!
+//! Maps and lists are rejected, unless a flattening separator is configured with
+//! [`CsvOptions::flatten`]. Then each nested value gets its own column, named by its path: map
+//! keys joined by the separator, and list indexes in brackets, such as `address.lines[0] (S)`.
+//! Importing rebuilds the maps and lists from those paths. Empty maps and lists have no columns,
+//! so they are not preserved.
+//!
+//! ```
+//! use serde_dynamo::csv::{csv_to_items, items_to_csv, CsvOptions};
+//! use serde_dynamo::{dynamo_item, Item};
+//!
+//! let items = vec![
+//!     dynamo_item! { "id": "a", "age": 37, "address": { "city": "Springfield" } },
+//!     dynamo_item! { "id": "b", "tags": ss["new"] },
+//! ];
+//! let options = CsvOptions::new().flatten(".");
+//!
+//! let mut csv = Vec::new();
+//! items_to_csv(&items, &mut csv, &options)?;
+//! assert_eq!(
+//!     String::from_utf8(csv.clone()).unwrap(),
+//!     "address.city (S),age (N),id (S),tags (SS)\n\
+//!      Springfield,37,a,\n\
+//!      ,,b,\"[\"\"new\"\"]\"\n",
+//! );
+//!
+//! let imported = csv_to_items(csv.as_slice(), &options)?;
+//! assert_eq!(imported, items);
+//! # Ok::<(), serde_dynamo::Error>(())
+//! ```
+
+use crate::{error::ErrorImpl, path, ser::to_json, AttributeValue, Item, Result};
+use base64::Engine;
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+
+/// Options for [`items_to_csv`] and [`csv_to_items`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CsvOptions {
+    separator: Option<String>,
+}
+
+impl CsvOptions {
+    /// Create the default options, which reject maps and lists
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Give each value nested in a map or list its own column, with map keys joined by
+    /// `separator`
+    ///
+    /// Exporting fails if a map key contains the separator or a `[`, since the column name would
+    /// be ambiguous.
+    pub fn flatten(mut self, separator: impl Into<String>) -> Self {
+        self.separator = Some(separator.into());
+        self
+    }
+}
+
+/// Write items as CSV
+///
+/// See the [module documentation](self) for the format.
+pub fn items_to_csv<W>(items: &[Item], writer: W, options: &CsvOptions) -> Result<()>
+where
+    W: io::Write,
+{
+    let rows = items
+        .iter()
+        .map(|item| {
+            let mut cells = BTreeMap::new();
+            for (name, value) in item.iter() {
+                check_key(name, options)?;
+                flatten(name.clone(), value, options, &mut cells)?;
+            }
+            Ok(cells)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut columns: Vec<&(String, &str)> = rows.iter().flat_map(BTreeMap::keys).collect();
+    columns.sort_unstable();
+    columns.dedup();
+
+    let mut writer = ::__csv::Writer::from_writer(writer);
+    writer
+        .write_record(columns.iter().map(|(name, ty)| format!("{name} ({ty})")))
+        .map_err(csv_error)?;
+    for row in &rows {
+        writer
+            .write_record(
+                columns
+                    .iter()
+                    .map(|column| row.get(*column).map(String::as_str).unwrap_or_default()),
+            )
+            .map_err(csv_error)?;
+    }
+    writer
+        .flush()
+        .map_err(|err| ErrorImpl::Csv(err.to_string()).into())
+}
+
+/// Read items from CSV written by [`items_to_csv`] or the DynamoDB console
+///
+/// See the [module documentation](self) for the format.
+pub fn csv_to_items<R>(reader: R, options: &CsvOptions) -> Result<Vec<Item>>
+where
+    R: io::Read,
+{
+    let mut reader = ::__csv::Reader::from_reader(reader);
+    let columns = reader
+        .headers()
+        .map_err(csv_error)?
+        .iter()
+        .map(parse_header)
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut items = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(csv_error)?;
+        let mut item = HashMap::new();
+        for ((name, ty), cell) in columns.iter().zip(record.iter()) {
+            if cell.is_empty() {
+                continue;
+            }
+            let value = parse_cell(ty, cell).ok_or_else(|| -> crate::Error {
+                ErrorImpl::Csv(format!("`{cell}` is not a valid {ty} value for `{name}`")).into()
+            })?;
+            match &options.separator {
+                Some(separator) => {
+                    let segments = path::parse_separated(name, separator)?;
+                    path::insert(&mut item, &segments, value, true)?;
+                }
+                None => {
+                    item.insert(name.clone(), value);
+                }
+            }
+        }
+        items.push(Item::from(item));
+    }
+    Ok(items)
+}
+
+fn csv_error(err: ::__csv::Error) -> crate::Error {
+    ErrorImpl::Csv(err.to_string()).into()
+}
+
+fn check_key(key: &str, options: &CsvOptions) -> Result<()> {
+    match &options.separator {
+        Some(separator) if key.contains(separator.as_str()) || key.contains('[') => {
+            Err(ErrorImpl::Csv(format!(
+                "attribute name `{key}` contains the separator `{separator}` or `[`"
+            ))
+            .into())
+        }
+        _ => Ok(()),
+    }
+}
+
+fn flatten<'a>(
+    name: String,
+    value: &'a AttributeValue,
+    options: &CsvOptions,
+    cells: &mut BTreeMap<(String, &'a str), String>,
+) -> Result<()> {
+    let separator = match (value, &options.separator) {
+        (AttributeValue::M(_) | AttributeValue::L(_), None) => {
+            return Err(ErrorImpl::Csv(format!(
+                "attribute `{name}` is a nested {}; configure a flattening separator to export it",
+                value.type_descriptor()
+            ))
+            .into())
+        }
+        (_, separator) => separator,
+    };
+
+    let cell = match value {
+        AttributeValue::M(m) => {
+            let separator = separator.as_deref().unwrap_or_default();
+            for (key, value) in m {
+                check_key(key, options)?;
+                flatten(format!("{name}{separator}{key}"), value, options, cells)?;
+            }
+            return Ok(());
+        }
+        AttributeValue::L(l) => {
+            for (index, value) in l.iter().enumerate() {
+                flatten(format!("{name}[{index}]"), value, options, cells)?;
+            }
+            return Ok(());
+        }
+        AttributeValue::S(s) => s.clone(),
+        AttributeValue::N(n) => n.clone(),
+        AttributeValue::Bool(b) => b.to_string(),
+        AttributeValue::Null(_) => String::from("true"),
+        AttributeValue::B(b) => base64::engine::general_purpose::STANDARD.encode(b),
+        // Numbers in a set are written as strings, so every set is an array of strings
+        AttributeValue::Ns(ns) => to_json(&AttributeValue::Ss(ns.clone())),
+        AttributeValue::Ss(_) | AttributeValue::Bs(_) => to_json(value),
+    };
+    cells.insert((name, value.type_descriptor()), cell);
+    Ok(())
+}
+
+fn parse_header(header: &str) -> Result<(String, String)> {
+    header
+        .strip_suffix(')')
+        .and_then(|header| header.rsplit_once(" ("))
+        .map(|(name, ty)| (name.to_string(), ty.to_string()))
+        .ok_or_else(|| {
+            ErrorImpl::Csv(format!(
+                "header `{header}` is not an attribute name and type, such as `id (S)`"
+            ))
+            .into()
+        })
+}
+
+fn parse_cell(ty: &str, cell: &str) -> Option<AttributeValue> {
+    let base64 = |b: &str| base64::engine::general_purpose::STANDARD.decode(b).ok();
+    Some(match ty {
+        "S" => AttributeValue::S(cell.to_string()),
+        "N" => AttributeValue::N(cell.to_string()),
+        "BOOL" => AttributeValue::Bool(cell.parse().ok()?),
+        "NULL" => AttributeValue::Null(cell.parse().ok()?),
+        "B" => AttributeValue::B(base64(cell)?),
+        "SS" => AttributeValue::Ss(parse_string_array(cell)?),
+        "NS" => AttributeValue::Ns(parse_string_array(cell)?),
+        "BS" => AttributeValue::Bs(
+            parse_string_array(cell)?
+                .iter()
+                .map(|b| base64(b))
+                .collect::<Option<_>>()?,
+        ),
+        _ => return None,
+    })
+}
+
+/// Parse a JSON array of strings, such as `["a","b"]`
+fn parse_string_array(cell: &str) -> Option<Vec<String>> {
+    let mut chars = cell
+        .trim()
+        .strip_prefix('[')?
+        .strip_suffix(']')?
+        .chars()
+        .peekable();
+    let mut strings = Vec::new();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        match chars.next() {
+            None if strings.is_empty() => return Some(strings),
+            Some('"') => {}
+            _ => return None,
+        }
+
+        let mut string = String::new();
+        loop {
+            match chars.next()? {
+                '"' => break,
+                '\\' => string.push(match chars.next()? {
+                    'n' => '\n',
+                    'r' => '\r',
+                    't' => '\t',
+                    'b' => '\u{8}',
+                    'f' => '\u{c}',
+                    'u' => {
+                        let hex: String = chars.by_ref().take(4).collect();
+                        char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?
+                    }
+                    c @ ('"' | '\\' | '/') => c,
+                    _ => return None,
+                }),
+                c => string.push(c),
+            }
+        }
+        strings.push(string);
+
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        match chars.next() {
+            None => return Some(strings),
+            Some(',') => {}
+            _ => return None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dynamo_item;
+
+    fn round_trip(items: &[Item], options: &CsvOptions) -> Vec<Item> {
+        let mut csv = Vec::new();
+        items_to_csv(items, &mut csv, options).unwrap();
+        csv_to_items(csv.as_slice(), options).unwrap()
+    }
+
+    #[test]
+    fn round_trip_flat_items() {
+        let items = vec![
+            dynamo_item! {
+                "s": "comma, \"quote\"\nnewline",
+                "n": -1.5,
+                "bool": false,
+                "null": null,
+                "b": b([0u8, 255]),
+                "ss": ss["a\"b", "\u{1}"],
+                "ns": ns[1, 2.5],
+                "bs": bs[[1u8], [2u8, 3]],
+            },
+            dynamo_item! { "s": 1 },
+        ];
+        assert_eq!(round_trip(&items, &CsvOptions::new()), items);
+    }
+
+    #[test]
+    fn round_trip_flattened_items() {
+        let items = vec![dynamo_item! {
+            "a": { "b": [1, { "c": true }, [ss["x"]]] },
+        }];
+        let options = CsvOptions::new().flatten("/");
+        assert_eq!(round_trip(&items, &options), items);
+    }
+
+    #[test]
+    fn nested_values_need_a_separator() {
+        let items = vec![dynamo_item! { "a": [1] }];
+        let err = items_to_csv(&items, Vec::new(), &CsvOptions::new()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "CSV conversion failed: attribute `a` is a nested L; configure a flattening separator to export it"
+        );
+
+        let items = vec![dynamo_item! { "a": { "b.c": 1 } }];
+        let err = items_to_csv(&items, Vec::new(), &CsvOptions::new().flatten(".")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "CSV conversion failed: attribute name `b.c` contains the separator `.` or `[`"
+        );
+    }
+
+    #[test]
+    fn invalid_cells_and_headers() {
+        let err = csv_to_items("id\na".as_bytes(), &CsvOptions::new()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "CSV conversion failed: header `id` is not an attribute name and type, such as `id (S)`"
+        );
+
+        let err = csv_to_items("flag (BOOL)\nyes".as_bytes(), &CsvOptions::new()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "CSV conversion failed: `yes` is not a valid BOOL value for `flag`"
+        );
+
+        assert_eq!(parse_string_array("[]"), Some(vec![]));
+        assert_eq!(parse_string_array("[\"a\",]"), None);
+        assert_eq!(parse_string_array("[1]"), None);
+    }
+}
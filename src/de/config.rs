@@ -0,0 +1,455 @@
+use crate::{
+    error::ErrorImpl, AttributeValue, NewtypeStructs, NonePolicy, Result, Warning, Warnings,
+};
+
+/// Options that change how a [`Deserializer`](super::Deserializer) interprets its input
+///
+/// The default configuration is strict: every Rust type must be stored as the attribute type it
+/// serializes to. The options relax that for data written by other tools.
+///
+/// ```
+/// use serde_dynamo::{AttributeValue, DeserializerConfig};
+///
+/// let config = DeserializerConfig::new().coerce_bools(true);
+///
+/// let flag: bool =
+///     serde_dynamo::from_attribute_value_with_config(AttributeValue::N(String::from("1")), config)
+///         .unwrap();
+/// assert!(flag);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeserializerConfig {
+    pub(crate) coerce_bools: bool,
+    pub(crate) coerce_strings: bool,
+    pub(crate) coerce_base64: bool,
+    pub(crate) coerce_singletons: bool,
+    pub(crate) lenient_numbers: bool,
+    pub(crate) warnings: Option<Warnings>,
+    #[cfg(feature = "stats")]
+    pub(crate) stats: Option<crate::Stats>,
+    pub(crate) binary_policy: BinaryPolicy,
+    pub(crate) unknown_keys: UnknownKeyPolicy,
+    pub(crate) null_false: NullFalsePolicy,
+    pub(crate) flattened_adjacent_content: Option<(&'static str, &'static str)>,
+    pub(crate) ignored_prefixes: Vec<String>,
+    pub(crate) newtype_structs: NewtypeStructs,
+    pub(crate) none_values: NonePolicy,
+}
+
+impl DeserializerConfig {
+    /// Create the default, strict configuration
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accept `N("0")`/`N("1")` and `S("false")`/`S("true")` wherever a `bool` is expected
+    ///
+    /// Some imported datasets store booleans this way. Disabled by default.
+    pub fn coerce_bools(mut self, coerce_bools: bool) -> Self {
+        self.coerce_bools = coerce_bools;
+        self
+    }
+
+    /// Accept `N` and `Bool` attributes wherever a string is expected
+    ///
+    /// Numbers keep their stored text, and booleans become `"true"` or `"false"`. Useful when
+    /// reading items generically, such as in analytics code that treats every value as a string.
+    /// Disabled by default.
+    pub fn coerce_strings(mut self, coerce_strings: bool) -> Self {
+        self.coerce_strings = coerce_strings;
+        self
+    }
+
+    /// Accept `B` attributes wherever a string is expected, and `S` attributes wherever binary
+    /// data is expected, converting between them with standard base64
+    ///
+    /// DynamoDB JSON, as used by the HTTP API and exports, writes binary as base64 strings. This
+    /// lets one Rust type read both representations: a `String` field reads the base64 encoding
+    /// of a `B`, and a `Vec<u8>` or `serde_bytes::ByteBuf` field decodes an `S`. A string that
+    /// isn't valid base64 is an error. Disabled by default.
+    ///
+    /// Combined with [`coerce_singletons`](Self::coerce_singletons), an `S` read into a sequence
+    /// is decoded as base64 if it is valid base64, and read as a list of one string otherwise. A
+    /// `Vec<String>` therefore still fails on a string that happens to be valid base64, such as
+    /// `"abcd"`.
+    ///
+    /// ```
+    /// use serde_dynamo::{AttributeValue, DeserializerConfig};
+    ///
+    /// let config = DeserializerConfig::new().coerce_base64(true);
+    ///
+    /// let encoded: String =
+    ///     serde_dynamo::from_attribute_value_with_config(AttributeValue::B(b"PNG".to_vec()), config.clone())
+    ///         .unwrap();
+    /// assert_eq!(encoded, "UE5H");
+    ///
+    /// let bytes: Vec<u8> =
+    ///     serde_dynamo::from_attribute_value_with_config(AttributeValue::S(encoded), config)
+    ///         .unwrap();
+    /// assert_eq!(bytes, b"PNG");
+    /// ```
+    pub fn coerce_base64(mut self, coerce_base64: bool) -> Self {
+        self.coerce_base64 = coerce_base64;
+        self
+    }
+
+    /// Accept a list of one element wherever a single value is expected, and a single value
+    /// wherever a list is expected
+    ///
+    /// Items written by schemaless code or migrated from other stores sometimes hold `L([x])`
+    /// where the Rust type has a plain `T`, or `x` where it has a `Vec<T>`, because the attribute
+    /// was a list in some versions and not in others. With this option `L([x])` reads into a
+    /// number, string, bool, bytes, enum, map or struct as `x` would, and any attribute other than
+    /// a list, set or `NULL` reads into a sequence as a list of one. Lists of any other length are
+    /// still errors. Disabled by default.
+    ///
+    /// With [`coerce_base64`](Self::coerce_base64) as well, base64 decoding comes first: an `S`
+    /// read into a sequence is only a list of one if it isn't valid base64.
+    ///
+    /// ```
+    /// use serde_dynamo::{dynamo_value, AttributeValue, DeserializerConfig};
+    ///
+    /// let config = DeserializerConfig::new().coerce_singletons(true);
+    ///
+    /// let n: u32 =
+    ///     serde_dynamo::from_attribute_value_with_config(dynamo_value!([42]), config.clone())
+    ///         .unwrap();
+    /// assert_eq!(n, 42);
+    ///
+    /// let tags: Vec<String> =
+    ///     serde_dynamo::from_attribute_value_with_config(dynamo_value!("new"), config)
+    ///         .unwrap();
+    /// assert_eq!(tags, ["new"]);
+    /// ```
+    pub fn coerce_singletons(mut self, coerce_singletons: bool) -> Self {
+        self.coerce_singletons = coerce_singletons;
+        self
+    }
+
+    /// Accept `N` values with surrounding whitespace, a leading `+`, or leading zeros
+    ///
+    /// DynamoDB never returns numbers like `" 42"`, `"+42"` or `"007"`, but data imported by other
+    /// tools sometimes contains them. With this option they read as `42` and `7`. Disabled by
+    /// default.
+    ///
+    /// Negative zero reads as zero whether or not this is enabled, so `"-0"` can be read into an
+    /// unsigned integer.
+    ///
+    /// ```
+    /// use serde_dynamo::{AttributeValue, DeserializerConfig};
+    ///
+    /// let config = DeserializerConfig::new().lenient_numbers(true);
+    ///
+    /// let n: u8 =
+    ///     serde_dynamo::from_attribute_value_with_config(AttributeValue::N(String::from(" 007")), config)
+    ///         .unwrap();
+    /// assert_eq!(n, 7);
+    /// ```
+    pub fn lenient_numbers(mut self, lenient_numbers: bool) -> Self {
+        self.lenient_numbers = lenient_numbers;
+        self
+    }
+
+    /// How to hand `B` and `BS` values to a target that accepts any type
+    ///
+    /// See [`BinaryPolicy`]. Defaults to [`BinaryPolicy::Bytes`].
+    pub fn binary_policy(mut self, binary_policy: BinaryPolicy) -> Self {
+        self.binary_policy = binary_policy;
+        self
+    }
+
+    /// What to do with map keys that an enum key type doesn't have a variant for
+    ///
+    /// See [`UnknownKeyPolicy`]. Defaults to [`UnknownKeyPolicy::Error`].
+    pub fn unknown_keys(mut self, unknown_keys: UnknownKeyPolicy) -> Self {
+        self.unknown_keys = unknown_keys;
+        self
+    }
+
+    /// How to read `NULL` attributes whose value is `false`
+    ///
+    /// See [`NullFalsePolicy`]. Defaults to [`NullFalsePolicy::Null`].
+    pub fn null_false(mut self, null_false: NullFalsePolicy) -> Self {
+        self.null_false = null_false;
+        self
+    }
+
+    /// Read adjacently tagged enums whose content was written beside the tag
+    ///
+    /// This reverses
+    /// [`SerializerConfig::flattened_adjacent_content`](crate::SerializerConfig::flattened_adjacent_content).
+    /// When an enum tagged with these `tag` and `content` names is read from a map that has the
+    /// tag but no content attribute, every attribute other than the tag is read as the content.
+    /// Maps that do have the content attribute are read as usual.
+    pub fn flattened_adjacent_content(mut self, tag: &'static str, content: &'static str) -> Self {
+        self.flattened_adjacent_content = Some((tag, content));
+        self
+    }
+
+    /// How newtype structs, such as `struct UserId(String)`, were written
+    ///
+    /// See [`NewtypeStructs`]. Defaults to [`NewtypeStructs::Transparent`]. This must match
+    /// [`SerializerConfig::newtype_structs`](crate::SerializerConfig::newtype_structs) for the
+    /// data being read.
+    pub fn newtype_structs(mut self, newtype_structs: NewtypeStructs) -> Self {
+        self.newtype_structs = newtype_structs;
+        self
+    }
+
+    /// How `None` was written, and so how to read `NULL` into an `Option`
+    ///
+    /// See [`NonePolicy`]. Defaults to [`NonePolicy::Null`]. This must match
+    /// [`SerializerConfig::none_values`](crate::SerializerConfig::none_values) for the data being
+    /// read.
+    pub fn none_values(mut self, none_values: NonePolicy) -> Self {
+        self.none_values = none_values;
+        self
+    }
+
+    /// Leave out map attributes whose names start with any of `prefixes`
+    ///
+    /// Global tables add replication attributes such as `aws:rep:updatetime` to every item, so
+    /// structs marked `#[serde(deny_unknown_fields)]` fail to read them. Ignoring the `aws:rep:`
+    /// prefix lets strict structs and global tables coexist. Other unknown attributes are still
+    /// rejected.
+    ///
+    /// The attributes are left out of every map, including nested maps and maps read into a
+    /// `HashMap` or `serde_json::Value`. Replaces any prefixes set before.
+    ///
+    /// ```
+    /// use serde_dynamo::{dynamo_item, DeserializerConfig};
+    /// # use serde_derive::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// #[serde(deny_unknown_fields)]
+    /// struct User {
+    ///     id: String,
+    /// }
+    ///
+    /// let item = dynamo_item! {
+    ///     "id": "fSsgVtal8TpP",
+    ///     "aws:rep:updatetime": 1700000000.123,
+    ///     "aws:rep:updateregion": "us-east-1",
+    /// };
+    ///
+    /// assert!(serde_dynamo::from_item::<_, User>(item.clone()).is_err());
+    ///
+    /// let config = DeserializerConfig::new().ignored_attribute_prefixes(["aws:rep:"]);
+    /// let user: User = serde_dynamo::from_item_with_config(item, config).unwrap();
+    /// assert_eq!(user.id, "fSsgVtal8TpP");
+    /// ```
+    pub fn ignored_attribute_prefixes<I, S>(mut self, prefixes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.ignored_prefixes = prefixes.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Record each coercion made by the options above in `warnings`
+    pub fn warnings(mut self, warnings: Warnings) -> Self {
+        self.warnings = Some(warnings);
+        self
+    }
+
+    /// Measure every value read in `stats`
+    ///
+    /// See [`Stats`](crate::Stats). Each item read by [`from_items`](crate::from_items) and the
+    /// other functions for several items is measured separately.
+    #[cfg(feature = "stats")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "stats")))]
+    pub fn stats(mut self, stats: crate::Stats) -> Self {
+        self.stats = Some(stats);
+        self
+    }
+
+    /// Whether `value` should be read as `None`, according to the [`NonePolicy`]
+    pub(crate) fn is_none(&self, value: &AttributeValue) -> Result<bool> {
+        let is_null = self.is_null(value)?;
+        Ok(is_null && self.none_values == NonePolicy::Null)
+    }
+
+    /// Whether `value` is a null, according to the [`NullFalsePolicy`]
+    pub(crate) fn is_null(&self, value: &AttributeValue) -> Result<bool> {
+        match value {
+            AttributeValue::Null(true) => Ok(true),
+            AttributeValue::Null(false) => match self.null_false {
+                NullFalsePolicy::Null => Ok(true),
+                NullFalsePolicy::Reject => Err(ErrorImpl::NullFalse.into()),
+            },
+            _ => Ok(false),
+        }
+    }
+
+    /// Decode a string read as binary data under
+    /// [`coerce_base64`](Self::coerce_base64)
+    pub(crate) fn base64(&self, s: &str) -> Result<Vec<u8>> {
+        use base64::Engine;
+
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(s)
+            .map_err(|err| ErrorImpl::InvalidBase64(err).into())?;
+        self.warn(Warning::CoercedType {
+            from: "S",
+            to: "binary",
+        });
+        Ok(bytes)
+    }
+
+    /// Normalize a stored number before it is parsed
+    pub(crate) fn number(&self, n: String) -> String {
+        match crate::number::normalize(&n, self.lenient_numbers) {
+            Some(normalized) => {
+                if self.lenient_numbers {
+                    self.warn(Warning::NormalizedNumber { original: n });
+                }
+                normalized
+            }
+            None => n,
+        }
+    }
+
+    /// Whether the map attribute `name` is left out by
+    /// [`ignored_attribute_prefixes`](Self::ignored_attribute_prefixes)
+    pub(crate) fn ignores(&self, name: &str) -> bool {
+        self.ignored_prefixes
+            .iter()
+            .any(|prefix| name.starts_with(prefix.as_str()))
+    }
+
+    /// Record the measurement of a value about to be read, if there is a [`Stats`](crate::Stats)
+    #[cfg(feature = "stats")]
+    pub(crate) fn measure(&self, measure: impl FnOnce() -> crate::ItemStats) {
+        if let Some(stats) = &self.stats {
+            stats.push(measure());
+        }
+    }
+
+    pub(crate) fn warn(&self, warning: Warning) {
+        if let Some(warnings) = &self.warnings {
+            warnings.push(warning);
+        }
+    }
+}
+
+/// How binary values are presented to self-describing targets
+///
+/// Targets such as `serde_json::Value` don't ask for a particular type; they take whatever the
+/// input contains. DynamoDB binary has no JSON equivalent, so this policy decides what such a
+/// target sees for each `B` value and each element of a `BS`. It applies only to those targets:
+/// types that ask for bytes, such as `serde_bytes::ByteBuf`, always receive the raw bytes.
+///
+/// ```
+/// use serde_dynamo::{AttributeValue, BinaryPolicy, DeserializerConfig};
+///
+/// let value = AttributeValue::L(vec![
+///     AttributeValue::S(String::from("logo")),
+///     AttributeValue::B(b"PNG".to_vec()),
+/// ]);
+///
+/// let config = DeserializerConfig::new().binary_policy(BinaryPolicy::Base64);
+/// let json: serde_json::Value =
+///     serde_dynamo::from_attribute_value_with_config(value.clone(), config).unwrap();
+/// assert_eq!(json, serde_json::json!(["logo", "UE5H"]));
+///
+/// let config = DeserializerConfig::new().binary_policy(BinaryPolicy::ByteArray);
+/// let json: serde_json::Value =
+///     serde_dynamo::from_attribute_value_with_config(value, config).unwrap();
+/// assert_eq!(json, serde_json::json!(["logo", [80, 78, 71]]));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BinaryPolicy {
+    /// Present the raw bytes
+    ///
+    /// Targets that don't accept bytes, including `serde_json::Value`, fail with an invalid type
+    /// error.
+    #[default]
+    Bytes,
+    /// Present a string holding the standard base64 encoding of the bytes
+    Base64,
+    /// Present a sequence of `u8`s
+    ByteArray,
+    /// Fail with an error naming the binary policy
+    Reject,
+}
+
+/// How map keys that an enum key type doesn't know are handled
+///
+/// A `HashMap<Color, V>` fails to deserialize as soon as the table holds a key that `Color` has no
+/// variant for. When writers are upgraded before readers, that breaks every reader that sees a
+/// new key. This policy lets readers ignore such keys, or gather them under one variant, instead.
+/// It only applies to map keys whose type is an enum; struct fields are unaffected.
+///
+/// ```
+/// use serde_dynamo::{dynamo_value, DeserializerConfig, UnknownKeyPolicy};
+/// # use serde_derive::Deserialize;
+/// use std::collections::HashMap;
+///
+/// #[derive(Debug, Deserialize, PartialEq, Eq, Hash)]
+/// enum Color {
+///     Red,
+///     Blue,
+///     Other,
+/// }
+///
+/// let value = dynamo_value!({ "Red": 1, "Green": 2 });
+///
+/// let config = DeserializerConfig::new().unknown_keys(UnknownKeyPolicy::Skip);
+/// let counts: HashMap<Color, u32> =
+///     serde_dynamo::from_attribute_value_with_config(value.clone(), config).unwrap();
+/// assert_eq!(counts, HashMap::from([(Color::Red, 1)]));
+///
+/// let config = DeserializerConfig::new().unknown_keys(UnknownKeyPolicy::CatchAll("Other"));
+/// let counts: HashMap<Color, u32> =
+///     serde_dynamo::from_attribute_value_with_config(value, config).unwrap();
+/// assert_eq!(counts, HashMap::from([(Color::Red, 1), (Color::Other, 2)]));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum UnknownKeyPolicy {
+    /// Fail with serde's unknown variant error
+    #[default]
+    Error,
+    /// Leave the entry out of the map
+    ///
+    /// A map must yield at least one key before the variants are known, so a map whose keys are
+    /// all unknown still fails.
+    Skip,
+    /// Deserialize the key as this unit variant instead
+    ///
+    /// The original key is lost. If several unknown keys are present, the map keeps the value of
+    /// whichever is read last. Fails as [`Error`](Self::Error) does if the enum has no such
+    /// variant.
+    CatchAll(&'static str),
+}
+
+/// How `NULL` attributes with the value `false` are read
+///
+/// DynamoDB only stores `NULL: true`, and rejects writes of `NULL: false`, but the attribute value
+/// format can express it, and some third-party producers of stream records and exports emit it.
+/// This policy applies wherever a null is accepted: options, units, and unit structs.
+///
+/// ```
+/// use serde_dynamo::{AttributeValue, DeserializerConfig, NullFalsePolicy};
+///
+/// let value = AttributeValue::Null(false);
+///
+/// let nickname: Option<String> = serde_dynamo::from_attribute_value(value.clone()).unwrap();
+/// assert_eq!(nickname, None);
+///
+/// let config = DeserializerConfig::new().null_false(NullFalsePolicy::Reject);
+/// let err = serde_dynamo::from_attribute_value_with_config::<_, Option<String>>(value, config)
+///     .unwrap_err();
+/// assert_eq!(err.to_string(), "Found `NULL: false`, which the null policy rejects");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum NullFalsePolicy {
+    /// Read it as a null, exactly like `NULL: true`
+    #[default]
+    Null,
+    /// Fail with an error naming the null policy
+    Reject,
+}
@@ -6,32 +6,132 @@ use super::{
     deserializer_seq::{
         DeserializerSeq, DeserializerSeqBytes, DeserializerSeqNumbers, DeserializerSeqStrings,
     },
-    AttributeValue, Error, ErrorImpl, Result,
+    AttributeValue, BinaryPolicy, DeserializerConfig, Error, ErrorImpl, Result,
 };
+use crate::{Item, NewtypeStructs, Warning};
 use serde::de::{self, IntoDeserializer, Visitor};
+use std::collections::HashMap;
+use std::sync::Arc;
 
 /// A structure that deserializes [`AttributeValue`]s into Rust values.
+///
+/// [`AttributeValue`] and [`Item`] convert into one with
+/// [`IntoDeserializer`](serde::de::IntoDeserializer), and `&AttributeValue` converts into a
+/// [`DeserializerRef`](crate::DeserializerRef), so they work with APIs generic over it.
+///
+/// ```
+/// use serde::de::{Deserialize, IntoDeserializer};
+/// use serde_dynamo::AttributeValue;
+///
+/// let value = AttributeValue::N(String::from("7"));
+/// assert_eq!(u8::deserialize((&value).into_deserializer()).unwrap(), 7);
+/// assert_eq!(u8::deserialize(value.into_deserializer()).unwrap(), 7);
+/// ```
 #[derive(Debug)]
 pub struct Deserializer {
     input: AttributeValue,
+    config: Arc<DeserializerConfig>,
 }
 
 impl Deserializer {
     /// Create a Deserializer from an AttributeValue
     pub fn from_attribute_value(input: AttributeValue) -> Self {
-        Deserializer { input }
+        Self::from_attribute_value_with_config(input, DeserializerConfig::default())
+    }
+
+    /// Create a Deserializer from an AttributeValue, using the given configuration
+    pub fn from_attribute_value_with_config(
+        input: AttributeValue,
+        config: DeserializerConfig,
+    ) -> Self {
+        Self::with_shared_config(input, Arc::new(config))
+    }
+
+    /// Create a Deserializer for a nested value, sharing its parent's configuration
+    pub(crate) fn with_shared_config(
+        input: AttributeValue,
+        config: Arc<DeserializerConfig>,
+    ) -> Self {
+        Deserializer { input, config }
+    }
+
+    /// The only element of a list of one, if singletons are coerced
+    ///
+    /// See [`DeserializerConfig::coerce_singletons`].
+    fn unwrap_singleton(self) -> Self {
+        match self.input {
+            AttributeValue::L(l) if self.config.coerce_singletons => {
+                match <[AttributeValue; 1]>::try_from(l) {
+                    Ok([value]) => {
+                        self.config.warn(Warning::CoercedType {
+                            from: "L",
+                            to: "single value",
+                        });
+                        Self::with_shared_config(value, self.config)
+                    }
+                    Err(l) => Self::with_shared_config(AttributeValue::L(l), self.config),
+                }
+            }
+            input => Self::with_shared_config(input, self.config),
+        }
+    }
+
+    /// Visit a value that isn't a list as a list of one
+    ///
+    /// See [`DeserializerConfig::coerce_singletons`].
+    fn visit_singleton_seq<'de, V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.config.warn(Warning::CoercedType {
+            from: self.input.type_descriptor(),
+            to: "list",
+        });
+        visitor.visit_seq(DeserializerSeq::from_vec(vec![self.input], self.config))
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for AttributeValue {
+    type Deserializer = Deserializer;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        Deserializer::from_attribute_value(self)
     }
 }
 
+impl<'de> IntoDeserializer<'de, Error> for Item {
+    type Deserializer = Deserializer;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        Deserializer::from_attribute_value(AttributeValue::M(self.into()))
+    }
+}
+
+/// The tag and content names, if `m` is an adjacently tagged enum whose content was flattened
+/// beside its tag
+///
+/// See [`DeserializerConfig::flattened_adjacent_content`].
+pub(super) fn flattened_content<A>(
+    config: &DeserializerConfig,
+    fields: &'static [&'static str],
+    m: &HashMap<String, A>,
+) -> Option<(&'static str, &'static str)> {
+    let (tag, content) = config.flattened_adjacent_content?;
+    let adjacently_tagged = fields == [tag, content];
+    (adjacently_tagged && m.contains_key(tag) && !m.contains_key(content) && m.len() > 1)
+        .then_some((tag, content))
+}
+
 macro_rules! deserialize_number {
-    ($self:expr, $visitor:expr, $ty:ty, $fn:ident) => {
-        if let AttributeValue::N(n) = $self.input {
-            let de = DeserializerNumber::from_string(n);
+    ($self:expr, $visitor:expr, $ty:ty, $fn:ident) => {{
+        let this = $self.unwrap_singleton();
+        if let AttributeValue::N(n) = this.input {
+            let de = DeserializerNumber::from_string(this.config.number(n));
             de.$fn($visitor)
         } else {
             return Err(ErrorImpl::ExpectedNum.into());
         }
-    };
+    }};
 }
 
 impl<'de> de::Deserializer<'de> for Deserializer {
@@ -44,21 +144,21 @@ impl<'de> de::Deserializer<'de> for Deserializer {
     where
         V: Visitor<'de>,
     {
-        if let AttributeValue::N(s) = self.input {
-            DeserializerNumber::from_string(s).deserialize_any(visitor)
-        } else {
-            match self.input {
-                AttributeValue::S(_) => self.deserialize_string(visitor),
-                AttributeValue::Bool(_) => self.deserialize_bool(visitor),
-                AttributeValue::B(_) => self.deserialize_bytes(visitor),
-                AttributeValue::Null(_) => self.deserialize_unit(visitor),
-                AttributeValue::M(_) => self.deserialize_map(visitor),
-                AttributeValue::L(_)
-                | AttributeValue::Ss(_)
-                | AttributeValue::Ns(_)
-                | AttributeValue::Bs(_) => self.deserialize_seq(visitor),
-                _ => unreachable!(),
+        match self.input {
+            AttributeValue::N(s) => {
+                DeserializerNumber::from_string(self.config.number(s)).deserialize_any(visitor)
+            }
+            AttributeValue::S(_) => self.deserialize_string(visitor),
+            AttributeValue::Bool(_) => self.deserialize_bool(visitor),
+            AttributeValue::B(b) => {
+                DeserializerBytes::from_bytes(b, self.config.binary_policy).deserialize_any(visitor)
             }
+            AttributeValue::Null(_) => self.deserialize_unit(visitor),
+            AttributeValue::M(_) => self.deserialize_map(visitor),
+            AttributeValue::L(_)
+            | AttributeValue::Ss(_)
+            | AttributeValue::Ns(_)
+            | AttributeValue::Bs(_) => self.deserialize_seq(visitor),
         }
     }
 
@@ -136,21 +236,38 @@ impl<'de> de::Deserializer<'de> for Deserializer {
     where
         V: Visitor<'de>,
     {
-        if let AttributeValue::S(s) = self.input {
-            visitor.visit_string(s)
-        } else {
-            Err(ErrorImpl::ExpectedString.into())
-        }
+        self.deserialize_string(visitor)
     }
 
     fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        if let AttributeValue::S(s) = self.input {
-            visitor.visit_string(s)
-        } else {
-            Err(ErrorImpl::ExpectedString.into())
+        let de = self.unwrap_singleton();
+        match de.input {
+            AttributeValue::S(s) => visitor.visit_string(s),
+            AttributeValue::N(n) if de.config.coerce_strings => {
+                de.config.warn(Warning::CoercedType {
+                    from: "N",
+                    to: "string",
+                });
+                visitor.visit_string(n)
+            }
+            AttributeValue::Bool(b) if de.config.coerce_strings => {
+                de.config.warn(Warning::CoercedType {
+                    from: "BOOL",
+                    to: "string",
+                });
+                visitor.visit_string(b.to_string())
+            }
+            AttributeValue::B(b) if de.config.coerce_base64 => {
+                de.config.warn(Warning::CoercedType {
+                    from: "B",
+                    to: "string",
+                });
+                DeserializerBytes::from_bytes(b, BinaryPolicy::Base64).deserialize_any(visitor)
+            }
+            _ => Err(ErrorImpl::ExpectedString.into()),
         }
     }
 
@@ -160,7 +277,7 @@ impl<'de> de::Deserializer<'de> for Deserializer {
     {
         match self.input {
             AttributeValue::L(l) => {
-                let deserializer_seq = DeserializerSeq::from_vec(l);
+                let deserializer_seq = DeserializerSeq::from_vec(l, self.config);
                 visitor.visit_seq(deserializer_seq)
             }
             AttributeValue::Ss(ss) => {
@@ -168,13 +285,29 @@ impl<'de> de::Deserializer<'de> for Deserializer {
                 visitor.visit_seq(deserializer_seq)
             }
             AttributeValue::Ns(ns) => {
-                let deserializer_seq = DeserializerSeqNumbers::from_vec(ns);
+                let deserializer_seq = DeserializerSeqNumbers::from_vec(ns, self.config);
                 visitor.visit_seq(deserializer_seq)
             }
             AttributeValue::Bs(bs) => {
-                let deserializer_seq = DeserializerSeqBytes::from_vec(bs);
+                let deserializer_seq =
+                    DeserializerSeqBytes::from_vec(bs, self.config.binary_policy);
                 visitor.visit_seq(deserializer_seq)
             }
+            // With both coercions, a string is decoded as base64 if it can be, and is a list of
+            // one string otherwise
+            AttributeValue::S(s) if self.config.coerce_base64 => match self.config.base64(&s) {
+                Ok(b) => DeserializerBytes::from_bytes(b, BinaryPolicy::ByteArray)
+                    .deserialize_any(visitor),
+                Err(_) if self.config.coerce_singletons => {
+                    Self::with_shared_config(AttributeValue::S(s), self.config)
+                        .visit_singleton_seq(visitor)
+                }
+                Err(err) => Err(err),
+            },
+            AttributeValue::Null(_) => Err(ErrorImpl::ExpectedSeq.into()),
+            input if self.config.coerce_singletons => {
+                Self::with_shared_config(input, self.config).visit_singleton_seq(visitor)
+            }
             _ => Err(ErrorImpl::ExpectedSeq.into()),
         }
     }
@@ -183,8 +316,9 @@ impl<'de> de::Deserializer<'de> for Deserializer {
     where
         V: Visitor<'de>,
     {
-        if let AttributeValue::M(mut m) = self.input {
-            let deserializer_map = DeserializerMap::from_item(&mut m);
+        let de = self.unwrap_singleton();
+        if let AttributeValue::M(mut m) = de.input {
+            let deserializer_map = DeserializerMap::from_item(&mut m, de.config);
             visitor.visit_map(deserializer_map)
         } else {
             Err(ErrorImpl::ExpectedMap.into())
@@ -195,10 +329,34 @@ impl<'de> de::Deserializer<'de> for Deserializer {
     where
         V: Visitor<'de>,
     {
-        if let AttributeValue::Bool(b) = self.input {
-            visitor.visit_bool(b)
-        } else {
-            Err(ErrorImpl::ExpectedBool.into())
+        let de = self.unwrap_singleton();
+        match de.input {
+            AttributeValue::Bool(b) => visitor.visit_bool(b),
+            AttributeValue::N(n) if de.config.coerce_bools => {
+                let b = match n.as_str() {
+                    "0" => false,
+                    "1" => true,
+                    _ => return Err(ErrorImpl::ExpectedBool.into()),
+                };
+                de.config.warn(Warning::CoercedType {
+                    from: "N",
+                    to: "bool",
+                });
+                visitor.visit_bool(b)
+            }
+            AttributeValue::S(s) if de.config.coerce_bools => {
+                let b = match s.as_str() {
+                    "false" => false,
+                    "true" => true,
+                    _ => return Err(ErrorImpl::ExpectedBool.into()),
+                };
+                de.config.warn(Warning::CoercedType {
+                    from: "S",
+                    to: "bool",
+                });
+                visitor.visit_bool(b)
+            }
+            _ => Err(ErrorImpl::ExpectedBool.into()),
         }
     }
 
@@ -206,7 +364,8 @@ impl<'de> de::Deserializer<'de> for Deserializer {
     where
         V: Visitor<'de>,
     {
-        if let AttributeValue::S(s) = self.input {
+        let de = self.unwrap_singleton();
+        if let AttributeValue::S(s) = de.input {
             let mut chars = s.chars();
             if let Some(ch) = chars.next() {
                 let result = visitor.visit_char(ch)?;
@@ -227,7 +386,7 @@ impl<'de> de::Deserializer<'de> for Deserializer {
     where
         V: Visitor<'de>,
     {
-        if let AttributeValue::Null(true) = self.input {
+        if self.config.is_null(&self.input)? {
             visitor.visit_unit()
         } else {
             Err(ErrorImpl::ExpectedUnit.into())
@@ -237,16 +396,18 @@ impl<'de> de::Deserializer<'de> for Deserializer {
     fn deserialize_enum<V>(
         self,
         _name: &'static str,
-        _variants: &'static [&'static str],
+        variants: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        match self.input {
+        // Unknown variant names are reported by the visitor, which knows the variants too
+        let de = self.unwrap_singleton();
+        match de.input {
             AttributeValue::S(s) => visitor.visit_enum(s.into_deserializer()),
-            AttributeValue::M(m) => visitor.visit_enum(DeserializerEnum::from_item(m)),
-            _ => Err(ErrorImpl::ExpectedEnum.into()),
+            AttributeValue::M(m) => visitor.visit_enum(DeserializerEnum::from_item(m, de.config)),
+            _ => Err(ErrorImpl::ExpectedEnum(variants).into()),
         }
     }
 
@@ -254,18 +415,40 @@ impl<'de> de::Deserializer<'de> for Deserializer {
     where
         V: Visitor<'de>,
     {
-        if let AttributeValue::B(b) = self.input {
-            let de = DeserializerBytes::from_bytes(b);
-            de.deserialize_bytes(visitor)
-        } else {
-            Err(ErrorImpl::ExpectedBytes.into())
+        let de = self.unwrap_singleton();
+        match de.input {
+            AttributeValue::B(b) => {
+                DeserializerBytes::from_bytes(b, BinaryPolicy::Bytes).deserialize_bytes(visitor)
+            }
+            AttributeValue::S(s) if de.config.coerce_base64 => {
+                let b = de.config.base64(&s)?;
+                DeserializerBytes::from_bytes(b, BinaryPolicy::Bytes).deserialize_bytes(visitor)
+            }
+            _ => Err(ErrorImpl::ExpectedBytes.into()),
         }
     }
 
-    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
+        // Tuples and fixed-size arrays must match the stored length exactly. Checking up front
+        // gives a useful error instead of serde's "invalid length" for short sequences, and
+        // catches long sequences whose trailing elements would otherwise be silently dropped.
+        if let AttributeValue::B(b) = self.input {
+            return DeserializerBytes::from_bytes(b, BinaryPolicy::Bytes)
+                .deserialize_tuple(len, visitor);
+        }
+        let found = match &self.input {
+            AttributeValue::L(l) => l.len(),
+            AttributeValue::Ss(ss) => ss.len(),
+            AttributeValue::Ns(ns) => ns.len(),
+            AttributeValue::Bs(bs) => bs.len(),
+            _ => return Err(ErrorImpl::ExpectedSeq.into()),
+        };
+        if found != len {
+            return Err(ErrorImpl::ExpectedSeqLength(len, found).into());
+        }
         self.deserialize_seq(visitor)
     }
 
@@ -273,7 +456,7 @@ impl<'de> de::Deserializer<'de> for Deserializer {
     where
         V: Visitor<'de>,
     {
-        if let AttributeValue::Null(true) = self.input {
+        if self.config.is_none(&self.input)? {
             visitor.visit_none()
         } else {
             visitor.visit_some(self)
@@ -281,14 +464,31 @@ impl<'de> de::Deserializer<'de> for Deserializer {
     }
 
     fn deserialize_struct<V>(
-        self,
+        mut self,
         _name: &'static str,
-        _fields: &'static [&'static str],
+        fields: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
+        // A struct can also be stored as a list of its fields, so only a list holding a map is
+        // the struct itself
+        if let AttributeValue::L(l) = &self.input {
+            if let [AttributeValue::M(_)] = l.as_slice() {
+                self = self.unwrap_singleton();
+            }
+        }
+
+        if let AttributeValue::M(m) = &mut self.input {
+            if let Some((tag, content)) = flattened_content(&self.config, fields, m) {
+                let tag_value = m.remove(tag);
+                let nested = std::mem::take(m);
+                m.extend(tag_value.map(|value| (tag.to_string(), value)));
+                m.insert(content.to_string(), AttributeValue::M(nested));
+            }
+        }
+
         if let AttributeValue::L(_) = self.input {
             self.deserialize_seq(visitor)
         } else {
@@ -322,7 +522,7 @@ impl<'de> de::Deserializer<'de> for Deserializer {
     where
         V: Visitor<'de>,
     {
-        if let AttributeValue::Null(true) = self.input {
+        if self.config.is_null(&self.input)? {
             visitor.visit_unit()
         } else {
             Err(ErrorImpl::ExpectedUnitStruct.into())
@@ -339,23 +539,47 @@ impl<'de> de::Deserializer<'de> for Deserializer {
     fn deserialize_tuple_struct<V>(
         self,
         _name: &'static str,
-        _len: usize,
+        len: usize,
         visitor: V,
     ) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        self.deserialize_seq(visitor)
+        self.deserialize_tuple(len, visitor)
     }
 
     fn deserialize_newtype_struct<V>(
         self,
-        _name: &'static str,
+        name: &'static str,
         visitor: V,
     ) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        visitor.visit_newtype_struct(self)
+        let tag = match self.config.newtype_structs {
+            NewtypeStructs::Transparent => return visitor.visit_newtype_struct(self),
+            NewtypeStructs::Indexed => None,
+            NewtypeStructs::Named(tag) => Some(tag),
+        };
+        let AttributeValue::M(mut m) = self.input else {
+            return Err(ErrorImpl::ExpectedMap.into());
+        };
+        if let Some(tag) = tag {
+            match m.remove(tag) {
+                Some(AttributeValue::S(found)) if found == name => {}
+                Some(AttributeValue::S(found)) => {
+                    return Err(ErrorImpl::NewtypeNameMismatch(name, found).into())
+                }
+                Some(_) => {
+                    let err: Error = ErrorImpl::ExpectedString.into();
+                    return Err(err.in_attribute(tag));
+                }
+                None => return Err(ErrorImpl::MissingAttribute(tag.to_string()).into()),
+            }
+        }
+        let Some(value) = m.remove("0") else {
+            return Err(ErrorImpl::MissingAttribute(String::from("0")).into());
+        };
+        visitor.visit_newtype_struct(Deserializer::with_shared_config(value, self.config))
     }
 }
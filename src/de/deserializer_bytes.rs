@@ -1,20 +1,37 @@
-use super::{Error, Result};
-use serde::de::{self, Visitor};
+use super::{BinaryPolicy, Error, ErrorImpl, Result};
+use base64::Engine;
+use serde::de::{self, value::SeqDeserializer, Visitor};
 use serde::forward_to_deserialize_any;
 
 pub struct DeserializerBytes<T> {
     input: T,
+    /// The position of the bytes within a binary set, used in error messages
+    index: Option<usize>,
+    /// How to present the bytes to `deserialize_any`
+    policy: BinaryPolicy,
 }
 
 impl<T> DeserializerBytes<T> {
-    pub fn from_bytes(input: T) -> Self {
-        DeserializerBytes { input }
+    pub fn from_bytes(input: T, policy: BinaryPolicy) -> Self {
+        DeserializerBytes {
+            input,
+            index: None,
+            policy,
+        }
+    }
+
+    pub fn from_set_element(input: T, index: usize, policy: BinaryPolicy) -> Self {
+        DeserializerBytes {
+            input,
+            index: Some(index),
+            policy,
+        }
     }
 }
 
 impl<'de, T> de::Deserializer<'de> for DeserializerBytes<T>
 where
-    T: AsRef<[u8]>,
+    T: AsRef<[u8]> + Into<Vec<u8>>,
 {
     type Error = Error;
 
@@ -25,14 +42,25 @@ where
     where
         V: Visitor<'de>,
     {
-        self.deserialize_bytes(visitor)
+        match self.policy {
+            BinaryPolicy::Bytes => self.deserialize_bytes(visitor),
+            BinaryPolicy::Base64 => visitor.visit_string(
+                base64::engine::general_purpose::STANDARD.encode(self.input.as_ref()),
+            ),
+            BinaryPolicy::ByteArray => visitor.visit_seq(SeqDeserializer::<_, Error>::new(
+                self.input.into().into_iter(),
+            )),
+            BinaryPolicy::Reject => Err(ErrorImpl::BinaryRejected(self.index).into()),
+        }
     }
 
+    // The bytes are owned, so hand them over rather than making the visitor copy them. This
+    // matters for untagged enums, which buffer the whole value before trying each variant.
     fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        visitor.visit_bytes(self.input.as_ref())
+        visitor.visit_byte_buf(self.input.into())
     }
 
     fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, Self::Error>
@@ -42,15 +70,28 @@ where
         self.deserialize_bytes(visitor)
     }
 
+    // Skipping a value shouldn't trip `BinaryPolicy::Reject`
     fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        self.deserialize_any(visitor)
+        self.deserialize_bytes(visitor)
+    }
+
+    // Fixed-width binary, such as `[u8; 32]`, is deserialized as a tuple of bytes
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let bytes = self.input.as_ref();
+        if bytes.len() != len {
+            return Err(ErrorImpl::ExpectedBytesLength(len, bytes.len(), self.index).into());
+        }
+        visitor.visit_seq(SeqDeserializer::<_, Error>::new(bytes.iter().copied()))
     }
 
     forward_to_deserialize_any! {
-        i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 str string seq map bool char unit enum tuple option struct identifier
+        i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 str string seq map bool char unit enum option struct identifier
         unit_struct tuple_struct newtype_struct
     }
 }
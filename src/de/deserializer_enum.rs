@@ -1,16 +1,21 @@
-use super::{AttributeValue, Deserializer, Error, ErrorImpl, Result};
+use super::{AttributeValue, Deserializer, DeserializerConfig, Error, ErrorImpl, Result};
 use serde::de::{
     DeserializeSeed, Deserializer as _, EnumAccess, IntoDeserializer, VariantAccess, Visitor,
 };
 use std::collections::HashMap;
+use std::sync::Arc;
 
 pub struct DeserializerEnum {
     input: HashMap<String, AttributeValue>,
+    config: Arc<DeserializerConfig>,
 }
 
 impl DeserializerEnum {
-    pub fn from_item(input: HashMap<String, AttributeValue>) -> Self {
-        Self { input }
+    pub fn from_item(
+        input: HashMap<String, AttributeValue>,
+        config: Arc<DeserializerConfig>,
+    ) -> Self {
+        Self { input, config }
     }
 }
 
@@ -29,7 +34,7 @@ impl<'de> EnumAccess<'de> for DeserializerEnum {
         if drain.next().is_some() {
             return Err(ErrorImpl::ExpectedSingleKey.into());
         }
-        let deserializer = DeserializerVariant::from_attribute_value(value);
+        let deserializer = DeserializerVariant::from_attribute_value(value, self.config);
         let value = seed.deserialize(key.into_deserializer())?;
         Ok((value, deserializer))
     }
@@ -37,11 +42,12 @@ impl<'de> EnumAccess<'de> for DeserializerEnum {
 
 pub struct DeserializerVariant {
     input: AttributeValue,
+    config: Arc<DeserializerConfig>,
 }
 
 impl DeserializerVariant {
-    pub fn from_attribute_value(input: AttributeValue) -> Self {
-        Self { input }
+    pub fn from_attribute_value(input: AttributeValue, config: Arc<DeserializerConfig>) -> Self {
+        Self { input, config }
     }
 }
 
@@ -56,7 +62,7 @@ impl<'de> VariantAccess<'de> for DeserializerVariant {
     where
         S: DeserializeSeed<'de>,
     {
-        let deserializer = Deserializer::from_attribute_value(self.input);
+        let deserializer = Deserializer::with_shared_config(self.input, self.config);
         seed.deserialize(deserializer)
     }
 
@@ -64,7 +70,7 @@ impl<'de> VariantAccess<'de> for DeserializerVariant {
     where
         V: Visitor<'de>,
     {
-        let deserializer = Deserializer::from_attribute_value(self.input);
+        let deserializer = Deserializer::with_shared_config(self.input, self.config);
         deserializer.deserialize_seq(visitor)
     }
 
@@ -72,7 +78,7 @@ impl<'de> VariantAccess<'de> for DeserializerVariant {
     where
         V: Visitor<'de>,
     {
-        let deserializer = Deserializer::from_attribute_value(self.input);
+        let deserializer = Deserializer::with_shared_config(self.input, self.config);
         deserializer.deserialize_map(visitor)
     }
 }
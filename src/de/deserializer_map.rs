@@ -1,20 +1,32 @@
-use super::{AttributeValue, Deserializer, Error, ErrorImpl, Result};
+use super::{
+    AttributeValue, Deserializer, DeserializerConfig, Error, ErrorImpl, Result, UnknownKeyPolicy,
+};
 use serde::{
     de::{self, DeserializeSeed, MapAccess, Visitor},
-    forward_to_deserialize_any, serde_if_integer128,
+    forward_to_deserialize_any,
 };
+use std::borrow::Cow;
+use std::cell::Cell;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 pub struct DeserializerMap<'a> {
     drain: std::collections::hash_map::Drain<'a, String, AttributeValue>,
     remaining_value: Option<AttributeValue>,
+    config: Arc<DeserializerConfig>,
+    key_variants: Cell<Option<&'static [&'static str]>>,
 }
 
 impl<'a> DeserializerMap<'a> {
-    pub fn from_item(item: &'a mut HashMap<String, AttributeValue>) -> Self {
+    pub fn from_item(
+        item: &'a mut HashMap<String, AttributeValue>,
+        config: Arc<DeserializerConfig>,
+    ) -> Self {
         Self {
             drain: item.drain(),
             remaining_value: None,
+            config,
+            key_variants: Cell::new(None),
         }
     }
 }
@@ -26,13 +38,33 @@ impl<'de, 'a> MapAccess<'de> for DeserializerMap<'a> {
     where
         K: DeserializeSeed<'de>,
     {
-        if let Some((key, value)) = self.drain.next() {
-            self.remaining_value = Some(value);
-            let de = DeserializerMapKey::from_string(key);
-            seed.deserialize(de).map(Some)
-        } else {
-            Ok(None)
+        let policy = self.config.unknown_keys;
+        let known = known_key(&self.config, self.key_variants.get());
+        let Some((key, value)) = self.drain.find(|(key, _)| known(key)) else {
+            return Ok(None);
+        };
+        self.remaining_value = Some(value);
+        if policy == UnknownKeyPolicy::Error {
+            return seed
+                .deserialize(DeserializerMapKey::new(Cow::Owned(key)))
+                .map(Some);
         }
+
+        let (drain, remaining_value) = (&mut self.drain, &mut self.remaining_value);
+        let mut replace = |variants: &[&str]| {
+            let (key, value) = drain.find(|(key, _)| variants.contains(&key.as_str()))?;
+            *remaining_value = Some(value);
+            Some(Cow::Owned(key))
+        };
+        let de = DeserializerMapKey::with_unknown_keys(
+            Cow::Owned(key),
+            UnknownKeys {
+                policy,
+                variants: &self.key_variants,
+                replace: &mut replace,
+            },
+        );
+        seed.deserialize(de).map(Some)
     }
 
     fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
@@ -40,7 +72,7 @@ impl<'de, 'a> MapAccess<'de> for DeserializerMap<'a> {
         V: DeserializeSeed<'de>,
     {
         if let Some(value) = self.remaining_value.take() {
-            let de = Deserializer::from_attribute_value(value);
+            let de = Deserializer::with_shared_config(value, self.config.clone());
             seed.deserialize(de)
         } else {
             unreachable!("Value without a corresponding key")
@@ -52,13 +84,64 @@ impl<'de, 'a> MapAccess<'de> for DeserializerMap<'a> {
     }
 }
 
-struct DeserializerMapKey {
-    input: String,
+/// Whether a map key should be handed to the key type, given the key type's enum variants once
+/// they are known
+pub(super) fn known_key<'c>(
+    config: &'c DeserializerConfig,
+    variants: Option<&'static [&'static str]>,
+) -> impl Fn(&str) -> bool + 'c {
+    move |key| {
+        !config.ignores(key)
+            && match (config.unknown_keys, variants) {
+                (UnknownKeyPolicy::Skip, Some(variants)) => variants.contains(&key),
+                _ => true,
+            }
+    }
+}
+
+/// How a map key handles a key that its enum type doesn't know
+///
+/// The variants are only known once the key type asks for an enum, so the first key records
+/// them for the rest of the map. A skipped first key can't be reported to the map, so `replace`
+/// swaps in the next known key and its value instead.
+pub(super) struct UnknownKeys<'m> {
+    pub(super) policy: UnknownKeyPolicy,
+    pub(super) variants: &'m Cell<Option<&'static [&'static str]>>,
+    pub(super) replace: &'m mut dyn FnMut(&[&str]) -> Option<Cow<'m, str>>,
 }
 
-impl DeserializerMapKey {
-    fn from_string(input: String) -> Self {
-        Self { input }
+/// Deserializes a map key
+///
+/// Keys of borrowed maps are borrowed too, so struct fields, which only compare the key against
+/// their names, don't allocate a copy of every attribute name.
+pub(super) struct DeserializerMapKey<'m> {
+    input: Cow<'m, str>,
+    unknown_keys: Option<UnknownKeys<'m>>,
+}
+
+impl<'m> DeserializerMapKey<'m> {
+    pub(super) fn new(input: Cow<'m, str>) -> Self {
+        Self {
+            input,
+            unknown_keys: None,
+        }
+    }
+
+    pub(super) fn with_unknown_keys(input: Cow<'m, str>, unknown_keys: UnknownKeys<'m>) -> Self {
+        Self {
+            input,
+            unknown_keys: Some(unknown_keys),
+        }
+    }
+
+    fn visit<'de, V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.input {
+            Cow::Borrowed(input) => visitor.visit_str(input),
+            Cow::Owned(input) => visitor.visit_string(input),
+        }
     }
 }
 
@@ -78,7 +161,7 @@ macro_rules! deserialize_integer_key {
     };
 }
 
-impl<'de> de::Deserializer<'de> for DeserializerMapKey {
+impl<'de> de::Deserializer<'de> for DeserializerMapKey<'_> {
     type Error = Error;
 
     // Look at the input data to decide what Serde data model type to
@@ -88,28 +171,28 @@ impl<'de> de::Deserializer<'de> for DeserializerMapKey {
     where
         V: Visitor<'de>,
     {
-        self.deserialize_string(visitor)
+        self.visit(visitor)
     }
 
     fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        visitor.visit_string(self.input)
+        self.visit(visitor)
     }
 
     fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        visitor.visit_string(self.input)
+        self.visit(visitor)
     }
 
     fn deserialize_identifier<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        visitor.visit_string(self.input)
+        self.visit(visitor)
     }
 
     fn deserialize_enum<V>(
@@ -121,7 +204,22 @@ impl<'de> de::Deserializer<'de> for DeserializerMapKey {
     where
         V: de::Visitor<'de>,
     {
-        let de = Deserializer::from_attribute_value(AttributeValue::S(self.input));
+        let mut input = self.input;
+        if let Some(unknown_keys) = self.unknown_keys {
+            unknown_keys.variants.set(Some(variants));
+            if !variants.contains(&&*input) {
+                input = match unknown_keys.policy {
+                    UnknownKeyPolicy::Skip => (unknown_keys.replace)(variants)
+                        .ok_or_else(|| de::Error::unknown_variant(&input, variants))?,
+                    UnknownKeyPolicy::CatchAll(catch_all) if variants.contains(&catch_all) => {
+                        Cow::Borrowed(catch_all)
+                    }
+                    _ => input,
+                };
+            }
+        }
+
+        let de = Deserializer::from_attribute_value(AttributeValue::S(input.into_owned()));
         de.deserialize_enum(name, variants, visitor)
     }
 
@@ -140,22 +238,18 @@ impl<'de> de::Deserializer<'de> for DeserializerMapKey {
     deserialize_integer_key!(deserialize_i16  => visit_i16);
     deserialize_integer_key!(deserialize_i32  => visit_i32);
     deserialize_integer_key!(deserialize_i64  => visit_i64);
-    serde_if_integer128! {
-        deserialize_integer_key!(deserialize_i128 => visit_i128);
-    }
+    deserialize_integer_key!(deserialize_i128 => visit_i128);
     deserialize_integer_key!(deserialize_u8   => visit_u8);
     deserialize_integer_key!(deserialize_u16  => visit_u16);
     deserialize_integer_key!(deserialize_u32  => visit_u32);
     deserialize_integer_key!(deserialize_u64  => visit_u64);
-    serde_if_integer128! {
-        deserialize_integer_key!(deserialize_u128 => visit_u128);
-    }
+    deserialize_integer_key!(deserialize_u128 => visit_u128);
 
     fn deserialize_bool<V>(self, visitor: V) -> std::result::Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        match self.input.as_str() {
+        match &*self.input {
             "true" => visitor.visit_bool(true),
             "false" => visitor.visit_bool(false),
             _ => Err(ErrorImpl::ExpectedString.into()),
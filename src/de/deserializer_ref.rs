@@ -0,0 +1,511 @@
+use super::{
+    deserializer::flattened_content,
+    deserializer_map::{known_key, DeserializerMapKey, UnknownKeys},
+    AttributeValue, Deserializer, DeserializerConfig, Error, ErrorImpl, Result, UnknownKeyPolicy,
+};
+use crate::NewtypeStructs;
+use serde::de::{self, DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor};
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// An attribute value that can be deserialized without owning it
+///
+/// Implemented for [`AttributeValue`] and for the attribute value of each supported SDK, so a
+/// borrowed SDK item is read the same way as a borrowed [`Item`](crate::Item), without converting
+/// all of it first.
+///
+/// The trait is public only so it can bound [`DeserializerRef`]; it can't be named outside the
+/// crate.
+pub trait BorrowedAttributeValue: Sized {
+    /// The entries, if this is an `M`
+    fn map(&self) -> Option<&HashMap<String, Self>>;
+
+    /// The elements, if this is an `L`
+    fn list(&self) -> Option<&[Self]>;
+
+    /// The flag, if this is a `NULL`
+    fn null(&self) -> Option<bool>;
+
+    /// Copy this value for the owning [`Deserializer`], failing if it has a type this crate
+    /// doesn't know
+    fn to_owned_value(&self) -> Result<AttributeValue>;
+
+    /// Add the size of this value, which isn't a map or list, to `stats`
+    #[cfg(feature = "stats")]
+    fn measure_scalar(&self, stats: &mut crate::ItemStats) {
+        if let Ok(value) = self.to_owned_value() {
+            stats.add_scalar(&value);
+        }
+    }
+}
+
+impl BorrowedAttributeValue for AttributeValue {
+    fn map(&self) -> Option<&HashMap<String, Self>> {
+        match self {
+            AttributeValue::M(m) => Some(m),
+            _ => None,
+        }
+    }
+
+    fn list(&self) -> Option<&[Self]> {
+        match self {
+            AttributeValue::L(l) => Some(l),
+            _ => None,
+        }
+    }
+
+    fn null(&self) -> Option<bool> {
+        match self {
+            AttributeValue::Null(null) => Some(*null),
+            _ => None,
+        }
+    }
+
+    fn to_owned_value(&self) -> Result<AttributeValue> {
+        Ok(self.clone())
+    }
+
+    #[cfg(feature = "stats")]
+    fn measure_scalar(&self, stats: &mut crate::ItemStats) {
+        stats.add_scalar(self);
+    }
+}
+
+/// Deserializes a borrowed [`AttributeValue`]
+///
+/// Maps and lists are walked by reference. Everything else is copied and handed to the owning
+/// [`Deserializer`], so only the leaves the target actually reads are copied, and the owned and
+/// borrowed paths can't disagree about how a value is interpreted.
+///
+/// Create one with [`IntoDeserializer`](serde::de::IntoDeserializer) on an `&AttributeValue`.
+#[derive(Debug)]
+pub struct DeserializerRef<'a, A = AttributeValue> {
+    input: &'a A,
+    config: Arc<DeserializerConfig>,
+}
+
+impl<'a, A> DeserializerRef<'a, A>
+where
+    A: BorrowedAttributeValue,
+{
+    pub(crate) fn from_attribute_value(input: &'a A, config: Arc<DeserializerConfig>) -> Self {
+        Self { input, config }
+    }
+
+    fn owned(self) -> Result<Deserializer> {
+        Ok(Deserializer::with_shared_config(
+            self.input.to_owned_value()?,
+            self.config,
+        ))
+    }
+}
+
+macro_rules! forward_to_owned {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V>(self, visitor: V) -> Result<V::Value>
+            where
+                V: Visitor<'de>,
+            {
+                de::Deserializer::$method(self.owned()?, visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, A> de::Deserializer<'de> for DeserializerRef<'_, A>
+where
+    A: BorrowedAttributeValue,
+{
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if self.input.map().is_some() {
+            self.deserialize_map(visitor)
+        } else if self.input.list().is_some() {
+            self.deserialize_seq(visitor)
+        } else {
+            self.owned()?.deserialize_any(visitor)
+        }
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.input.list() {
+            Some(l) => visitor.visit_seq(DeserializerSeqRef {
+                iter: l.iter(),
+                config: self.config,
+            }),
+            None => self.owned()?.deserialize_seq(visitor),
+        }
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.input.map() {
+            Some(m) => visitor.visit_map(DeserializerMapRef::from_item(m, self.config)),
+            None => self.owned()?.deserialize_map(visitor),
+        }
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.input.list() {
+            Some([element]) if self.config.coerce_singletons && element.map().is_some() => {
+                return self.owned()?.deserialize_struct(name, fields, visitor);
+            }
+            Some(_) => return self.deserialize_seq(visitor),
+            None => {}
+        }
+        match self.input.map() {
+            Some(m) if flattened_content(&self.config, fields, m).is_none() => {
+                self.deserialize_map(visitor)
+            }
+            _ => self.owned()?.deserialize_struct(name, fields, visitor),
+        }
+    }
+
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.input.list() {
+            Some(l) if l.len() != len => Err(ErrorImpl::ExpectedSeqLength(len, l.len()).into()),
+            Some(_) => self.deserialize_seq(visitor),
+            None => self.owned()?.deserialize_tuple(len, visitor),
+        }
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let is_null = match self.input.null() {
+            Some(null) => self.config.is_none(&AttributeValue::Null(null))?,
+            None => false,
+        };
+        if is_null {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if self.config.newtype_structs != NewtypeStructs::Transparent {
+            return self.owned()?.deserialize_newtype_struct(name, visitor);
+        }
+        visitor.visit_newtype_struct(self)
+    }
+
+    // Skipped values don't need to be looked at, let alone cloned
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.owned()?.deserialize_unit_struct(name, visitor)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.owned()?.deserialize_enum(name, variants, visitor)
+    }
+
+    forward_to_owned! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_i128 deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_u128 deserialize_f32 deserialize_f64 deserialize_char deserialize_str
+        deserialize_string deserialize_bytes deserialize_byte_buf deserialize_unit
+        deserialize_identifier
+    }
+}
+
+impl<'a, 'de> IntoDeserializer<'de, Error> for &'a AttributeValue {
+    type Deserializer = DeserializerRef<'a>;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        DeserializerRef::from_attribute_value(self, Arc::default())
+    }
+}
+
+/// Deserializes a borrowed [`Item`](crate::Item) as a map
+pub(crate) struct DeserializerItemRef<'a, A = AttributeValue> {
+    input: &'a HashMap<String, A>,
+    config: Arc<DeserializerConfig>,
+}
+
+impl<'a, A> DeserializerItemRef<'a, A>
+where
+    A: BorrowedAttributeValue,
+{
+    pub fn from_item(input: &'a HashMap<String, A>, config: Arc<DeserializerConfig>) -> Self {
+        Self { input, config }
+    }
+
+    fn owned(self) -> Result<Deserializer> {
+        let item = self
+            .input
+            .iter()
+            .map(|(key, value)| Ok((key.clone(), value.to_owned_value()?)))
+            .collect::<Result<_>>()?;
+        Ok(Deserializer::with_shared_config(
+            AttributeValue::M(item),
+            self.config,
+        ))
+    }
+}
+
+impl<'de, A> de::Deserializer<'de> for DeserializerItemRef<'_, A>
+where
+    A: BorrowedAttributeValue,
+{
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_map(DeserializerMapRef::from_item(self.input, self.config))
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if flattened_content(&self.config, fields, self.input).is_some() {
+            return self.owned()?.deserialize_struct(name, fields, visitor);
+        }
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if self.config.newtype_structs != NewtypeStructs::Transparent {
+            return self.owned()?.deserialize_newtype_struct(name, visitor);
+        }
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    // Anything else is an unusual way to read an item, and goes through the owning deserializer
+    // so it fails or succeeds exactly as `from_item` would
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.owned()?.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.owned()?.deserialize_tuple_struct(name, len, visitor)
+    }
+
+    fn deserialize_unit_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.owned()?.deserialize_unit_struct(name, visitor)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.owned()?.deserialize_enum(name, variants, visitor)
+    }
+
+    forward_to_owned! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_i128 deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_u128 deserialize_f32 deserialize_f64 deserialize_char deserialize_str
+        deserialize_string deserialize_bytes deserialize_byte_buf deserialize_unit
+        deserialize_identifier deserialize_seq
+    }
+}
+
+struct DeserializerSeqRef<'a, A> {
+    iter: std::slice::Iter<'a, A>,
+    config: Arc<DeserializerConfig>,
+}
+
+impl<'de, A> SeqAccess<'de> for DeserializerSeqRef<'_, A>
+where
+    A: BorrowedAttributeValue,
+{
+    type Error = Error;
+
+    fn next_element_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        if let Some(value) = self.iter.next() {
+            let de = DeserializerRef::from_attribute_value(value, self.config.clone());
+            seed.deserialize(de).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+struct DeserializerMapRef<'a, A> {
+    iter: std::collections::hash_map::Iter<'a, String, A>,
+    remaining_value: Option<&'a A>,
+    config: Arc<DeserializerConfig>,
+    key_variants: Cell<Option<&'static [&'static str]>>,
+}
+
+impl<'a, A> DeserializerMapRef<'a, A> {
+    fn from_item(item: &'a HashMap<String, A>, config: Arc<DeserializerConfig>) -> Self {
+        Self {
+            iter: item.iter(),
+            remaining_value: None,
+            config,
+            key_variants: Cell::new(None),
+        }
+    }
+}
+
+impl<'de, A> MapAccess<'de> for DeserializerMapRef<'_, A>
+where
+    A: BorrowedAttributeValue,
+{
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        let policy = self.config.unknown_keys;
+        let known = known_key(&self.config, self.key_variants.get());
+        let Some((key, value)) = self.iter.find(|(key, _)| known(key)) else {
+            return Ok(None);
+        };
+        self.remaining_value = Some(value);
+        if policy == UnknownKeyPolicy::Error {
+            return seed
+                .deserialize(DeserializerMapKey::new(Cow::Borrowed(key)))
+                .map(Some);
+        }
+
+        let (iter, remaining_value) = (&mut self.iter, &mut self.remaining_value);
+        let mut replace = |variants: &[&str]| {
+            let (key, value) = iter.find(|(key, _)| variants.contains(&key.as_str()))?;
+            *remaining_value = Some(value);
+            Some(Cow::Borrowed(key.as_str()))
+        };
+        let de = DeserializerMapKey::with_unknown_keys(
+            Cow::Borrowed(key),
+            UnknownKeys {
+                policy,
+                variants: &self.key_variants,
+                replace: &mut replace,
+            },
+        );
+        seed.deserialize(de).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        if let Some(value) = self.remaining_value.take() {
+            let de = DeserializerRef::from_attribute_value(value, self.config.clone());
+            seed.deserialize(de)
+        } else {
+            unreachable!("Value without a corresponding key")
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
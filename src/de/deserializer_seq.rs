@@ -1,16 +1,22 @@
 use super::deserializer_bytes::DeserializerBytes;
 use super::deserializer_number::DeserializerNumber;
-use super::{AttributeValue, Deserializer, Error, Result};
-use serde::de::{DeserializeSeed, IntoDeserializer, SeqAccess};
+use super::{
+    AttributeValue, BinaryPolicy, Deserializer, DeserializerConfig, Error, ErrorImpl, Result,
+};
+use serde::de::{self, DeserializeSeed, IntoDeserializer, SeqAccess, Visitor};
+use serde::forward_to_deserialize_any;
+use std::sync::Arc;
 
 pub struct DeserializerSeq {
     iter: std::vec::IntoIter<AttributeValue>,
+    config: Arc<DeserializerConfig>,
 }
 
 impl DeserializerSeq {
-    pub fn from_vec(vec: Vec<AttributeValue>) -> Self {
+    pub fn from_vec(vec: Vec<AttributeValue>, config: Arc<DeserializerConfig>) -> Self {
         Self {
             iter: vec.into_iter(),
+            config,
         }
     }
 }
@@ -23,7 +29,7 @@ impl<'de> SeqAccess<'de> for DeserializerSeq {
         S: DeserializeSeed<'de>,
     {
         if let Some(value) = self.iter.next() {
-            let de = Deserializer::from_attribute_value(value);
+            let de = Deserializer::with_shared_config(value, self.config.clone());
             seed.deserialize(de).map(Some)
         } else {
             Ok(None)
@@ -36,13 +42,13 @@ impl<'de> SeqAccess<'de> for DeserializerSeq {
 }
 
 pub struct DeserializerSeqStrings {
-    iter: std::vec::IntoIter<String>,
+    iter: std::iter::Enumerate<std::vec::IntoIter<String>>,
 }
 
 impl DeserializerSeqStrings {
     pub fn from_vec(vec: Vec<String>) -> Self {
         Self {
-            iter: vec.into_iter(),
+            iter: vec.into_iter().enumerate(),
         }
     }
 }
@@ -54,23 +60,91 @@ impl<'de> SeqAccess<'de> for DeserializerSeqStrings {
     where
         T: DeserializeSeed<'de>,
     {
-        if let Some(value) = self.iter.next() {
-            let de = value.into_deserializer();
+        if let Some((index, value)) = self.iter.next() {
+            let de = DeserializerSetString {
+                input: value,
+                index,
+            };
             seed.deserialize(de).map(Some)
         } else {
             Ok(None)
         }
     }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+/// A string set element
+///
+/// Behaves like a plain string, except that asking for a map or struct fails with an error that
+/// names the string set. Targeting `Vec<MyStruct>` at an `SS` attribute is a common mistake, and
+/// serde's own "invalid type" error doesn't make the cause obvious.
+struct DeserializerSetString {
+    input: String,
+    index: usize,
+}
+
+impl<'de> de::Deserializer<'de> for DeserializerSetString {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_string(self.input)
+    }
+
+    fn deserialize_map<V>(self, _visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        Err(ErrorImpl::ExpectedMapFoundStringSet(self.index).into())
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        IntoDeserializer::<Error>::into_deserializer(self.input)
+            .deserialize_enum(name, variants, visitor)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct identifier ignored_any
+    }
 }
 
 pub struct DeserializerSeqNumbers {
     iter: std::vec::IntoIter<String>,
+    config: Arc<DeserializerConfig>,
 }
 
 impl DeserializerSeqNumbers {
-    pub fn from_vec(vec: Vec<String>) -> Self {
+    pub fn from_vec(vec: Vec<String>, config: Arc<DeserializerConfig>) -> Self {
         Self {
             iter: vec.into_iter(),
+            config,
         }
     }
 }
@@ -83,29 +157,35 @@ impl<'de> SeqAccess<'de> for DeserializerSeqNumbers {
         T: DeserializeSeed<'de>,
     {
         if let Some(value) = self.iter.next() {
-            let de = DeserializerNumber::from_string(value);
+            let de = DeserializerNumber::from_string(self.config.number(value));
             seed.deserialize(de).map(Some)
         } else {
             Ok(None)
         }
     }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
 }
 
 pub struct DeserializerSeqBytes<T> {
-    iter: std::vec::IntoIter<T>,
+    iter: std::iter::Enumerate<std::vec::IntoIter<T>>,
+    policy: BinaryPolicy,
 }
 
 impl<T> DeserializerSeqBytes<T> {
-    pub fn from_vec(vec: Vec<T>) -> Self {
+    pub fn from_vec(vec: Vec<T>, policy: BinaryPolicy) -> Self {
         Self {
-            iter: vec.into_iter(),
+            iter: vec.into_iter().enumerate(),
+            policy,
         }
     }
 }
 
 impl<'de, B> SeqAccess<'de> for DeserializerSeqBytes<B>
 where
-    B: AsRef<[u8]>,
+    B: AsRef<[u8]> + Into<Vec<u8>>,
 {
     type Error = Error;
 
@@ -113,11 +193,15 @@ where
     where
         T: DeserializeSeed<'de>,
     {
-        if let Some(value) = self.iter.next() {
-            let de = DeserializerBytes::from_bytes(value);
+        if let Some((index, value)) = self.iter.next() {
+            let de = DeserializerBytes::from_set_element(value, index, self.policy);
             seed.deserialize(de).map(Some)
         } else {
             Ok(None)
         }
     }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
 }
@@ -2,18 +2,25 @@ use super::AttributeValue;
 use crate::{error::ErrorImpl, Error, Item, Items, Result};
 use serde::Deserialize;
 use std::collections::HashMap;
+use std::sync::Arc;
 
+mod config;
 mod deserializer;
 mod deserializer_bytes;
 mod deserializer_enum;
 mod deserializer_map;
 mod deserializer_number;
+mod deserializer_ref;
 mod deserializer_seq;
 
 #[cfg(test)]
 mod tests;
 
+pub use config::{BinaryPolicy, DeserializerConfig, NullFalsePolicy, UnknownKeyPolicy};
 pub use deserializer::Deserializer;
+pub(crate) use deserializer_ref::BorrowedAttributeValue;
+use deserializer_ref::DeserializerItemRef;
+pub use deserializer_ref::DeserializerRef;
 
 /// Interpret an [`AttributeValue`] as an instance of type `T`.
 ///
@@ -24,9 +31,36 @@ where
     AV: Into<AttributeValue>,
     T: Deserialize<'a>,
 {
-    let attribute_value: AttributeValue = attribute_value.into();
-    let deserializer = Deserializer::from_attribute_value(attribute_value);
-    T::deserialize(deserializer)
+    from_attribute_value_with_config(attribute_value, DeserializerConfig::default())
+}
+
+/// Interpret an [`AttributeValue`] as an instance of type `T`, using the given configuration.
+///
+/// See [`from_attribute_value`] and [`DeserializerConfig`].
+pub fn from_attribute_value_with_config<'a, AV, T>(
+    attribute_value: AV,
+    config: DeserializerConfig,
+) -> Result<T>
+where
+    AV: Into<AttributeValue>,
+    T: Deserialize<'a>,
+{
+    from_attribute_value_shared(attribute_value, Arc::new(config))
+}
+
+/// Interpret an [`AttributeValue`] as a `T`, using a configuration shared between calls
+pub(crate) fn from_attribute_value_shared<'a, AV, T>(
+    attribute_value: AV,
+    config: Arc<DeserializerConfig>,
+) -> Result<T>
+where
+    AV: Into<AttributeValue>,
+    T: Deserialize<'a>,
+{
+    let attribute_value = attribute_value.into();
+    #[cfg(feature = "stats")]
+    config.measure(|| crate::ItemStats::of(&attribute_value));
+    T::deserialize(Deserializer::with_shared_config(attribute_value, config))
 }
 
 /// Interpret an [`Item`] as an instance of type `T`.
@@ -56,13 +90,62 @@ where
 /// # }
 /// ```
 pub fn from_item<'a, I, T>(item: I) -> Result<T>
+where
+    I: Into<Item>,
+    T: Deserialize<'a>,
+{
+    from_item_with_config(item, DeserializerConfig::default())
+}
+
+/// Interpret an [`Item`] as an instance of type `T`, using the given configuration.
+///
+/// See [`from_item`] and [`DeserializerConfig`].
+pub fn from_item_with_config<'a, I, T>(item: I, config: DeserializerConfig) -> Result<T>
 where
     I: Into<Item>,
     T: Deserialize<'a>,
 {
     let item: Item = item.into();
-    let deserializer = Deserializer::from_attribute_value(AttributeValue::M(item.into()));
-    T::deserialize(deserializer)
+    from_attribute_value_shared(AttributeValue::M(item.into()), Arc::new(config))
+}
+
+/// Interpret an optional [`Item`] as an instance of type `T`, if there is one.
+///
+/// This is a shortcut for the common case of a [get_item] call, which returns an item only if it
+/// exists.
+///
+/// ```no_run
+/// # use __aws_sdk_dynamodb_1::client::Client;
+/// # use serde_derive::{Serialize, Deserialize};
+/// # use serde_dynamo::{from_optional_item, to_attribute_value};
+/// # use std::collections::HashMap;
+/// #
+/// # async fn get(client: &Client) -> Result<(), Box<dyn std::error::Error>> {
+/// #[derive(Serialize, Deserialize)]
+/// pub struct User {
+///     id: String,
+///     name: String,
+///     age: u8,
+/// };
+///
+/// let key = HashMap::from([
+///     (String::from("id"), to_attribute_value("fSsgVtal8TpP")?),
+/// ]);
+///
+/// let result = client.get_item().table_name("users").set_key(Some(key)).send().await?;
+///
+/// let user: Option<User> = from_optional_item(result.item)?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [get_item]: https://docs.rs/aws-sdk-dynamodb/*/aws_sdk_dynamodb/client/struct.Client.html#method.get_item
+pub fn from_optional_item<'a, I, T>(item: Option<I>) -> Result<Option<T>>
+where
+    I: Into<Item>,
+    T: Deserialize<'a>,
+{
+    item.map(from_item).transpose()
 }
 
 /// Interpret a [`Items`] as a `Vec<T>`.
@@ -91,13 +174,276 @@ where
 /// # }
 /// ```
 pub fn from_items<'a, Is, T>(items: Is) -> Result<Vec<T>>
+where
+    Is: Into<Items>,
+    T: Deserialize<'a>,
+{
+    from_items_with_config(items, DeserializerConfig::default())
+}
+
+/// Interpret a [`Items`] as a `Vec<T>`, using the given configuration.
+///
+/// See [`from_items`] and [`DeserializerConfig`].
+pub fn from_items_with_config<'a, Is, T>(items: Is, config: DeserializerConfig) -> Result<Vec<T>>
+where
+    Is: Into<Items>,
+    T: Deserialize<'a>,
+{
+    from_items_shared(items, Arc::new(config))
+}
+
+/// Interpret a [`Items`] as a `Vec<T>`, using a configuration shared between calls
+pub(crate) fn from_items_shared<'a, Is, T>(
+    items: Is,
+    config: Arc<DeserializerConfig>,
+) -> Result<Vec<T>>
 where
     Is: Into<Items>,
     T: Deserialize<'a>,
 {
     let items: Items = items.into();
     let items = Vec::<HashMap<String, AttributeValue>>::from(items);
+    #[cfg(feature = "stats")]
+    for item in &items {
+        config.measure(|| crate::ItemStats::of_borrowed_item(item));
+    }
     let attribute_value = AttributeValue::L(items.into_iter().map(AttributeValue::M).collect());
-    let deserializer = Deserializer::from_attribute_value(attribute_value);
-    Vec::<T>::deserialize(deserializer)
+    Vec::<T>::deserialize(Deserializer::with_shared_config(attribute_value, config))
+}
+
+/// Interpret each item yielded by an iterator as a `T`, collecting them into a `Vec<T>`.
+///
+/// Unlike [`from_items`], this doesn't need the items collected into a `Vec` first, so it takes
+/// any iterator of maps, such as a paginator adapter or the owned items of a slice.
+///
+/// ```
+/// use serde_dynamo::AttributeValue;
+/// # use serde_derive::Deserialize;
+/// use std::collections::HashMap;
+///
+/// #[derive(Deserialize)]
+/// struct User {
+///     id: String,
+/// }
+///
+/// let pages = vec![
+///     vec![HashMap::from([(String::from("id"), AttributeValue::S(String::from("a")))])],
+///     vec![HashMap::from([(String::from("id"), AttributeValue::S(String::from("b")))])],
+/// ];
+///
+/// let users: Vec<User> = serde_dynamo::from_items_iter(pages.into_iter().flatten())?;
+/// assert_eq!(users[1].id, "b");
+/// # Ok::<(), serde_dynamo::Error>(())
+/// ```
+pub fn from_items_iter<'a, Is, T>(items: Is) -> Result<Vec<T>>
+where
+    Is: IntoIterator,
+    Is::Item: Into<Item>,
+    T: Deserialize<'a>,
+{
+    from_items_iter_with_config(items, DeserializerConfig::default())
+}
+
+/// Interpret each item yielded by an iterator as a `T`, using the given configuration.
+///
+/// See [`from_items_iter`] and [`DeserializerConfig`].
+pub fn from_items_iter_with_config<'a, Is, T>(
+    items: Is,
+    config: DeserializerConfig,
+) -> Result<Vec<T>>
+where
+    Is: IntoIterator,
+    Is::Item: Into<Item>,
+    T: Deserialize<'a>,
+{
+    from_items_iter_shared(items, Arc::new(config))
+}
+
+/// Interpret each item yielded by an iterator as a `T`, using a configuration shared between
+/// calls
+pub(crate) fn from_items_iter_shared<'a, Is, T>(
+    items: Is,
+    config: Arc<DeserializerConfig>,
+) -> Result<Vec<T>>
+where
+    Is: IntoIterator,
+    Is::Item: Into<Item>,
+    T: Deserialize<'a>,
+{
+    items
+        .into_iter()
+        .map(|item| {
+            let item = AttributeValue::M(item.into().into());
+            #[cfg(feature = "stats")]
+            config.measure(|| crate::ItemStats::of(&item));
+            T::deserialize(Deserializer::with_shared_config(item, config.clone()))
+        })
+        .collect()
+}
+
+/// An SDK attribute value that converts into an [`AttributeValue`]
+///
+/// The SDK modules' `From` conversions panic on values of a type this crate doesn't know, such as
+/// one added to DynamoDB after the SDK version was released, or a rusoto value with no field set.
+/// Their `from_*` functions convert with this instead, and fail with
+/// [`ErrorCode::UnsupportedType`](crate::ErrorCode::UnsupportedType).
+// Only implemented with one of the SDK features
+#[allow(dead_code)]
+pub(crate) trait IntoAttributeValue {
+    fn into_attribute_value(self) -> Result<AttributeValue>;
+}
+
+/// Convert an SDK item, failing on the first attribute of an unknown type
+#[allow(dead_code)]
+pub(crate) fn try_into_item<AV>(item: HashMap<String, AV>) -> Result<Item>
+where
+    AV: IntoAttributeValue,
+{
+    item.into_iter()
+        .map(|(key, value)| Ok((key, value.into_attribute_value()?)))
+        .collect::<Result<HashMap<_, _>>>()
+        .map(Item::from)
+}
+
+/// Interpret a borrowed [`Item`] as an instance of type `T`.
+///
+/// Unlike [`from_item`], this doesn't need to own the item. Only the leaf values that `T`
+/// actually reads are copied, so several types can be extracted from the same item without
+/// cloning it first.
+///
+/// ```
+/// use serde_dynamo::{AttributeValue, Item};
+/// # use serde_derive::Deserialize;
+/// use std::collections::HashMap;
+///
+/// #[derive(Deserialize)]
+/// struct Id {
+///     id: String,
+/// }
+///
+/// let item = Item::from(HashMap::from([
+///     (String::from("id"), AttributeValue::S(String::from("u-42"))),
+///     (String::from("bio"), AttributeValue::S(String::from("A long biography..."))),
+/// ]));
+///
+/// let id: Id = serde_dynamo::from_item_ref(&item)?;
+/// assert_eq!(id.id, "u-42");
+/// # Ok::<(), serde_dynamo::Error>(())
+/// ```
+pub fn from_item_ref<'a, T>(item: &Item) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    from_item_ref_with_config(item, DeserializerConfig::default())
+}
+
+/// Interpret a borrowed [`Item`] as an instance of type `T`, using the given configuration.
+///
+/// See [`from_item_ref`] and [`DeserializerConfig`].
+pub fn from_item_ref_with_config<'a, T>(item: &Item, config: DeserializerConfig) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    from_borrowed_item(item, Arc::new(config))
+}
+
+/// Interpret a slice of borrowed [`Item`]s as a `Vec<T>`.
+///
+/// This is [`from_items`] for a page of items that is still needed afterwards, such as when the
+/// same page is passed to more than one typed extractor. See [`from_item_ref`].
+///
+/// ```
+/// use serde_dynamo::{AttributeValue, Item};
+/// # use serde_derive::Deserialize;
+/// use std::collections::HashMap;
+///
+/// #[derive(Deserialize)]
+/// struct Id {
+///     id: String,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Age {
+///     age: u8,
+/// }
+///
+/// let page = vec![Item::from(HashMap::from([
+///     (String::from("id"), AttributeValue::S(String::from("u-42"))),
+///     (String::from("age"), AttributeValue::N(String::from("37"))),
+/// ]))];
+///
+/// let ids: Vec<Id> = serde_dynamo::from_items_ref(&page)?;
+/// let ages: Vec<Age> = serde_dynamo::from_items_ref(&page)?;
+/// assert_eq!(ids[0].id, "u-42");
+/// assert_eq!(ages[0].age, 37);
+/// # Ok::<(), serde_dynamo::Error>(())
+/// ```
+pub fn from_items_ref<'a, T>(items: &[Item]) -> Result<Vec<T>>
+where
+    T: Deserialize<'a>,
+{
+    from_items_ref_with_config(items, DeserializerConfig::default())
+}
+
+/// Interpret a slice of borrowed [`Item`]s as a `Vec<T>`, using the given configuration.
+///
+/// See [`from_items_ref`] and [`DeserializerConfig`].
+pub fn from_items_ref_with_config<'a, T>(
+    items: &[Item],
+    config: DeserializerConfig,
+) -> Result<Vec<T>>
+where
+    T: Deserialize<'a>,
+{
+    from_borrowed_items(items.iter().map(Item::inner), Arc::new(config))
+}
+
+/// Interpret a borrowed map of any [`BorrowedAttributeValue`] as an instance of type `T`
+///
+/// This is how the SDK modules read borrowed SDK items without converting them first.
+pub(crate) fn from_borrowed_item<'a, A, T>(
+    item: &HashMap<String, A>,
+    config: Arc<DeserializerConfig>,
+) -> Result<T>
+where
+    A: BorrowedAttributeValue,
+    T: Deserialize<'a>,
+{
+    #[cfg(feature = "stats")]
+    config.measure(|| crate::ItemStats::of_borrowed_item(item));
+    T::deserialize(DeserializerItemRef::from_item(item, config))
+}
+
+/// Interpret borrowed maps of any [`BorrowedAttributeValue`] as a `Vec<T>`
+///
+/// See [`from_borrowed_item`].
+pub(crate) fn from_borrowed_items<'a, 'b, A, I, T>(
+    items: I,
+    config: Arc<DeserializerConfig>,
+) -> Result<Vec<T>>
+where
+    A: BorrowedAttributeValue + 'b,
+    I: IntoIterator<Item = &'b HashMap<String, A>>,
+    T: Deserialize<'a>,
+{
+    items
+        .into_iter()
+        .map(|item| {
+            #[cfg(feature = "stats")]
+            config.measure(|| crate::ItemStats::of_borrowed_item(item));
+            T::deserialize(DeserializerItemRef::from_item(item, config.clone()))
+        })
+        .collect()
+}
+
+/// Interpret a borrowed [`AttributeValue`] as an instance of type `T`
+pub(crate) fn from_attribute_value_ref<'a, T>(attribute_value: &AttributeValue) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    let config = Arc::new(DeserializerConfig::default());
+    T::deserialize(DeserializerRef::from_attribute_value(
+        attribute_value,
+        config,
+    ))
 }
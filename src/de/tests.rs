@@ -179,6 +179,47 @@ fn deserialize_byte_arrays() {
     );
 }
 
+#[test]
+fn deserialize_fixed_width_bytes() {
+    use crate::error::ErrorImpl;
+    use crate::Error;
+
+    #[derive(Debug, Deserialize, Eq, PartialEq)]
+    struct Subject {
+        hash: [u8; 4],
+        hashes: Vec<[u8; 2]>,
+    }
+
+    let attribute_value = AttributeValue::M(HashMap::from([
+        (String::from("hash"), AttributeValue::B(vec![1, 2, 3, 4])),
+        (
+            String::from("hashes"),
+            AttributeValue::Bs(vec![vec![1, 2], vec![3, 4]]),
+        ),
+    ]));
+    let s: Subject = from_attribute_value(attribute_value).unwrap();
+    assert_eq!(
+        s,
+        Subject {
+            hash: [1, 2, 3, 4],
+            hashes: vec![[1, 2], [3, 4]],
+        }
+    );
+
+    let err = from_attribute_value::<_, [u8; 4]>(AttributeValue::B(vec![1, 2])).unwrap_err();
+    assert_eq!(
+        err,
+        Into::<Error>::into(ErrorImpl::ExpectedBytesLength(4, 2, None))
+    );
+
+    let attribute_value = AttributeValue::Bs(vec![vec![1, 2], vec![3, 4, 5]]);
+    let err = from_attribute_value::<_, Vec<[u8; 2]>>(attribute_value).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Expected binary data of 2 bytes, found 3 at binary set index 1"
+    );
+}
+
 #[test]
 fn deserialize_struct_with_aws_extra_data() {
     #[derive(Debug, Serialize, Deserialize, Eq, PartialEq)]
@@ -367,6 +408,53 @@ fn deserialize_tuple() {
     assert_identical_json!((usize, usize), attribute_value.clone())
 }
 
+#[test]
+fn deserialize_fixed_size_arrays() {
+    let attribute_value = AttributeValue::Ns(vec![
+        String::from("0.5"),
+        String::from("1"),
+        String::from("-2"),
+        String::from("3.25"),
+    ]);
+    let s: [f32; 4] = from_attribute_value(attribute_value).unwrap();
+    assert_eq!(s, [0.5, 1.0, -2.0, 3.25]);
+
+    let attribute_value = AttributeValue::L(vec![
+        AttributeValue::N(String::from("1")),
+        AttributeValue::N(String::from("2")),
+    ]);
+    let s: [u8; 2] = from_attribute_value(attribute_value.clone()).unwrap();
+    assert_eq!(s, [1, 2]);
+    assert_identical_json!([u8; 2], attribute_value.clone());
+
+    let attribute_value = AttributeValue::Ss(vec![String::from("a"), String::from("b")]);
+    let s: [String; 2] = from_attribute_value(attribute_value).unwrap();
+    assert_eq!(s, [String::from("a"), String::from("b")]);
+}
+
+#[test]
+fn deserialize_fixed_size_arrays_with_wrong_length() {
+    use crate::error::ErrorImpl;
+    use crate::Error;
+
+    let too_short = AttributeValue::Ns(vec![String::from("1"), String::from("2")]);
+    let err = from_attribute_value::<_, [f32; 4]>(too_short).unwrap_err();
+    assert_eq!(err, Into::<Error>::into(ErrorImpl::ExpectedSeqLength(4, 2)));
+    assert_eq!(err.to_string(), "Expected seq of 4 elements, found 2");
+
+    let too_long = AttributeValue::L(vec![
+        AttributeValue::N(String::from("1")),
+        AttributeValue::N(String::from("2")),
+        AttributeValue::N(String::from("3")),
+    ]);
+    let err = from_attribute_value::<_, (u8, u8)>(too_long).unwrap_err();
+    assert_eq!(err, Into::<Error>::into(ErrorImpl::ExpectedSeqLength(2, 3)));
+
+    let not_a_list = AttributeValue::N(String::from("1"));
+    let err = from_attribute_value::<_, [u8; 1]>(not_a_list).unwrap_err();
+    assert_eq!(err, Into::<Error>::into(ErrorImpl::ExpectedSeq));
+}
+
 #[test]
 fn deserialize_map_with_strings() {
     let attribute_value = AttributeValue::M(HashMap::from([
@@ -541,6 +629,39 @@ fn deserialize_enum_struct_variant() {
     assert_identical_json!(Subject, attribute_value.clone())
 }
 
+#[test]
+fn deserialize_enum_errors_list_variants() {
+    use crate::{dynamo_item, from_item, from_item_ref, Item};
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    enum Shape {
+        Circle,
+        Square(u8),
+        Line { length: u8 },
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Drawing {
+        shape: Shape,
+    }
+
+    let message = |item: Item| {
+        let borrowed = from_item_ref::<Drawing>(&item).unwrap_err();
+        let owned = from_item::<_, Drawing>(item).unwrap_err();
+        assert_eq!(borrowed, owned);
+        owned.to_string()
+    };
+
+    assert_eq!(
+        message(dynamo_item! { "shape": "Triangle" }),
+        "unknown variant `Triangle`, expected one of `Circle`, `Square`, `Line`"
+    );
+    assert_eq!(
+        message(dynamo_item! { "shape": 3 }),
+        "Expected enum, one of `Circle`, `Square`, `Line`"
+    );
+}
+
 #[test]
 fn deserialize_internally_tagged_enum() {
     #[derive(Debug, Deserialize, Eq, PartialEq)]
@@ -561,6 +682,350 @@ fn deserialize_internally_tagged_enum() {
     assert_identical_json!(Subject, attribute_value.clone())
 }
 
+#[test]
+fn deserialize_untagged_enum_from_sets_and_bytes() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(untagged)]
+    enum Subject {
+        Numbers(Vec<u8>),
+        Strings(Vec<String>),
+        Struct {
+            blob: serde_bytes::ByteBuf,
+            tags: Vec<String>,
+        },
+    }
+
+    let attribute_value = AttributeValue::Ns(vec![String::from("1"), String::from("2")]);
+    let s: Subject = from_attribute_value(attribute_value).unwrap();
+    assert_eq!(s, Subject::Numbers(vec![1, 2]));
+
+    let attribute_value = AttributeValue::Ss(vec![String::from("a"), String::from("b")]);
+    let s: Subject = from_attribute_value(attribute_value).unwrap();
+    assert_eq!(
+        s,
+        Subject::Strings(vec![String::from("a"), String::from("b")])
+    );
+
+    let attribute_value = AttributeValue::M(HashMap::from([
+        (String::from("blob"), AttributeValue::B(vec![0, 1, 2])),
+        (
+            String::from("tags"),
+            AttributeValue::Ss(vec![String::from("x")]),
+        ),
+    ]));
+    let s: Subject = from_attribute_value(attribute_value).unwrap();
+    assert_eq!(
+        s,
+        Subject::Struct {
+            blob: serde_bytes::ByteBuf::from(vec![0, 1, 2]),
+            tags: vec![String::from("x")],
+        }
+    );
+}
+
+#[test]
+fn deserialize_string_set_into_structs() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Tag {
+        name: String,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    enum Color {
+        Red,
+    }
+
+    let attribute_value = AttributeValue::Ss(vec![String::from("a"), String::from("b")]);
+    let err = from_attribute_value::<_, Vec<Tag>>(attribute_value).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Expected map, found string set element at index 0; a string set can only be deserialized as a sequence of strings"
+    );
+
+    let attribute_value = AttributeValue::Ss(vec![String::from("Red")]);
+    let s: Vec<Color> = from_attribute_value(attribute_value).unwrap();
+    assert_eq!(s, vec![Color::Red]);
+}
+
+#[test]
+fn deserialize_coerced_bools() {
+    use crate::{from_attribute_value_with_config, DeserializerConfig};
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Subject {
+        active: bool,
+        flags: Vec<bool>,
+    }
+
+    let attribute_value = AttributeValue::M(HashMap::from([
+        (String::from("active"), AttributeValue::N(String::from("1"))),
+        (
+            String::from("flags"),
+            AttributeValue::L(vec![
+                AttributeValue::N(String::from("0")),
+                AttributeValue::S(String::from("true")),
+                AttributeValue::Bool(false),
+            ]),
+        ),
+    ]));
+
+    let err = from_attribute_value::<_, Subject>(attribute_value.clone()).unwrap_err();
+    assert_eq!(err.to_string(), "Expected bool");
+
+    let config = DeserializerConfig::new().coerce_bools(true);
+    let s: Subject = from_attribute_value_with_config(attribute_value, config.clone()).unwrap();
+    assert_eq!(
+        s,
+        Subject {
+            active: true,
+            flags: vec![false, true, false],
+        }
+    );
+
+    let err =
+        from_attribute_value_with_config::<_, bool>(AttributeValue::N(String::from("2")), config)
+            .unwrap_err();
+    assert_eq!(err.to_string(), "Expected bool");
+}
+
+#[test]
+fn deserialize_coerced_strings() {
+    use crate::{from_attribute_value_with_config, DeserializerConfig};
+
+    let attribute_value = AttributeValue::M(HashMap::from([
+        (
+            String::from("count"),
+            AttributeValue::N(String::from("1.50")),
+        ),
+        (String::from("active"), AttributeValue::Bool(true)),
+        (String::from("name"), AttributeValue::S(String::from("one"))),
+    ]));
+
+    let err =
+        from_attribute_value::<_, HashMap<String, String>>(attribute_value.clone()).unwrap_err();
+    assert_eq!(err.to_string(), "Expected string");
+
+    let config = DeserializerConfig::new().coerce_strings(true);
+    let s: HashMap<String, String> =
+        from_attribute_value_with_config(attribute_value, config.clone()).unwrap();
+    assert_eq!(
+        s,
+        HashMap::from([
+            (String::from("count"), String::from("1.50")),
+            (String::from("active"), String::from("true")),
+            (String::from("name"), String::from("one")),
+        ])
+    );
+
+    let err = from_attribute_value_with_config::<_, String>(AttributeValue::Null(true), config)
+        .unwrap_err();
+    assert_eq!(err.to_string(), "Expected string");
+}
+
+#[test]
+fn deserialize_coerced_base64() {
+    use crate::{from_attribute_value_with_config, DeserializerConfig, Warnings};
+
+    let binary = AttributeValue::B(b"PNG".to_vec());
+    let string = AttributeValue::S(String::from("UE5H"));
+
+    let err = from_attribute_value::<_, String>(binary.clone()).unwrap_err();
+    assert_eq!(err.to_string(), "Expected string");
+    let err = from_attribute_value::<_, Vec<u8>>(string.clone()).unwrap_err();
+    assert_eq!(err.to_string(), "Expected seq");
+
+    let warnings = Warnings::new();
+    let config = DeserializerConfig::new()
+        .coerce_base64(true)
+        .warnings(warnings.clone());
+    let s: String = from_attribute_value_with_config(binary, config.clone()).unwrap();
+    assert_eq!(s, "UE5H");
+    let b: Vec<u8> = from_attribute_value_with_config(string.clone(), config.clone()).unwrap();
+    assert_eq!(b, b"PNG");
+    let b: serde_bytes::ByteBuf = from_attribute_value_with_config(string, config.clone()).unwrap();
+    assert_eq!(b.into_vec(), b"PNG");
+    assert_eq!(warnings.take().len(), 3);
+
+    let err = from_attribute_value_with_config::<_, Vec<u8>>(
+        AttributeValue::S(String::from("P?")),
+        config,
+    )
+    .unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("Expected binary data or a base64 string: "));
+}
+
+#[test]
+fn deserialize_coerced_singletons() {
+    use crate::{
+        dynamo_item, dynamo_value, from_attribute_value_with_config, from_item_ref,
+        from_item_ref_with_config, DeserializerConfig, Warning, Warnings,
+    };
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Address {
+        city: String,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Subject {
+        age: u8,
+        name: String,
+        active: bool,
+        tags: Vec<String>,
+        address: Address,
+        nickname: Option<String>,
+    }
+
+    let item = dynamo_item! {
+        "age": [42],
+        "name": ["one"],
+        "active": [true],
+        "tags": "new",
+        "address": [{ "city": "Oslo" }],
+        "nickname": ["o"],
+    };
+    let expected = Subject {
+        age: 42,
+        name: String::from("one"),
+        active: true,
+        tags: vec![String::from("new")],
+        address: Address {
+            city: String::from("Oslo"),
+        },
+        nickname: Some(String::from("o")),
+    };
+
+    assert!(from_item_ref::<Subject>(&item).is_err());
+
+    let warnings = Warnings::new();
+    let config = DeserializerConfig::new()
+        .coerce_singletons(true)
+        .warnings(warnings.clone());
+    let s: Subject = from_item_ref_with_config(&item, config.clone()).unwrap();
+    assert_eq!(s, expected);
+    let s: Subject = from_attribute_value_with_config(
+        AttributeValue::M(item.clone().into_inner()),
+        config.clone(),
+    )
+    .unwrap();
+    assert_eq!(s, expected);
+    let taken = warnings.take();
+    assert_eq!(taken.len(), 12);
+    assert!(taken.contains(&Warning::CoercedType {
+        from: "S",
+        to: "list",
+    }));
+    assert!(taken.contains(&Warning::CoercedType {
+        from: "L",
+        to: "single value",
+    }));
+
+    // Lists of any other length, and nulls read as sequences, are still errors
+    let err = from_attribute_value_with_config::<_, u8>(dynamo_value!([1, 2]), config.clone())
+        .unwrap_err();
+    assert_eq!(err.to_string(), "Expected num");
+    let err =
+        from_attribute_value_with_config::<_, u8>(dynamo_value!([]), config.clone()).unwrap_err();
+    assert_eq!(err.to_string(), "Expected num");
+    let err = from_attribute_value_with_config::<_, Vec<u8>>(dynamo_value!(null), config.clone())
+        .unwrap_err();
+    assert_eq!(err.to_string(), "Expected seq");
+
+    // Sequences still read lists of one as themselves
+    let l: Vec<Vec<u8>> = from_attribute_value_with_config(dynamo_value!([[1]]), config).unwrap();
+    assert_eq!(l, [[1]]);
+}
+
+#[test]
+fn deserialize_coerced_base64_and_singletons() {
+    use crate::{
+        dynamo_item, from_item_ref_with_config, from_item_with_config, DeserializerConfig,
+    };
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Subject {
+        tags: Vec<String>,
+        image: Vec<u8>,
+    }
+
+    // Strings that aren't base64 are lists of one, and base64 strings are decoded
+    let item = dynamo_item! {
+        "tags": "abc",
+        "image": "UE5H",
+    };
+    let config = DeserializerConfig::new()
+        .coerce_base64(true)
+        .coerce_singletons(true);
+    let expected = Subject {
+        tags: vec![String::from("abc")],
+        image: b"PNG".to_vec(),
+    };
+    let s: Subject = from_item_ref_with_config(&item, config.clone()).unwrap();
+    assert_eq!(s, expected);
+    let s: Subject = from_item_with_config(item, config.clone()).unwrap();
+    assert_eq!(s, expected);
+
+    // Base64 decoding comes first, so a string that is valid base64 isn't a list of strings
+    let item = dynamo_item! {
+        "tags": "abcd",
+        "image": "UE5H",
+    };
+    assert!(from_item_ref_with_config::<Subject>(&item, config).is_err());
+}
+
+#[test]
+fn deserialize_null_false() {
+    use crate::{
+        from_attribute_value_with_config, from_item_ref, from_item_ref_with_config,
+        DeserializerConfig, Item, NullFalsePolicy,
+    };
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Marker;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Subject {
+        nickname: Option<String>,
+    }
+
+    let null = AttributeValue::Null(false);
+    let item = Item::from(HashMap::from([(String::from("nickname"), null.clone())]));
+
+    assert_eq!(
+        from_attribute_value::<_, Option<u8>>(null.clone()),
+        Ok(None)
+    );
+    assert_eq!(from_attribute_value::<_, ()>(null.clone()), Ok(()));
+    assert_eq!(from_attribute_value::<_, Marker>(null.clone()), Ok(Marker));
+    assert_eq!(
+        from_attribute_value::<_, serde_json::Value>(null.clone()),
+        Ok(serde_json::Value::Null)
+    );
+    assert_eq!(
+        from_item_ref::<Subject>(&item),
+        Ok(Subject { nickname: None })
+    );
+
+    let config = DeserializerConfig::new().null_false(NullFalsePolicy::Reject);
+    let message = "Found `NULL: false`, which the null policy rejects";
+    for err in [
+        from_attribute_value_with_config::<_, Option<u8>>(null.clone(), config.clone())
+            .unwrap_err(),
+        from_attribute_value_with_config::<_, ()>(null.clone(), config.clone()).unwrap_err(),
+        from_attribute_value_with_config::<_, Marker>(null.clone(), config.clone()).unwrap_err(),
+        from_attribute_value_with_config::<_, serde_json::Value>(null, config.clone()).unwrap_err(),
+        from_item_ref_with_config::<Subject>(&item, config.clone()).unwrap_err(),
+    ] {
+        assert!(err.to_string().ends_with(message), "{err}");
+    }
+
+    let value: Option<u8> =
+        from_attribute_value_with_config(AttributeValue::Null(true), config).unwrap();
+    assert_eq!(value, None);
+}
+
 #[test]
 fn deserialize_chrono_datetime() {
     use chrono::{DateTime, Utc};
@@ -703,3 +1168,524 @@ mod issue_87 {
         assert_identical_json!(Subject, attribute_value.clone())
     }
 }
+
+#[test]
+fn deserialize_binary_into_json_values() {
+    use crate::{from_attribute_value_with_config, BinaryPolicy, DeserializerConfig};
+
+    let value = AttributeValue::M(HashMap::from([
+        (String::from("blob"), AttributeValue::B(vec![0xff, 0x00])),
+        (
+            String::from("blobs"),
+            AttributeValue::Bs(vec![vec![1], vec![2, 3]]),
+        ),
+    ]));
+    let config = |policy| DeserializerConfig::new().binary_policy(policy);
+
+    let json: serde_json::Value =
+        from_attribute_value_with_config(value.clone(), config(BinaryPolicy::Base64)).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({"blob": "/wA=", "blobs": ["AQ==", "AgM="]})
+    );
+
+    let json: serde_json::Value =
+        from_attribute_value_with_config(value.clone(), config(BinaryPolicy::ByteArray)).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({"blob": [255, 0], "blobs": [[1], [2, 3]]})
+    );
+
+    let err = from_attribute_value_with_config::<_, serde_json::Value>(
+        AttributeValue::Bs(vec![vec![1], vec![2, 3]]),
+        config(BinaryPolicy::Reject),
+    )
+    .unwrap_err();
+    assert_eq!(err, crate::error::ErrorImpl::BinaryRejected(Some(0)).into());
+
+    // Typed targets still receive the raw bytes, and ignored fields aren't rejected
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Subject {
+        blob: serde_bytes::ByteBuf,
+    }
+    let subject: Subject =
+        from_attribute_value_with_config(value, config(BinaryPolicy::Reject)).unwrap();
+    assert_eq!(subject.blob.into_vec(), vec![0xff, 0x00]);
+}
+
+#[test]
+fn deserialize_borrowed_items_like_owned_items() {
+    use crate::{from_item, from_item_ref, from_items_ref, to_item, Item};
+
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+    enum Kind {
+        Unit,
+        Newtype(u8),
+        Struct { a: String },
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+    struct Wrapper(Vec<u32>);
+
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+    struct Inner {
+        name: String,
+        pair: (i64, bool),
+        kinds: Vec<Kind>,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+    struct Subject {
+        id: String,
+        missing: Option<u8>,
+        null: Option<u8>,
+        inner: Option<Inner>,
+        wrapper: Wrapper,
+        map: HashMap<u16, Vec<Inner>>,
+        #[serde(with = "crate::string_set")]
+        tags: Vec<String>,
+        #[serde(with = "serde_bytes")]
+        blob: Vec<u8>,
+    }
+
+    let inner = Inner {
+        name: String::from("inner"),
+        pair: (-3, true),
+        kinds: vec![
+            Kind::Unit,
+            Kind::Newtype(4),
+            Kind::Struct {
+                a: String::from("a"),
+            },
+        ],
+    };
+    let subject = Subject {
+        id: String::from("s-1"),
+        missing: None,
+        null: None,
+        inner: Some(inner.clone()),
+        wrapper: Wrapper(vec![1, 2]),
+        map: HashMap::from([(7, vec![inner])]),
+        tags: vec![String::from("x")],
+        blob: vec![1, 2, 3],
+    };
+
+    let mut item: Item = to_item(&subject).unwrap();
+    item.remove("missing");
+    item.insert(String::from("ignored"), AttributeValue::Bool(true));
+
+    let borrowed: Subject = from_item_ref(&item).unwrap();
+    assert_eq!(borrowed, subject);
+    assert_eq!(borrowed, from_item::<_, Subject>(item.clone()).unwrap());
+
+    let page = vec![item.clone(), item];
+    let subjects: Vec<Subject> = from_items_ref(&page).unwrap();
+    assert_eq!(subjects, vec![subject.clone(), subject]);
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Pair {
+        pair: (i64, bool, u8),
+    }
+    let item = Item::from(HashMap::from([(
+        String::from("pair"),
+        AttributeValue::L(vec![
+            AttributeValue::N(String::from("1")),
+            AttributeValue::Bool(false),
+        ]),
+    )]));
+    assert_eq!(
+        from_item_ref::<Pair>(&item).unwrap_err(),
+        from_item::<_, Pair>(item).unwrap_err(),
+    );
+}
+
+#[test]
+fn deserialize_unknown_enum_map_keys() {
+    use crate::{dynamo_item, UnknownKeyPolicy};
+    use crate::{from_item_ref_with_config, from_item_with_config, DeserializerConfig, Item};
+
+    #[derive(Debug, Deserialize, PartialEq, Eq, Hash)]
+    enum Color {
+        Red,
+        Blue,
+        Other,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Palette {
+        counts: HashMap<Color, u32>,
+    }
+
+    let item: Item = dynamo_item! {
+        "counts": { "Red": 1, "Green": 2, "Blue": 3, "Pink": 4 },
+    };
+    let read = |policy| {
+        let config = DeserializerConfig::new().unknown_keys(policy);
+        let borrowed = from_item_ref_with_config::<Palette>(&item, config.clone());
+        let owned = from_item_with_config::<_, Palette>(item.clone(), config);
+        assert_eq!(borrowed, owned);
+        owned
+    };
+
+    assert!(read(UnknownKeyPolicy::Error).is_err());
+    assert_eq!(
+        read(UnknownKeyPolicy::Skip).unwrap().counts,
+        HashMap::from([(Color::Red, 1), (Color::Blue, 3)])
+    );
+    let caught = read(UnknownKeyPolicy::CatchAll("Other")).unwrap().counts;
+    assert_eq!(caught.len(), 3);
+    assert!([2, 4].contains(&caught[&Color::Other]));
+    assert!(read(UnknownKeyPolicy::CatchAll("Missing")).is_err());
+
+    // Keys that aren't enums are unaffected
+    let config = DeserializerConfig::new().unknown_keys(UnknownKeyPolicy::Skip);
+    let strings: HashMap<String, HashMap<String, u32>> =
+        from_item_with_config(item.clone(), config.clone()).unwrap();
+    assert_eq!(strings["counts"].len(), 4);
+
+    // Every key being unknown leaves nothing to stand in for the first one
+    let item: Item = dynamo_item! { "counts": { "Green": 2 } };
+    assert_eq!(
+        from_item_with_config::<_, Palette>(item, config)
+            .unwrap_err()
+            .to_string(),
+        "unknown variant `Green`, expected one of `Red`, `Blue`, `Other`"
+    );
+}
+
+#[test]
+fn deserialize_unusual_number_text() {
+    use crate::{from_attribute_value_with_config, DeserializerConfig, Warning, Warnings};
+
+    let n = |s: &str| AttributeValue::N(String::from(s));
+    let strict = DeserializerConfig::new;
+
+    // Negative zero is zero for every target
+    for zero in ["-0", "-0.0", "-0E3"] {
+        assert_eq!(
+            from_attribute_value::<_, u64>(n(zero)).unwrap(),
+            0,
+            "{zero}"
+        );
+        assert_eq!(from_attribute_value::<_, i8>(n(zero)).unwrap(), 0, "{zero}");
+        let float: f64 = from_attribute_value(n(zero)).unwrap();
+        assert!(float == 0.0 && float.is_sign_positive(), "{zero}");
+    }
+
+    let warnings = Warnings::new();
+    let lenient = || {
+        DeserializerConfig::new()
+            .lenient_numbers(true)
+            .warnings(warnings.clone())
+    };
+    for (input, expected) in [
+        (" 42", 42),
+        ("42\n", 42),
+        ("+42", 42),
+        ("007", 7),
+        ("-007", -7),
+    ] {
+        assert_eq!(
+            from_attribute_value_with_config::<_, i64>(n(input), lenient()).unwrap(),
+            expected,
+            "{input:?}"
+        );
+        let float: f64 = from_attribute_value_with_config(n(input), lenient()).unwrap();
+        assert_eq!(float, expected as f64, "{input:?}");
+    }
+    assert_eq!(
+        from_attribute_value_with_config::<_, serde_json::Value>(n(" 0.50 "), lenient()).unwrap(),
+        serde_json::json!(0.5)
+    );
+    assert_eq!(
+        from_attribute_value_with_config::<_, Vec<u8>>(
+            AttributeValue::Ns(vec![String::from(" 1")]),
+            lenient()
+        )
+        .unwrap(),
+        vec![1]
+    );
+    assert_eq!(
+        warnings.take()[0],
+        Warning::NormalizedNumber {
+            original: String::from(" 42")
+        }
+    );
+
+    for input in [" 42", "42\n", "4 2"] {
+        assert!(
+            from_attribute_value_with_config::<_, i64>(n(input), strict()).is_err(),
+            "{input:?}"
+        );
+    }
+    assert!(from_attribute_value_with_config::<_, i64>(n("4 2"), lenient()).is_err());
+}
+
+#[test]
+fn deserialize_nonzero_integers() {
+    use std::num::{NonZeroI32, NonZeroU64, NonZeroU8};
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Session {
+        #[serde(default, with = "crate::zero_as_none")]
+        expires_at: Option<NonZeroU64>,
+        #[serde(default, with = "crate::zero_as_none")]
+        offset: Option<NonZeroI32>,
+    }
+
+    let n = |n: &str| AttributeValue::N(String::from(n));
+
+    assert_eq!(
+        from_attribute_value::<_, NonZeroU8>(n("7")).unwrap(),
+        NonZeroU8::new(7).unwrap()
+    );
+    assert_eq!(
+        from_attribute_value::<_, NonZeroU64>(n("0"))
+            .unwrap_err()
+            .to_string(),
+        "Found `0` where a nonzero u64 was expected; annotate an optional field with \
+         `#[serde(with = \"serde_dynamo::zero_as_none\")]` to read zero as `None`"
+    );
+    assert!(from_attribute_value::<_, Option<NonZeroI32>>(n("-0")).is_err());
+
+    for (expires_at, offset, expected) in [
+        (n("0"), n("-0"), None),
+        (AttributeValue::Null(true), n("0"), None),
+        (n("1700000000"), n("-5"), NonZeroU64::new(1_700_000_000)),
+    ] {
+        let session: Session = from_attribute_value(AttributeValue::M(HashMap::from([
+            (String::from("expires_at"), expires_at),
+            (String::from("offset"), offset),
+        ])))
+        .unwrap();
+        assert_eq!(session.expires_at, expected);
+    }
+    let session: Session = from_attribute_value(AttributeValue::M(HashMap::new())).unwrap();
+    assert_eq!(
+        session,
+        Session {
+            expires_at: None,
+            offset: None
+        }
+    );
+}
+
+#[test]
+fn borrowed_items_lend_their_keys() {
+    use crate::{from_item, from_item_ref, Item};
+    use serde::de::{Deserializer, Visitor};
+    use std::fmt;
+
+    /// Records whether the key arrived as a borrowed or an owned string
+    #[derive(Debug, PartialEq, Eq, Hash)]
+    enum Key {
+        Borrowed(String),
+        Owned(String),
+    }
+
+    impl<'de> serde::Deserialize<'de> for Key {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            struct KeyVisitor;
+
+            impl Visitor<'_> for KeyVisitor {
+                type Value = Key;
+
+                fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    f.write_str("a key")
+                }
+
+                fn visit_str<E>(self, v: &str) -> Result<Key, E> {
+                    Ok(Key::Borrowed(v.to_string()))
+                }
+
+                fn visit_string<E>(self, v: String) -> Result<Key, E> {
+                    Ok(Key::Owned(v))
+                }
+            }
+
+            deserializer.deserialize_identifier(KeyVisitor)
+        }
+    }
+
+    let item = Item::from(HashMap::from([(
+        String::from("id"),
+        AttributeValue::Bool(true),
+    )]));
+
+    let borrowed: HashMap<Key, bool> = from_item_ref(&item).unwrap();
+    assert_eq!(
+        borrowed,
+        HashMap::from([(Key::Borrowed(String::from("id")), true)])
+    );
+    let owned: HashMap<Key, bool> = from_item(item).unwrap();
+    assert_eq!(
+        owned,
+        HashMap::from([(Key::Owned(String::from("id")), true)])
+    );
+}
+
+#[test]
+fn deserialize_ignoring_attribute_prefixes() {
+    use crate::{
+        dynamo_item, from_item_ref_with_config, from_item_with_config, DeserializerConfig,
+    };
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(deny_unknown_fields)]
+    struct Order {
+        id: String,
+        lines: HashMap<String, u32>,
+    }
+
+    let item = dynamo_item! {
+        "id": "o1",
+        "aws:rep:updatetime": 1700000000.5,
+        "aws:rep:deleting": false,
+        "lines": { "sku1": 2, "aws:rep:x": 9 },
+    };
+    let expected = Order {
+        id: String::from("o1"),
+        lines: HashMap::from([(String::from("sku1"), 2)]),
+    };
+    let config = || DeserializerConfig::new().ignored_attribute_prefixes(["aws:rep:"]);
+
+    assert_eq!(
+        from_item_ref_with_config::<Order>(&item, config()).unwrap(),
+        expected
+    );
+    assert_eq!(
+        from_item_with_config::<_, Order>(item.clone(), config()).unwrap(),
+        expected
+    );
+    assert!(from_item_with_config::<_, Order>(item.clone(), DeserializerConfig::new()).is_err());
+
+    let mut item = item;
+    item.insert(String::from("note"), AttributeValue::Null(true));
+    assert!(from_item_with_config::<_, Order>(item, config()).is_err());
+}
+
+#[test]
+fn deserialize_items_from_iterators() {
+    use crate::{dynamo_item, from_items, from_items_iter, Item};
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Row {
+        n: u8,
+    }
+
+    let page: Vec<Item> = (0..3).map(|n| dynamo_item! { "n": n }).collect();
+
+    let odd = dynamo_item! { "n": 1 };
+    let rows: Vec<Row> =
+        from_items_iter(page.iter().filter(|&item| *item != odd).cloned()).unwrap();
+    assert_eq!(rows, [Row { n: 0 }, Row { n: 2 }]);
+
+    let maps = page
+        .iter()
+        .cloned()
+        .map(HashMap::<String, AttributeValue>::from);
+    let rows: Vec<Row> = from_items_iter(maps).unwrap();
+    assert_eq!(rows, from_items::<_, Row>(page.clone()).unwrap());
+
+    let mut bad = page;
+    bad.push(dynamo_item! { "n": "x" });
+    assert!(from_items_iter::<_, Row>(bad).is_err());
+}
+
+#[test]
+fn deserialize_through_into_deserializer() {
+    use crate::{dynamo_item, Error, Item};
+    use serde::de::{Deserialize, IntoDeserializer};
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct User {
+        name: String,
+        tags: Vec<String>,
+    }
+
+    fn read<'de, T, D>(input: D) -> Result<T, Error>
+    where
+        T: Deserialize<'de>,
+        D: IntoDeserializer<'de, Error>,
+    {
+        T::deserialize(input.into_deserializer())
+    }
+
+    let item: Item = dynamo_item! { "name": "ann", "tags": ["a", "b"] };
+    let expected = User {
+        name: String::from("ann"),
+        tags: vec![String::from("a"), String::from("b")],
+    };
+    let value = AttributeValue::M(item.clone().into());
+
+    assert_eq!(read::<User, _>(&value).unwrap(), expected);
+    assert_eq!(read::<User, _>(value).unwrap(), expected);
+    assert_eq!(read::<User, _>(item).unwrap(), expected);
+    assert!(read::<User, _>(&AttributeValue::Null(true)).is_err());
+}
+
+#[test]
+fn deserialize_unsupported_borrowed_types() {
+    use super::{from_borrowed_item, BorrowedAttributeValue};
+    use crate::{ErrorCode, Result};
+
+    /// Stands in for an SDK attribute value with an `Unknown` variant
+    #[derive(Debug)]
+    enum SdkValue {
+        S(String),
+        M(HashMap<String, SdkValue>),
+        Unknown,
+    }
+
+    impl BorrowedAttributeValue for SdkValue {
+        fn map(&self) -> Option<&HashMap<String, Self>> {
+            match self {
+                SdkValue::M(m) => Some(m),
+                _ => None,
+            }
+        }
+
+        fn list(&self) -> Option<&[Self]> {
+            None
+        }
+
+        fn null(&self) -> Option<bool> {
+            None
+        }
+
+        fn to_owned_value(&self) -> Result<AttributeValue> {
+            match self {
+                SdkValue::S(s) => Ok(AttributeValue::S(s.clone())),
+                SdkValue::M(_) => unimplemented!("maps are walked by reference"),
+                SdkValue::Unknown => {
+                    Err(crate::error::ErrorImpl::UnsupportedType(format!("{self:?}")).into())
+                }
+            }
+        }
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct User {
+        name: String,
+        extra: Option<serde_json::Value>,
+    }
+
+    let item = HashMap::from([
+        (String::from("name"), SdkValue::S(String::from("ann"))),
+        (
+            String::from("extra"),
+            SdkValue::M(HashMap::from([(String::from("new"), SdkValue::Unknown)])),
+        ),
+    ]);
+    let err = from_borrowed_item::<_, User>(&item, Default::default()).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::UnsupportedType);
+    assert_eq!(
+        err.to_string(),
+        "Attribute value of an unsupported type: Unknown"
+    );
+}
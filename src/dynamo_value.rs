@@ -0,0 +1,249 @@
+/// Construct an [`AttributeValue`](crate::AttributeValue) from a literal.
+///
+/// The syntax follows `serde_json::json!`, with additions for the DynamoDB types JSON lacks:
+///
+/// * `null`, `true` and `false` are `NULL` and `BOOL` values
+/// * `[...]` is an `L` and `{ "key": value, ... }` is an `M`. A key can also be any expression
+///   in parentheses that converts into a `String`.
+/// * `ss[...]`, `ns[...]` and `bs[...]` are string, number and binary sets, built from
+///   expressions that are strings, numbers, and byte slices respectively
+/// * `b(...)` is a `B` value, built from a byte slice expression
+/// * anything else is an expression that is converted with
+///   [`to_attribute_value`](crate::to_attribute_value), panicking if that fails
+///
+/// ```
+/// use serde_dynamo::{dynamo_value, AttributeValue};
+///
+/// let name = "Ann";
+/// let value = dynamo_value!({
+///     "name": name,
+///     "age": 37,
+///     "balance": -1.5,
+///     "tags": ss["admin", "beta"],
+///     "scores": ns[10, 2.5],
+///     "avatar": b(b"\x89PNG"),
+///     "history": [null, true, { "at": "2024-01-01" }],
+/// });
+///
+/// let AttributeValue::M(m) = value else { panic!() };
+/// assert_eq!(m["name"], AttributeValue::S(String::from("Ann")));
+/// assert_eq!(m["balance"], AttributeValue::N(String::from("-1.5")));
+/// assert_eq!(m["scores"], AttributeValue::Ns(vec![String::from("10"), String::from("2.5")]));
+/// assert_eq!(m["avatar"], AttributeValue::B(b"\x89PNG".to_vec()));
+/// ```
+#[macro_export]
+macro_rules! dynamo_value {
+    (null) => {
+        $crate::AttributeValue::Null(true)
+    };
+    (true) => {
+        $crate::AttributeValue::Bool(true)
+    };
+    (false) => {
+        $crate::AttributeValue::Bool(false)
+    };
+    ([ $($tt:tt)* ]) => {
+        $crate::AttributeValue::L($crate::__dynamo_value!(@list [] [] $($tt)*))
+    };
+    ({ $($tt:tt)* }) => {
+        $crate::AttributeValue::M($crate::__dynamo_value!(@map [] () $($tt)*))
+    };
+    (ss [ $($element:expr),* $(,)? ]) => {
+        $crate::AttributeValue::Ss(vec![$(::std::string::String::from($element)),*])
+    };
+    (ns [ $($element:expr),* $(,)? ]) => {
+        $crate::AttributeValue::Ns(vec![$(::std::string::ToString::to_string(&$element)),*])
+    };
+    (bs [ $($element:expr),* $(,)? ]) => {
+        $crate::AttributeValue::Bs(vec![$(<_ as ::std::convert::AsRef<[u8]>>::as_ref(&$element).to_vec()),*])
+    };
+    (b ( $bytes:expr )) => {
+        $crate::AttributeValue::B(<_ as ::std::convert::AsRef<[u8]>>::as_ref(&$bytes).to_vec())
+    };
+    ($other:expr) => {
+        $crate::to_attribute_value::<_, $crate::AttributeValue>(&$other)
+            .expect("dynamo_value! expression failed to serialize")
+    };
+}
+
+/// Construct an [`Item`](crate::Item) from a literal.
+///
+/// This takes the contents of a [`dynamo_value!`] map.
+///
+/// ```
+/// use serde_dynamo::{dynamo_item, AttributeValue, Item};
+///
+/// let item: Item = dynamo_item! {
+///     "pk": "USER#1",
+///     "sk": "PROFILE",
+///     "verified": true,
+/// };
+///
+/// assert_eq!(item["pk"], AttributeValue::S(String::from("USER#1")));
+/// assert_eq!(item.len(), 3);
+/// ```
+#[macro_export]
+macro_rules! dynamo_item {
+    ($($tt:tt)*) => {
+        $crate::Item::from($crate::__dynamo_value!(@map [] () $($tt)*))
+    };
+}
+
+/// Construct an [`Item`](crate::Item) from `name => value` pairs.
+///
+/// Each value is converted with `AttributeValue::from`, so it can be a string, a `bool`, an
+/// integer that DynamoDB stores exactly, a `Vec<u8>`, or an
+/// [`AttributeValue`](crate::AttributeValue). Unlike [`dynamo_item!`], nothing is serialized,
+/// so building the item can't fail. This suits keys and small test fixtures.
+///
+/// ```
+/// use serde_dynamo::{item, AttributeValue};
+///
+/// let key = item! { "pk" => "USER#1", "sk" => format!("ORDER#{}", 7) };
+/// assert_eq!(key["sk"], AttributeValue::S(String::from("ORDER#7")));
+///
+/// let user = item! {
+///     "pk" => "USER#1",
+///     "age" => 42,
+///     "admin" => false,
+///     "tags" => AttributeValue::Ss(vec![String::from("beta")]),
+/// };
+/// assert_eq!(user["age"], AttributeValue::N(String::from("42")));
+/// ```
+#[macro_export]
+macro_rules! item {
+    ($($name:expr => $value:expr),* $(,)?) => {
+        $crate::Item::from(
+            <::std::collections::HashMap<::std::string::String, $crate::AttributeValue>>::from([
+                $((::std::string::String::from($name), $crate::AttributeValue::from($value))),*
+            ]),
+        )
+    };
+}
+
+/// Split a list or map literal at its top-level commas
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __dynamo_value {
+    // Lists: `[done elements] [tokens of the current element] rest`
+    (@list [$($done:expr,)*] [$($current:tt)+] , $($rest:tt)*) => {
+        $crate::__dynamo_value!(@list [$($done,)* $crate::dynamo_value!($($current)+),] [] $($rest)*)
+    };
+    (@list [$($done:expr,)*] [$($current:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::__dynamo_value!(@list [$($done,)*] [$($current)* $next] $($rest)*)
+    };
+    (@list [$($done:expr,)*] []) => {
+        vec![$($done),*]
+    };
+    (@list [$($done:expr,)*] [$($current:tt)+]) => {
+        vec![$($done,)* $crate::dynamo_value!($($current)+)]
+    };
+
+    // Maps: `[done entries] (key) rest`, then `[done entries] (key) [tokens of the value] rest`
+    (@map [$($done:expr,)*] () $key:tt : $($rest:tt)*) => {
+        $crate::__dynamo_value!(@map [$($done,)*] ($key) [] $($rest)*)
+    };
+    (@map [$($done:expr,)*] ($key:tt) [$($current:tt)+] , $($rest:tt)*) => {
+        $crate::__dynamo_value!(
+            @map
+            [$($done,)* (::std::string::String::from($key), $crate::dynamo_value!($($current)+)),]
+            ()
+            $($rest)*
+        )
+    };
+    (@map [$($done:expr,)*] ($key:tt) [$($current:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::__dynamo_value!(@map [$($done,)*] ($key) [$($current)* $next] $($rest)*)
+    };
+    (@map [$($done:expr,)*] ($key:tt) [$($current:tt)+]) => {
+        $crate::__dynamo_value!(
+            @map
+            [$($done,)* (::std::string::String::from($key), $crate::dynamo_value!($($current)+)),]
+            ()
+        )
+    };
+    (@map [$($done:expr,)*] ()) => {
+        <::std::collections::HashMap<::std::string::String, $crate::AttributeValue>>::from([$($done),*])
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AttributeValue, Item};
+    use std::collections::HashMap;
+
+    #[test]
+    fn nested_literals() {
+        let key = String::from("dynamic");
+        let value = dynamo_value!([
+            [],
+            {},
+            { (key.clone()): 1 + 2, "nested": { "list": [ns[1], bs[b"a", vec![2u8]]] } },
+            Some("x"),
+            None::<u8>,
+        ]);
+
+        assert_eq!(
+            value,
+            AttributeValue::L(vec![
+                AttributeValue::L(vec![]),
+                AttributeValue::M(HashMap::new()),
+                AttributeValue::M(HashMap::from([
+                    (key, AttributeValue::N(String::from("3"))),
+                    (
+                        String::from("nested"),
+                        AttributeValue::M(HashMap::from([(
+                            String::from("list"),
+                            AttributeValue::L(vec![
+                                AttributeValue::Ns(vec![String::from("1")]),
+                                AttributeValue::Bs(vec![b"a".to_vec(), vec![2]]),
+                            ]),
+                        )])),
+                    ),
+                ])),
+                AttributeValue::S(String::from("x")),
+                AttributeValue::Null(true),
+            ])
+        );
+
+        let item: Item = dynamo_item! {};
+        assert!(item.is_empty());
+    }
+
+    #[test]
+    fn item_from_pairs() {
+        let name = String::from("Ann");
+        let item = crate::item! {
+            "pk" => "USER#1",
+            String::from("name") => name,
+            "age" => 42u8,
+            "visits" => u64::MAX,
+            "admin" => true,
+            "avatar" => vec![1u8, 2],
+            "tags" => AttributeValue::Ss(vec![String::from("beta")]),
+        };
+        assert_eq!(
+            item,
+            Item::from(HashMap::from([
+                (
+                    String::from("pk"),
+                    AttributeValue::S(String::from("USER#1"))
+                ),
+                (String::from("name"), AttributeValue::S(String::from("Ann"))),
+                (String::from("age"), AttributeValue::N(String::from("42"))),
+                (
+                    String::from("visits"),
+                    AttributeValue::N(u64::MAX.to_string())
+                ),
+                (String::from("admin"), AttributeValue::Bool(true)),
+                (String::from("avatar"), AttributeValue::B(vec![1, 2])),
+                (
+                    String::from("tags"),
+                    AttributeValue::Ss(vec![String::from("beta")])
+                ),
+            ]))
+        );
+
+        assert_eq!(crate::item! {}, Item::new());
+        assert!(Item::with_capacity(8).capacity() >= 8);
+    }
+}
@@ -0,0 +1,509 @@
+//! Helpers for integration tests against DynamoDB Local
+//!
+//! Unit tests can check that a type serializes to the [`Item`] you expect, but only the service
+//! itself checks that DynamoDB accepts that item and returns it unchanged. These helpers make
+//! that round trip cheap to write in your own crate's integration tests, using
+//! [DynamoDB Local] and the [aws-sdk-dynamodb] client.
+//!
+//! [`DynamoDbLocal`] connects to a running instance, or starts one in a Docker container.
+//! [`DynamoDbLocal::create_table`] creates a table with a fresh name from a [`TableSchema`], and
+//! the returned [`TempTable`] writes and reads values through [`to_item`](crate::to_item) and
+//! [`from_item`](crate::from_item).
+//!
+//! ```no_run
+//! use serde_dynamo::dynamodb_local::{DynamoDbLocal, KeyAttributeType, TableSchema};
+//! # use serde_derive::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct User {
+//!     id: String,
+//!     age: u8,
+//! }
+//!
+//! # async fn test() -> Result<(), serde_dynamo::dynamodb_local::BoxError> {
+//! // Uses DYNAMODB_ENDPOINT, or http://localhost:8000
+//! let local = DynamoDbLocal::from_env();
+//! let table = local
+//!     .create_table(&TableSchema::new("id", KeyAttributeType::S))
+//!     .await?;
+//!
+//! let user = User { id: String::from("fSsgVtal8TpP"), age: 42 };
+//! table.put(&user).await?;
+//!
+//! let key = serde_dynamo::dynamo_item! { "id": "fSsgVtal8TpP" };
+//! let read: Option<User> = table.get(&key).await?;
+//! assert_eq!(read, Some(user));
+//!
+//! table.delete().await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! To run DynamoDB Local yourself, for example in CI, start its Docker image:
+//!
+//! ```sh
+//! docker run --rm -p 8000:8000 amazon/dynamodb-local
+//! ```
+//!
+//! The image keeps its tables in memory, so they disappear when the container stops.
+//!
+//! [DynamoDB Local]: https://docs.aws.amazon.com/amazondynamodb/latest/developerguide/DynamoDBLocal.html
+//! [aws-sdk-dynamodb]: https://docs.rs/aws-sdk-dynamodb
+
+use crate::Item;
+use __aws_sdk_dynamodb_1::{
+    config::{BehaviorVersion, Credentials, Region},
+    operation::create_table::{builders::CreateTableInputBuilder, CreateTableInput},
+    types::{AttributeDefinition, BillingMode, KeySchemaElement, KeyType, ScalarAttributeType},
+    Client, Config,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::net::TcpStream;
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// The error returned by these helpers
+///
+/// Failures come from the SDK, Docker and serialization alike, so they are boxed.
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// The environment variable [`DynamoDbLocal::from_env`] reads the endpoint from
+pub const ENDPOINT_VARIABLE: &str = "DYNAMODB_ENDPOINT";
+
+/// The endpoint DynamoDB Local listens on by default
+pub const DEFAULT_ENDPOINT: &str = "http://localhost:8000";
+
+/// The Docker image [`DynamoDbLocal::start`] runs
+const IMAGE: &str = "amazon/dynamodb-local";
+
+/// How long [`DynamoDbLocal::start`] waits for the container to accept connections
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A connection to a DynamoDB Local instance
+///
+/// The client uses fixed dummy credentials and the `us-east-1` region, which DynamoDB Local
+/// accepts. An instance started with [`start`](Self::start) is removed when this is dropped.
+#[derive(Debug)]
+pub struct DynamoDbLocal {
+    client: Client,
+    endpoint: String,
+    container: Option<String>,
+}
+
+impl DynamoDbLocal {
+    /// Connect to DynamoDB Local at `endpoint`, such as `http://localhost:8000`
+    ///
+    /// No request is made until the client is used.
+    pub fn connect(endpoint: impl Into<String>) -> Self {
+        let endpoint = endpoint.into();
+        let config = Config::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .endpoint_url(&endpoint)
+            .region(Region::new("us-east-1"))
+            .credentials_provider(Credentials::new(
+                "local",
+                "local",
+                None,
+                None,
+                "serde_dynamo",
+            ))
+            .build();
+        Self {
+            client: Client::from_conf(config),
+            endpoint,
+            container: None,
+        }
+    }
+
+    /// Connect to the endpoint in the `DYNAMODB_ENDPOINT` environment variable, or to
+    /// `http://localhost:8000` if it isn't set
+    pub fn from_env() -> Self {
+        let endpoint =
+            std::env::var(ENDPOINT_VARIABLE).unwrap_or_else(|_| DEFAULT_ENDPOINT.to_string());
+        Self::connect(endpoint)
+    }
+
+    /// Start DynamoDB Local in a new Docker container and connect to it
+    ///
+    /// The container publishes its port on a free local port and is removed when this is
+    /// dropped. Requires the `docker` command. Waits until the container accepts connections,
+    /// which can take a few seconds, and longer the first time while the image is pulled.
+    pub fn start() -> Result<Self, BoxError> {
+        let id = docker(&["run", "--detach", "--publish", "127.0.0.1::8000", IMAGE])?;
+        // Remove the container if anything below fails
+        let mut local = Self::connect(DEFAULT_ENDPOINT);
+        local.container = Some(id.clone());
+
+        let port = docker(&["port", &id, "8000/tcp"])?;
+        let address = port
+            .lines()
+            .next()
+            .ok_or_else(|| format!("container {id} has no published port"))?
+            .to_string();
+
+        let started = Instant::now();
+        while TcpStream::connect(&address).is_err() {
+            if started.elapsed() > STARTUP_TIMEOUT {
+                return Err(format!("DynamoDB Local didn't start listening on {address}").into());
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+
+        let container = local.container.take();
+        local = Self::connect(format!("http://{address}"));
+        local.container = container;
+        Ok(local)
+    }
+
+    /// The client connected to this instance
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// The endpoint this instance is reached at
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// Create a table with a fresh name from `schema`
+    ///
+    /// The table uses on-demand billing and has no secondary indexes.
+    pub async fn create_table(&self, schema: &TableSchema) -> Result<TempTable, BoxError> {
+        static TABLES: AtomicUsize = AtomicUsize::new(0);
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.subsec_nanos());
+        let name = format!(
+            "serde_dynamo_{}_{}_{nanos}",
+            std::process::id(),
+            TABLES.fetch_add(1, Ordering::Relaxed)
+        );
+
+        schema
+            .create_table_input(&name)?
+            .send_with(&self.client)
+            .await?;
+
+        Ok(TempTable {
+            client: self.client.clone(),
+            name,
+        })
+    }
+}
+
+impl Drop for DynamoDbLocal {
+    fn drop(&mut self) {
+        if let Some(id) = self.container.take() {
+            let _ = docker(&["rm", "--force", &id]);
+        }
+    }
+}
+
+/// Run a Docker command and return its trimmed output
+fn docker(args: &[&str]) -> Result<String, BoxError> {
+    let output = Command::new("docker")
+        .args(args)
+        .output()
+        .map_err(|err| format!("failed to run docker: {err}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("docker {} failed: {}", args[0], stderr.trim()).into());
+    }
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
+
+/// The type of a key attribute
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum KeyAttributeType {
+    /// A string
+    S,
+    /// A number
+    N,
+    /// Binary
+    B,
+}
+
+impl KeyAttributeType {
+    fn scalar_attribute_type(self) -> ScalarAttributeType {
+        match self {
+            KeyAttributeType::S => ScalarAttributeType::S,
+            KeyAttributeType::N => ScalarAttributeType::N,
+            KeyAttributeType::B => ScalarAttributeType::B,
+        }
+    }
+}
+
+/// The primary key of a table to create
+///
+/// ```
+/// use serde_dynamo::dynamodb_local::{KeyAttributeType, TableSchema};
+///
+/// let schema = TableSchema::new("pk", KeyAttributeType::S).sort_key("sk", KeyAttributeType::N);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableSchema {
+    partition_key: (String, KeyAttributeType),
+    sort_key: Option<(String, KeyAttributeType)>,
+}
+
+impl TableSchema {
+    /// A table keyed by the `name` attribute alone
+    pub fn new(name: impl Into<String>, attribute_type: KeyAttributeType) -> Self {
+        Self {
+            partition_key: (name.into(), attribute_type),
+            sort_key: None,
+        }
+    }
+
+    /// Add a sort key
+    pub fn sort_key(mut self, name: impl Into<String>, attribute_type: KeyAttributeType) -> Self {
+        self.sort_key = Some((name.into(), attribute_type));
+        self
+    }
+
+    /// The request creating a table called `name` with this key, on-demand billing and no
+    /// secondary indexes
+    fn create_table_input(&self, name: &str) -> Result<CreateTableInputBuilder, BoxError> {
+        let keys = std::iter::once((&self.partition_key, KeyType::Hash))
+            .chain(self.sort_key.iter().map(|key| (key, KeyType::Range)));
+        let mut input = CreateTableInput::builder()
+            .table_name(name)
+            .billing_mode(BillingMode::PayPerRequest);
+        for ((attribute, attribute_type), key_type) in keys {
+            input = input
+                .attribute_definitions(
+                    AttributeDefinition::builder()
+                        .attribute_name(attribute)
+                        .attribute_type(attribute_type.scalar_attribute_type())
+                        .build()?,
+                )
+                .key_schema(
+                    KeySchemaElement::builder()
+                        .attribute_name(attribute)
+                        .key_type(key_type)
+                        .build()?,
+                );
+        }
+        Ok(input)
+    }
+}
+
+/// A table created by [`DynamoDbLocal::create_table`]
+///
+/// Dropping this leaves the table in place. Call [`delete`](Self::delete) to remove it, or let it
+/// disappear with the DynamoDB Local instance.
+#[derive(Debug, Clone)]
+pub struct TempTable {
+    client: Client,
+    name: String,
+}
+
+impl TempTable {
+    /// The name of the table
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Serialize `value` with [`to_item`](crate::to_item) and put it in the table
+    pub async fn put<T>(&self, value: &T) -> Result<(), BoxError>
+    where
+        T: Serialize,
+    {
+        let item = crate::to_item(value)?;
+        self.put_item(item).await
+    }
+
+    /// Put an item in the table as it is
+    pub async fn put_item(&self, item: Item) -> Result<(), BoxError> {
+        self.client
+            .put_item()
+            .table_name(&self.name)
+            .set_item(Some(item.into()))
+            .send()
+            .await?;
+        Ok(())
+    }
+
+    /// Get the item with `key` and deserialize it with [`from_item`](crate::from_item)
+    ///
+    /// `key` must serialize to a map holding exactly the table's key attributes. Returns `None`
+    /// if there is no such item.
+    pub async fn get<K, T>(&self, key: &K) -> Result<Option<T>, BoxError>
+    where
+        K: Serialize,
+        T: DeserializeOwned,
+    {
+        match self.get_item(key).await? {
+            Some(item) => Ok(Some(crate::from_item(item)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Get the item with `key` as DynamoDB stored it
+    ///
+    /// Useful for checking the exact attributes a type serializes to.
+    pub async fn get_item<K>(&self, key: &K) -> Result<Option<Item>, BoxError>
+    where
+        K: Serialize,
+    {
+        let key: Item = crate::to_item(key)?;
+        let output = self
+            .client
+            .get_item()
+            .table_name(&self.name)
+            .set_key(Some(key.into()))
+            .consistent_read(true)
+            .send()
+            .await?;
+        Ok(output.item.map(Item::from))
+    }
+
+    /// Delete the table
+    pub async fn delete(self) -> Result<(), BoxError> {
+        self.client
+            .delete_table()
+            .table_name(&self.name)
+            .send()
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_derive::{Deserialize, Serialize};
+
+    #[test]
+    fn key_attribute_types() {
+        assert_eq!(
+            KeyAttributeType::S.scalar_attribute_type(),
+            ScalarAttributeType::S
+        );
+        assert_eq!(
+            KeyAttributeType::N.scalar_attribute_type(),
+            ScalarAttributeType::N
+        );
+        assert_eq!(
+            KeyAttributeType::B.scalar_attribute_type(),
+            ScalarAttributeType::B
+        );
+    }
+
+    #[test]
+    fn table_schema() {
+        let schema = TableSchema::new("pk", KeyAttributeType::S);
+        assert_eq!(
+            schema.partition_key,
+            (String::from("pk"), KeyAttributeType::S)
+        );
+        assert_eq!(schema.sort_key, None);
+
+        let schema = schema.sort_key("sk", KeyAttributeType::N);
+        assert_eq!(
+            schema.sort_key,
+            Some((String::from("sk"), KeyAttributeType::N))
+        );
+    }
+
+    #[test]
+    fn create_table_input() {
+        let definition = |name, attribute_type| {
+            AttributeDefinition::builder()
+                .attribute_name(name)
+                .attribute_type(attribute_type)
+                .build()
+                .unwrap()
+        };
+        let element = |name, key_type| {
+            KeySchemaElement::builder()
+                .attribute_name(name)
+                .key_type(key_type)
+                .build()
+                .unwrap()
+        };
+
+        let input = TableSchema::new("pk", KeyAttributeType::S)
+            .create_table_input("users")
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(input.table_name(), Some("users"));
+        assert_eq!(input.billing_mode(), Some(&BillingMode::PayPerRequest));
+        assert_eq!(
+            input.attribute_definitions(),
+            [definition("pk", ScalarAttributeType::S)]
+        );
+        assert_eq!(input.key_schema(), [element("pk", KeyType::Hash)]);
+        assert!(input.global_secondary_indexes().is_empty());
+
+        let input = TableSchema::new("pk", KeyAttributeType::B)
+            .sort_key("sk", KeyAttributeType::N)
+            .create_table_input("messages")
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(
+            input.attribute_definitions(),
+            [
+                definition("pk", ScalarAttributeType::B),
+                definition("sk", ScalarAttributeType::N),
+            ]
+        );
+        assert_eq!(
+            input.key_schema(),
+            [element("pk", KeyType::Hash), element("sk", KeyType::Range)]
+        );
+    }
+
+    /// Needs DynamoDB Local at `DYNAMODB_ENDPOINT`, or `http://localhost:8000`
+    #[tokio::test]
+    #[ignore]
+    async fn round_trip_against_endpoint() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Message {
+            channel: String,
+            sent_at: u64,
+            body: String,
+        }
+
+        #[derive(Serialize)]
+        struct MessageKey<'a> {
+            channel: &'a str,
+            sent_at: u64,
+        }
+
+        let local = DynamoDbLocal::from_env();
+        let table = local
+            .create_table(
+                &TableSchema::new("channel", KeyAttributeType::S)
+                    .sort_key("sent_at", KeyAttributeType::N),
+            )
+            .await
+            .unwrap();
+
+        let message = Message {
+            channel: String::from("general"),
+            sent_at: 1700000000,
+            body: String::from("Hello"),
+        };
+        table.put(&message).await.unwrap();
+
+        let key = MessageKey {
+            channel: "general",
+            sent_at: 1700000000,
+        };
+        let read: Option<Message> = table.get(&key).await.unwrap();
+        assert_eq!(read, Some(message));
+        let key = MessageKey {
+            channel: "general",
+            sent_at: 0,
+        };
+        let read: Option<Message> = table.get(&key).await.unwrap();
+        assert_eq!(read, None);
+
+        table.delete().await.unwrap();
+    }
+}
@@ -0,0 +1,270 @@
+//! A standard envelope of item metadata
+//!
+//! Most tables end up with the same handful of bookkeeping attributes on every item: what kind of
+//! entity it is, when it was created and last updated, an optimistic-locking version, and an
+//! optional [time to live]. [`Envelope`] standardizes them, and stores the wrapped payload's
+//! attributes alongside them in the same item.
+//!
+//! All timestamps are whole seconds since the Unix epoch, which is the format DynamoDB requires
+//! for its TTL attribute.
+//!
+//! # Examples
+//!
+//! ```
+//! use serde_derive::{Serialize, Deserialize};
+//! use serde_dynamo::{envelope::Envelope, AttributeValue, Item};
+//! use std::time::Duration;
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Session {
+//!     id: String,
+//!     user_id: String,
+//! }
+//!
+//! let mut session = Envelope::new(
+//!     "session",
+//!     Session {
+//!         id: String::from("s-1"),
+//!         user_id: String::from("u-1"),
+//!     },
+//! )
+//! .with_ttl(Duration::from_secs(60 * 60));
+//!
+//! // Before writing an update, bump the version and the update time
+//! session.touch();
+//!
+//! let item: Item = serde_dynamo::to_item(&session).unwrap();
+//! assert_eq!(item["entity_type"], AttributeValue::S(String::from("session")));
+//! assert_eq!(item["version"], AttributeValue::N(String::from("2")));
+//! assert_eq!(item["id"], AttributeValue::S(String::from("s-1")));
+//!
+//! let round_tripped: Envelope<Session> = serde_dynamo::from_item(item).unwrap();
+//! assert_eq!(round_tripped, session);
+//! ```
+//!
+//! [time to live]: https://docs.aws.amazon.com/amazondynamodb/latest/developerguide/TTL.html
+
+use crate::flatten::{FlatMapSerializer, FlattenedMap};
+use crate::ttl::now;
+use serde::de::{self, Deserialize, Deserializer, MapAccess, Visitor};
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+use std::time::Duration;
+
+/// A payload wrapped with standard item metadata
+///
+/// The payload is [flattened] into the item, so its fields must not collide with the envelope's
+/// `entity_type`, `version`, `created_at`, `updated_at`, and `ttl` attributes. The payload must
+/// serialize as a struct or map.
+///
+/// See the [module documentation][crate::envelope] for more information.
+///
+/// [flattened]: https://serde.rs/attr-flatten.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Envelope<T> {
+    /// The kind of entity stored in the item
+    pub entity_type: String,
+    /// A version number that is incremented on every write
+    pub version: u64,
+    /// When the item was created, in seconds since the Unix epoch
+    pub created_at: u64,
+    /// When the item was last updated, in seconds since the Unix epoch
+    pub updated_at: u64,
+    /// When DynamoDB may expire the item, in seconds since the Unix epoch
+    pub ttl: Option<u64>,
+    /// The wrapped value
+    pub payload: T,
+}
+
+impl<T> Envelope<T> {
+    /// Wrap a new payload, created now, at version 1, without a TTL.
+    pub fn new<E>(entity_type: E, payload: T) -> Self
+    where
+        E: Into<String>,
+    {
+        let now = now();
+        Envelope {
+            entity_type: entity_type.into(),
+            version: 1,
+            created_at: now,
+            updated_at: now,
+            ttl: None,
+            payload,
+        }
+    }
+
+    /// Set the item to expire `ttl` from now.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(now().saturating_add(ttl.as_secs()));
+        self
+    }
+
+    /// Mark the envelope as updated by incrementing the version and setting the update time to
+    /// now.
+    ///
+    /// Call this before writing a modified item back to DynamoDB.
+    pub fn touch(&mut self) {
+        self.version += 1;
+        self.updated_at = now().max(self.updated_at);
+    }
+
+    /// Whether the TTL, if any, has passed
+    ///
+    /// DynamoDB deletes expired items in the background, so reads can return items that have
+    /// already expired.
+    pub fn is_expired(&self) -> bool {
+        self.ttl.is_some_and(|ttl| ttl <= now())
+    }
+
+    /// Unwrap the payload, discarding the metadata
+    pub fn into_payload(self) -> T {
+        self.payload
+    }
+}
+
+impl<T> Serialize for Envelope<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("entity_type", &self.entity_type)?;
+        map.serialize_entry("version", &self.version)?;
+        map.serialize_entry("created_at", &self.created_at)?;
+        map.serialize_entry("updated_at", &self.updated_at)?;
+        if let Some(ttl) = self.ttl {
+            map.serialize_entry("ttl", &ttl)?;
+        }
+        self.payload.serialize(FlatMapSerializer(&mut map))?;
+        map.end()
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Envelope<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(EnvelopeVisitor(PhantomData))
+    }
+}
+
+struct EnvelopeVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for EnvelopeVisitor<T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = Envelope<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an enveloped item")
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut entity_type = None;
+        let mut version = None;
+        let mut created_at = None;
+        let mut updated_at = None;
+        let mut ttl = None;
+
+        let payload = FlattenedMap::new(map, |key, map: &mut A| {
+            match key {
+                "entity_type" => entity_type = Some(map.next_value()?),
+                "version" => version = Some(map.next_value()?),
+                "created_at" => created_at = Some(map.next_value()?),
+                "updated_at" => updated_at = Some(map.next_value()?),
+                "ttl" => ttl = map.next_value()?,
+                _ => return Ok(false),
+            }
+            Ok(true)
+        })
+        .deserialize_payload()?;
+
+        Ok(Envelope {
+            entity_type: entity_type.ok_or_else(|| de::Error::missing_field("entity_type"))?,
+            version: version.ok_or_else(|| de::Error::missing_field("version"))?,
+            created_at: created_at.ok_or_else(|| de::Error::missing_field("created_at"))?,
+            updated_at: updated_at.ok_or_else(|| de::Error::missing_field("updated_at"))?,
+            ttl,
+            payload,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Envelope;
+    use crate::{from_item, to_item, AttributeValue, Item};
+    use serde_derive::{Deserialize, Serialize};
+    use std::time::Duration;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Payload {
+        id: String,
+    }
+
+    #[test]
+    fn round_trip() {
+        let envelope = Envelope::new(
+            "thing",
+            Payload {
+                id: String::from("1"),
+            },
+        );
+        let item: Item = to_item(&envelope).unwrap();
+        assert_eq!(item.len(), 5);
+        assert!(!item.contains_key("ttl"));
+        assert_eq!(item["version"], AttributeValue::N(String::from("1")));
+
+        let round_tripped: Envelope<Payload> = from_item(item).unwrap();
+        assert_eq!(round_tripped, envelope);
+    }
+
+    #[test]
+    fn ttl_and_missing_metadata() {
+        let envelope = Envelope::new(
+            "thing",
+            Payload {
+                id: String::from("1"),
+            },
+        )
+        .with_ttl(Duration::from_secs(60));
+        let mut item: Item = to_item(&envelope).unwrap();
+        assert_eq!(item.len(), 6);
+        assert_eq!(
+            from_item::<_, Envelope<Payload>>(item.clone()).unwrap(),
+            envelope
+        );
+
+        item.remove("entity_type");
+        let err = from_item::<_, Envelope<Payload>>(item).unwrap_err();
+        assert_eq!(err.to_string(), "missing field `entity_type`");
+    }
+
+    #[test]
+    fn touch_and_ttl() {
+        let mut envelope = Envelope::new("thing", ()).with_ttl(Duration::from_secs(60));
+        let created_at = envelope.created_at;
+        envelope.touch();
+        envelope.touch();
+        assert_eq!(envelope.version, 3);
+        assert_eq!(envelope.created_at, created_at);
+        assert!(envelope.updated_at >= created_at);
+        assert!(envelope.ttl.unwrap() >= created_at + 60);
+        assert!(!envelope.is_expired());
+
+        envelope.ttl = Some(0);
+        assert!(envelope.is_expired());
+    }
+}
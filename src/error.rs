@@ -14,6 +14,273 @@ impl Display for Error {
 
 impl std::error::Error for Error {}
 
+impl Error {
+    /// Name the attribute that the error occurred in
+    pub(crate) fn in_attribute(self, name: &str) -> Self {
+        let mut path = String::new();
+        crate::path::push_key(&mut path, name);
+        self.in_path(path)
+    }
+
+    /// Name the attribute path that the error occurred in, joined with any path it already has
+    pub(crate) fn in_path(self, path: impl Into<String>) -> Self {
+        let mut path = path.into();
+        match self.0 {
+            ErrorImpl::InAttribute(inner_path, err) => {
+                if !inner_path.starts_with('[') {
+                    path.push('.');
+                }
+                path.push_str(&inner_path);
+                ErrorImpl::InAttribute(path, err).into()
+            }
+            err => ErrorImpl::InAttribute(path, Box::new(err)).into(),
+        }
+    }
+
+    /// The [path](crate::path) of the attribute the error occurred in, if it is known
+    ///
+    /// Names that contain `.`, `[`, `]`, or `\` are escaped, so [`path::parse`](crate::path::parse)
+    /// gives back the exact keys.
+    ///
+    /// ```
+    /// use serde_dynamo::{dynamo_item, path, Item};
+    ///
+    /// let item: Item = dynamo_item! { "size.cm": "large" };
+    /// let err = item.get_as::<u32>("size.cm").unwrap_err();
+    /// assert_eq!(err.attribute_path(), Some(r"size\.cm"));
+    /// assert_eq!(path::parse(err.attribute_path().unwrap()).unwrap().len(), 1);
+    /// ```
+    pub fn attribute_path(&self) -> Option<&str> {
+        match &self.0 {
+            ErrorImpl::InAttribute(path, _) => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Name the attribute a rejected float was written to, leaving other errors as they are
+    pub(crate) fn float_in_attribute(self, name: &str) -> Self {
+        if self.is_rejected_float() {
+            self.in_attribute(name)
+        } else {
+            self
+        }
+    }
+
+    /// Name the path an error occurred at, unless it is a missing attribute, which names itself
+    pub(crate) fn in_path_unless_missing(self, path: &str) -> Self {
+        if matches!(self.0, ErrorImpl::MissingAttribute(_)) {
+            self
+        } else {
+            self.in_path(path)
+        }
+    }
+
+    /// Name the list element a rejected float was written to, leaving other errors as they are
+    pub(crate) fn float_in_element(self, index: usize) -> Self {
+        if self.is_rejected_float() {
+            self.in_path(format!("[{index}]"))
+        } else {
+            self
+        }
+    }
+
+    fn is_rejected_float(&self) -> bool {
+        match &self.0 {
+            ErrorImpl::InAttribute(_, err) => matches!(**err, ErrorImpl::RejectedFloat),
+            err => matches!(err, ErrorImpl::RejectedFloat),
+        }
+    }
+
+    /// Whether this asks the enclosing struct or map to leave the field out
+    pub(crate) fn is_omitted(&self) -> bool {
+        matches!(self.0, ErrorImpl::OmittedEmptySet | ErrorImpl::OmittedNone)
+    }
+
+    /// The stable [`ErrorCode`] of this error
+    ///
+    /// Errors within an attribute report the code of the underlying error.
+    ///
+    /// ```
+    /// # use serde_dynamo::{AttributeValue, ErrorCode, Item};
+    /// # use serde_derive::Deserialize;
+    /// # use std::collections::HashMap;
+    /// #[derive(Debug, Deserialize)]
+    /// struct User {
+    ///     age: u8,
+    /// }
+    ///
+    /// let item: Item = HashMap::from([
+    ///     (String::from("age"), AttributeValue::S(String::from("old"))),
+    /// ]).into();
+    /// let err = serde_dynamo::from_item::<_, User>(item).unwrap_err();
+    /// assert_eq!(err.code(), ErrorCode::ExpectedNum);
+    /// assert_eq!(err.to_code(), 2006);
+    /// ```
+    pub fn code(&self) -> ErrorCode {
+        self.0.code()
+    }
+
+    /// The stable numeric value of this error's [`ErrorCode`]
+    pub fn to_code(&self) -> u32 {
+        self.code() as u32
+    }
+}
+
+/// A stable numeric code for each kind of [`Error`]
+///
+/// Codes can be matched across FFI or WASM boundaries and in structured logs without comparing
+/// messages. The number of a code never changes; new codes may be added in any release.
+///
+/// Codes are grouped by their thousands digit:
+///
+/// * `1xxx`: custom messages from serde or the data types
+/// * `2xxx`: the value doesn't have the expected shape
+/// * `3xxx`: numbers, strings, and binary data that can't be read or stored
+/// * `4xxx`: serializer misuse and limits
+/// * `5xxx`: items, keys, and attribute paths
+/// * `6xxx`: optional integrations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+#[repr(u32)]
+pub enum ErrorCode {
+    /// A custom message
+    Message = 1000,
+
+    /// Not a map-like object
+    NotMaplike = 2000,
+    /// Not a set-like sequence
+    NotSetlike = 2001,
+    /// Expected a string
+    ExpectedString = 2002,
+    /// Expected a map
+    ExpectedMap = 2003,
+    /// Expected a sequence
+    ExpectedSeq = 2004,
+    /// Expected a sequence of a specific length
+    ExpectedSeqLength = 2005,
+    /// Expected a number
+    ExpectedNum = 2006,
+    /// Expected a bool
+    ExpectedBool = 2007,
+    /// Expected a char
+    ExpectedChar = 2008,
+    /// Expected unit
+    ExpectedUnit = 2009,
+    /// Expected a unit struct
+    ExpectedUnitStruct = 2010,
+    /// Expected an enum
+    ExpectedEnum = 2011,
+    /// Expected binary data
+    ExpectedBytes = 2012,
+    /// Expected binary data of a specific length
+    ExpectedBytesLength = 2013,
+    /// Expected an item with a single key
+    ExpectedSingleKey = 2014,
+    /// Expected a map, but found an element of a string set
+    ExpectedMapFoundStringSet = 2015,
+    /// Found `NULL: false` under `NullFalsePolicy::Reject`
+    NullFalse = 2016,
+    /// Binary data given to a self-describing target under `BinaryPolicy::Reject`
+    BinaryRejected = 2017,
+    /// A string set contains a non-string element
+    StringSetExpectedType = 2018,
+    /// A number set contains a non-number element
+    NumberSetExpectedType = 2019,
+    /// A binary set contains a non-binary element
+    BinarySetExpectedType = 2020,
+    /// A named newtype struct stored with another name
+    NewtypeNameMismatch = 2021,
+    /// An attribute value of a type this crate doesn't know
+    UnsupportedType = 2022,
+
+    /// Failed to parse an integer
+    FailedToParseInt = 3000,
+    /// Failed to parse a float
+    FailedToParseFloat = 3001,
+    /// Zero given to a nonzero integer
+    ZeroForNonZero = 3002,
+    /// A number that DynamoDB can't store
+    UnrepresentableNumber = 3003,
+    /// A string read as binary data isn't valid base64
+    InvalidBase64 = 3004,
+    /// A string contains a replacement character from a lossy conversion
+    LossyString = 3005,
+    /// A float written while floats are rejected
+    RejectedFloat = 3006,
+
+    /// A map key that isn't a string
+    KeyMustBeAString = 4000,
+    /// `SerializeMap::serialize_key` called twice
+    SerializeMapKeyCalledTwice = 4001,
+    /// `SerializeMap::serialize_value` called before `serialize_key`
+    SerializeMapValueBeforeKey = 4002,
+    /// An empty optional set that should be omitted, outside of a struct or map
+    OmittedEmptySet = 4003,
+    /// A flattened content field has the same name as the enum's tag
+    FlattenedContentCollision = 4004,
+    /// The same attribute was written twice to one map
+    DuplicateAttribute = 4005,
+    /// A serializer limit was exceeded
+    LimitExceeded = 4006,
+    /// A `None` that should be omitted, outside of a struct or map
+    OmittedNone = 4007,
+    /// A string, number or binary set that is empty or has duplicate or invalid members
+    ///
+    /// Only builds with debug assertions check sets.
+    InvalidSet = 4008,
+
+    /// A type serialized to an item without attributes
+    EmptyItem = 5000,
+    /// An attribute is missing
+    MissingAttribute = 5001,
+    /// Key attributes that are missing, empty, or of the wrong type
+    InvalidKeyAttributes = 5002,
+    /// Key values that can't be ordered against each other
+    IncomparableKeys = 5003,
+    /// A projection field that isn't an attribute of the full item type
+    ProjectedAttributeNotInItem = 5004,
+    /// An item attribute that isn't a field of the projection
+    AttributeNotInProjection = 5005,
+    /// An attribute path could not be parsed
+    InvalidPath = 5006,
+    /// An attribute path doesn't fit the structure of the item
+    PathTypeMismatch = 5007,
+    /// An item was written with a newer schema than the migrations know
+    SchemaVersionTooNew = 5008,
+    /// A maximum chunked item size too small to hold any payload
+    ChunkSizeTooSmall = 5009,
+    /// Chunked items that can't be joined
+    InvalidChunks = 5010,
+    /// An item too large to write
+    ItemTooLarge = 5011,
+    /// No candidate type of a chain matched
+    NoCandidateMatched = 5012,
+    /// A list of items has more or fewer items than expected
+    UnexpectedItemCount = 5013,
+    /// A condition expression that can't be built or that DynamoDB would reject
+    InvalidCondition = 5014,
+    /// A projection type without named fields
+    ProjectionWithoutFields = 5015,
+    /// An unprocessed batch write request with neither an item to put nor a key to delete
+    InvalidWriteRequest = 5016,
+
+    /// Ion text could not be converted to items
+    Ion = 6000,
+    /// Items could not be converted to or from CSV
+    Csv = 6001,
+    /// Items could not be converted to an Arrow record batch
+    Arrow = 6002,
+    /// Validation failed
+    ValidationFailed = 6003,
+}
+
+impl ErrorCode {
+    /// The stable numeric value of this code
+    pub fn to_code(self) -> u32 {
+        self as u32
+    }
+}
+
 impl ser::Error for Error {
     fn custom<T: Display>(msg: T) -> Self {
         <ErrorImpl as ser::Error>::custom(msg).into()
@@ -24,6 +291,30 @@ impl de::Error for Error {
     fn custom<T: Display>(msg: T) -> Self {
         <ErrorImpl as de::Error>::custom(msg).into()
     }
+
+    fn invalid_value(unexp: de::Unexpected, exp: &dyn de::Expected) -> Self {
+        // Serde reports a zero given to a `NonZero*` type only as an invalid value, so a zero is
+        // checked against the expectation's text for the integer it names
+        if let de::Unexpected::Unsigned(0) | de::Unexpected::Signed(0) = unexp {
+            let expected = exp.to_string();
+            if let Some(integer) = nonzero_integer(&expected) {
+                return ErrorImpl::ZeroForNonZero(integer.to_string()).into();
+            }
+        }
+        <ErrorImpl as de::Error>::invalid_value(unexp, exp).into()
+    }
+}
+
+/// The integer type in the expectation of a serde `NonZero*` visitor
+///
+/// Serde 1.0.229 writes it as "a nonzero u64" and so on. If a serde release words it
+/// differently, this returns `None` and the zero is reported as an ordinary invalid value.
+fn nonzero_integer(expected: &str) -> Option<&str> {
+    const INTEGERS: [&str; 12] = [
+        "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+    ];
+    let (description, integer) = expected.rsplit_once(' ')?;
+    (description.contains("nonzero") && INTEGERS.contains(&integer)).then_some(integer)
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -42,6 +333,10 @@ pub enum ErrorImpl {
     ExpectedMap,
     /// Expected seq
     ExpectedSeq,
+    /// Expected a seq of a specific length (expected, found)
+    ExpectedSeqLength(usize, usize),
+    /// Expected a map, but found an element of a string set (set index)
+    ExpectedMapFoundStringSet(usize),
     /// Expected num
     ExpectedNum,
     /// Expected bool
@@ -52,16 +347,26 @@ pub enum ErrorImpl {
     ExpectedUnit,
     /// Expected unit struct
     ExpectedUnitStruct,
-    /// Expected enum
-    ExpectedEnum,
+    /// Found `NULL: false` under `NullFalsePolicy::Reject`
+    NullFalse,
+    /// Expected enum (variant names)
+    ExpectedEnum(&'static [&'static str]),
     /// Exprected binary data
     ExpectedBytes,
+    /// Binary data given to a self-describing target under `BinaryPolicy::Reject` (binary set index)
+    BinaryRejected(Option<usize>),
+    /// Expected binary data of a specific length (expected, found, binary set index)
+    ExpectedBytesLength(usize, usize, Option<usize>),
+    /// A string read as binary data isn't valid base64
+    InvalidBase64(base64::DecodeError),
     /// Expected an item with a single key
     ExpectedSingleKey,
     /// Failed to parse as an integer
     FailedToParseInt(String, std::num::ParseIntError),
     /// Failed to parse as a float
     FailedToParseFloat(String, std::num::ParseFloatError),
+    /// Zero given to a nonzero integer (integer type)
+    ZeroForNonZero(String),
     /// Key must be a string
     KeyMustBeAString,
     /// SerializeMap's serialize_key called twice!
@@ -74,6 +379,154 @@ pub enum ErrorImpl {
     NumberSetExpectedType,
     /// Binary set contains non-binary element
     BinarySetExpectedType,
+    /// An empty optional set that should be omitted, outside of a struct or map
+    OmittedEmptySet,
+    /// A `None` that should be omitted, outside of a struct or map
+    OmittedNone,
+    /// A set that DynamoDB rejects (type descriptor, problem)
+    InvalidSet(&'static str, String),
+    /// A projection field that isn't an attribute of the full item type
+    ProjectedAttributeNotInItem(String),
+    /// An item attribute that isn't a field of the projection
+    AttributeNotInProjection(String),
+    /// A projection type without named fields (type name)
+    ProjectionWithoutFields(&'static str),
+    /// String contains a replacement character from a lossy conversion
+    LossyString,
+    /// A float written while floats are rejected
+    RejectedFloat,
+    /// A flattened content field has the same name as the enum's tag
+    FlattenedContentCollision(String),
+    /// A named newtype struct stored with another name (expected, found)
+    NewtypeNameMismatch(&'static str, String),
+    /// A type serialized to an item without attributes
+    EmptyItem(&'static str),
+    /// A number that DynamoDB can't store, and why
+    UnrepresentableNumber(String, &'static str),
+    /// Key attributes that are missing, empty, or of the wrong type
+    InvalidKeyAttributes(Vec<String>),
+    /// Attribute is missing
+    MissingAttribute(String),
+    /// Error within an attribute (attribute path, error)
+    InAttribute(String, Box<ErrorImpl>),
+    /// Attribute path could not be parsed
+    InvalidPath(String),
+    /// Item was written with a newer schema than the migrations know (attribute, found, latest)
+    SchemaVersionTooNew(String, u32, u32),
+    /// Attribute path doesn't fit the structure of the item (type found)
+    PathTypeMismatch(&'static str),
+    /// Maximum chunked item size too small to hold any payload (maximum size)
+    ChunkSizeTooSmall(usize),
+    /// Chunked items that can't be joined, and why
+    InvalidChunks(String),
+    /// Key values that can't be ordered against each other (types found)
+    IncomparableKeys(&'static str, &'static str),
+    /// An item too large to write (estimated size, maximum size)
+    ItemTooLarge(usize, usize),
+    /// The same attribute was written twice to one map (attribute name)
+    DuplicateAttribute(String),
+    /// No candidate type of a chain matched (why each failed)
+    NoCandidateMatched(Vec<String>),
+    /// A serializer limit was exceeded (limit, what is counted)
+    LimitExceeded(usize, &'static str),
+    /// A list of items has more or fewer items than expected (expected, found)
+    UnexpectedItemCount(&'static str, usize),
+    /// A condition expression that can't be built or that DynamoDB would reject (why)
+    InvalidCondition(String),
+    /// An SDK attribute value of a type this crate doesn't know (debug representation)
+    // Only constructed with one of the SDK features
+    #[allow(dead_code)]
+    UnsupportedType(String),
+    /// An unprocessed batch write request with neither an item to put nor a key to delete
+    /// (table, problem)
+    // Only constructed with one of the SDK features
+    #[allow(dead_code)]
+    InvalidWriteRequest(String, &'static str),
+    /// Ion text could not be converted to items
+    #[cfg(feature = "ion")]
+    Ion(String),
+    /// Items could not be converted to or from CSV
+    #[cfg(feature = "csv")]
+    Csv(String),
+    /// Items could not be converted to an Arrow record batch
+    #[cfg(feature = "arrow")]
+    Arrow(String),
+    /// Validation failures, each prefixed with its attribute path
+    #[cfg(feature = "validator")]
+    ValidationFailed(Vec<String>),
+}
+
+impl ErrorImpl {
+    fn code(&self) -> ErrorCode {
+        match self {
+            ErrorImpl::Message(_) => ErrorCode::Message,
+            ErrorImpl::NotMaplike => ErrorCode::NotMaplike,
+            ErrorImpl::NotSetlike => ErrorCode::NotSetlike,
+            ErrorImpl::ExpectedString => ErrorCode::ExpectedString,
+            ErrorImpl::ExpectedMap => ErrorCode::ExpectedMap,
+            ErrorImpl::ExpectedSeq => ErrorCode::ExpectedSeq,
+            ErrorImpl::ExpectedSeqLength(..) => ErrorCode::ExpectedSeqLength,
+            ErrorImpl::ExpectedMapFoundStringSet(_) => ErrorCode::ExpectedMapFoundStringSet,
+            ErrorImpl::ExpectedNum => ErrorCode::ExpectedNum,
+            ErrorImpl::ExpectedBool => ErrorCode::ExpectedBool,
+            ErrorImpl::ExpectedChar => ErrorCode::ExpectedChar,
+            ErrorImpl::ExpectedUnit => ErrorCode::ExpectedUnit,
+            ErrorImpl::ExpectedUnitStruct => ErrorCode::ExpectedUnitStruct,
+            ErrorImpl::NullFalse => ErrorCode::NullFalse,
+            ErrorImpl::ExpectedEnum(_) => ErrorCode::ExpectedEnum,
+            ErrorImpl::ExpectedBytes => ErrorCode::ExpectedBytes,
+            ErrorImpl::BinaryRejected(_) => ErrorCode::BinaryRejected,
+            ErrorImpl::ExpectedBytesLength(..) => ErrorCode::ExpectedBytesLength,
+            ErrorImpl::InvalidBase64(_) => ErrorCode::InvalidBase64,
+            ErrorImpl::ExpectedSingleKey => ErrorCode::ExpectedSingleKey,
+            ErrorImpl::FailedToParseInt(..) => ErrorCode::FailedToParseInt,
+            ErrorImpl::FailedToParseFloat(..) => ErrorCode::FailedToParseFloat,
+            ErrorImpl::ZeroForNonZero(_) => ErrorCode::ZeroForNonZero,
+            ErrorImpl::KeyMustBeAString => ErrorCode::KeyMustBeAString,
+            ErrorImpl::SerializeMapKeyCalledTwice => ErrorCode::SerializeMapKeyCalledTwice,
+            ErrorImpl::SerializeMapValueBeforeKey => ErrorCode::SerializeMapValueBeforeKey,
+            ErrorImpl::StringSetExpectedType => ErrorCode::StringSetExpectedType,
+            ErrorImpl::NumberSetExpectedType => ErrorCode::NumberSetExpectedType,
+            ErrorImpl::BinarySetExpectedType => ErrorCode::BinarySetExpectedType,
+            ErrorImpl::OmittedEmptySet => ErrorCode::OmittedEmptySet,
+            ErrorImpl::OmittedNone => ErrorCode::OmittedNone,
+            ErrorImpl::InvalidSet(..) => ErrorCode::InvalidSet,
+            ErrorImpl::ProjectedAttributeNotInItem(_) => ErrorCode::ProjectedAttributeNotInItem,
+            ErrorImpl::AttributeNotInProjection(_) => ErrorCode::AttributeNotInProjection,
+            ErrorImpl::ProjectionWithoutFields(_) => ErrorCode::ProjectionWithoutFields,
+            ErrorImpl::LossyString => ErrorCode::LossyString,
+            ErrorImpl::RejectedFloat => ErrorCode::RejectedFloat,
+            ErrorImpl::FlattenedContentCollision(_) => ErrorCode::FlattenedContentCollision,
+            ErrorImpl::NewtypeNameMismatch(..) => ErrorCode::NewtypeNameMismatch,
+            ErrorImpl::EmptyItem(_) => ErrorCode::EmptyItem,
+            ErrorImpl::UnrepresentableNumber(..) => ErrorCode::UnrepresentableNumber,
+            ErrorImpl::InvalidKeyAttributes(_) => ErrorCode::InvalidKeyAttributes,
+            ErrorImpl::MissingAttribute(_) => ErrorCode::MissingAttribute,
+            ErrorImpl::InAttribute(_, inner) => inner.code(),
+            ErrorImpl::InvalidPath(_) => ErrorCode::InvalidPath,
+            ErrorImpl::SchemaVersionTooNew(..) => ErrorCode::SchemaVersionTooNew,
+            ErrorImpl::PathTypeMismatch(_) => ErrorCode::PathTypeMismatch,
+            ErrorImpl::ChunkSizeTooSmall(_) => ErrorCode::ChunkSizeTooSmall,
+            ErrorImpl::InvalidChunks(_) => ErrorCode::InvalidChunks,
+            ErrorImpl::IncomparableKeys(..) => ErrorCode::IncomparableKeys,
+            ErrorImpl::ItemTooLarge(..) => ErrorCode::ItemTooLarge,
+            ErrorImpl::DuplicateAttribute(_) => ErrorCode::DuplicateAttribute,
+            ErrorImpl::NoCandidateMatched(_) => ErrorCode::NoCandidateMatched,
+            ErrorImpl::LimitExceeded(..) => ErrorCode::LimitExceeded,
+            ErrorImpl::UnexpectedItemCount(..) => ErrorCode::UnexpectedItemCount,
+            ErrorImpl::InvalidCondition(_) => ErrorCode::InvalidCondition,
+            ErrorImpl::UnsupportedType(_) => ErrorCode::UnsupportedType,
+            ErrorImpl::InvalidWriteRequest(..) => ErrorCode::InvalidWriteRequest,
+            #[cfg(feature = "ion")]
+            ErrorImpl::Ion(_) => ErrorCode::Ion,
+            #[cfg(feature = "csv")]
+            ErrorImpl::Csv(_) => ErrorCode::Csv,
+            #[cfg(feature = "arrow")]
+            ErrorImpl::Arrow(_) => ErrorCode::Arrow,
+            #[cfg(feature = "validator")]
+            ErrorImpl::ValidationFailed(_) => ErrorCode::ValidationFailed,
+        }
+    }
 }
 
 #[allow(clippy::from_over_into)]
@@ -92,13 +545,53 @@ impl Display for ErrorImpl {
             ErrorImpl::ExpectedString => f.write_str("Expected string"),
             ErrorImpl::ExpectedMap => f.write_str("Expected map"),
             ErrorImpl::ExpectedSeq => f.write_str("Expected seq"),
+            ErrorImpl::ExpectedSeqLength(expected, found) => {
+                write!(f, "Expected seq of {expected} elements, found {found}")
+            }
+            ErrorImpl::ExpectedMapFoundStringSet(index) => write!(
+                f,
+                "Expected map, found string set element at index {index}; a string set can only be deserialized as a sequence of strings"
+            ),
             ErrorImpl::ExpectedNum => f.write_str("Expected num"),
             ErrorImpl::ExpectedBool => f.write_str("Expected bool"),
             ErrorImpl::ExpectedChar => f.write_str("Expected char"),
             ErrorImpl::ExpectedUnit => f.write_str("Expected unit"),
             ErrorImpl::ExpectedUnitStruct => f.write_str("Expected unit struct"),
-            ErrorImpl::ExpectedEnum => f.write_str("Expected enum"),
+            ErrorImpl::NullFalse => {
+                f.write_str("Found `NULL: false`, which the null policy rejects")
+            }
+            ErrorImpl::ExpectedEnum([]) => f.write_str("Expected enum"),
+            // Worded like serde's unknown variant errors
+            ErrorImpl::ExpectedEnum([variant]) => write!(f, "Expected enum `{variant}`"),
+            ErrorImpl::ExpectedEnum([a, b]) => write!(f, "Expected enum `{a}` or `{b}`"),
+            ErrorImpl::ExpectedEnum(variants) => {
+                f.write_str("Expected enum, one of ")?;
+                for (i, variant) in variants.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "`{variant}`")?;
+                }
+                Ok(())
+            }
             ErrorImpl::ExpectedBytes => f.write_str("Expected binary data"),
+            ErrorImpl::BinaryRejected(None) => {
+                f.write_str("Found binary data, which the binary policy rejects")
+            }
+            ErrorImpl::BinaryRejected(Some(index)) => write!(
+                f,
+                "Found binary data at binary set index {index}, which the binary policy rejects"
+            ),
+            ErrorImpl::ExpectedBytesLength(expected, found, None) => {
+                write!(f, "Expected binary data of {expected} bytes, found {found}")
+            }
+            ErrorImpl::ExpectedBytesLength(expected, found, Some(index)) => write!(
+                f,
+                "Expected binary data of {expected} bytes, found {found} at binary set index {index}"
+            ),
+            ErrorImpl::InvalidBase64(err) => {
+                write!(f, "Expected binary data or a base64 string: {err}")
+            }
             ErrorImpl::ExpectedSingleKey => f.write_str("Expected an item with a single key"),
             ErrorImpl::FailedToParseInt(s, err) => {
                 write!(f, "Failed to parse '{s}' as an integer: {err}")
@@ -122,6 +615,111 @@ impl Display for ErrorImpl {
             ErrorImpl::BinarySetExpectedType => {
                 f.write_str("Binary set element does not serialize to binary")
             }
+            ErrorImpl::OmittedEmptySet => f.write_str(
+                "An empty optional set can only be omitted from a struct field or map entry",
+            ),
+            ErrorImpl::OmittedNone => {
+                f.write_str("`None` can only be omitted from a struct field or map entry")
+            }
+            ErrorImpl::InvalidSet(type_descriptor, problem) => {
+                write!(f, "Invalid {type_descriptor} attribute value: {problem}")
+            }
+            ErrorImpl::ZeroForNonZero(integer) => write!(
+                f,
+                "Found `0` where a nonzero {integer} was expected; annotate an optional field with \
+                 `#[serde(with = \"serde_dynamo::zero_as_none\")]` to read zero as `None`"
+            ),
+            ErrorImpl::ProjectedAttributeNotInItem(name) => write!(
+                f,
+                "Projected attribute `{name}` is not an attribute of the full item"
+            ),
+            ErrorImpl::AttributeNotInProjection(name) => {
+                write!(f, "Attribute `{name}` is not part of the projection")
+            }
+            ErrorImpl::ProjectionWithoutFields(name) => {
+                write!(f, "Projection type `{name}` has no named fields")
+            }
+            ErrorImpl::LossyString => f.write_str(
+                "String contains U+FFFD replacement characters from a lossy conversion",
+            ),
+            ErrorImpl::RejectedFloat => f.write_str(
+                "Found a float where floats are rejected; write exact numbers as integers or \
+                 decimal strings",
+            ),
+            ErrorImpl::FlattenedContentCollision(tag) => write!(
+                f,
+                "Content field `{tag}` can't be flattened beside the tag of the same name"
+            ),
+            ErrorImpl::NewtypeNameMismatch(expected, found) => {
+                write!(f, "Expected a `{expected}` value, found a `{found}` value")
+            }
+            ErrorImpl::EmptyItem(type_name) => write!(
+                f,
+                "`{type_name}` serialized to an empty item, which DynamoDB rejects"
+            ),
+            ErrorImpl::UnrepresentableNumber(n, reason) => {
+                write!(f, "Number `{n}` can't be stored in DynamoDB: {reason}")
+            }
+            ErrorImpl::InvalidKeyAttributes(problems) => {
+                write!(f, "Invalid key attributes: {}", problems.join(", "))
+            }
+            ErrorImpl::MissingAttribute(name) => write!(f, "Attribute `{name}` is missing"),
+            ErrorImpl::InAttribute(name, err) => write!(f, "Attribute `{name}`: {err}"),
+            ErrorImpl::InvalidPath(path) => write!(f, "Invalid attribute path `{path}`"),
+            ErrorImpl::SchemaVersionTooNew(name, found, latest) => write!(
+                f,
+                "Attribute `{name}` is schema version {found}, but migrations only reach version {latest}"
+            ),
+            ErrorImpl::PathTypeMismatch(found) => write!(
+                f,
+                "Attribute path does not match the structure of the item at a {found} value"
+            ),
+            ErrorImpl::ChunkSizeTooSmall(max_size) => write!(
+                f,
+                "A maximum item size of {max_size} bytes leaves no room for the chunk payload"
+            ),
+            ErrorImpl::InvalidChunks(reason) => write!(f, "Invalid chunked items: {reason}"),
+            ErrorImpl::ItemTooLarge(size, max) => write!(
+                f,
+                "Item of {size} bytes is larger than the maximum of {max} bytes"
+            ),
+            ErrorImpl::DuplicateAttribute(name) => {
+                write!(f, "Attribute `{name}` is written more than once")
+            }
+            ErrorImpl::NoCandidateMatched(errors) => write!(
+                f,
+                "Item matched none of the candidate types: {}",
+                errors.join("; ")
+            ),
+            ErrorImpl::LimitExceeded(limit, counted) => {
+                write!(f, "Serialized value exceeds the limit of {limit} {counted}")
+            }
+            ErrorImpl::UnexpectedItemCount(expected, found) => {
+                write!(f, "Expected {expected}, found {found} items")
+            }
+            ErrorImpl::InvalidCondition(reason) => write!(f, "Invalid condition: {reason}"),
+            ErrorImpl::UnsupportedType(value) => {
+                write!(f, "Attribute value of an unsupported type: {value}")
+            }
+            ErrorImpl::InvalidWriteRequest(table, problem) => {
+                write!(f, "Invalid write request for table `{table}`: {problem}")
+            }
+            ErrorImpl::IncomparableKeys(a, b) => write!(
+                f,
+                "Can't order key values of type {a} and {b}, both must be S, both N, or both B"
+            ),
+            #[cfg(feature = "ion")]
+            ErrorImpl::Ion(message) => write!(f, "Ion conversion failed: {message}"),
+            #[cfg(feature = "csv")]
+            ErrorImpl::Csv(message) => write!(f, "CSV conversion failed: {message}"),
+            #[cfg(feature = "arrow")]
+            ErrorImpl::Arrow(message) => {
+                write!(f, "Failed to build Arrow record batch: {message}")
+            }
+            #[cfg(feature = "validator")]
+            ErrorImpl::ValidationFailed(failures) => {
+                write!(f, "Validation failed: {}", failures.join(", "))
+            }
         }
     }
 }
@@ -0,0 +1,646 @@
+//! Helpers for building DynamoDB expressions
+//!
+//! DynamoDB reserves several hundred words (`name`, `status`, `timestamp`, …) that cannot be used
+//! directly as attribute names in condition, filter, key condition, projection, or update
+//! expressions. Instead, they must be replaced with a `#placeholder` and listed in the request's
+//! `ExpressionAttributeNames`.
+//!
+//! [`Expression`] takes care of this automatically, and collects the
+//! `ExpressionAttributeValues` for the expression at the same time.
+//!
+//! # Examples
+//!
+//! ```
+//! use serde_dynamo::expression::Expression;
+//!
+//! let expression = Expression::new("name = :name AND age > :age")
+//!     .with_value(":name", "Arthur Dent")?
+//!     .with_value(":age", 42)?;
+//!
+//! assert_eq!(expression.expression(), "#__sd_name = :name AND age > :age");
+//! assert_eq!(expression.names()["#__sd_name"], "name");
+//! # Ok::<(), serde_dynamo::Error>(())
+//! ```
+//!
+//! The parts can then be handed to any of the supported SDKs.
+//!
+//! ```no_run
+//! # use __aws_sdk_dynamodb_1::client::Client;
+//! # use __aws_sdk_dynamodb_1::types::AttributeValue;
+//! use serde_dynamo::expression::Expression;
+//!
+//! # async fn query(client: &Client) -> Result<(), Box<dyn std::error::Error>> {
+//! let (expression, names, values) = Expression::new("status = :status")
+//!     .with_value(":status", "active")?
+//!     .into_parts::<AttributeValue>();
+//!
+//! client
+//!     .scan()
+//!     .table_name("users")
+//!     .filter_expression(expression)
+//!     .set_expression_attribute_names(names)
+//!     .set_expression_attribute_values(values)
+//!     .send()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{AttributeValue, Result};
+use serde::{de, Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+
+/// A DynamoDB expression along with its attribute name placeholders and attribute values
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Expression {
+    expression: String,
+    names: HashMap<String, String>,
+    values: HashMap<String, AttributeValue>,
+}
+
+impl Expression {
+    /// Create an expression, replacing any DynamoDB reserved words used as attribute names with
+    /// `#placeholder`s.
+    ///
+    /// See [`escape_reserved_words`] for the rules used to find attribute names.
+    pub fn new(expression: &str) -> Self {
+        let (expression, names) = escape_reserved_words(expression);
+        Expression {
+            expression,
+            names,
+            values: HashMap::new(),
+        }
+    }
+
+    /// Create an expression that is used exactly as given.
+    pub fn raw<S>(expression: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Expression {
+            expression: expression.into(),
+            names: HashMap::new(),
+            values: HashMap::new(),
+        }
+    }
+
+    /// Create an expression from parts that are already escaped and serialized
+    pub(crate) fn from_parts(
+        expression: String,
+        names: HashMap<String, String>,
+        values: HashMap<String, AttributeValue>,
+    ) -> Self {
+        Expression {
+            expression,
+            names,
+            values,
+        }
+    }
+
+    /// Add an attribute name placeholder, such as `#n` → `name`.
+    pub fn with_name<P, N>(mut self, placeholder: P, name: N) -> Self
+    where
+        P: Into<String>,
+        N: Into<String>,
+    {
+        self.names.insert(placeholder.into(), name.into());
+        self
+    }
+
+    /// Serialize `value` and add it as an attribute value placeholder, such as `:age` → `42`.
+    pub fn with_value<P, T>(mut self, placeholder: P, value: T) -> Result<Self>
+    where
+        P: Into<String>,
+        T: Serialize,
+    {
+        let value = crate::to_attribute_value(value)?;
+        self.values.insert(placeholder.into(), value);
+        Ok(self)
+    }
+
+    /// The expression text
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// The `ExpressionAttributeNames` used by the expression
+    pub fn names(&self) -> &HashMap<String, String> {
+        &self.names
+    }
+
+    /// The `ExpressionAttributeValues` used by the expression
+    pub fn values(&self) -> &HashMap<String, AttributeValue> {
+        &self.values
+    }
+
+    /// Split the expression into the expression text, the `ExpressionAttributeNames`, and the
+    /// `ExpressionAttributeValues`.
+    ///
+    /// DynamoDB rejects requests with empty name or value maps, so empty maps are returned as
+    /// `None`. The results can be passed directly to the SDK's `set_expression_attribute_*`
+    /// methods.
+    #[allow(clippy::type_complexity)]
+    pub fn into_parts<AV>(
+        self,
+    ) -> (
+        String,
+        Option<HashMap<String, String>>,
+        Option<HashMap<String, AV>>,
+    )
+    where
+        AV: From<AttributeValue>,
+    {
+        let names = if self.names.is_empty() {
+            None
+        } else {
+            Some(self.names)
+        };
+        let values = if self.values.is_empty() {
+            None
+        } else {
+            Some(
+                self.values
+                    .into_iter()
+                    .map(|(k, v)| (k, AV::from(v)))
+                    .collect(),
+            )
+        };
+        (self.expression, names, values)
+    }
+}
+
+/// Whether `word` is a DynamoDB reserved word. The check is case-insensitive.
+///
+/// ```
+/// use serde_dynamo::expression::is_reserved_word;
+///
+/// assert!(is_reserved_word("name"));
+/// assert!(is_reserved_word("Timestamp"));
+/// assert!(!is_reserved_word("first_name"));
+/// ```
+pub fn is_reserved_word(word: &str) -> bool {
+    RESERVED_WORDS
+        .binary_search_by(|probe| cmp_ignore_ascii_case(probe, word))
+        .is_ok()
+}
+
+fn cmp_ignore_ascii_case(upper: &str, word: &str) -> Ordering {
+    upper
+        .bytes()
+        .cmp(word.bytes().map(|b| b.to_ascii_uppercase()))
+}
+
+/// Replace any reserved words used as attribute names in `expression` with `#placeholder`s, and
+/// return the rewritten expression along with the placeholder map.
+///
+/// A word is treated as an attribute name unless it
+///
+/// * is already a `#name` or `:value` placeholder,
+/// * is immediately followed by `(` (a function such as `size` or `begins_with`), or
+/// * is one of the expression keywords `AND`, `OR`, `NOT`, `BETWEEN`, `IN`, `SET`, `REMOVE`,
+///   `ADD`, and `DELETE`.
+///
+/// Words following a `.` are always attribute names, so `profile.name` becomes
+/// `profile.#__sd_name`.
+///
+/// Placeholders are the word prefixed with `#__sd_`, so that they don't clash with placeholders
+/// added by hand.
+///
+/// ```
+/// use serde_dynamo::expression::escape_reserved_words;
+///
+/// let (expression, names) =
+///     escape_reserved_words("SET status = :status REMOVE profile.comment");
+///
+/// assert_eq!(expression, "SET #__sd_status = :status REMOVE profile.#__sd_comment");
+/// assert_eq!(names["#__sd_status"], "status");
+/// assert_eq!(names["#__sd_comment"], "comment");
+/// ```
+pub fn escape_reserved_words(expression: &str) -> (String, HashMap<String, String>) {
+    let bytes = expression.as_bytes();
+    let mut output = String::with_capacity(expression.len());
+    let mut names = HashMap::new();
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        if b == b'#' || b == b':' || b.is_ascii_digit() {
+            let end = identifier_end(bytes, i + 1);
+            output.push_str(&expression[i..end]);
+            i = end;
+        } else if b.is_ascii_alphabetic() || b == b'_' {
+            let end = identifier_end(bytes, i);
+            let word = &expression[i..end];
+            let after_dot = expression[..i].trim_end().ends_with('.');
+            let is_function = expression[end..].trim_start().starts_with('(');
+            let is_keyword = KEYWORDS.iter().any(|k| k.eq_ignore_ascii_case(word));
+
+            if is_reserved_word(word) && (after_dot || !(is_function || is_keyword)) {
+                let placeholder = placeholder(word);
+                output.push_str(&placeholder);
+                names.insert(placeholder, word.to_string());
+            } else {
+                output.push_str(word);
+            }
+            i = end;
+        } else {
+            let ch = expression[i..].chars().next().unwrap();
+            output.push(ch);
+            i += ch.len_utf8();
+        }
+    }
+
+    (output, names)
+}
+
+/// The `#placeholder` generated for an attribute name
+///
+/// The name is prefixed with `#__sd_`, which keeps generated placeholders apart from the ones
+/// callers choose for [`Expression::with_name`], such as `#name`. ASCII letters and digits are
+/// kept, and every other byte is written as `_` followed by two hex digits, so every name gets a
+/// distinct placeholder. Reserved words only contain letters, so their placeholder is the word
+/// itself behind the prefix.
+///
+/// ```
+/// use serde_dynamo::expression::placeholder;
+///
+/// assert_eq!(placeholder("status"), "#__sd_status");
+/// assert_eq!(placeholder("first-name"), "#__sd_first_2dname");
+/// assert_eq!(placeholder("a.b"), "#__sd_a_2eb");
+/// ```
+pub fn placeholder(attribute: &str) -> String {
+    let mut placeholder = String::with_capacity(attribute.len() + 6);
+    placeholder.push_str("#__sd_");
+    push_escaped(&mut placeholder, attribute);
+    placeholder
+}
+
+/// The `:placeholder` generated for a value a helper binds to an attribute
+///
+/// `role` is a lowercase word naming what the value is, such as `read` for the value an attribute
+/// was read with, so that helpers binding different values to the same attribute can share one
+/// map of values. The attribute is escaped as in [`placeholder`].
+pub(crate) fn value_placeholder(role: &str, attribute: &str) -> String {
+    debug_assert!(role.bytes().all(|b| b.is_ascii_lowercase()));
+    let mut placeholder = String::with_capacity(role.len() + attribute.len() + 7);
+    placeholder.push_str(":__sd_");
+    placeholder.push_str(role);
+    placeholder.push('_');
+    push_escaped(&mut placeholder, attribute);
+    placeholder
+}
+
+fn push_escaped(placeholder: &mut String, attribute: &str) {
+    for b in attribute.bytes() {
+        if b.is_ascii_alphanumeric() {
+            placeholder.push(char::from(b));
+        } else {
+            placeholder.push_str(&format!("_{b:02x}"));
+        }
+    }
+}
+
+/// Whether `attribute` can be used in an expression as it is
+///
+/// That is, it is a word of ASCII letters, digits and underscores that doesn't start with a digit,
+/// and isn't a reserved word.
+fn is_plain_attribute(attribute: &str) -> bool {
+    let bytes = attribute.as_bytes();
+    match bytes.first() {
+        Some(b) if b.is_ascii_alphabetic() || *b == b'_' => {}
+        _ => return false,
+    }
+    identifier_end(bytes, 0) == bytes.len() && !is_reserved_word(attribute)
+}
+
+fn identifier_end(bytes: &[u8], start: usize) -> usize {
+    start
+        + bytes[start..]
+            .iter()
+            .take_while(|b| b.is_ascii_alphanumeric() || **b == b'_')
+            .count()
+}
+
+/// Build the `#placeholder` map for whichever of `attributes` can't be used in an expression as
+/// they are.
+///
+/// That is every reserved word, and every name that isn't a word of ASCII letters, digits and
+/// underscores, or that starts with a digit. Each gets the [`placeholder`] for its name, so `a.b`
+/// is a top-level attribute rather than a path.
+///
+/// Combined with [`field_names`], this gives the placeholders needed for every field of a struct.
+///
+/// ```
+/// use serde_dynamo::expression::attribute_names;
+///
+/// let names = attribute_names(["id", "name", "first-name", "a.b", "1st"]);
+///
+/// assert_eq!(names.len(), 4);
+/// assert_eq!(names["#__sd_name"], "name");
+/// assert_eq!(names["#__sd_first_2dname"], "first-name");
+/// assert_eq!(names["#__sd_a_2eb"], "a.b");
+/// assert_eq!(names["#__sd_1st"], "1st");
+/// ```
+pub fn attribute_names<I, S>(attributes: I) -> HashMap<String, String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    attributes
+        .into_iter()
+        .filter(|attribute| !is_plain_attribute(attribute.as_ref()))
+        .map(|attribute| {
+            let attribute = attribute.as_ref();
+            (placeholder(attribute), attribute.to_string())
+        })
+        .collect()
+}
+
+/// The attribute names a struct is deserialized from, as seen by serde.
+///
+/// Renames (including `rename_all`) are taken into account. Types that are not structs, and
+/// structs that use `#[serde(flatten)]`, do not expose their fields and return an empty slice.
+///
+/// ```
+/// # use serde_derive::Deserialize;
+/// use serde_dynamo::expression::field_names;
+///
+/// #[derive(Deserialize)]
+/// #[serde(rename_all = "camelCase")]
+/// struct User {
+///     user_id: String,
+///     name: String,
+/// }
+///
+/// assert_eq!(field_names::<User>(), &["userId", "name"]);
+/// ```
+pub fn field_names<'de, T>() -> &'static [&'static str]
+where
+    T: Deserialize<'de>,
+{
+    match T::deserialize(FieldNamesDeserializer) {
+        Err(FieldNamesError(Some(fields))) => fields,
+        _ => &[],
+    }
+}
+
+/// A deserializer that does nothing but capture the fields serde asks for
+struct FieldNamesDeserializer;
+
+#[derive(Debug)]
+struct FieldNamesError(Option<&'static [&'static str]>);
+
+impl fmt::Display for FieldNamesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Not a struct")
+    }
+}
+
+impl std::error::Error for FieldNamesError {}
+
+impl de::Error for FieldNamesError {
+    fn custom<T: fmt::Display>(_msg: T) -> Self {
+        FieldNamesError(None)
+    }
+}
+
+impl<'de> de::Deserializer<'de> for FieldNamesDeserializer {
+    type Error = FieldNamesError;
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        Err(FieldNamesError(None))
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        Err(FieldNamesError(Some(fields)))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+/// Words that are part of the expression syntax and are never treated as attribute names by
+/// [`escape_reserved_words`]
+static KEYWORDS: &[&str] = &[
+    "AND", "OR", "NOT", "BETWEEN", "IN", "SET", "REMOVE", "ADD", "DELETE",
+];
+
+/// DynamoDB reserved words, sorted so they can be binary searched
+///
+/// See <https://docs.aws.amazon.com/amazondynamodb/latest/developerguide/ReservedWords.html>
+#[rustfmt::skip]
+static RESERVED_WORDS: &[&str] = &[
+    "ABORT", "ABSOLUTE", "ACTION", "ADD", "AFTER", "AGENT", "AGGREGATE", "ALL", "ALLOCATE", "ALTER",
+    "ANALYZE", "AND", "ANY", "ARCHIVE", "ARE", "ARRAY", "AS", "ASC", "ASCII", "ASENSITIVE",
+    "ASSERTION", "ASYMMETRIC", "AT", "ATOMIC", "ATTACH", "ATTRIBUTE", "AUTH", "AUTHORIZATION",
+    "AUTHORIZE", "AUTO", "AVG", "BACK", "BACKUP", "BASE", "BATCH", "BEFORE", "BEGIN", "BETWEEN",
+    "BIGINT", "BINARY", "BIT", "BLOB", "BLOCK", "BOOLEAN", "BOTH", "BREADTH", "BUCKET", "BULK",
+    "BY", "BYTE", "CALL", "CALLED", "CALLING", "CAPACITY", "CASCADE", "CASCADED", "CASE", "CAST",
+    "CATALOG", "CHAR", "CHARACTER", "CHECK", "CLASS", "CLOB", "CLOSE", "CLUSTER", "CLUSTERED",
+    "CLUSTERING", "CLUSTERS", "COALESCE", "COLLATE", "COLLATION", "COLLECTION", "COLUMN", "COLUMNS",
+    "COMBINE", "COMMENT", "COMMIT", "COMPACT", "COMPILE", "COMPRESS", "CONDITION", "CONFLICT",
+    "CONNECT", "CONNECTION", "CONSISTENCY", "CONSISTENT", "CONSTRAINT", "CONSTRAINTS",
+    "CONSTRUCTOR", "CONSUMED", "CONTINUE", "CONVERT", "COPY", "CORRESPONDING", "COUNT", "COUNTER",
+    "CREATE", "CROSS", "CUBE", "CURRENT", "CURSOR", "CYCLE", "DATA", "DATABASE", "DATE", "DATETIME",
+    "DAY", "DEALLOCATE", "DEC", "DECIMAL", "DECLARE", "DEFAULT", "DEFERRABLE", "DEFERRED", "DEFINE",
+    "DEFINED", "DEFINITION", "DELETE", "DELIMITED", "DEPTH", "DEREF", "DESC", "DESCRIBE",
+    "DESCRIPTOR", "DETACH", "DETERMINISTIC", "DIAGNOSTICS", "DIRECTORIES", "DISABLE", "DISCONNECT",
+    "DISTINCT", "DISTRIBUTE", "DO", "DOMAIN", "DOUBLE", "DROP", "DUMP", "DURATION", "DYNAMIC",
+    "EACH", "ELEMENT", "ELSE", "ELSEIF", "EMPTY", "ENABLE", "END", "EQUAL", "EQUALS", "ERROR",
+    "ESCAPE", "ESCAPED", "EVAL", "EVALUATE", "EXCEEDED", "EXCEPT", "EXCEPTION", "EXCEPTIONS",
+    "EXCLUSIVE", "EXEC", "EXECUTE", "EXISTS", "EXIT", "EXPLAIN", "EXPLODE", "EXPORT", "EXPRESSION",
+    "EXTENDED", "EXTERNAL", "EXTRACT", "FAIL", "FALSE", "FAMILY", "FETCH", "FIELDS", "FILE",
+    "FILTER", "FILTERING", "FINAL", "FINISH", "FIRST", "FIXED", "FLATTERN", "FLOAT", "FOR", "FORCE",
+    "FOREIGN", "FORMAT", "FORWARD", "FOUND", "FREE", "FROM", "FULL", "FUNCTION", "FUNCTIONS",
+    "GENERAL", "GENERATE", "GET", "GLOB", "GLOBAL", "GO", "GOTO", "GRANT", "GREATER", "GROUP",
+    "GROUPING", "HANDLER", "HASH", "HAVE", "HAVING", "HEAP", "HIDDEN", "HOLD", "HOUR", "IDENTIFIED",
+    "IDENTITY", "IF", "IGNORE", "IMMEDIATE", "IMPORT", "IN", "INCLUDING", "INCLUSIVE", "INCREMENT",
+    "INCREMENTAL", "INDEX", "INDEXED", "INDEXES", "INDICATOR", "INFINITE", "INITIALLY", "INLINE",
+    "INNER", "INNTER", "INOUT", "INPUT", "INSENSITIVE", "INSERT", "INSTEAD", "INT", "INTEGER",
+    "INTERSECT", "INTERVAL", "INTO", "INVALIDATE", "IS", "ISOLATION", "ITEM", "ITEMS", "ITERATE",
+    "JOIN", "KEY", "KEYS", "LAG", "LANGUAGE", "LARGE", "LAST", "LATERAL", "LEAD", "LEADING",
+    "LEAVE", "LEFT", "LENGTH", "LESS", "LEVEL", "LIKE", "LIMIT", "LIMITED", "LINES", "LIST", "LOAD",
+    "LOCAL", "LOCALTIME", "LOCALTIMESTAMP", "LOCATION", "LOCATOR", "LOCK", "LOCKS", "LOG", "LOGED",
+    "LONG", "LOOP", "LOWER", "MAP", "MATCH", "MATERIALIZED", "MAX", "MAXLEN", "MEMBER", "MERGE",
+    "METHOD", "METRICS", "MIN", "MINUS", "MINUTE", "MISSING", "MOD", "MODE", "MODIFIES", "MODIFY",
+    "MODULE", "MONTH", "MULTI", "MULTISET", "NAME", "NAMES", "NATIONAL", "NATURAL", "NCHAR",
+    "NCLOB", "NEW", "NEXT", "NO", "NONE", "NOT", "NULL", "NULLIF", "NUMBER", "NUMERIC", "OBJECT",
+    "OF", "OFFLINE", "OFFSET", "OLD", "ON", "ONLINE", "ONLY", "OPAQUE", "OPEN", "OPERATOR",
+    "OPTION", "OR", "ORDER", "ORDINALITY", "OTHER", "OTHERS", "OUT", "OUTER", "OUTPUT", "OVER",
+    "OVERLAPS", "OVERRIDE", "OWNER", "PAD", "PARALLEL", "PARAMETER", "PARAMETERS", "PARTIAL",
+    "PARTITION", "PARTITIONED", "PARTITIONS", "PATH", "PERCENT", "PERCENTILE", "PERMISSION",
+    "PERMISSIONS", "PIPE", "PIPELINED", "PLAN", "POOL", "POSITION", "PRECISION", "PREPARE",
+    "PRESERVE", "PRIMARY", "PRIOR", "PRIVATE", "PRIVILEGES", "PROCEDURE", "PROCESSED", "PROJECT",
+    "PROJECTION", "PROPERTY", "PROVISIONING", "PUBLIC", "PUT", "QUERY", "QUIT", "QUORUM", "RAISE",
+    "RANDOM", "RANGE", "RANK", "RAW", "READ", "READS", "REAL", "REBUILD", "RECORD", "RECURSIVE",
+    "REDUCE", "REF", "REFERENCE", "REFERENCES", "REFERENCING", "REGEXP", "REGION", "REINDEX",
+    "RELATIVE", "RELEASE", "REMAINDER", "RENAME", "REPEAT", "REPLACE", "REQUEST", "RESET",
+    "RESIGNAL", "RESOURCE", "RESPONSE", "RESTORE", "RESTRICT", "RESULT", "RETURN", "RETURNING",
+    "RETURNS", "REVERSE", "REVOKE", "RIGHT", "ROLE", "ROLES", "ROLLBACK", "ROLLUP", "ROUTINE",
+    "ROW", "ROWS", "RULE", "RULES", "SAMPLE", "SATISFIES", "SAVE", "SAVEPOINT", "SCAN", "SCHEMA",
+    "SCOPE", "SCROLL", "SEARCH", "SECOND", "SECTION", "SEGMENT", "SEGMENTS", "SELECT", "SELF",
+    "SEMI", "SENSITIVE", "SEPARATE", "SEQUENCE", "SERIALIZABLE", "SESSION", "SET", "SETS", "SHARD",
+    "SHARE", "SHARED", "SHORT", "SHOW", "SIGNAL", "SIMILAR", "SIZE", "SKEWED", "SMALLINT",
+    "SNAPSHOT", "SOME", "SOURCE", "SPACE", "SPACES", "SPARSE", "SPECIFIC", "SPECIFICTYPE", "SPLIT",
+    "SQL", "SQLCODE", "SQLERROR", "SQLEXCEPTION", "SQLSTATE", "SQLWARNING", "START", "STATE",
+    "STATIC", "STATUS", "STORAGE", "STORE", "STORED", "STREAM", "STRING", "STRUCT", "STYLE", "SUB",
+    "SUBMULTISET", "SUBPARTITION", "SUBSTRING", "SUBTYPE", "SUM", "SUPER", "SYMMETRIC", "SYNONYM",
+    "SYSTEM", "TABLE", "TABLESAMPLE", "TEMP", "TEMPORARY", "TERMINATED", "TEXT", "THAN", "THEN",
+    "THROUGHPUT", "TIME", "TIMESTAMP", "TIMEZONE", "TINYINT", "TO", "TOKEN", "TOTAL", "TOUCH",
+    "TRAILING", "TRANSACTION", "TRANSFORM", "TRANSLATE", "TRANSLATION", "TREAT", "TRIGGER", "TRIM",
+    "TRUE", "TRUNCATE", "TTL", "TUPLE", "TYPE", "UNDER", "UNDO", "UNION", "UNIQUE", "UNIT",
+    "UNKNOWN", "UNLOGGED", "UNNEST", "UNPROCESSED", "UNSIGNED", "UNTIL", "UPDATE", "UPPER", "URL",
+    "USAGE", "USE", "USER", "USERS", "USING", "UUID", "VACUUM", "VALUE", "VALUED", "VALUES",
+    "VARCHAR", "VARIABLE", "VARIANCE", "VARINT", "VARYING", "VIEW", "VIEWS", "VIRTUAL", "VOID",
+    "WAIT", "WHEN", "WHENEVER", "WHERE", "WHILE", "WINDOW", "WITH", "WITHIN", "WITHOUT", "WORK",
+    "WRAPPED", "WRITE", "YEAR", "ZONE",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_derive::Deserialize;
+
+    #[test]
+    fn reserved_words_are_sorted() {
+        assert!(RESERVED_WORDS.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn escapes_reserved_words() {
+        let (expression, names) = escape_reserved_words(
+            "attribute_exists(id) AND (name = :name OR size(tags) > :n) AND #ts < :ts",
+        );
+        assert_eq!(
+            expression,
+            "attribute_exists(id) AND (#__sd_name = :name OR size(tags) > :n) AND #ts < :ts"
+        );
+        assert_eq!(
+            names,
+            HashMap::from([(String::from("#__sd_name"), String::from("name"))])
+        );
+    }
+
+    #[test]
+    fn generated_names_leave_chosen_names_alone() {
+        let expression =
+            Expression::new("name = :name AND #name = :other").with_name("#name", "display_name");
+        assert_eq!(
+            expression.expression(),
+            "#__sd_name = :name AND #name = :other"
+        );
+        assert_eq!(
+            expression.names(),
+            &HashMap::from([
+                (String::from("#__sd_name"), String::from("name")),
+                (String::from("#name"), String::from("display_name")),
+            ])
+        );
+    }
+
+    #[test]
+    fn escapes_paths() {
+        let (expression, names) =
+            escape_reserved_words("SET profile.status = :s, tags[0].size = :n");
+        assert_eq!(
+            expression,
+            "SET profile.#__sd_status = :s, tags[0].#__sd_size = :n"
+        );
+        assert_eq!(names.len(), 2);
+    }
+
+    #[test]
+    fn keeps_case() {
+        let (expression, names) = escape_reserved_words("Timestamp BETWEEN :a AND :b");
+        assert_eq!(expression, "#__sd_Timestamp BETWEEN :a AND :b");
+        assert_eq!(names["#__sd_Timestamp"], "Timestamp");
+    }
+
+    #[test]
+    fn builder() {
+        let expression = Expression::new("status = :status")
+            .with_value(":status", "active")
+            .unwrap();
+        let (expression, names, values) = expression.into_parts::<AttributeValue>();
+        assert_eq!(expression, "#__sd_status = :status");
+        assert_eq!(
+            names,
+            Some(HashMap::from([(
+                String::from("#__sd_status"),
+                String::from("status")
+            )]))
+        );
+        assert_eq!(
+            values,
+            Some(HashMap::from([(
+                String::from(":status"),
+                AttributeValue::S(String::from("active"))
+            )]))
+        );
+
+        let (_, names, values) = Expression::raw("id = id").into_parts::<AttributeValue>();
+        assert_eq!(names, None);
+        assert_eq!(values, None);
+    }
+
+    #[test]
+    fn names_from_struct_fields() {
+        #[derive(Deserialize)]
+        #[allow(dead_code)]
+        struct Event {
+            id: String,
+            #[serde(rename = "timestamp")]
+            at: u64,
+            status: String,
+        }
+
+        let names = attribute_names(field_names::<Event>());
+        assert_eq!(
+            names,
+            HashMap::from([
+                (String::from("#__sd_timestamp"), String::from("timestamp")),
+                (String::from("#__sd_status"), String::from("status")),
+            ])
+        );
+
+        assert!(field_names::<String>().is_empty());
+    }
+
+    #[test]
+    fn names_that_are_not_identifiers() {
+        let names = attribute_names(["first-name", "status", "a.b", "1st", "first_name", "_id"]);
+        assert_eq!(
+            names,
+            HashMap::from([
+                (
+                    String::from("#__sd_first_2dname"),
+                    String::from("first-name")
+                ),
+                (String::from("#__sd_status"), String::from("status")),
+                (String::from("#__sd_a_2eb"), String::from("a.b")),
+                (String::from("#__sd_1st"), String::from("1st")),
+            ])
+        );
+
+        // `_` is escaped too, so names that only differ in punctuation don't share a placeholder
+        assert_eq!(placeholder("a_2eb"), "#__sd_a_5f2eb");
+        assert_ne!(placeholder("a-b"), placeholder("a.b"));
+        assert_eq!(placeholder("café"), "#__sd_caf_c3_a9");
+    }
+}
@@ -0,0 +1,74 @@
+//! Serializer codec for storing `f32` vectors as packed binary
+//!
+//! Numeric vectors such as ML embeddings are common in DynamoDB items, but storing them as a
+//! number set or list stores every element as a decimal string. This module instead packs the
+//! values as little-endian `f32`s in a single `B` attribute, which is roughly an order of
+//! magnitude smaller.
+//!
+//! # Usage
+//!
+//! To use, annotate the field with `#[serde(with = "serde_dynamo::f32_bytes")]`.
+//!
+//! The field may be a `Vec<f32>`, a `Box<[f32]>`, or a fixed-size `[f32; N]`. When the
+//! field is a fixed-size array, deserializing checks that the stored vector has exactly `N`
+//! elements.
+//!
+//! # Errors
+//!
+//! The deserializer in this module will return an error if:
+//!
+//! * the attribute is not binary
+//! * the length of the binary is not a multiple of 4 bytes
+//! * the field is a fixed-size array and the stored vector has a different number of elements
+//!
+//! # Examples
+//!
+//! ```
+//! use serde_derive::{Serialize, Deserialize};
+//! use serde_dynamo::{AttributeValue, Item};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Document {
+//!     id: String,
+//!     #[serde(with = "serde_dynamo::f32_bytes")]
+//!     embedding: Vec<f32>,
+//!     #[serde(with = "serde_dynamo::f32_bytes")]
+//!     position: [f32; 3],
+//! }
+//!
+//! let document = Document {
+//!     id: String::from("doc-1"),
+//!     embedding: vec![0.25; 1536],
+//!     position: [1.0, 2.0, 3.0],
+//! };
+//!
+//! let item: Item = serde_dynamo::to_item(&document).unwrap();
+//! assert!(matches!(&item["embedding"], AttributeValue::B(b) if b.len() == 1536 * 4));
+//!
+//! let round_tripped: Document = serde_dynamo::from_item(item).unwrap();
+//! assert_eq!(round_tripped, document);
+//! ```
+
+use crate::float_bytes::{self, FloatVector};
+
+/// Serializes the given floats as packed little-endian binary
+///
+/// See the [module documentation][crate::f32_bytes] for additional usage information.
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: FloatVector<f32>,
+    S: serde::Serializer,
+{
+    float_bytes::serialize(value, serializer)
+}
+
+/// Deserializes packed little-endian binary as floats
+///
+/// See the [module documentation][crate::f32_bytes] for additional usage information.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: FloatVector<f32>,
+    D: serde::Deserializer<'de>,
+{
+    float_bytes::deserialize(deserializer)
+}
@@ -0,0 +1,328 @@
+//! Hand-written equivalents of `#[serde(flatten)]`
+//!
+//! Types that store a generic payload's fields beside their own, such as
+//! [`Envelope`](crate::envelope::Envelope) and [`Edge`](crate::adjacency::Edge), implement
+//! `Serialize` and `Deserialize` with these, so that the crate doesn't need serde's derive
+//! macros.
+//!
+//! Serializing writes the payload's fields into the map that holds the outer fields. Deserializing
+//! takes the outer fields out of the map as they are read and hands every other entry to the
+//! payload, so nothing is buffered.
+
+use serde::de::{self, DeserializeSeed, IntoDeserializer, MapAccess, Visitor};
+use serde::ser::{self, Impossible, Serialize, SerializeMap, SerializeStruct, Serializer};
+
+/// Serializes a struct or map into another map's entries
+pub(crate) struct FlatMapSerializer<'a, M>(pub(crate) &'a mut M);
+
+fn flatten_error<E>(what: &str) -> E
+where
+    E: ser::Error,
+{
+    E::custom(format_args!(
+        "can only flatten structs and maps, not {what}"
+    ))
+}
+
+impl<'a, M> Serializer for FlatMapSerializer<'a, M>
+where
+    M: SerializeMap,
+{
+    type Ok = ();
+    type Error = M::Error;
+
+    type SerializeSeq = Impossible<(), M::Error>;
+    type SerializeTuple = Impossible<(), M::Error>;
+    type SerializeTupleStruct = Impossible<(), M::Error>;
+    type SerializeTupleVariant = Impossible<(), M::Error>;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Impossible<(), M::Error>;
+
+    fn serialize_bool(self, _: bool) -> Result<(), M::Error> {
+        Err(flatten_error("a bool"))
+    }
+    fn serialize_i8(self, _: i8) -> Result<(), M::Error> {
+        Err(flatten_error("a number"))
+    }
+    fn serialize_i16(self, _: i16) -> Result<(), M::Error> {
+        Err(flatten_error("a number"))
+    }
+    fn serialize_i32(self, _: i32) -> Result<(), M::Error> {
+        Err(flatten_error("a number"))
+    }
+    fn serialize_i64(self, _: i64) -> Result<(), M::Error> {
+        Err(flatten_error("a number"))
+    }
+    fn serialize_u8(self, _: u8) -> Result<(), M::Error> {
+        Err(flatten_error("a number"))
+    }
+    fn serialize_u16(self, _: u16) -> Result<(), M::Error> {
+        Err(flatten_error("a number"))
+    }
+    fn serialize_u32(self, _: u32) -> Result<(), M::Error> {
+        Err(flatten_error("a number"))
+    }
+    fn serialize_u64(self, _: u64) -> Result<(), M::Error> {
+        Err(flatten_error("a number"))
+    }
+    fn serialize_f32(self, _: f32) -> Result<(), M::Error> {
+        Err(flatten_error("a number"))
+    }
+    fn serialize_f64(self, _: f64) -> Result<(), M::Error> {
+        Err(flatten_error("a number"))
+    }
+    fn serialize_char(self, _: char) -> Result<(), M::Error> {
+        Err(flatten_error("a char"))
+    }
+    fn serialize_str(self, _: &str) -> Result<(), M::Error> {
+        Err(flatten_error("a string"))
+    }
+    fn serialize_bytes(self, _: &[u8]) -> Result<(), M::Error> {
+        Err(flatten_error("bytes"))
+    }
+    fn serialize_none(self) -> Result<(), M::Error> {
+        Ok(())
+    }
+    fn serialize_some<T>(self, value: &T) -> Result<(), M::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+    fn serialize_unit(self) -> Result<(), M::Error> {
+        Ok(())
+    }
+    fn serialize_unit_struct(self, _: &'static str) -> Result<(), M::Error> {
+        Ok(())
+    }
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+    ) -> Result<(), M::Error> {
+        Err(flatten_error("an enum"))
+    }
+    fn serialize_newtype_struct<T>(self, _: &'static str, value: &T) -> Result<(), M::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+    fn serialize_newtype_variant<T>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<(), M::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(flatten_error("an enum"))
+    }
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, M::Error> {
+        Err(flatten_error("a sequence"))
+    }
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, M::Error> {
+        Err(flatten_error("a tuple"))
+    }
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleStruct, M::Error> {
+        Err(flatten_error("a tuple struct"))
+    }
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant, M::Error> {
+        Err(flatten_error("an enum"))
+    }
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, M::Error> {
+        Ok(self)
+    }
+    fn serialize_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStruct, M::Error> {
+        Ok(self)
+    }
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, M::Error> {
+        Err(flatten_error("an enum"))
+    }
+}
+
+impl<M> SerializeMap for FlatMapSerializer<'_, M>
+where
+    M: SerializeMap,
+{
+    type Ok = ();
+    type Error = M::Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), M::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.0.serialize_key(key)
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), M::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.0.serialize_value(value)
+    }
+
+    fn end(self) -> Result<(), M::Error> {
+        Ok(())
+    }
+}
+
+impl<M> SerializeStruct for FlatMapSerializer<'_, M>
+where
+    M: SerializeMap,
+{
+    type Ok = ();
+    type Error = M::Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), M::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.0.serialize_entry(key, value)
+    }
+
+    fn end(self) -> Result<(), M::Error> {
+        Ok(())
+    }
+}
+
+/// A map whose outer fields are taken out by `take` as they are read, leaving the payload's
+///
+/// `take` is called with each key and the map positioned at its value. It reads the value and
+/// returns `true` if the key is an outer field, or returns `false` to hand the entry to the
+/// payload.
+pub(crate) struct FlattenedMap<A, F> {
+    map: A,
+    take: F,
+}
+
+impl<'de, A, F> FlattenedMap<A, F>
+where
+    A: MapAccess<'de>,
+    F: FnMut(&str, &mut A) -> Result<bool, A::Error>,
+{
+    pub(crate) fn new(map: A, take: F) -> Self {
+        FlattenedMap { map, take }
+    }
+
+    /// Deserialize the payload from the entries that aren't outer fields
+    pub(crate) fn deserialize_payload<T>(mut self) -> Result<T, A::Error>
+    where
+        T: de::Deserialize<'de>,
+    {
+        let payload = T::deserialize(PayloadDeserializer(&mut self))?;
+        // Outer fields after the last payload field are still to be taken
+        while self.next_key::<de::IgnoredAny>()?.is_some() {
+            self.next_value::<de::IgnoredAny>()?;
+        }
+        Ok(payload)
+    }
+}
+
+impl<'de, A, F> MapAccess<'de> for FlattenedMap<A, F>
+where
+    A: MapAccess<'de>,
+    F: FnMut(&str, &mut A) -> Result<bool, A::Error>,
+{
+    type Error = A::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, A::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        while let Some(key) = self.map.next_key::<String>()? {
+            if !(self.take)(&key, &mut self.map)? {
+                return seed.deserialize(key.into_deserializer()).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, A::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        self.map.next_value_seed(seed)
+    }
+}
+
+/// Deserializes a payload from the remaining entries of a [`FlattenedMap`]
+///
+/// Unit payloads take nothing, and optional payloads are always present, as with
+/// `#[serde(flatten)]`.
+struct PayloadDeserializer<'a, M>(&'a mut M);
+
+impl<'de, M> de::Deserializer<'de> for PayloadDeserializer<'_, M>
+where
+    M: MapAccess<'de>,
+{
+    type Error = M::Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, M::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_map(self.0)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, M::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, M::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V>(self, _: &'static str, visitor: V) -> Result<V::Value, M::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, M::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf seq
+        tuple tuple_struct map struct enum identifier ignored_any
+    }
+}
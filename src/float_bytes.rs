@@ -0,0 +1,241 @@
+//! Shared implementation of [`f32_bytes`][crate::f32_bytes] and
+//! [`f64_bytes`][crate::f64_bytes]
+
+use serde::{de, Deserializer, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+
+/// A floating point type that can be packed as little-endian bytes
+pub(crate) trait Float: Copy {
+    const SIZE: usize;
+
+    fn extend_le_bytes(self, bytes: &mut Vec<u8>);
+
+    fn from_le_slice(bytes: &[u8]) -> Self;
+}
+
+impl Float for f32 {
+    const SIZE: usize = 4;
+
+    fn extend_le_bytes(self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.to_le_bytes());
+    }
+
+    fn from_le_slice(bytes: &[u8]) -> Self {
+        f32::from_le_bytes(bytes.try_into().expect("chunk is SIZE bytes"))
+    }
+}
+
+impl Float for f64 {
+    const SIZE: usize = 8;
+
+    fn extend_le_bytes(self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.to_le_bytes());
+    }
+
+    fn from_le_slice(bytes: &[u8]) -> Self {
+        f64::from_le_bytes(bytes.try_into().expect("chunk is SIZE bytes"))
+    }
+}
+
+/// A collection of floats that can be stored as packed bytes
+///
+/// Implemented for `Vec<F>`, `Box<[F]>`, and `[F; N]`. Fixed-size arrays check the number of
+/// stored floats when deserializing. Name it in bounds to write generic code over
+/// [`f32_bytes`](crate::f32_bytes) and [`f64_bytes`](crate::f64_bytes) fields.
+///
+/// This trait is sealed and cannot be implemented outside of this crate.
+pub trait FloatVector<F>: sealed::Sealed<F> + Sized {
+    #[doc(hidden)]
+    fn as_floats(&self) -> &[F];
+
+    #[doc(hidden)]
+    fn from_floats(floats: Vec<F>) -> Result<Self, Vec<F>>;
+
+    #[doc(hidden)]
+    fn dimension() -> Option<usize>;
+}
+
+impl<F> FloatVector<F> for Vec<F> {
+    fn as_floats(&self) -> &[F] {
+        self
+    }
+
+    fn from_floats(floats: Vec<F>) -> Result<Self, Vec<F>> {
+        Ok(floats)
+    }
+
+    fn dimension() -> Option<usize> {
+        None
+    }
+}
+
+impl<F> FloatVector<F> for Box<[F]> {
+    fn as_floats(&self) -> &[F] {
+        self
+    }
+
+    fn from_floats(floats: Vec<F>) -> Result<Self, Vec<F>> {
+        Ok(floats.into_boxed_slice())
+    }
+
+    fn dimension() -> Option<usize> {
+        None
+    }
+}
+
+impl<F, const N: usize> FloatVector<F> for [F; N] {
+    fn as_floats(&self) -> &[F] {
+        self
+    }
+
+    fn from_floats(floats: Vec<F>) -> Result<Self, Vec<F>> {
+        floats.try_into()
+    }
+
+    fn dimension() -> Option<usize> {
+        Some(N)
+    }
+}
+
+mod sealed {
+    pub trait Sealed<F> {}
+
+    impl<F> Sealed<F> for Vec<F> {}
+    impl<F> Sealed<F> for Box<[F]> {}
+    impl<F, const N: usize> Sealed<F> for [F; N] {}
+}
+
+pub(crate) fn serialize<F, T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    F: Float,
+    T: FloatVector<F>,
+    S: Serializer,
+{
+    let floats = value.as_floats();
+    let mut bytes = Vec::with_capacity(floats.len() * F::SIZE);
+    for float in floats {
+        float.extend_le_bytes(&mut bytes);
+    }
+    serializer.serialize_bytes(&bytes)
+}
+
+pub(crate) fn deserialize<'de, F, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    F: Float,
+    T: FloatVector<F>,
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_bytes(FloatBytesVisitor(PhantomData))
+}
+
+struct FloatBytesVisitor<F, T>(PhantomData<(F, T)>);
+
+impl<'de, F, T> de::Visitor<'de> for FloatBytesVisitor<F, T>
+where
+    F: Float,
+    T: FloatVector<F>,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match T::dimension() {
+            Some(n) => write!(formatter, "{} bytes of packed floats", n * F::SIZE),
+            None => formatter.write_str("packed floats"),
+        }
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        // `usize::is_multiple_of` needs Rust 1.87
+        #[allow(clippy::manual_is_multiple_of)]
+        if v.len() % F::SIZE != 0 {
+            return Err(E::invalid_length(v.len(), &self));
+        }
+        if let Some(n) = T::dimension() {
+            if v.len() / F::SIZE != n {
+                return Err(E::invalid_length(v.len(), &self));
+            }
+        }
+        let floats = v.chunks_exact(F::SIZE).map(F::from_le_slice).collect();
+        T::from_floats(floats).map_err(|floats| E::invalid_length(floats.len() * F::SIZE, &self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{from_item, to_item, AttributeValue, Item};
+    use serde_derive::{Deserialize, Serialize};
+    use std::collections::HashMap;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Vectors {
+        #[serde(with = "crate::f32_bytes")]
+        small: Vec<f32>,
+        #[serde(with = "crate::f64_bytes")]
+        precise: Box<[f64]>,
+        #[serde(with = "crate::f32_bytes")]
+        fixed: [f32; 2],
+    }
+
+    #[test]
+    fn round_trip() {
+        let vectors = Vectors {
+            small: vec![1.0, -0.5, f32::MAX],
+            precise: vec![std::f64::consts::PI].into_boxed_slice(),
+            fixed: [0.0, 1.5],
+        };
+
+        let item: Item = to_item(&vectors).unwrap();
+        let mut expected = Vec::new();
+        for f in [1.0f32, -0.5, f32::MAX] {
+            expected.extend_from_slice(&f.to_le_bytes());
+        }
+        assert_eq!(item["small"], AttributeValue::B(expected));
+        assert_eq!(
+            item["precise"],
+            AttributeValue::B(std::f64::consts::PI.to_le_bytes().to_vec())
+        );
+
+        let round_tripped: Vectors = from_item(item).unwrap();
+        assert_eq!(round_tripped, vectors);
+    }
+
+    #[test]
+    fn wrong_lengths() {
+        let item = |small: Vec<u8>, fixed: Vec<u8>| {
+            Item::from(HashMap::from([
+                (String::from("small"), AttributeValue::B(small)),
+                (String::from("precise"), AttributeValue::B(vec![])),
+                (String::from("fixed"), AttributeValue::B(fixed)),
+            ]))
+        };
+
+        let err = from_item::<_, Vectors>(item(vec![0; 3], vec![0; 8])).unwrap_err();
+        assert_eq!(err.to_string(), "invalid length 3, expected packed floats");
+
+        let err = from_item::<_, Vectors>(item(vec![0; 4], vec![0; 12])).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid length 12, expected 8 bytes of packed floats"
+        );
+    }
+
+    #[test]
+    fn generic_over_vectors() {
+        #[derive(Serialize)]
+        struct Embedding<V: crate::FloatVector<f32>> {
+            #[serde(with = "crate::f32_bytes")]
+            values: V,
+        }
+
+        let boxed = to_item::<_, Item>(Embedding {
+            values: vec![1.0f32].into_boxed_slice(),
+        })
+        .unwrap();
+        let fixed = to_item::<_, Item>(Embedding { values: [1.0f32] }).unwrap();
+        assert_eq!(boxed, fixed);
+    }
+}
@@ -0,0 +1,267 @@
+use crate::de::{self, DeserializerConfig};
+use crate::ser::{self, SerializerConfig};
+use crate::{AttributeValue, Item, Items, Result};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// A serializer and deserializer configuration, set up once and reused for every call
+///
+/// Services usually settle on one policy, such as rejecting floats or ignoring replication
+/// attributes, and apply it everywhere. Instead of passing a [`SerializerConfig`] and a
+/// [`DeserializerConfig`] to each `*_with_config` call, build a `SerdeDynamo` at startup and
+/// call its methods, which mirror the crate's free functions. The configurations are shared
+/// rather than copied, so cloning the handle and calling its methods don't copy them.
+///
+/// ```
+/// use serde_dynamo::{DeserializerConfig, Item, SerdeDynamo, SerializerConfig};
+/// # use serde_derive::{Deserialize, Serialize};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Invoice {
+///     id: String,
+///     cents: u64,
+/// }
+///
+/// let dynamo = SerdeDynamo::new(
+///     SerializerConfig::new().reject_floats(true),
+///     DeserializerConfig::new().ignored_attribute_prefixes(["aws:rep:"]),
+/// );
+///
+/// let invoice = Invoice {
+///     id: String::from("inv-1"),
+///     cents: 1010,
+/// };
+/// let item: Item = dynamo.to_item(&invoice)?;
+/// assert_eq!(dynamo.from_item::<_, Invoice>(item)?, invoice);
+/// # Ok::<(), serde_dynamo::Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SerdeDynamo {
+    serializer_config: Arc<SerializerConfig>,
+    deserializer_config: Arc<DeserializerConfig>,
+}
+
+impl SerdeDynamo {
+    /// Create a handle that writes with `serializer_config` and reads with `deserializer_config`
+    pub fn new(
+        serializer_config: SerializerConfig,
+        deserializer_config: DeserializerConfig,
+    ) -> Self {
+        SerdeDynamo {
+            serializer_config: Arc::new(serializer_config),
+            deserializer_config: Arc::new(deserializer_config),
+        }
+    }
+
+    /// The configuration values are written with
+    pub fn serializer_config(&self) -> &SerializerConfig {
+        &self.serializer_config
+    }
+
+    /// The configuration values are read with
+    pub fn deserializer_config(&self) -> &DeserializerConfig {
+        &self.deserializer_config
+    }
+
+    /// Convert a `T` into an [`AttributeValue`]
+    ///
+    /// See [`to_attribute_value`](crate::to_attribute_value).
+    pub fn to_attribute_value<T, AV>(&self, value: T) -> Result<AV>
+    where
+        T: Serialize,
+        AV: From<AttributeValue>,
+    {
+        ser::to_attribute_value_shared(value, self.serializer_config.clone())
+    }
+
+    /// Convert a `T` into an [`Item`]
+    ///
+    /// See [`to_item`](crate::to_item).
+    pub fn to_item<T, I>(&self, value: T) -> Result<I>
+    where
+        T: Serialize,
+        I: From<Item>,
+    {
+        ser::to_item_shared(value, self.serializer_config.clone())
+    }
+
+    /// Serialize a `T` and add its attributes to an existing [`Item`]
+    ///
+    /// See [`serialize_into`](crate::serialize_into).
+    pub fn serialize_into<T>(
+        &self,
+        item: &mut Item,
+        key_prefix: Option<&str>,
+        value: T,
+    ) -> Result<()>
+    where
+        T: Serialize,
+    {
+        ser::serialize_into_shared(item, key_prefix, value, self.serializer_config.clone())
+    }
+
+    /// Interpret an [`AttributeValue`] as an instance of type `T`
+    ///
+    /// See [`from_attribute_value`](crate::from_attribute_value).
+    pub fn from_attribute_value<'a, AV, T>(&self, attribute_value: AV) -> Result<T>
+    where
+        AV: Into<AttributeValue>,
+        T: Deserialize<'a>,
+    {
+        de::from_attribute_value_shared(attribute_value, self.deserializer_config.clone())
+    }
+
+    /// Interpret an [`Item`] as an instance of type `T`
+    ///
+    /// See [`from_item`](crate::from_item).
+    pub fn from_item<'a, I, T>(&self, item: I) -> Result<T>
+    where
+        I: Into<Item>,
+        T: Deserialize<'a>,
+    {
+        let item: Item = item.into();
+        self.from_attribute_value(AttributeValue::M(item.into()))
+    }
+
+    /// Interpret an optional [`Item`] as an instance of type `T`, if there is one
+    ///
+    /// See [`from_optional_item`](crate::from_optional_item).
+    pub fn from_optional_item<'a, I, T>(&self, item: Option<I>) -> Result<Option<T>>
+    where
+        I: Into<Item>,
+        T: Deserialize<'a>,
+    {
+        item.map(|item| self.from_item(item)).transpose()
+    }
+
+    /// Interpret a [`Items`] as a `Vec<T>`
+    ///
+    /// See [`from_items`](crate::from_items).
+    pub fn from_items<'a, Is, T>(&self, items: Is) -> Result<Vec<T>>
+    where
+        Is: Into<Items>,
+        T: Deserialize<'a>,
+    {
+        de::from_items_shared(items, self.deserializer_config.clone())
+    }
+
+    /// Interpret each item yielded by an iterator as a `T`, collecting them into a `Vec<T>`
+    ///
+    /// See [`from_items_iter`](crate::from_items_iter).
+    pub fn from_items_iter<'a, Is, T>(&self, items: Is) -> Result<Vec<T>>
+    where
+        Is: IntoIterator,
+        Is::Item: Into<Item>,
+        T: Deserialize<'a>,
+    {
+        de::from_items_iter_shared(items, self.deserializer_config.clone())
+    }
+
+    /// Interpret a borrowed [`Item`] as an instance of type `T`
+    ///
+    /// See [`from_item_ref`](crate::from_item_ref).
+    pub fn from_item_ref<'a, T>(&self, item: &Item) -> Result<T>
+    where
+        T: Deserialize<'a>,
+    {
+        de::from_borrowed_item(item.inner(), self.deserializer_config.clone())
+    }
+
+    /// Interpret a slice of borrowed [`Item`]s as a `Vec<T>`
+    ///
+    /// See [`from_items_ref`](crate::from_items_ref).
+    pub fn from_items_ref<'a, T>(&self, items: &[Item]) -> Result<Vec<T>>
+    where
+        T: Deserialize<'a>,
+    {
+        de::from_borrowed_items(
+            items.iter().map(Item::inner),
+            self.deserializer_config.clone(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SerdeDynamo;
+    use crate::{
+        dynamo_item, AttributeValue, DeserializerConfig, ErrorCode, Item, SerializerConfig,
+    };
+    use serde_derive::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Line {
+        sku: String,
+        price: f64,
+    }
+
+    #[test]
+    fn configs_apply_to_every_call() {
+        let dynamo = SerdeDynamo::new(
+            SerializerConfig::new().reject_floats(true),
+            DeserializerConfig::new().coerce_strings(true),
+        );
+        let line = Line {
+            sku: String::from("a"),
+            price: 1.5,
+        };
+
+        let err = dynamo.to_item::<_, Item>(&line).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::RejectedFloat);
+        let err = dynamo
+            .to_attribute_value::<_, AttributeValue>(&line)
+            .unwrap_err();
+        assert_eq!(err.code(), ErrorCode::RejectedFloat);
+        let err = dynamo
+            .serialize_into(&mut Item::new(), None, &line)
+            .unwrap_err();
+        assert_eq!(err.code(), ErrorCode::RejectedFloat);
+
+        // A numeric SKU is only read as a string with the deserializer config
+        let item = dynamo_item! { "sku": 7, "price": 1.5 };
+        let expected = Line {
+            sku: String::from("7"),
+            price: 1.5,
+        };
+        assert_eq!(dynamo.from_item::<_, Line>(item.clone()).unwrap(), expected);
+        assert_eq!(dynamo.from_item_ref::<Line>(&item).unwrap(), expected);
+        assert_eq!(
+            dynamo
+                .from_optional_item::<_, Line>(Some(item.clone()))
+                .unwrap(),
+            Some(expected)
+        );
+        let items = vec![item.clone()];
+        assert_eq!(dynamo.from_items_ref::<Line>(&items).unwrap().len(), 1);
+        assert_eq!(
+            dynamo.from_items::<_, Line>(items.clone()).unwrap().len(),
+            1
+        );
+        assert_eq!(dynamo.from_items_iter::<_, Line>(items).unwrap().len(), 1);
+        assert!(crate::from_item::<_, Line>(item).is_err());
+
+        // The default handle behaves like the free functions
+        let dynamo = SerdeDynamo::default();
+        let item: Item = dynamo.to_item(&line).unwrap();
+        assert_eq!(dynamo.from_item::<_, Line>(item.clone()).unwrap(), line);
+        assert_eq!(dynamo.from_items_ref::<Line>(&[item]).unwrap(), vec![line]);
+        assert_eq!(dynamo.from_optional_item::<Item, Line>(None).unwrap(), None);
+    }
+
+    #[test]
+    fn clones_share_configs() {
+        let dynamo = SerdeDynamo::new(
+            SerializerConfig::new().reject_floats(true),
+            DeserializerConfig::new(),
+        );
+        let clone = dynamo.clone();
+        assert!(std::ptr::eq(
+            dynamo.serializer_config(),
+            clone.serializer_config()
+        ));
+        assert!(std::ptr::eq(
+            dynamo.deserializer_config(),
+            clone.deserializer_config()
+        ));
+    }
+}
@@ -0,0 +1,692 @@
+//! Convert between Amazon Ion text and items
+//!
+//! [DynamoDB exports to S3] can be written in [Amazon Ion] text, one `{ Item: { ... } }` struct
+//! per item. Ion keeps DynamoDB's types exactly: numbers are Ion decimals and integers, binary
+//! values are blobs, and sets are lists annotated with `$dynamodb_SS`, `$dynamodb_NS`, or
+//! `$dynamodb_BS`. [`ion_to_items`] reads that format into [`Item`]s, so export pipelines can use
+//! the same typed deserialization as online reads, and [`items_to_ion`] writes it.
+//!
+//! Numbers keep their digits exactly. Ion's decimal exponent marker `d` is read as DynamoDB's
+//! `E`, so `1.5d-3` becomes the number `1.5E-3`, and a trailing decimal point is dropped, so
+//! `37.` becomes `37`.
+//!
+//! Only the parts of Ion that exports use are supported. Timestamps, S-expressions, and the
+//! special floats `nan`, `+inf` and `-inf` are rejected, since they have no DynamoDB
+//! equivalent. Typed nulls, such as `null.string`, are read as `NULL`, and symbols as strings.
+//!
+//! ```
+//! use serde_dynamo::ion::{ion_to_items, items_to_ion};
+//! use serde_dynamo::{dynamo_item, AttributeValue};
+//!
+//! let export = r#"
+//!     $ion_1_0
+//!     {Item:{id:"a",price:12.50,scale:1.5d-3,tags:$dynamodb_SS::["new","sale"],png:{{iVBORw==}}}}
+//! "#;
+//!
+//! let items = ion_to_items(export)?;
+//! assert_eq!(items[0]["price"], AttributeValue::N(String::from("12.50")));
+//! assert_eq!(items[0]["scale"], AttributeValue::N(String::from("1.5E-3")));
+//! assert_eq!(items[0]["png"], AttributeValue::B(vec![0x89, 0x50, 0x4e, 0x47]));
+//!
+//! assert_eq!(ion_to_items(&items_to_ion(&items))?, items);
+//! # Ok::<(), serde_dynamo::Error>(())
+//! ```
+//!
+//! [DynamoDB exports to S3]: https://docs.aws.amazon.com/amazondynamodb/latest/developerguide/S3DataExport.Output.html
+//! [Amazon Ion]: https://amazon-ion.github.io/ion-docs/
+
+use crate::{error::ErrorImpl, AttributeValue, Item, Result};
+use base64::Engine;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Read items from Ion text
+///
+/// Each top-level struct is one item. When it has an `Item` field, as in DynamoDB exports, that
+/// field is the item; otherwise the struct itself is. See the [module documentation](self).
+pub fn ion_to_items(ion: &str) -> Result<Vec<Item>> {
+    let mut parser = Parser { input: ion, pos: 0 };
+    let mut items = Vec::new();
+    loop {
+        parser.skip_whitespace()?;
+        if parser.pos == ion.len() {
+            return Ok(items);
+        }
+        let start = parser.pos;
+        match parser.value()? {
+            Value::Symbol(version) if version.starts_with("$ion_") => {}
+            Value::Attribute(AttributeValue::M(mut m)) => {
+                let item = match m.remove("Item") {
+                    Some(AttributeValue::M(item)) => item,
+                    Some(other) => {
+                        m.insert(String::from("Item"), other);
+                        m
+                    }
+                    None => m,
+                };
+                items.push(Item::from(item));
+            }
+            _ => return Err(parser.error_at(start, "expected a struct")),
+        }
+    }
+}
+
+/// Write items as Ion text, in the format of DynamoDB exports
+///
+/// See the [module documentation](self).
+pub fn items_to_ion(items: &[Item]) -> String {
+    let mut out = String::from("$ion_1_0\n");
+    for item in items {
+        out.push_str("{Item:");
+        write_struct(&mut out, item);
+        out.push_str("}\n");
+    }
+    out
+}
+
+fn write_value(out: &mut String, value: &AttributeValue) {
+    match value {
+        AttributeValue::Null(_) => out.push_str("null"),
+        AttributeValue::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        AttributeValue::N(n) => write_number(out, n),
+        AttributeValue::S(s) => write_string(out, s),
+        AttributeValue::B(b) => write_blob(out, b),
+        AttributeValue::M(m) => write_struct(out, m),
+        AttributeValue::L(l) => write_list(out, "", l, write_value),
+        AttributeValue::Ss(ss) => {
+            write_list(out, "$dynamodb_SS::", ss, |out, s| write_string(out, s))
+        }
+        AttributeValue::Ns(ns) => {
+            write_list(out, "$dynamodb_NS::", ns, |out, n| write_number(out, n))
+        }
+        AttributeValue::Bs(bs) => {
+            write_list(out, "$dynamodb_BS::", bs, |out, b| write_blob(out, b))
+        }
+    }
+}
+
+fn write_struct(out: &mut String, m: &HashMap<String, AttributeValue>) {
+    // Sort the fields so the same item always produces the same text
+    let mut fields: Vec<_> = m.iter().collect();
+    fields.sort_unstable_by_key(|(name, _)| *name);
+
+    out.push('{');
+    for (index, (name, value)) in fields.into_iter().enumerate() {
+        if index > 0 {
+            out.push(',');
+        }
+        write_string(out, name);
+        out.push(':');
+        write_value(out, value);
+    }
+    out.push('}');
+}
+
+fn write_list<T>(
+    out: &mut String,
+    annotation: &str,
+    elements: &[T],
+    write: impl Fn(&mut String, &T),
+) {
+    out.push_str(annotation);
+    out.push('[');
+    for (index, element) in elements.iter().enumerate() {
+        if index > 0 {
+            out.push(',');
+        }
+        write(out, element);
+    }
+    out.push(']');
+}
+
+/// Write a DynamoDB number as an Ion integer or decimal, keeping its digits
+fn write_number(out: &mut String, n: &str) {
+    let n = n.strip_prefix('+').unwrap_or(n);
+    let (sign, digits) = match n.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", n),
+    };
+    // Ion doesn't allow leading zeros on integers or decimals
+    let trimmed = digits.trim_start_matches('0');
+    let digits = if trimmed.is_empty() || !trimmed.starts_with(|c: char| c.is_ascii_digit()) {
+        &digits[digits.len() - trimmed.len() - 1..]
+    } else {
+        trimmed
+    };
+    out.push_str(sign);
+    match digits.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => {
+            let _ = write!(out, "{mantissa}d{exponent}");
+        }
+        None => out.push_str(digits),
+    }
+}
+
+fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c < ' ' || c == '\u{7f}' => {
+                let _ = write!(out, "\\x{:02x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn write_blob(out: &mut String, b: &[u8]) {
+    out.push_str("{{");
+    out.push_str(&base64::engine::general_purpose::STANDARD.encode(b));
+    out.push_str("}}");
+}
+
+/// A parsed Ion value
+enum Value {
+    Attribute(AttributeValue),
+    /// An unquoted symbol, which may be a version marker
+    Symbol(String),
+}
+
+impl Value {
+    fn into_attribute(self) -> AttributeValue {
+        match self {
+            Value::Attribute(value) => value,
+            Value::Symbol(symbol) => AttributeValue::S(symbol),
+        }
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error_at(&self, pos: usize, message: &str) -> crate::Error {
+        let line = self.input[..pos].matches('\n').count() + 1;
+        ErrorImpl::Ion(format!("{message} at line {line}")).into()
+    }
+
+    fn error(&self, message: &str) -> crate::Error {
+        self.error_at(self.pos, message)
+    }
+
+    fn rest(&self) -> &str {
+        &self.input[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn eat(&mut self, prefix: &str) -> bool {
+        if self.rest().starts_with(prefix) {
+            self.pos += prefix.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, prefix: &str) -> Result<()> {
+        self.skip_whitespace()?;
+        if self.eat(prefix) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{prefix}`")))
+        }
+    }
+
+    fn skip_whitespace(&mut self) -> Result<()> {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+            if self.eat("//") {
+                let end = self.rest().find('\n').unwrap_or(self.rest().len());
+                self.pos += end;
+            } else if self.eat("/*") {
+                let end = self
+                    .rest()
+                    .find("*/")
+                    .ok_or_else(|| self.error("unterminated comment"))?;
+                self.pos += end + 2;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<Value> {
+        self.skip_whitespace()?;
+        let start = self.pos;
+
+        // Annotations are symbols followed by `::`
+        let mut annotations = Vec::new();
+        loop {
+            let before = self.pos;
+            match self.symbol()? {
+                Some(symbol) => {
+                    self.skip_whitespace()?;
+                    if self.eat("::") {
+                        annotations.push(symbol);
+                        self.skip_whitespace()?;
+                    } else {
+                        self.pos = before;
+                        break;
+                    }
+                }
+                None => break,
+            }
+        }
+
+        let value = self.unannotated()?;
+        match (annotations.as_slice(), value) {
+            ([], value) => Ok(value),
+            ([set], Value::Attribute(AttributeValue::L(l))) => {
+                let elements = l.into_iter();
+                let set = match set.as_str() {
+                    "$dynamodb_SS" => AttributeValue::Ss(
+                        elements
+                            .map(|e| match e {
+                                AttributeValue::S(s) => Ok(s),
+                                _ => Err(self.error_at(start, "expected strings in a string set")),
+                            })
+                            .collect::<Result<_>>()?,
+                    ),
+                    "$dynamodb_NS" => AttributeValue::Ns(
+                        elements
+                            .map(|e| match e {
+                                AttributeValue::N(n) => Ok(n),
+                                _ => Err(self.error_at(start, "expected numbers in a number set")),
+                            })
+                            .collect::<Result<_>>()?,
+                    ),
+                    "$dynamodb_BS" => AttributeValue::Bs(
+                        elements
+                            .map(|e| match e {
+                                AttributeValue::B(b) => Ok(b),
+                                _ => Err(self.error_at(start, "expected blobs in a binary set")),
+                            })
+                            .collect::<Result<_>>()?,
+                    ),
+                    _ => return Err(self.error_at(start, &format!("unknown annotation `{set}`"))),
+                };
+                Ok(Value::Attribute(set))
+            }
+            _ => Err(self.error_at(start, "unsupported annotations")),
+        }
+    }
+
+    fn unannotated(&mut self) -> Result<Value> {
+        let start = self.pos;
+        let value = match self.peek() {
+            Some('{') if self.rest().starts_with("{{") => self.lob()?,
+            Some('{') => self.structure()?,
+            Some('[') => {
+                self.pos += 1;
+                let mut list = Vec::new();
+                self.sequence(']', |parser| {
+                    list.push(parser.value()?.into_attribute());
+                    Ok(())
+                })?;
+                AttributeValue::L(list)
+            }
+            Some('"') | Some('\'') if !self.rest().starts_with("''''") => {
+                if self.rest().starts_with("'''") {
+                    AttributeValue::S(self.long_string()?)
+                } else if self.peek() == Some('"') {
+                    AttributeValue::S(self.quoted('"')?)
+                } else {
+                    // Quoted symbols read as strings
+                    AttributeValue::S(self.quoted('\'')?)
+                }
+            }
+            Some(c) if c == '-' || c.is_ascii_digit() || c == '+' => self.number()?,
+            Some('(') => return Err(self.error("S-expressions are not supported")),
+            _ => match self.symbol()? {
+                Some(symbol) => match symbol.as_str() {
+                    "true" => AttributeValue::Bool(true),
+                    "false" => AttributeValue::Bool(false),
+                    "null" => {
+                        // Typed nulls, such as `null.string`
+                        if self.eat(".") {
+                            self.symbol()?;
+                        }
+                        AttributeValue::Null(true)
+                    }
+                    "nan" => return Err(self.error_at(start, "`nan` is not a DynamoDB number")),
+                    _ => return Ok(Value::Symbol(symbol)),
+                },
+                None => return Err(self.error("expected a value")),
+            },
+        };
+        Ok(Value::Attribute(value))
+    }
+
+    /// Parse comma-separated elements up to `close`, after the opening bracket
+    fn sequence(
+        &mut self,
+        close: char,
+        mut element: impl FnMut(&mut Self) -> Result<()>,
+    ) -> Result<()> {
+        self.skip_whitespace()?;
+        if self.eat(&close.to_string()) {
+            return Ok(());
+        }
+        loop {
+            element(self)?;
+            self.skip_whitespace()?;
+            if self.eat(&close.to_string()) {
+                return Ok(());
+            }
+            self.expect(",")?;
+            // Ion allows a trailing comma
+            self.skip_whitespace()?;
+            if self.eat(&close.to_string()) {
+                return Ok(());
+            }
+        }
+    }
+
+    fn structure(&mut self) -> Result<AttributeValue> {
+        self.pos += 1;
+        let mut m = HashMap::new();
+        self.sequence('}', |parser| {
+            parser.skip_whitespace()?;
+            let name = match parser.peek() {
+                Some('"') => parser.quoted('"')?,
+                Some('\'') if parser.rest().starts_with("'''") => parser.long_string()?,
+                Some('\'') => parser.quoted('\'')?,
+                _ => parser
+                    .symbol()?
+                    .ok_or_else(|| parser.error("expected a field name"))?,
+            };
+            parser.expect(":")?;
+            let value = parser.value()?.into_attribute();
+            m.insert(name, value);
+            Ok(())
+        })?;
+        Ok(AttributeValue::M(m))
+    }
+
+    /// An identifier symbol, such as a field name, keyword, or annotation
+    fn symbol(&mut self) -> Result<Option<String>> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '$'))
+            .unwrap_or(rest.len());
+        if len == 0 || rest.starts_with(|c: char| c.is_ascii_digit()) {
+            return Ok(None);
+        }
+        let symbol = rest[..len].to_string();
+        self.pos += len;
+        Ok(Some(symbol))
+    }
+
+    fn number(&mut self) -> Result<AttributeValue> {
+        let start = self.pos;
+        if self.eat("+inf") || self.eat("-inf") {
+            return Err(self.error_at(start, "infinity is not a DynamoDB number"));
+        }
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '+' | '.' | '_')))
+            .unwrap_or(rest.len());
+        let literal = rest[..len].to_string();
+        self.pos += len;
+
+        let invalid = || self.error_at(start, &format!("`{literal}` is not a number"));
+        let text: String = literal
+            .chars()
+            .filter(|c| *c != '_')
+            .map(|c| if c == 'd' || c == 'D' { 'E' } else { c })
+            .collect();
+        let (mantissa, exponent) = match text.split_once(['e', 'E']) {
+            Some((mantissa, exponent)) => (mantissa, Some(exponent)),
+            None => (text.as_str(), None),
+        };
+        let digits = mantissa.strip_prefix('-').unwrap_or(mantissa);
+        let valid_mantissa = !digits.is_empty()
+            && digits.starts_with(|c: char| c.is_ascii_digit())
+            && digits.chars().all(|c| c.is_ascii_digit() || c == '.')
+            && digits.matches('.').count() <= 1;
+        let valid_exponent = exponent.is_none_or(|exponent| {
+            let exponent = exponent.strip_prefix(['-', '+']).unwrap_or(exponent);
+            !exponent.is_empty() && exponent.chars().all(|c| c.is_ascii_digit())
+        });
+        if !valid_mantissa || !valid_exponent {
+            return Err(invalid());
+        }
+
+        let mantissa = mantissa.strip_suffix('.').unwrap_or(mantissa);
+        Ok(AttributeValue::N(match exponent {
+            Some(exponent) => format!("{mantissa}E{exponent}"),
+            None => mantissa.to_string(),
+        }))
+    }
+
+    fn quoted(&mut self, quote: char) -> Result<String> {
+        let start = self.pos;
+        self.pos += 1;
+        let mut s = String::new();
+        loop {
+            let c = self
+                .peek()
+                .ok_or_else(|| self.error_at(start, "unterminated string"))?;
+            self.pos += c.len_utf8();
+            match c {
+                c if c == quote => return Ok(s),
+                '\\' => self.escape(&mut s)?,
+                '\n' => return Err(self.error_at(start, "unterminated string")),
+                c => s.push(c),
+            }
+        }
+    }
+
+    /// One or more `'''`-quoted strings, which Ion concatenates
+    fn long_string(&mut self) -> Result<String> {
+        let mut s = String::new();
+        loop {
+            let start = self.pos;
+            self.pos += 3;
+            loop {
+                if self.eat("'''") {
+                    break;
+                }
+                let c = self
+                    .peek()
+                    .ok_or_else(|| self.error_at(start, "unterminated string"))?;
+                self.pos += c.len_utf8();
+                if c == '\\' {
+                    self.escape(&mut s)?;
+                } else {
+                    s.push(c);
+                }
+            }
+
+            let before = self.pos;
+            self.skip_whitespace()?;
+            if !self.rest().starts_with("'''") {
+                self.pos = before;
+                return Ok(s);
+            }
+        }
+    }
+
+    fn escape(&mut self, s: &mut String) -> Result<()> {
+        let start = self.pos - 1;
+        let c = self
+            .peek()
+            .ok_or_else(|| self.error_at(start, "unterminated escape"))?;
+        self.pos += c.len_utf8();
+        let mut hex = |parser: &mut Self, len: usize| -> Result<()> {
+            let code = parser
+                .rest()
+                .get(..len)
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .and_then(char::from_u32)
+                .ok_or_else(|| parser.error_at(start, "invalid escape"))?;
+            parser.pos += len;
+            s.push(code);
+            Ok(())
+        };
+        match c {
+            'n' => s.push('\n'),
+            'r' => s.push('\r'),
+            't' => s.push('\t'),
+            'a' => s.push('\u{7}'),
+            'b' => s.push('\u{8}'),
+            'f' => s.push('\u{c}'),
+            'v' => s.push('\u{b}'),
+            '0' => s.push('\0'),
+            'x' => hex(self, 2)?,
+            'u' => hex(self, 4)?,
+            'U' => hex(self, 8)?,
+            // An escaped newline continues the string
+            '\n' => {}
+            '"' | '\'' | '\\' | '/' | '?' => s.push(c),
+            _ => return Err(self.error_at(start, "invalid escape")),
+        }
+        Ok(())
+    }
+
+    /// A blob, `{{ base64 }}`, or a clob, `{{ "text" }}`
+    fn lob(&mut self) -> Result<AttributeValue> {
+        let start = self.pos;
+        self.pos += 2;
+        self.skip_whitespace()?;
+        let bytes = if self.peek() == Some('"') || self.rest().starts_with("'''") {
+            let text = if self.peek() == Some('"') {
+                self.quoted('"')?
+            } else {
+                self.long_string()?
+            };
+            text.chars()
+                .map(|c| u8::try_from(c).map_err(|_| self.error_at(start, "clobs must be ASCII")))
+                .collect::<Result<Vec<u8>>>()?
+        } else {
+            let end = self
+                .rest()
+                .find("}}")
+                .ok_or_else(|| self.error_at(start, "unterminated blob"))?;
+            let base64: String = self.rest()[..end].split_whitespace().collect();
+            self.pos += end;
+            base64::engine::general_purpose::STANDARD
+                .decode(base64)
+                .map_err(|_| self.error_at(start, "invalid base64 in blob"))?
+        };
+        self.expect("}}")?;
+        Ok(AttributeValue::B(bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dynamo_item;
+
+    #[test]
+    fn round_trip_every_type() {
+        let mut items = vec![
+            dynamo_item! {
+                "s": "quote \" backslash \\ newline \n control \u{1} unicode é",
+                "n": "-0.00120",
+                "exponent": 12,
+                "bool": true,
+                "null": null,
+                "b": b([0u8, 1, 255]),
+                "m": { "field name": [1, { "x": false }] },
+                "ss": ss["a", "b"],
+                "ns": ns[1, -2.5],
+                "bs": bs[[1u8], [2u8, 3]],
+            },
+            dynamo_item! {},
+        ];
+        items[0].insert(
+            String::from("n"),
+            AttributeValue::N(String::from("-0.00120")),
+        );
+        items[0].insert(
+            String::from("exponent"),
+            AttributeValue::N(String::from("1.5E+10")),
+        );
+
+        let ion = items_to_ion(&items);
+        assert!(ion.contains("-0.00120"), "{ion}");
+        assert!(ion.contains("1.5d+10"), "{ion}");
+        assert_eq!(ion_to_items(&ion).unwrap(), items);
+    }
+
+    #[test]
+    fn read_ion_syntax() {
+        let ion = r#"
+            // A comment
+            $ion_1_0
+            /* Another comment */
+            {
+                'quoted symbol': 'sym',
+                plain: sym,
+                "string": '''long ''' '''string''',
+                ints: [0, -7, 1_000],
+                decimals: [37., 1.5d-3, 2D2, 1e3],
+                clob: {{ "hi" }},
+                blob: {{ aGk= }},
+                typed: null.string,
+                trailing: [1, 2,],
+            }
+        "#;
+        let mut expected = dynamo_item! {
+            "quoted symbol": "sym",
+            "plain": "sym",
+            "string": "long string",
+            "ints": [0, -7, 1000],
+            "clob": b(b"hi"),
+            "blob": b(b"hi"),
+            "typed": null,
+            "trailing": [1, 2],
+        };
+        expected.insert(
+            String::from("decimals"),
+            AttributeValue::L(
+                ["37", "1.5E-3", "2E2", "1E3"]
+                    .into_iter()
+                    .map(|n| AttributeValue::N(String::from(n)))
+                    .collect(),
+            ),
+        );
+        assert_eq!(ion_to_items(ion).unwrap(), vec![expected]);
+    }
+
+    #[test]
+    fn reject_unsupported_values() {
+        for (ion, message) in [
+            ("{a: nan}", "`nan` is not a DynamoDB number at line 1"),
+            ("{a: +inf}", "infinity is not a DynamoDB number at line 1"),
+            (
+                "{a: 2007-01-01T}",
+                "`2007-01-01T` is not a number at line 1",
+            ),
+            ("{a: (1 2)}", "S-expressions are not supported at line 1"),
+            ("\n[1]", "expected a struct at line 2"),
+            (
+                "{a: $dynamodb_SS::[1]}",
+                "expected strings in a string set at line 1",
+            ),
+            ("{a: \"open}", "unterminated string at line 1"),
+        ] {
+            let err = ion_to_items(ion).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                format!("Ion conversion failed: {message}"),
+                "{ion}"
+            );
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use crate::{error::ErrorImpl, Error, Item, Result};
+use serde::de::value::UnitDeserializer;
+use serde::Deserialize;
+
+/// Define a typed, borrowed view over an [`Item`](crate::Item)
+///
+/// Hot paths that read one or two fields from each of many large items pay for deserializing
+/// every field with [`from_item`](crate::from_item). A view wraps a `&Item` instead, with a
+/// getter per field that deserializes just that attribute when it is called. List the fields
+/// of the full struct that are worth reading this way, with the same names and types.
+///
+/// Each getter returns a [`Result`](crate::Result) whose error names the attribute. A missing
+/// attribute reads as `None` for `Option` fields and is an error otherwise. Write
+/// `field as "name"` for a field whose attribute has another name, such as one renamed with
+/// `#[serde(rename)]`. Attributes on fields, such as doc comments, are copied to the getters.
+///
+/// ```
+/// use serde_dynamo::{dynamo_item, item_view};
+///
+/// item_view! {
+///     /// The fields of a user that listings show
+///     pub struct UserView {
+///         /// The user's id
+///         id: String,
+///         display_name as "displayName": String,
+///         age: Option<u8>,
+///     }
+/// }
+///
+/// let item = dynamo_item! {
+///     "id": "u-42",
+///     "displayName": "Ann",
+///     "bio": "A long biography...",
+/// };
+///
+/// let view = UserView::new(&item);
+/// assert_eq!(view.display_name()?, "Ann");
+/// assert_eq!(view.age()?, None);
+/// # Ok::<(), serde_dynamo::Error>(())
+/// ```
+#[macro_export]
+macro_rules! item_view {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident {
+            $(
+                $(#[$field_attr:meta])*
+                $field:ident $(as $attribute:literal)? : $ty:ty
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, Copy)]
+        $vis struct $name<'a> {
+            item: &'a $crate::Item,
+        }
+
+        impl<'a> $name<'a> {
+            /// View the fields of `item`
+            $vis fn new(item: &'a $crate::Item) -> Self {
+                Self { item }
+            }
+
+            /// The item being viewed
+            $vis fn item(&self) -> &'a $crate::Item {
+                self.item
+            }
+
+            $(
+                $(#[$field_attr])*
+                $vis fn $field(&self) -> $crate::Result<$ty> {
+                    $crate::__view_field(self.item, $crate::__item_view_attribute!($field $(as $attribute)?))
+                }
+            )*
+        }
+
+        impl<'a> ::std::convert::From<&'a $crate::Item> for $name<'a> {
+            fn from(item: &'a $crate::Item) -> Self {
+                Self::new(item)
+            }
+        }
+    };
+}
+
+/// The attribute name of a field of an [`item_view!`]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __item_view_attribute {
+    ($field:ident) => {
+        ::std::stringify!($field)
+    };
+    ($field:ident as $attribute:literal) => {
+        $attribute
+    };
+}
+
+/// Deserialize the attribute `name` of `item`, reading a missing attribute as `None`
+#[doc(hidden)]
+pub fn __view_field<'de, T>(item: &Item, name: &str) -> Result<T>
+where
+    T: Deserialize<'de>,
+{
+    if item.contains_key(name) {
+        return item.get_as(name);
+    }
+    T::deserialize(UnitDeserializer::<Error>::new())
+        .map_err(|_| ErrorImpl::MissingAttribute(name.to_string()).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dynamo_item;
+
+    item_view! {
+        struct OrderView {
+            id: String,
+            total: u64,
+            placed_at as "placedAt": Option<String>,
+            lines: Vec<u32>,
+        }
+    }
+
+    #[test]
+    fn reads_fields_on_access() {
+        let item = dynamo_item! {
+            "id": "o-1",
+            "total": "not a number",
+            "placedAt": null,
+            "lines": [1, 2],
+        };
+        let view = OrderView::from(&item);
+
+        assert_eq!(view.id().unwrap(), "o-1");
+        assert_eq!(view.placed_at().unwrap(), None);
+        assert_eq!(view.lines().unwrap(), [1, 2]);
+        assert_eq!(
+            view.total().unwrap_err().to_string(),
+            "Attribute `total`: Expected num"
+        );
+        assert!(std::ptr::eq(view.item(), &item));
+
+        let empty = dynamo_item! {};
+        let view = OrderView::new(&empty);
+        assert_eq!(view.placed_at().unwrap(), None);
+        assert_eq!(
+            view.id().unwrap_err().to_string(),
+            "Attribute `id` is missing"
+        );
+    }
+}
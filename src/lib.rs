@@ -110,6 +110,10 @@
 //! See [`aws_sdk_dynamodb_1`] for examples and more information. See
 //! [`aws_sdk_dynamodbstreams_1`] for DynamoDb streams support.
 //!
+//! Features for several versions can be enabled at once, for example while migrating from one to
+//! the next. Each version gets its own module, and [`sdk`] re-exports the newest one enabled, so
+//! code that imports from [`sdk`] moves to the new version when the old feature is removed.
+//!
 //! ## aws_lambda_events support
 //!
 //! [aws_lambda_events], starting with version 0.8, uses **serde_dynamo** directly, so no feature
@@ -208,6 +212,13 @@
 //! because crates.io doesn't support feature names with dots). For example, support for
 //! `aws-sdk-dynamodb` version `0.13` is enabled with the feature `aws-sdk-dynamodb+0_13`.
 //!
+//! Other features add support for more types. For example, with the `bytes` feature,
+//! `bytes::Bytes` and `bytes::BytesMut` fields are stored as binary attributes, and reading them
+//! takes over the decoded buffer rather than copying it.
+//!
+//! Helpers that only belong in tests, [`testing`](crate::testing) and
+//! [`tokens`](crate::tokens), are behind the `testing` feature. Enable it for your `[dev-dependencies]` only.
+//!
 //! ## Converting to and from DynamoDB JSON
 //!
 //! In most cases, libraries already exist to handle the raw DynamoDB JSON and convert it into an
@@ -257,24 +268,115 @@
 //! [aws_lambda_events]: https://docs.rs/aws_lambda_events
 //! [rusoto_dynamodb]: https://docs.rs/rusoto_dynamodb
 
+mod attr_names;
 mod attribute_value;
+#[cfg(feature = "bytes")]
+mod bytes;
+#[cfg(feature = "compact_str")]
+mod compact_attribute_value;
 mod de;
+mod dynamo_value;
 mod error;
+mod flatten;
+mod float_bytes;
+mod handle;
+mod item_view;
 mod macros;
+mod map_key;
+mod number;
+mod optional_list;
+mod seq_of;
 mod ser;
-
+#[cfg(feature = "stats")]
+mod stats;
+mod tagged_tuple;
+mod try_chain;
+mod warnings;
+
+pub mod adjacency;
+#[cfg(feature = "arrow")]
+#[cfg_attr(docsrs, doc(cfg(feature = "arrow")))]
+pub mod arrow;
+pub mod batch;
 pub mod binary_set;
+pub mod chunking;
+pub mod compat;
+pub mod counter;
+#[cfg(feature = "csv")]
+#[cfg_attr(docsrs, doc(cfg(feature = "csv")))]
+pub mod csv;
+#[cfg(feature = "integration-tests")]
+#[cfg_attr(docsrs, doc(cfg(feature = "integration-tests")))]
+pub mod dynamodb_local;
+pub mod envelope;
+pub mod expression;
+pub mod f32_bytes;
+pub mod f64_bytes;
+#[cfg(feature = "ion")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ion")))]
+pub mod ion;
+pub mod list_update;
+pub mod map_as_pairs;
+pub mod migrations;
 pub mod number_set;
+pub mod path;
+pub mod prelude;
+pub mod projection;
+#[cfg(feature = "prost")]
+#[cfg_attr(docsrs, doc(cfg(feature = "prost")))]
+pub mod prost_message;
+pub mod remote;
+pub mod std_enums;
 pub mod string_set;
-
-pub use attribute_value::{AttributeValue, Item, Items};
-pub use de::{from_attribute_value, from_item, from_items, Deserializer};
-pub use error::{Error, Result};
+#[cfg(feature = "testing")]
+#[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+pub mod testing;
+#[cfg(feature = "testing")]
+#[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+pub mod tokens;
+pub mod ttl;
+pub mod zero_as_none;
+
+pub use attribute_value::{AttributeValue, Item, Items, ScrubPolicy};
+#[cfg(feature = "compact_str")]
+#[cfg_attr(docsrs, doc(cfg(feature = "compact_str")))]
+pub use compact_attribute_value::CompactAttributeValue;
+pub use de::{
+    from_attribute_value, from_attribute_value_with_config, from_item, from_item_ref,
+    from_item_ref_with_config, from_item_with_config, from_items, from_items_iter,
+    from_items_iter_with_config, from_items_ref, from_items_ref_with_config,
+    from_items_with_config, from_optional_item, BinaryPolicy, Deserializer, DeserializerConfig,
+    DeserializerRef, NullFalsePolicy, UnknownKeyPolicy,
+};
+pub use error::{Error, ErrorCode, Result};
+pub use float_bytes::FloatVector;
+pub use handle::SerdeDynamo;
+#[doc(hidden)]
+pub use item_view::__view_field;
 use macros::{
-    aws_lambda_events_macro, aws_sdk_macro, aws_sdk_macro_before_0_35, aws_sdk_streams_macro,
-    rusoto_macro, rusoto_streams_macro,
+    aws_lambda_events_macro, aws_sdk_macro, aws_sdk_macro_before_0_35, aws_sdk_newest_macro,
+    aws_sdk_streams_macro, rusoto_macro, rusoto_streams_macro,
+};
+pub use map_key::MapKey;
+pub use optional_list::OptionalList;
+pub use seq_of::{SeqOf, SeqOnce};
+pub use ser::{
+    condition, serialize_into, serialize_into_with_config, to_attribute_value,
+    to_attribute_value_with_config, to_item, to_item_with_config, ConfiguredSerializer,
+    EmptySetPolicy, FieldOverride, NewtypeStructs, NonePolicy, NumberPrecision, Serializer,
+    SerializerConfig, SerializerLimits,
 };
-pub use ser::{to_attribute_value, to_item, Serializer};
+#[cfg(feature = "validator")]
+#[cfg_attr(docsrs, doc(cfg(feature = "validator")))]
+pub use ser::{to_item_validated, to_item_validated_with_config};
+#[doc(hidden)]
+pub use serde as __serde;
+#[cfg(feature = "stats")]
+#[cfg_attr(docsrs, doc(cfg(feature = "stats")))]
+pub use stats::{ItemStats, Stats};
+pub use tagged_tuple::TaggedTuple;
+pub use try_chain::{try_chain, TryChain};
+pub use warnings::{Warning, Warnings};
 
 aws_sdk_macro_before_0_35!(
     feature = "aws-sdk-dynamodb+0_7",
@@ -606,6 +708,77 @@ aws_sdk_macro!(
     config_version = "1",
 );
 
+aws_sdk_newest_macro!(
+    /// The newest enabled version of [aws-sdk-dynamodb](https://docs.rs/aws-sdk-dynamodb)
+    ///
+    /// This re-exports the module of the newest version whose feature is enabled, along with its
+    /// [`AttributeValue`](sdk::AttributeValue) type and an [`Item`](sdk::Item) alias for its
+    /// items. It only exists when at least one `aws-sdk-dynamodb` feature is enabled.
+    ///
+    /// Enabling features for several versions compiles, but each version's types are distinct,
+    /// and mixing up imports gives confusing errors about mismatched `AttributeValue` types.
+    /// Import from `sdk` for the version in use, and from a versioned module only for code that
+    /// deliberately targets an older version during a migration.
+    ///
+    /// ```
+    /// # use serde_derive::{Deserialize, Serialize};
+    /// use serde_dynamo::sdk::{self, AttributeValue};
+    ///
+    /// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    /// struct User {
+    ///     id: String,
+    /// }
+    ///
+    /// let user = User { id: String::from("fSsgVtal8TpP") };
+    /// let item: sdk::Item = sdk::to_item(&user)?;
+    /// assert_eq!(item["id"], AttributeValue::S(String::from("fSsgVtal8TpP")));
+    /// assert_eq!(sdk::from_item::<User>(item)?, user);
+    /// # Ok::<(), serde_dynamo::Error>(())
+    /// ```
+    ///
+    /// # Supported versions
+    ///
+    /// | Feature | Module | SDK attribute value type |
+    /// |---|---|---|
+    /// | `aws-sdk-dynamodb+1` | `aws_sdk_dynamodb_1` | `aws_sdk_dynamodb::types::AttributeValue` |
+    /// | `aws-sdk-dynamodb+0_35` to `+0_39` | `aws_sdk_dynamodb_0_35` to `_0_39` | `aws_sdk_dynamodb::types::AttributeValue` |
+    /// | `aws-sdk-dynamodb+0_7` to `+0_34`, except `+0_20` | `aws_sdk_dynamodb_0_7` to `_0_34` | `aws_sdk_dynamodb::model::AttributeValue` |
+    mod sdk;
+    "aws-sdk-dynamodb+1" => aws_sdk_dynamodb_1,
+    "aws-sdk-dynamodb+0_39" => aws_sdk_dynamodb_0_39,
+    "aws-sdk-dynamodb+0_38" => aws_sdk_dynamodb_0_38,
+    "aws-sdk-dynamodb+0_37" => aws_sdk_dynamodb_0_37,
+    "aws-sdk-dynamodb+0_36" => aws_sdk_dynamodb_0_36,
+    "aws-sdk-dynamodb+0_35" => aws_sdk_dynamodb_0_35,
+    "aws-sdk-dynamodb+0_34" => aws_sdk_dynamodb_0_34,
+    "aws-sdk-dynamodb+0_33" => aws_sdk_dynamodb_0_33,
+    "aws-sdk-dynamodb+0_32" => aws_sdk_dynamodb_0_32,
+    "aws-sdk-dynamodb+0_31" => aws_sdk_dynamodb_0_31,
+    "aws-sdk-dynamodb+0_30" => aws_sdk_dynamodb_0_30,
+    "aws-sdk-dynamodb+0_29" => aws_sdk_dynamodb_0_29,
+    "aws-sdk-dynamodb+0_28" => aws_sdk_dynamodb_0_28,
+    "aws-sdk-dynamodb+0_27" => aws_sdk_dynamodb_0_27,
+    "aws-sdk-dynamodb+0_26" => aws_sdk_dynamodb_0_26,
+    "aws-sdk-dynamodb+0_25" => aws_sdk_dynamodb_0_25,
+    "aws-sdk-dynamodb+0_24" => aws_sdk_dynamodb_0_24,
+    "aws-sdk-dynamodb+0_23" => aws_sdk_dynamodb_0_23,
+    "aws-sdk-dynamodb+0_22" => aws_sdk_dynamodb_0_22,
+    "aws-sdk-dynamodb+0_21" => aws_sdk_dynamodb_0_21,
+    "aws-sdk-dynamodb+0_19" => aws_sdk_dynamodb_0_19,
+    "aws-sdk-dynamodb+0_18" => aws_sdk_dynamodb_0_18,
+    "aws-sdk-dynamodb+0_17" => aws_sdk_dynamodb_0_17,
+    "aws-sdk-dynamodb+0_16" => aws_sdk_dynamodb_0_16,
+    "aws-sdk-dynamodb+0_15" => aws_sdk_dynamodb_0_15,
+    "aws-sdk-dynamodb+0_14" => aws_sdk_dynamodb_0_14,
+    "aws-sdk-dynamodb+0_13" => aws_sdk_dynamodb_0_13,
+    "aws-sdk-dynamodb+0_12" => aws_sdk_dynamodb_0_12,
+    "aws-sdk-dynamodb+0_11" => aws_sdk_dynamodb_0_11,
+    "aws-sdk-dynamodb+0_10" => aws_sdk_dynamodb_0_10,
+    "aws-sdk-dynamodb+0_9" => aws_sdk_dynamodb_0_9,
+    "aws-sdk-dynamodb+0_8" => aws_sdk_dynamodb_0_8,
+    "aws-sdk-dynamodb+0_7" => aws_sdk_dynamodb_0_7,
+);
+
 aws_sdk_streams_macro!(
     feature = "aws-sdk-dynamodbstreams+0_8",
     crate_name = __aws_sdk_dynamodbstreams_0_8,
@@ -952,3 +1125,11 @@ aws_lambda_events_macro!(
 
 #[cfg(test)]
 mod tests;
+
+// The prost_message module, and the code the prost derive generates in its tests, use the
+// crate's own name
+#[cfg(feature = "prost")]
+extern crate __prost as prost;
+// The validator derive refers to its crate by name
+#[cfg(all(test, feature = "validator"))]
+extern crate __validator as validator;
@@ -0,0 +1,174 @@
+//! Helpers for update expressions that change lists in place
+//!
+//! DynamoDB can add elements to either end of a list attribute, and remove elements by index,
+//! without reading the item first. These helpers build those update expressions for a document
+//! path, such as `tags` or `profile.addresses[0].lines`.
+//!
+//! Every map key in the path is given as a placeholder, so keys may be reserved words. Paths
+//! that don't parse, such as `a..b` or `a[x]`, are rejected before anything is sent.
+//!
+//! ```no_run
+//! # use __aws_sdk_dynamodb_1::client::Client;
+//! # use __aws_sdk_dynamodb_1::types::AttributeValue;
+//! use serde_dynamo::list_update;
+//!
+//! # async fn add_tags(client: &Client) -> Result<(), Box<dyn std::error::Error>> {
+//! let (expression, names, values) = list_update::append_expression("tags", ["new", "sale"])?
+//!     .into_parts::<AttributeValue>();
+//!
+//! client
+//!     .update_item()
+//!     .table_name("products")
+//!     .set_key(Some(serde_dynamo::to_item(serde_json::json!({ "id": "p1" }))?))
+//!     .update_expression(expression)
+//!     .set_expression_attribute_names(names)
+//!     .set_expression_attribute_values(values)
+//!     .send()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{error::ErrorImpl, expression::Expression, path, AttributeValue, Result};
+use serde::Serialize;
+
+/// Build the update expression `SET #path = list_append(#path, :items)`, which adds `items` to
+/// the end of the list at `path`
+///
+/// `items` must serialize to a list, such as a `Vec` or an array. The list attribute must
+/// already exist.
+///
+/// ```
+/// use serde_dynamo::{list_update, AttributeValue};
+///
+/// let expression = list_update::append_expression("history.events", vec![42])?;
+///
+/// assert_eq!(
+///     expression.expression(),
+///     "SET #path0.#path1 = list_append(#path0.#path1, :items)"
+/// );
+/// assert_eq!(expression.names()["#path1"], "events");
+/// assert_eq!(
+///     expression.values()[":items"],
+///     AttributeValue::L(vec![AttributeValue::N(String::from("42"))])
+/// );
+/// # Ok::<(), serde_dynamo::Error>(())
+/// ```
+pub fn append_expression<T>(path: &str, items: T) -> Result<Expression>
+where
+    T: Serialize,
+{
+    list_append(path, items, |path| format!("list_append({path}, :items)"))
+}
+
+/// Build the update expression `SET #path = list_append(:items, #path)`, which adds `items` to
+/// the start of the list at `path`
+///
+/// See [`append_expression`].
+pub fn prepend_expression<T>(path: &str, items: T) -> Result<Expression>
+where
+    T: Serialize,
+{
+    list_append(path, items, |path| format!("list_append(:items, {path})"))
+}
+
+/// Build the update expression `REMOVE #path[index]`, which removes one element from the list at
+/// `path`
+///
+/// Later elements move down to fill the gap. Removing an index past the end of the list does
+/// nothing.
+///
+/// ```
+/// use serde_dynamo::list_update;
+///
+/// let expression = list_update::remove_index_expression("tags", 3)?;
+///
+/// assert_eq!(expression.expression(), "REMOVE #path0[3]");
+/// assert_eq!(expression.names()["#path0"], "tags");
+/// assert!(expression.values().is_empty());
+/// # Ok::<(), serde_dynamo::Error>(())
+/// ```
+pub fn remove_index_expression(path: &str, index: usize) -> Result<Expression> {
+    let segments = path::parse(path)?;
+    let (path, names) = path::placeholders(&segments);
+    Ok(with_names(
+        Expression::raw(format!("REMOVE {path}[{index}]")),
+        names,
+    ))
+}
+
+fn list_append<T>(path: &str, items: T, function: fn(&str) -> String) -> Result<Expression>
+where
+    T: Serialize,
+{
+    let segments = path::parse(path)?;
+    let (path, names) = path::placeholders(&segments);
+    let expression = with_names(
+        Expression::raw(format!("SET {path} = {}", function(&path))),
+        names,
+    )
+    .with_value(":items", items)?;
+    if !matches!(expression.values()[":items"], AttributeValue::L(_)) {
+        return Err(ErrorImpl::ExpectedSeq.into());
+    }
+    Ok(expression)
+}
+
+fn with_names(
+    expression: Expression,
+    names: std::collections::HashMap<String, String>,
+) -> Expression {
+    names
+        .into_iter()
+        .fold(expression, |expression, (placeholder, name)| {
+            expression.with_name(placeholder, name)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn list_expressions() {
+        let s = |s: &str| AttributeValue::S(String::from(s));
+
+        let (expression, names, values) = prepend_expression("a.name[2]", ("x", "y"))
+            .unwrap()
+            .into_parts::<AttributeValue>();
+        assert_eq!(
+            expression,
+            "SET #path0.#path1[2] = list_append(:items, #path0.#path1[2])"
+        );
+        assert_eq!(
+            names,
+            Some(HashMap::from([
+                (String::from("#path0"), String::from("a")),
+                (String::from("#path1"), String::from("name")),
+            ]))
+        );
+        assert_eq!(
+            values,
+            Some(HashMap::from([(
+                String::from(":items"),
+                AttributeValue::L(vec![s("x"), s("y")])
+            )]))
+        );
+
+        let (expression, _, values) = remove_index_expression("a[0]", 1)
+            .unwrap()
+            .into_parts::<AttributeValue>();
+        assert_eq!(expression, "REMOVE #path0[0][1]");
+        assert_eq!(values, None);
+
+        assert_eq!(
+            append_expression("tags", "new").unwrap_err().to_string(),
+            "Expected seq"
+        );
+        for invalid in ["", "a..b", "a[x]", "[1]"] {
+            assert!(append_expression(invalid, ["x"]).is_err(), "{invalid}");
+            assert!(remove_index_expression(invalid, 0).is_err(), "{invalid}");
+        }
+    }
+}
@@ -19,6 +19,10 @@ macro_rules! aws_lambda_events_macro {
             use crate::Result;
             use ::$crate_name::dynamodb::attributes::AttributeValue;
 
+            /// # Panics
+            ///
+            /// Panics if an `N` or `NS` value isn't a number, since aws_lambda_events stores
+            /// numbers as `f64`.
             impl From<crate::AttributeValue> for AttributeValue {
                 fn from(attribute_value: crate::AttributeValue) -> AttributeValue {
                     match attribute_value {
@@ -95,6 +99,18 @@ macro_rules! aws_lambda_events_macro {
                 crate::de::from_item(item)
             }
 
+            /// A version of [`crate::from_optional_item`] that accepts an
+            /// `aws_lambda_events`-specific `Option<HashMap<String, AttributeValue>>` instead of an
+            /// `Option` of a [`serde_dynamo`-specific Item](crate::Item).
+            pub fn from_optional_item<'a, T>(
+                item: Option<std::collections::HashMap<String, AttributeValue>>,
+            ) -> Result<Option<T>>
+            where
+                T: serde::de::Deserialize<'a>,
+            {
+                crate::de::from_optional_item(item)
+            }
+
             /// A version of [`crate::from_items`] that accepts an
             /// `aws_lambda_events`-specific `Vec<HashMap<String, AttributeValue>>` instead of a
             /// [`serde_dynamo`-specific Items](crate::Items).
@@ -106,6 +122,16 @@ macro_rules! aws_lambda_events_macro {
             {
                 crate::de::from_items(items)
             }
+
+            /// The types and functions most code using this SDK needs
+            ///
+            /// Unlike the crate-root functions, these take and return this SDK's types, so their
+            /// results need no type annotations. See [`crate::prelude`].
+            pub mod prelude {
+                pub use super::{from_attribute_value, from_item, from_items, from_optional_item, to_attribute_value, to_item};
+                pub use ::$crate_name::dynamodb::attributes::AttributeValue;
+                pub use crate::{DeserializerConfig, Error, ErrorCode, SerializerConfig};
+            }
         }
     };
 }
@@ -79,6 +79,28 @@ macro_rules! aws_sdk_macro {
             //! # }
             //! ```
             //!
+            //! When the response is still needed afterwards, [`from_item_ref`] and
+            //! [`from_items_ref`] read borrowed items without cloning them first.
+            //!
+            //! ```
+            #![doc = concat!("# use ", stringify!($crate_name), "::client::Client;")]
+            //! # use serde_derive::{Serialize, Deserialize};
+            #![doc = concat!("use serde_dynamo::", stringify!($mod_name), "::from_items_ref;")]
+            //! #
+            //! # async fn scan(client: &Client) -> Result<(), Box<dyn std::error::Error>> {
+            //! #[derive(Serialize, Deserialize)]
+            //! pub struct User {
+            //!     id: String,
+            //!     name: String,
+            //! };
+            //!
+            //! let result = client.scan().table_name("user").send().await?;
+            //! let users: Vec<User> = from_items_ref(result.items())?;
+            //! println!("Got {} users, and still have the response", users.len());
+            //! # Ok(())
+            //! # }
+            //! ```
+            //!
             //!
             //! ## Creating items by serializing data structures
             //!
@@ -176,9 +198,12 @@ macro_rules! aws_sdk_macro {
             //! [aws_sdk_dynamodb::model::AttributeValue]: https://docs.rs/rusoto_dynamodb/0.47.0/rusoto_dynamodb/struct.AttributeValue.html
 
             use crate::Result;
-            use $attribute_value_path;
+            pub use $attribute_value_path;
             use $blob_path;
 
+            /// An item, as this version of aws-sdk-dynamodb represents it
+            pub type Item = std::collections::HashMap<String, AttributeValue>;
+
             impl From<crate::AttributeValue> for AttributeValue {
                 fn from(attribute_value: crate::AttributeValue) -> AttributeValue {
                     match attribute_value {
@@ -196,21 +221,35 @@ macro_rules! aws_sdk_macro {
                 }
             }
 
+            /// # Panics
+            ///
+            /// Panics if the value holds none of the data types this crate knows, such as one added
+            /// in a later version of DynamoDB. The `from_*` functions of this module fail with
+            /// [`ErrorCode::UnsupportedType`](crate::ErrorCode::UnsupportedType) instead.
             impl From<AttributeValue> for crate::AttributeValue {
                 fn from(attribute_value: AttributeValue) -> crate::AttributeValue {
-                    match attribute_value {
+                    crate::de::IntoAttributeValue::into_attribute_value(attribute_value)
+                        .unwrap_or_else(|err| panic!("{err}"))
+                }
+            }
+
+            impl crate::de::IntoAttributeValue for AttributeValue {
+                fn into_attribute_value(self) -> Result<crate::AttributeValue> {
+                    use crate::de::IntoAttributeValue;
+                    Ok(match self {
                         AttributeValue::N(n) => crate::AttributeValue::N(n),
                         AttributeValue::S(s) => crate::AttributeValue::S(s),
                         AttributeValue::Bool(b) => crate::AttributeValue::Bool(b),
                         AttributeValue::B(v) => crate::AttributeValue::B(v.into_inner()),
                         AttributeValue::Null(null) => crate::AttributeValue::Null(null),
-                        AttributeValue::M(m) => crate::AttributeValue::M(m.into_iter().map(|(key, attribute_value)| (key, crate::AttributeValue::from(attribute_value))).collect()),
-                        AttributeValue::L(l) => crate::AttributeValue::L(l.into_iter().map(crate::AttributeValue::from).collect()),
+                        AttributeValue::M(m) => crate::AttributeValue::M(m.into_iter().map(|(key, attribute_value)| Ok((key, attribute_value.into_attribute_value()?))).collect::<Result<_>>()?),
+                        AttributeValue::L(l) => crate::AttributeValue::L(l.into_iter().map(IntoAttributeValue::into_attribute_value).collect::<Result<_>>()?),
                         AttributeValue::Ss(ss) => crate::AttributeValue::Ss(ss),
                         AttributeValue::Ns(ns) => crate::AttributeValue::Ns(ns),
                         AttributeValue::Bs(bs) => crate::AttributeValue::Bs(bs.into_iter().map(Blob::into_inner).collect()),
-                        _ => panic!("Unexpectedly did not match any possible data types"),
-                    }
+                        // A type added to DynamoDB after this version of the SDK was released
+                        unknown => return Err(crate::error::ErrorImpl::UnsupportedType(format!("{unknown:?}")).into()),
+                    })
                 }
             }
 
@@ -247,7 +286,7 @@ macro_rules! aws_sdk_macro {
             where
                 T: serde::de::Deserialize<'a>,
             {
-                crate::de::from_attribute_value(attribute_value)
+                crate::de::from_attribute_value(crate::de::IntoAttributeValue::into_attribute_value(attribute_value)?)
             }
 
             /// A version of [`crate::from_item`] where the `AV` generic is tied to
@@ -261,7 +300,21 @@ macro_rules! aws_sdk_macro {
             where
                 T: serde::de::Deserialize<'a>,
             {
-                crate::de::from_item(item)
+                crate::de::from_item(crate::de::try_into_item(item)?)
+            }
+
+            /// A version of [`crate::from_optional_item`] where the `AV` generic is tied to
+            /// [`aws-sdk-dynamodb::model::AttributeValue`](AttributeValue).
+            ///
+            /// Useful in very generic code where the type checker can't determine the type of
+            /// `AV`.
+            pub fn from_optional_item<'a, T>(
+                item: Option<std::collections::HashMap<String, AttributeValue>>,
+            ) -> Result<Option<T>>
+            where
+                T: serde::de::Deserialize<'a>,
+            {
+                crate::de::from_optional_item(item.map(crate::de::try_into_item).transpose()?)
             }
 
             /// A version of [`crate::from_items`] where the `AV` generic is tied to
@@ -275,8 +328,103 @@ macro_rules! aws_sdk_macro {
             where
                 T: serde::de::Deserialize<'a>,
             {
-                crate::de::from_items(items)
+                crate::de::from_items(items.into_iter().map(crate::de::try_into_item).collect::<Result<Vec<_>>>()?)
+            }
+
+            /// A version of [`crate::from_items_iter`] where the `AV` generic is tied to
+            /// [`aws-sdk-dynamodb::model::AttributeValue`](AttributeValue).
+            ///
+            /// Useful in very generic code where the type checker can't determine the type of
+            /// `AV`.
+            pub fn from_items_iter<'a, I, T>(items: I) -> Result<Vec<T>>
+            where
+                I: IntoIterator<Item = std::collections::HashMap<String, AttributeValue>>,
+                T: serde::de::Deserialize<'a>,
+            {
+                items.into_iter().map(from_item).collect()
+            }
+
+            impl crate::de::BorrowedAttributeValue for AttributeValue {
+                fn map(&self) -> Option<&std::collections::HashMap<String, Self>> {
+                    self.as_m().ok()
+                }
+
+                fn list(&self) -> Option<&[Self]> {
+                    self.as_l().ok().map(Vec::as_slice)
+                }
+
+                fn null(&self) -> Option<bool> {
+                    self.as_null().ok().copied()
+                }
+
+                fn to_owned_value(&self) -> Result<crate::AttributeValue> {
+                    Ok(match self {
+                        AttributeValue::M(m) => crate::AttributeValue::M(
+                            m.iter()
+                                .map(|(key, value)| Ok((key.clone(), value.to_owned_value()?)))
+                                .collect::<Result<_>>()?,
+                        ),
+                        AttributeValue::L(l) => crate::AttributeValue::L(
+                            l.iter().map(Self::to_owned_value).collect::<Result<_>>()?,
+                        ),
+                        AttributeValue::N(_)
+                        | AttributeValue::S(_)
+                        | AttributeValue::Bool(_)
+                        | AttributeValue::B(_)
+                        | AttributeValue::Null(_)
+                        | AttributeValue::Ss(_)
+                        | AttributeValue::Ns(_)
+                        | AttributeValue::Bs(_) => crate::AttributeValue::from(self.clone()),
+                        // A type added to DynamoDB after this version of the SDK was released
+                        unknown => {
+                            return Err(crate::error::ErrorImpl::UnsupportedType(format!(
+                                "{unknown:?}"
+                            ))
+                            .into())
+                        }
+                    })
+                }
+            }
+
+            /// A version of [`crate::from_item_ref`] for a borrowed
+            /// [`aws-sdk-dynamodb::model::AttributeValue`](AttributeValue) map.
+            ///
+            /// Responses that are still needed afterwards can be read without cloning them.
+            /// Maps and lists are walked in place, and only the values `T` reads are copied.
+            pub fn from_item_ref<'a, T>(
+                item: &std::collections::HashMap<String, AttributeValue>,
+            ) -> Result<T>
+            where
+                T: serde::de::Deserialize<'a>,
+            {
+                crate::de::from_borrowed_item(item, Default::default())
+            }
+
+            /// A version of [`crate::from_items_ref`] for borrowed
+            /// [`aws-sdk-dynamodb::model::AttributeValue`](AttributeValue) maps.
+            ///
+            /// See [`from_item_ref`].
+            pub fn from_items_ref<'a, T>(
+                items: &[std::collections::HashMap<String, AttributeValue>],
+            ) -> Result<Vec<T>>
+            where
+                T: serde::de::Deserialize<'a>,
+            {
+                crate::de::from_borrowed_items(items, Default::default())
             }
+
+            /// The types and functions most code using this SDK needs
+            ///
+            /// Unlike the crate-root functions, these take and return this SDK's types, so their
+            /// results need no type annotations. See [`crate::prelude`].
+            pub mod prelude {
+                pub use super::{from_attribute_value, from_item, from_item_ref, from_items, from_items_iter, from_items_ref, from_optional_item, to_attribute_value, to_item, AttributeValue, Item};
+                pub use crate::{DeserializerConfig, Error, ErrorCode, SerializerConfig};
+            }
+
+            crate::macros::aws_sdk_batch_macro!(crate_name = $crate_name, mod_name = $mod_name,);
+            crate::macros::aws_sdk_table_macro!(crate_name = $crate_name, mod_name = $mod_name,);
+            crate::macros::aws_sdk_testing_macro!(crate_name = $crate_name,);
         }
 
         #[cfg(feature = $feature)]
@@ -307,7 +455,7 @@ macro_rules! aws_sdk_macro {
             where
                 T: serde::de::Deserialize<'a>,
             {
-                crate::de::from_attribute_value(attribute_value)
+                crate::de::from_attribute_value(crate::de::IntoAttributeValue::into_attribute_value(attribute_value)?)
             }
 
             #[deprecated(since = "4.0.0", note = "The double-underscore on the mod name is no longer necessary")]
@@ -317,7 +465,7 @@ macro_rules! aws_sdk_macro {
             where
                 T: serde::de::Deserialize<'a>,
             {
-                crate::de::from_item(item)
+                crate::de::from_item(crate::de::try_into_item(item)?)
             }
 
             #[deprecated(since = "4.0.0", note = "The double-underscore on the mod name is no longer necessary")]
@@ -327,7 +475,7 @@ macro_rules! aws_sdk_macro {
             where
                 T: serde::de::Deserialize<'a>,
             {
-                crate::de::from_items(items)
+                crate::de::from_items(items.into_iter().map(crate::de::try_into_item).collect::<Result<Vec<_>>>()?)
             }
         }
     };
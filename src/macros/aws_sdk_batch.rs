@@ -0,0 +1,105 @@
+#[allow(unused_macros)]
+macro_rules! aws_sdk_batch_macro {
+    (crate_name = $crate_name:ident, mod_name = $mod_name:ident,) => {
+        /// A write request from a [batch_write_item] call, with its item or key deserialized
+        ///
+        /// See [`from_unprocessed_items`].
+        ///
+        /// [batch_write_item]: https://docs.rs/aws-sdk-dynamodb/*/aws_sdk_dynamodb/client/struct.Client.html#method.batch_write_item
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub enum UnprocessedWrite<T, K> {
+            /// A put request and the value it was writing
+            Put(T),
+            /// A delete request and the key it was deleting
+            Delete(K),
+        }
+
+        /// Deserialize the `unprocessed_items` of a [batch_write_item] response, by table name
+        ///
+        /// DynamoDB hands back the writes it didn't get to as raw write requests. Converting them
+        /// back into domain types lets a retry loop work with the same values it started with.
+        /// Put requests are deserialized as `T` and delete requests as `K`.
+        ///
+        /// Fails with [`ErrorCode::InvalidWriteRequest`](crate::ErrorCode::InvalidWriteRequest)
+        /// on a request with neither a put nor a delete, or a put without an item or a delete
+        /// without a key, rather than dropping a write the caller would never retry.
+        ///
+        /// ```no_run
+        #[doc = concat!("# use ", stringify!($crate_name), "::client::Client;")]
+        /// # use serde_derive::{Serialize, Deserialize};
+        #[doc = concat!("use serde_dynamo::", stringify!($mod_name), "::{from_unprocessed_items, UnprocessedWrite};")]
+        #[doc = concat!("# use ", stringify!($crate_name), "::types::WriteRequest;")]
+        /// # use std::collections::HashMap;
+        /// #
+        /// # async fn write(client: &Client, request: HashMap<String, Vec<WriteRequest>>) -> Result<(), Box<dyn std::error::Error>> {
+        /// #[derive(Serialize, Deserialize)]
+        /// pub struct User {
+        ///     id: String,
+        ///     name: String,
+        /// };
+        ///
+        /// #[derive(Serialize, Deserialize)]
+        /// pub struct UserKey {
+        ///     id: String,
+        /// };
+        ///
+        /// let output = client.batch_write_item().set_request_items(Some(request)).send().await?;
+        ///
+        /// let unprocessed = from_unprocessed_items::<User, UserKey>(output.unprocessed_items)?;
+        /// for (table, writes) in unprocessed {
+        ///     for write in writes {
+        ///         match write {
+        ///             UnprocessedWrite::Put(user) => println!("{}: retry put of {}", table, user.id),
+        ///             UnprocessedWrite::Delete(key) => println!("{}: retry delete of {}", table, key.id),
+        ///         }
+        ///     }
+        /// }
+        /// # Ok(())
+        /// # }
+        /// ```
+        ///
+        /// [batch_write_item]: https://docs.rs/aws-sdk-dynamodb/*/aws_sdk_dynamodb/client/struct.Client.html#method.batch_write_item
+        pub fn from_unprocessed_items<T, K>(
+            unprocessed_items: Option<
+                std::collections::HashMap<String, Vec<$crate_name::types::WriteRequest>>,
+            >,
+        ) -> Result<std::collections::HashMap<String, Vec<UnprocessedWrite<T, K>>>>
+        where
+            T: serde::de::DeserializeOwned,
+            K: serde::de::DeserializeOwned,
+        {
+            unprocessed_items
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(table, requests)| {
+                    let writes = requests
+                        .into_iter()
+                        .map(|request| {
+                            let invalid = |problem| -> crate::Error {
+                                crate::error::ErrorImpl::InvalidWriteRequest(table.clone(), problem).into()
+                            };
+                            // Depending on the SDK version, the item and key are either required
+                            // or optional; `Option::from` accepts both.
+                            if let Some(put) = request.put_request {
+                                let item: Option<std::collections::HashMap<String, AttributeValue>> =
+                                    Option::from(put.item);
+                                let item = item.ok_or_else(|| invalid("the put request has no item"))?;
+                                from_item(item).map(UnprocessedWrite::Put)
+                            } else if let Some(delete) = request.delete_request {
+                                let key: Option<std::collections::HashMap<String, AttributeValue>> =
+                                    Option::from(delete.key);
+                                let key = key.ok_or_else(|| invalid("the delete request has no key"))?;
+                                from_item(key).map(UnprocessedWrite::Delete)
+                            } else {
+                                Err(invalid("it has neither a put nor a delete request"))
+                            }
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    Ok((table, writes))
+                })
+                .collect()
+        }
+    };
+}
+
+pub(crate) use aws_sdk_batch_macro;
@@ -0,0 +1,23 @@
+/// Define the module `$name` as a re-export of the first SDK module whose feature is enabled
+///
+/// List the modules newest first. Each candidate is only considered when every newer feature is
+/// disabled, so exactly one module is defined no matter how many features are enabled.
+macro_rules! aws_sdk_newest_macro {
+    ($(#[$attr:meta])* mod $name:ident;) => {};
+    (
+        $(#[$attr:meta])* mod $name:ident;
+        $feature:literal => $mod_name:ident,
+        $($rest:tt)*
+    ) => {
+        #[cfg(feature = $feature)]
+        $(#[$attr])*
+        pub mod $name {
+            pub use crate::$mod_name::*;
+        }
+
+        #[cfg(not(feature = $feature))]
+        crate::macros::aws_sdk_newest_macro!($(#[$attr])* mod $name; $($rest)*);
+    };
+}
+
+pub(crate) use aws_sdk_newest_macro;
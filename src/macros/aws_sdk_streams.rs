@@ -45,21 +45,35 @@ macro_rules! aws_sdk_streams_macro {
                 }
             }
 
+            /// # Panics
+            ///
+            /// Panics if the value holds none of the data types this crate knows, such as one added
+            /// in a later version of DynamoDB. The `from_*` functions of this module fail with
+            /// [`ErrorCode::UnsupportedType`](crate::ErrorCode::UnsupportedType) instead.
             impl From<AttributeValue> for crate::AttributeValue {
                 fn from(attribute_value: AttributeValue) -> crate::AttributeValue {
-                    match attribute_value {
+                    crate::de::IntoAttributeValue::into_attribute_value(attribute_value)
+                        .unwrap_or_else(|err| panic!("{err}"))
+                }
+            }
+
+            impl crate::de::IntoAttributeValue for AttributeValue {
+                fn into_attribute_value(self) -> Result<crate::AttributeValue> {
+                    use crate::de::IntoAttributeValue;
+                    Ok(match self {
                         AttributeValue::N(n) => crate::AttributeValue::N(n),
                         AttributeValue::S(s) => crate::AttributeValue::S(s),
                         AttributeValue::Bool(b) => crate::AttributeValue::Bool(b),
                         AttributeValue::B(v) => crate::AttributeValue::B(v.into_inner()),
                         AttributeValue::Null(null) => crate::AttributeValue::Null(null),
-                        AttributeValue::M(m) => crate::AttributeValue::M(m.into_iter().map(|(key, attribute_value)| (key, crate::AttributeValue::from(attribute_value))).collect()),
-                        AttributeValue::L(l) => crate::AttributeValue::L(l.into_iter().map(crate::AttributeValue::from).collect()),
+                        AttributeValue::M(m) => crate::AttributeValue::M(m.into_iter().map(|(key, attribute_value)| Ok((key, attribute_value.into_attribute_value()?))).collect::<Result<_>>()?),
+                        AttributeValue::L(l) => crate::AttributeValue::L(l.into_iter().map(IntoAttributeValue::into_attribute_value).collect::<Result<_>>()?),
                         AttributeValue::Ss(ss) => crate::AttributeValue::Ss(ss),
                         AttributeValue::Ns(ns) => crate::AttributeValue::Ns(ns),
                         AttributeValue::Bs(bs) => crate::AttributeValue::Bs(bs.into_iter().map(Blob::into_inner).collect()),
-                        _ => panic!("Unexpectedly did not match any possible data types"),
-                    }
+                        // A type added to DynamoDB after this version of the SDK was released
+                        unknown => return Err(crate::error::ErrorImpl::UnsupportedType(format!("{unknown:?}")).into()),
+                    })
                 }
             }
 
@@ -96,7 +110,7 @@ macro_rules! aws_sdk_streams_macro {
             where
                 T: serde::de::Deserialize<'a>,
             {
-                crate::de::from_attribute_value(attribute_value)
+                crate::de::from_attribute_value(crate::de::IntoAttributeValue::into_attribute_value(attribute_value)?)
             }
 
             /// A version of [`crate::from_item`] where the `AV` generic is tied to
@@ -110,7 +124,21 @@ macro_rules! aws_sdk_streams_macro {
             where
                 T: serde::de::Deserialize<'a>,
             {
-                crate::de::from_item(item)
+                crate::de::from_item(crate::de::try_into_item(item)?)
+            }
+
+            /// A version of [`crate::from_optional_item`] where the `AV` generic is tied to
+            /// [`aws-sdk-dynamodbstreams::model::AttributeValue`](AttributeValue).
+            ///
+            /// Useful in very generic code where the type checker can't determine the type of
+            /// `AV`.
+            pub fn from_optional_item<'a, T>(
+                item: Option<std::collections::HashMap<String, AttributeValue>>,
+            ) -> Result<Option<T>>
+            where
+                T: serde::de::Deserialize<'a>,
+            {
+                crate::de::from_optional_item(item.map(crate::de::try_into_item).transpose()?)
             }
 
             /// A version of [`crate::from_items`] where the `AV` generic is tied to
@@ -124,7 +152,17 @@ macro_rules! aws_sdk_streams_macro {
             where
                 T: serde::de::Deserialize<'a>,
             {
-                crate::de::from_items(items)
+                crate::de::from_items(items.into_iter().map(crate::de::try_into_item).collect::<Result<Vec<_>>>()?)
+            }
+
+            /// The types and functions most code using this SDK needs
+            ///
+            /// Unlike the crate-root functions, these take and return this SDK's types, so their
+            /// results need no type annotations. See [`crate::prelude`].
+            pub mod prelude {
+                pub use super::{from_attribute_value, from_item, from_items, from_optional_item, to_attribute_value, to_item};
+                pub use $attribute_value_path;
+                pub use crate::{DeserializerConfig, Error, ErrorCode, SerializerConfig};
             }
         }
 
@@ -156,7 +194,7 @@ macro_rules! aws_sdk_streams_macro {
             where
                 T: serde::de::Deserialize<'a>,
             {
-                crate::de::from_attribute_value(attribute_value)
+                crate::de::from_attribute_value(crate::de::IntoAttributeValue::into_attribute_value(attribute_value)?)
             }
 
             #[deprecated(since = "4.0.0", note = "The double-underscore on the mod name is no longer necessary")]
@@ -166,7 +204,7 @@ macro_rules! aws_sdk_streams_macro {
             where
                 T: serde::de::Deserialize<'a>,
             {
-                crate::de::from_item(item)
+                crate::de::from_item(crate::de::try_into_item(item)?)
             }
 
             #[deprecated(since = "4.0.0", note = "The double-underscore on the mod name is no longer necessary")]
@@ -176,7 +214,7 @@ macro_rules! aws_sdk_streams_macro {
             where
                 T: serde::de::Deserialize<'a>,
             {
-                crate::de::from_items(items)
+                crate::de::from_items(items.into_iter().map(crate::de::try_into_item).collect::<Result<Vec<_>>>()?)
             }
         }
     };
@@ -0,0 +1,259 @@
+#[allow(unused_macros)]
+macro_rules! aws_sdk_table_macro {
+    (crate_name = $crate_name:ident, mod_name = $mod_name:ident,) => {
+        /// A typed facade over a single table
+        ///
+        /// `Table` bundles a client, a table name and the table's key schema with the type `T`
+        /// stored in it, and uses serde_dynamo for every conversion. It covers the common single
+        /// item operations and querying a partition; anything more involved can still be done with
+        /// [`client`](Table::client) and the free functions in this module.
+        ///
+        /// Keys are passed as any serializable value:
+        ///
+        /// * a struct or map is used as the key item as-is,
+        /// * a two-element tuple is used as `(partition key, sort key)` when the table has a sort
+        ///   key, and
+        /// * anything else is used as the partition key value.
+        ///
+        /// A two-element tuple or list on a table without a sort key is therefore a partition key
+        /// value of type `L`, which DynamoDB rejects. Tables with a sort key should always be
+        /// given one with [`with_sort_key`](Table::with_sort_key).
+        ///
+        /// `Table` is only available for `aws-sdk-dynamodb` 0.35 and later. The older SDK versions
+        /// and rusoto have no facade; use their clients with the free functions instead.
+        ///
+        /// ```no_run
+        #[doc = concat!("# use ", stringify!($crate_name), "::client::Client;")]
+        /// # use serde_derive::{Serialize, Deserialize};
+        #[doc = concat!("# use serde_dynamo::", stringify!($mod_name), "::TableError;")]
+        #[doc = concat!("use serde_dynamo::", stringify!($mod_name), "::Table;")]
+        /// #
+        /// # async fn example(client: Client) -> Result<(), TableError> {
+        /// #[derive(Serialize, Deserialize)]
+        /// pub struct Message {
+        ///     channel: String,
+        ///     sent_at: u64,
+        ///     body: String,
+        /// }
+        ///
+        /// let messages = Table::<Message>::new(client, "messages", "channel").with_sort_key("sent_at");
+        ///
+        /// messages.put(&Message {
+        ///     channel: String::from("general"),
+        ///     sent_at: 1700000000,
+        ///     body: String::from("Hello"),
+        /// }).await?;
+        ///
+        /// let message: Option<Message> = messages.get(("general", 1700000000)).await?;
+        /// let channel: Vec<Message> = messages.query_pk("general").await?;
+        /// messages.delete(("general", 1700000000)).await?;
+        /// # Ok(())
+        /// # }
+        /// ```
+        #[derive(Debug, Clone)]
+        pub struct Table<T> {
+            client: $crate_name::client::Client,
+            name: String,
+            partition_key: String,
+            sort_key: Option<String>,
+            _type: std::marker::PhantomData<fn() -> T>,
+        }
+
+        impl<T> Table<T> {
+            /// Create a facade over the table with the given name and partition key attribute
+            pub fn new(
+                client: $crate_name::client::Client,
+                name: impl Into<String>,
+                partition_key: impl Into<String>,
+            ) -> Self {
+                Self {
+                    client,
+                    name: name.into(),
+                    partition_key: partition_key.into(),
+                    sort_key: None,
+                    _type: std::marker::PhantomData,
+                }
+            }
+
+            /// Set the table's sort key attribute
+            pub fn with_sort_key(mut self, sort_key: impl Into<String>) -> Self {
+                self.sort_key = Some(sort_key.into());
+                self
+            }
+
+            /// The underlying client
+            pub fn client(&self) -> &$crate_name::client::Client {
+                &self.client
+            }
+
+            /// The table name
+            pub fn name(&self) -> &str {
+                &self.name
+            }
+
+            fn key<K>(
+                &self,
+                key: K,
+            ) -> Result<std::collections::HashMap<String, AttributeValue>, TableError>
+            where
+                K: serde::ser::Serialize,
+            {
+                Ok(table_key(
+                    &self.partition_key,
+                    self.sort_key.as_deref(),
+                    key,
+                )?)
+            }
+        }
+
+        /// The key item for `key` on a table with the given key attributes, as described on
+        /// [`Table`]
+        pub(crate) fn table_key<K>(
+            partition_key: &str,
+            sort_key: Option<&str>,
+            key: K,
+        ) -> Result<std::collections::HashMap<String, AttributeValue>>
+        where
+            K: serde::ser::Serialize,
+        {
+            let key = match (
+                crate::to_attribute_value::<_, crate::AttributeValue>(key)?,
+                sort_key,
+            ) {
+                (crate::AttributeValue::M(key), _) => key,
+                (crate::AttributeValue::L(mut values), Some(sort_key)) if values.len() == 2 => {
+                    let sort_value = values.pop().unwrap();
+                    let partition_value = values.pop().unwrap();
+                    std::collections::HashMap::from([
+                        (String::from(partition_key), partition_value),
+                        (String::from(sort_key), sort_value),
+                    ])
+                }
+                (value, _) => {
+                    std::collections::HashMap::from([(String::from(partition_key), value)])
+                }
+            };
+            Ok(key
+                .into_iter()
+                .map(|(name, value)| (name, AttributeValue::from(value)))
+                .collect())
+        }
+
+        impl<T> Table<T>
+        where
+            T: serde::ser::Serialize + serde::de::DeserializeOwned,
+        {
+            /// Get the item with the given key, if it exists
+            pub async fn get<K>(&self, key: K) -> Result<Option<T>, TableError>
+            where
+                K: serde::ser::Serialize,
+            {
+                let output = self
+                    .client
+                    .get_item()
+                    .table_name(&self.name)
+                    .set_key(Some(self.key(key)?))
+                    .send()
+                    .await
+                    .map_err($crate_name::Error::from)?;
+                Ok(from_optional_item(output.item)?)
+            }
+
+            /// Write the value, replacing any existing item with the same key
+            pub async fn put(&self, value: &T) -> Result<(), TableError> {
+                self.client
+                    .put_item()
+                    .table_name(&self.name)
+                    .set_item(Some(to_item(value)?))
+                    .send()
+                    .await
+                    .map_err($crate_name::Error::from)?;
+                Ok(())
+            }
+
+            /// Get every item in the given partition, following pagination to the end
+            pub async fn query_pk<PK>(&self, partition_value: PK) -> Result<Vec<T>, TableError>
+            where
+                PK: serde::ser::Serialize,
+            {
+                let partition_value = to_attribute_value(partition_value)?;
+                let mut values = Vec::new();
+                let mut exclusive_start_key = None;
+                loop {
+                    let output = self
+                        .client
+                        .query()
+                        .table_name(&self.name)
+                        .key_condition_expression("#pk = :pk")
+                        .expression_attribute_names("#pk", &self.partition_key)
+                        .expression_attribute_values(":pk", partition_value.clone())
+                        .set_exclusive_start_key(exclusive_start_key)
+                        .send()
+                        .await
+                        .map_err($crate_name::Error::from)?;
+                    values.extend(from_items::<T>(output.items.unwrap_or_default())?);
+                    exclusive_start_key = output.last_evaluated_key;
+                    if exclusive_start_key.is_none() {
+                        return Ok(values);
+                    }
+                }
+            }
+
+            /// Delete the item with the given key
+            pub async fn delete<K>(&self, key: K) -> Result<(), TableError>
+            where
+                K: serde::ser::Serialize,
+            {
+                self.client
+                    .delete_item()
+                    .table_name(&self.name)
+                    .set_key(Some(self.key(key)?))
+                    .send()
+                    .await
+                    .map_err($crate_name::Error::from)?;
+                Ok(())
+            }
+        }
+
+        /// An error from a [`Table`] operation
+        #[derive(Debug)]
+        pub enum TableError {
+            /// Converting between the item and `T` failed
+            Serde(crate::Error),
+            /// The request to DynamoDB failed
+            Sdk(Box<$crate_name::Error>),
+        }
+
+        impl std::fmt::Display for TableError {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self {
+                    TableError::Serde(err) => err.fmt(f),
+                    TableError::Sdk(err) => err.fmt(f),
+                }
+            }
+        }
+
+        impl std::error::Error for TableError {
+            fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+                match self {
+                    TableError::Serde(err) => Some(err),
+                    TableError::Sdk(err) => Some(err.as_ref()),
+                }
+            }
+        }
+
+        impl From<crate::Error> for TableError {
+            fn from(err: crate::Error) -> Self {
+                TableError::Serde(err)
+            }
+        }
+
+        impl From<$crate_name::Error> for TableError {
+            fn from(err: $crate_name::Error) -> Self {
+                TableError::Sdk(Box::new(err))
+            }
+        }
+    };
+}
+
+pub(crate) use aws_sdk_table_macro;
@@ -0,0 +1,38 @@
+#[allow(unused_macros)]
+macro_rules! aws_sdk_testing_macro {
+    (crate_name = $crate_name:ident,) => {
+        #[cfg(feature = "testing")]
+        impl From<crate::testing::Page> for $crate_name::operation::query::QueryOutput {
+            /// A response holding the page's items, with `count` and `scanned_count` set to the
+            /// number of items
+            fn from(page: crate::testing::Page) -> Self {
+                let (items, last_evaluated_key) = page.into_parts();
+                let count = i32::try_from(items.len()).unwrap_or(i32::MAX);
+                Self::builder()
+                    .set_items(Some(items.into()))
+                    .set_last_evaluated_key(last_evaluated_key.map(Into::into))
+                    .count(count)
+                    .scanned_count(count)
+                    .build()
+            }
+        }
+
+        #[cfg(feature = "testing")]
+        impl From<crate::testing::Page> for $crate_name::operation::scan::ScanOutput {
+            /// A response holding the page's items, with `count` and `scanned_count` set to the
+            /// number of items
+            fn from(page: crate::testing::Page) -> Self {
+                let (items, last_evaluated_key) = page.into_parts();
+                let count = i32::try_from(items.len()).unwrap_or(i32::MAX);
+                Self::builder()
+                    .set_items(Some(items.into()))
+                    .set_last_evaluated_key(last_evaluated_key.map(Into::into))
+                    .count(count)
+                    .scanned_count(count)
+                    .build()
+            }
+        }
+    };
+}
+
+pub(crate) use aws_sdk_testing_macro;
@@ -1,13 +1,24 @@
 mod aws_lambda_events;
 mod aws_sdk;
+mod aws_sdk_batch;
 mod aws_sdk_before_0_35;
+mod aws_sdk_newest;
 mod aws_sdk_streams;
+mod aws_sdk_table;
+mod aws_sdk_testing;
 mod rusoto;
 mod rusoto_streams;
 
 pub(crate) use aws_lambda_events::aws_lambda_events_macro;
 pub(crate) use aws_sdk::aws_sdk_macro;
+#[allow(unused_imports)]
+pub(crate) use aws_sdk_batch::aws_sdk_batch_macro;
 pub(crate) use aws_sdk_before_0_35::aws_sdk_macro_before_0_35;
+pub(crate) use aws_sdk_newest::aws_sdk_newest_macro;
 pub(crate) use aws_sdk_streams::aws_sdk_streams_macro;
+#[allow(unused_imports)]
+pub(crate) use aws_sdk_table::aws_sdk_table_macro;
+#[allow(unused_imports)]
+pub(crate) use aws_sdk_testing::aws_sdk_testing_macro;
 pub(crate) use rusoto::rusoto_macro;
 pub(crate) use rusoto_streams::rusoto_streams_macro;
@@ -39,21 +39,35 @@ macro_rules! rusoto_streams_macro {
                 }
             }
 
+            /// # Panics
+            ///
+            /// Panics if the value holds none of the data types this crate knows, such as one added
+            /// in a later version of DynamoDB. The `from_*` functions of this module fail with
+            /// [`ErrorCode::UnsupportedType`](crate::ErrorCode::UnsupportedType) instead.
             impl From<AttributeValue> for crate::AttributeValue {
                 fn from(attribute_value: AttributeValue) -> crate::AttributeValue {
-                    if let Some(n) = attribute_value.n { crate::AttributeValue::N(n) }
-                    else if let Some(s) = attribute_value.s { crate::AttributeValue::S(s) }
-                    else if let Some(b) = attribute_value.bool { crate::AttributeValue::Bool(b) }
-                    else if let Some(v) = attribute_value.b { crate::AttributeValue::B(v.to_vec()) }
-                    else if let Some(null) = attribute_value.null { crate::AttributeValue::Null(null) }
-                    else if let Some(item) = attribute_value.m { crate::AttributeValue::M(item.into_iter().map(|(key, attribute_value)| (key, crate::AttributeValue::from(attribute_value))).collect()) }
-                    else if let Some(list) = attribute_value.l { crate::AttributeValue::L(list.into_iter().map(crate::AttributeValue::from).collect()) }
-                    else if let Some(ss)= attribute_value.ss { crate::AttributeValue::Ss(ss) }
-                    else if let Some(ns)= attribute_value.ns { crate::AttributeValue::Ns(ns) }
-                    else if let Some(bs)= attribute_value.bs { crate::AttributeValue::Bs(bs.into_iter().map(|b| b.to_vec()).collect()) }
+                    crate::de::IntoAttributeValue::into_attribute_value(attribute_value)
+                        .unwrap_or_else(|err| panic!("{err}"))
+                }
+            }
+
+            impl crate::de::IntoAttributeValue for AttributeValue {
+                fn into_attribute_value(self) -> Result<crate::AttributeValue> {
+                    use crate::de::IntoAttributeValue;
+                    Ok(if let Some(n) = self.n { crate::AttributeValue::N(n) }
+                    else if let Some(s) = self.s { crate::AttributeValue::S(s) }
+                    else if let Some(b) = self.bool { crate::AttributeValue::Bool(b) }
+                    else if let Some(v) = self.b { crate::AttributeValue::B(Vec::from(v)) }
+                    else if let Some(null) = self.null { crate::AttributeValue::Null(null) }
+                    else if let Some(item) = self.m { crate::AttributeValue::M(item.into_iter().map(|(key, attribute_value)| Ok((key, attribute_value.into_attribute_value()?))).collect::<Result<_>>()?) }
+                    else if let Some(list) = self.l { crate::AttributeValue::L(list.into_iter().map(IntoAttributeValue::into_attribute_value).collect::<Result<_>>()?) }
+                    else if let Some(ss)= self.ss { crate::AttributeValue::Ss(ss) }
+                    else if let Some(ns)= self.ns { crate::AttributeValue::Ns(ns) }
+                    else if let Some(bs)= self.bs { crate::AttributeValue::Bs(bs.into_iter().map(Vec::from).collect()) }
                     else {
-                        panic!("Unexpectedly did not match any possible data types")
-                    }
+                        // No field set, such as `AttributeValue::default()`
+                        return Err(crate::error::ErrorImpl::UnsupportedType(String::from("an attribute value with no type")).into());
+                    })
                 }
             }
 
@@ -90,7 +104,7 @@ macro_rules! rusoto_streams_macro {
             where
                 T: serde::de::Deserialize<'a>,
             {
-                crate::de::from_attribute_value(attribute_value)
+                crate::de::from_attribute_value(crate::de::IntoAttributeValue::into_attribute_value(attribute_value)?)
             }
 
             /// A version of [`crate::from_item`] where the `AV` generic is tied to
@@ -104,7 +118,21 @@ macro_rules! rusoto_streams_macro {
             where
                 T: serde::de::Deserialize<'a>,
             {
-                crate::de::from_item(item)
+                crate::de::from_item(crate::de::try_into_item(item)?)
+            }
+
+            /// A version of [`crate::from_optional_item`] where the `AV` generic is tied to
+            /// [`rusoto_dynamodbstreams::AttributeValue`](AttributeValue).
+            ///
+            /// Useful in very generic code where the type checker can't determine the type of
+            /// `AV`.
+            pub fn from_optional_item<'a, T>(
+                item: Option<std::collections::HashMap<String, AttributeValue>>,
+            ) -> Result<Option<T>>
+            where
+                T: serde::de::Deserialize<'a>,
+            {
+                crate::de::from_optional_item(item.map(crate::de::try_into_item).transpose()?)
             }
 
             /// A version of [`crate::from_items`] where the `AV` generic is tied to
@@ -118,7 +146,17 @@ macro_rules! rusoto_streams_macro {
             where
                 T: serde::de::Deserialize<'a>,
             {
-                crate::de::from_items(items)
+                crate::de::from_items(items.into_iter().map(crate::de::try_into_item).collect::<Result<Vec<_>>>()?)
+            }
+
+            /// The types and functions most code using this SDK needs
+            ///
+            /// Unlike the crate-root functions, these take and return this SDK's types, so their
+            /// results need no type annotations. See [`crate::prelude`].
+            pub mod prelude {
+                pub use super::{from_attribute_value, from_item, from_items, from_optional_item, to_attribute_value, to_item};
+                pub use ::$crate_name::AttributeValue;
+                pub use crate::{DeserializerConfig, Error, ErrorCode, SerializerConfig};
             }
         }
 
@@ -150,7 +188,7 @@ macro_rules! rusoto_streams_macro {
             where
                 T: serde::de::Deserialize<'a>,
             {
-                crate::de::from_attribute_value(attribute_value)
+                crate::de::from_attribute_value(crate::de::IntoAttributeValue::into_attribute_value(attribute_value)?)
             }
 
             #[deprecated(since = "4.0.0", note = "The double-underscore on the mod name is no longer necessary")]
@@ -160,7 +198,7 @@ macro_rules! rusoto_streams_macro {
             where
                 T: serde::de::Deserialize<'a>,
             {
-                crate::de::from_item(item)
+                crate::de::from_item(crate::de::try_into_item(item)?)
             }
 
             #[deprecated(since = "4.0.0", note = "The double-underscore on the mod name is no longer necessary")]
@@ -170,7 +208,7 @@ macro_rules! rusoto_streams_macro {
             where
                 T: serde::de::Deserialize<'a>,
             {
-                crate::de::from_items(items)
+                crate::de::from_items(items.into_iter().map(crate::de::try_into_item).collect::<Result<Vec<_>>>()?)
             }
         }
     };
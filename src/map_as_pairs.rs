@@ -0,0 +1,268 @@
+//! Serializer codec for storing maps with non-string keys as a list of pairs
+//!
+//! DynamoDB map keys must be strings, so a map such as `HashMap<(u32, u32), String>` cannot be
+//! serialized as an `M`; it fails with a "Key must be a string" error. This module instead stores
+//! the map as an `L` of two-field `M`s – one `key` and one `value` – and rebuilds the map when
+//! deserializing.
+//!
+//! # Usage
+//!
+//! To use, annotate the field with `#[serde(with = "serde_dynamo::map_as_pairs")]`.
+//!
+//! Any type that can be iterated by reference as `(&K, &V)` pairs can be serialized, and any type
+//! that implements `FromIterator<(K, V)>` can be deserialized. That includes [`HashMap`] and
+//! [`BTreeMap`]. If the stored list contains the same key more than once, the last pair wins.
+//!
+//! # Examples
+//!
+//! ```
+//! use serde_derive::{Serialize, Deserialize};
+//! use serde_dynamo::{AttributeValue, Item};
+//! use std::collections::HashMap;
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Board {
+//!     #[serde(with = "serde_dynamo::map_as_pairs")]
+//!     pieces: HashMap<(u8, u8), String>,
+//! }
+//!
+//! let board = Board {
+//!     pieces: HashMap::from([((0, 4), String::from("king"))]),
+//! };
+//!
+//! let item: Item = serde_dynamo::to_item(&board).unwrap();
+//! assert_eq!(
+//!     item["pieces"],
+//!     AttributeValue::L(vec![AttributeValue::M(HashMap::from([
+//!         (
+//!             String::from("key"),
+//!             AttributeValue::L(vec![
+//!                 AttributeValue::N(String::from("0")),
+//!                 AttributeValue::N(String::from("4")),
+//!             ]),
+//!         ),
+//!         (String::from("value"), AttributeValue::S(String::from("king"))),
+//!     ]))]),
+//! );
+//!
+//! let round_tripped: Board = serde_dynamo::from_item(item).unwrap();
+//! assert_eq!(round_tripped, board);
+//! ```
+//!
+//! [`HashMap`]: std::collections::HashMap
+//! [`BTreeMap`]: std::collections::BTreeMap
+
+use serde::{de, ser::SerializeStruct, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+
+const PAIR: &str = "Pair";
+const KEY: &str = "key";
+const VALUE: &str = "value";
+const FIELDS: &[&str] = &[KEY, VALUE];
+
+/// Serializes the given map as a list of `{ key, value }` pairs
+///
+/// See the [module documentation][crate::map_as_pairs] for additional usage information.
+pub fn serialize<'a, T, K, V, S>(map: &'a T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    &'a T: IntoIterator<Item = (&'a K, &'a V)>,
+    K: Serialize + 'a,
+    V: Serialize + 'a,
+    S: Serializer,
+{
+    serializer.collect_seq(map.into_iter().map(|(key, value)| Pair { key, value }))
+}
+
+/// Deserializes a list of `{ key, value }` pairs as a map
+///
+/// See the [module documentation][crate::map_as_pairs] for additional usage information.
+pub fn deserialize<'de, T, K, V, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: FromIterator<(K, V)>,
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_seq(PairsVisitor(PhantomData))
+}
+
+struct Pair<'a, K, V> {
+    key: &'a K,
+    value: &'a V,
+}
+
+impl<K, V> Serialize for Pair<'_, K, V>
+where
+    K: Serialize,
+    V: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut pair = serializer.serialize_struct(PAIR, 2)?;
+        pair.serialize_field(KEY, self.key)?;
+        pair.serialize_field(VALUE, self.value)?;
+        pair.end()
+    }
+}
+
+struct PairsVisitor<T, K, V>(PhantomData<(T, K, V)>);
+
+impl<'de, T, K, V> de::Visitor<'de> for PairsVisitor<T, K, V>
+where
+    T: FromIterator<(K, V)>,
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a list of key/value pairs")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        std::iter::from_fn(|| seq.next_element_seed(PairSeed(PhantomData)).transpose()).collect()
+    }
+}
+
+struct PairSeed<K, V>(PhantomData<(K, V)>);
+
+impl<'de, K, V> de::DeserializeSeed<'de> for PairSeed<K, V>
+where
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    type Value = (K, V);
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_struct(PAIR, FIELDS, self)
+    }
+}
+
+impl<'de, K, V> de::Visitor<'de> for PairSeed<K, V>
+where
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    type Value = (K, V);
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map with `key` and `value` fields")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        let mut key = None;
+        let mut value = None;
+        while let Some(field) = map.next_key::<String>()? {
+            match field.as_str() {
+                KEY => key = Some(map.next_value()?),
+                VALUE => value = Some(map.next_value()?),
+                _ => {
+                    map.next_value::<de::IgnoredAny>()?;
+                }
+            }
+        }
+        let key = key.ok_or_else(|| de::Error::missing_field(KEY))?;
+        let value = value.ok_or_else(|| de::Error::missing_field(VALUE))?;
+        Ok((key, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{from_item, to_item, AttributeValue, Item};
+    use serde_derive::{Deserialize, Serialize};
+    use std::collections::{BTreeMap, HashMap};
+
+    #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+    struct ComplexKey {
+        region: String,
+        shard: u32,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Struct {
+        #[serde(with = "crate::map_as_pairs")]
+        hash: HashMap<ComplexKey, u32>,
+        #[serde(with = "crate::map_as_pairs")]
+        btree: BTreeMap<u64, String>,
+    }
+
+    #[test]
+    fn round_trip() {
+        let value = Struct {
+            hash: HashMap::from([
+                (
+                    ComplexKey {
+                        region: String::from("us-east-1"),
+                        shard: 1,
+                    },
+                    10,
+                ),
+                (
+                    ComplexKey {
+                        region: String::from("eu-west-1"),
+                        shard: 2,
+                    },
+                    20,
+                ),
+            ]),
+            btree: BTreeMap::from([(2, String::from("two")), (1, String::from("one"))]),
+        };
+
+        let item: Item = to_item(&value).unwrap();
+        assert_eq!(
+            item["btree"],
+            AttributeValue::L(vec![
+                AttributeValue::M(HashMap::from([
+                    (String::from("key"), AttributeValue::N(String::from("1"))),
+                    (
+                        String::from("value"),
+                        AttributeValue::S(String::from("one"))
+                    ),
+                ])),
+                AttributeValue::M(HashMap::from([
+                    (String::from("key"), AttributeValue::N(String::from("2"))),
+                    (
+                        String::from("value"),
+                        AttributeValue::S(String::from("two"))
+                    ),
+                ])),
+            ])
+        );
+
+        let round_tripped: Struct = from_item(item).unwrap();
+        assert_eq!(round_tripped, value);
+    }
+
+    #[test]
+    fn missing_value() {
+        let item = Item::from(HashMap::from([
+            (
+                String::from("hash"),
+                AttributeValue::L(vec![AttributeValue::M(HashMap::from([(
+                    String::from("key"),
+                    AttributeValue::M(HashMap::from([
+                        (String::from("region"), AttributeValue::S(String::from("a"))),
+                        (String::from("shard"), AttributeValue::N(String::from("1"))),
+                    ])),
+                )]))]),
+            ),
+            (String::from("btree"), AttributeValue::L(vec![])),
+        ]));
+
+        let err = from_item::<_, Struct>(item).unwrap_err();
+        assert_eq!(err.to_string(), "missing field `value`");
+    }
+}
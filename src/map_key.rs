@@ -0,0 +1,157 @@
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::str::FromStr;
+
+/// A map key stored as its [`Display`](fmt::Display) text and read back with [`FromStr`]
+///
+/// The keys of a map attribute are strings, so a `HashMap` keyed by anything that serializes as
+/// something else, such as a UUID or a timestamp type that serializes as a struct or a number
+/// with a fraction, fails with [`ErrorCode::KeyMustBeAString`](crate::ErrorCode::KeyMustBeAString).
+/// Wrapping the key type in `MapKey` stores the key's text instead, without a custom `Serialize`
+/// implementation.
+///
+/// A key whose text doesn't parse is an error that includes the text and the parse error.
+///
+/// ```
+/// use serde_derive::{Deserialize, Serialize};
+/// use serde_dynamo::{dynamo_item, Item, MapKey};
+/// use std::collections::BTreeMap;
+/// use std::net::Ipv4Addr;
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Blocklist {
+///     hits: BTreeMap<MapKey<Ipv4Addr>, u32>,
+/// }
+///
+/// let blocklist = Blocklist {
+///     hits: BTreeMap::from([(MapKey(Ipv4Addr::new(10, 0, 0, 1)), 3)]),
+/// };
+///
+/// let item: Item = serde_dynamo::to_item(&blocklist)?;
+/// assert_eq!(item, dynamo_item! { "hits": { "10.0.0.1": 3 } });
+///
+/// let read: Blocklist = serde_dynamo::from_item(item)?;
+/// assert_eq!(read, blocklist);
+/// # Ok::<(), serde_dynamo::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MapKey<T>(pub T);
+
+impl<T> MapKey<T> {
+    /// Unwrap the key
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for MapKey<T> {
+    fn from(key: T) -> Self {
+        MapKey(key)
+    }
+}
+
+impl<T> Deref for MapKey<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> fmt::Display for MapKey<T>
+where
+    T: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T> Serialize for MapKey<T>
+where
+    T: fmt::Display,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(&self.0)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for MapKey<T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(MapKeyVisitor(PhantomData))
+    }
+}
+
+struct MapKeyVisitor<T>(PhantomData<fn() -> T>);
+
+impl<T> de::Visitor<'_> for MapKeyVisitor<T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    type Value = MapKey<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a string")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        v.parse()
+            .map(MapKey)
+            .map_err(|err| E::custom(format_args!("invalid map key {v:?}: {err}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MapKey;
+    use crate::{dynamo_item, from_item, from_item_ref, to_item, Item};
+    use chrono::{DateTime, TimeZone, Utc};
+    use serde_derive::{Deserialize, Serialize};
+    use std::collections::HashMap;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Calendar {
+        events: HashMap<MapKey<DateTime<Utc>>, String>,
+    }
+
+    #[test]
+    fn keys_are_stored_as_text() {
+        let at = Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap();
+        let calendar = Calendar {
+            events: HashMap::from([(MapKey(at), String::from("launch"))]),
+        };
+
+        let item: Item = to_item(&calendar).unwrap();
+        assert_eq!(
+            item,
+            dynamo_item! { "events": { "2024-01-02 03:04:05 UTC": "launch" } }
+        );
+        assert_eq!(from_item_ref::<Calendar>(&item).unwrap(), calendar);
+        assert_eq!(from_item::<_, Calendar>(item).unwrap(), calendar);
+    }
+
+    #[test]
+    fn unparseable_keys_are_errors() {
+        let item = dynamo_item! { "events": { "yesterday": "launch" } };
+        let err = from_item::<_, Calendar>(item).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("invalid map key \"yesterday\": "));
+    }
+}
@@ -0,0 +1,231 @@
+//! Lazy schema migrations applied when items are read
+//!
+//! Rewriting every item in a table when a type changes is slow and expensive, so DynamoDB schemas
+//! usually evolve on read instead: each item records the schema version it was written with, and
+//! readers upgrade old items in memory before deserializing them. Items are written back in the
+//! new shape the next time they are saved.
+//!
+//! [`Migrations`] holds the ordered list of upgrade steps, and [`from_item_migrated`] applies the
+//! ones an item still needs. The version is kept in a `schema_version` number attribute. An item
+//! without one is version 0, and the step registered first upgrades version 0 to version 1.
+//!
+//! ```
+//! # use serde_derive::Deserialize;
+//! use serde_dynamo::migrations::{from_item_migrated, Migrations};
+//! use serde_dynamo::{dynamo_item, AttributeValue, Item};
+//!
+//! #[derive(Debug, PartialEq, Deserialize)]
+//! struct User {
+//!     id: String,
+//!     given_name: String,
+//!     family_name: String,
+//! }
+//!
+//! // Version 1 split `name` into two attributes
+//! fn split_name(item: &mut Item) {
+//!     let attributes = item.inner_mut();
+//!     if let Some(AttributeValue::S(name)) = attributes.remove("name") {
+//!         let (given, family) = name.split_once(' ').unwrap_or((&name, ""));
+//!         attributes.insert(String::from("given_name"), AttributeValue::S(given.to_string()));
+//!         attributes.insert(String::from("family_name"), AttributeValue::S(family.to_string()));
+//!     }
+//! }
+//!
+//! let migrations = Migrations::new().migration(split_name);
+//!
+//! let item = dynamo_item! { "id": "fSsgVtal8TpP", "name": "Arthur Dent" };
+//! let migrated = from_item_migrated::<_, User>(item, &migrations)?;
+//!
+//! assert_eq!(migrated.from_version, 0);
+//! assert_eq!(migrated.version, 1);
+//! assert_eq!(migrated.value.family_name, "Dent");
+//! # Ok::<(), serde_dynamo::Error>(())
+//! ```
+
+use crate::{error::ErrorImpl, AttributeValue, Item, Result};
+use serde::de::DeserializeOwned;
+
+/// The attribute that holds an item's schema version, unless
+/// [`Migrations::version_attribute`] names another
+pub const VERSION_ATTRIBUTE: &str = "schema_version";
+
+/// An ordered list of schema migrations
+///
+/// The migration at index `i` upgrades an item from version `i` to version `i + 1`, so the latest
+/// version is the number of migrations. Migrations are plain functions that edit the item in
+/// place, and must not fail: data they can't upgrade is left for deserialization to reject.
+#[derive(Debug, Clone)]
+pub struct Migrations {
+    attribute: String,
+    steps: Vec<fn(&mut Item)>,
+}
+
+impl Default for Migrations {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Migrations {
+    /// An empty list of migrations, with the version kept in `schema_version`
+    pub fn new() -> Self {
+        Self {
+            attribute: VERSION_ATTRIBUTE.to_string(),
+            steps: Vec::new(),
+        }
+    }
+
+    /// Keep the schema version in the attribute `name` instead
+    pub fn version_attribute(mut self, name: impl Into<String>) -> Self {
+        self.attribute = name.into();
+        self
+    }
+
+    /// Add the migration to the next version
+    pub fn migration(mut self, migrate: fn(&mut Item)) -> Self {
+        self.steps.push(migrate);
+        self
+    }
+
+    /// The version items are at after migration
+    pub fn latest_version(&self) -> u32 {
+        self.steps.len() as u32
+    }
+
+    /// Apply the migrations `item` still needs, and set its version attribute to the latest
+    /// version
+    ///
+    /// Returns the version the item was at before. Fails if the version attribute isn't a
+    /// non-negative integer, or is newer than [`latest_version`](Self::latest_version), which
+    /// means the item was written by newer code.
+    pub fn migrate(&self, item: &mut Item) -> Result<u32> {
+        let from_version: u32 = item.get_as_opt(&self.attribute)?.unwrap_or_default();
+        let latest = self.latest_version();
+        if from_version > latest {
+            return Err(ErrorImpl::SchemaVersionTooNew(
+                self.attribute.clone(),
+                from_version,
+                latest,
+            )
+            .into());
+        }
+
+        for migrate in &self.steps[from_version as usize..] {
+            migrate(item);
+        }
+        item.inner_mut().insert(
+            self.attribute.clone(),
+            AttributeValue::N(latest.to_string()),
+        );
+        Ok(from_version)
+    }
+}
+
+/// A value read by [`from_item_migrated`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Migrated<T> {
+    /// The deserialized value
+    pub value: T,
+    /// The schema version the item was stored at
+    pub from_version: u32,
+    /// The schema version the item was migrated to, which is always the latest
+    pub version: u32,
+}
+
+impl<T> Migrated<T> {
+    /// Whether any migrations were applied, meaning the stored item is out of date
+    pub fn was_migrated(&self) -> bool {
+        self.from_version != self.version
+    }
+}
+
+/// Apply the migrations `item` still needs with [`Migrations::migrate`], then deserialize it
+///
+/// The version attribute is set to the latest version before deserializing, so `T` may have a
+/// `schema_version` field to write it back with.
+pub fn from_item_migrated<I, T>(item: I, migrations: &Migrations) -> Result<Migrated<T>>
+where
+    I: Into<Item>,
+    T: DeserializeOwned,
+{
+    let mut item = item.into();
+    let from_version = migrations.migrate(&mut item)?;
+    Ok(Migrated {
+        value: crate::from_item(item)?,
+        from_version,
+        version: migrations.latest_version(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dynamo_item;
+    use serde_derive::Deserialize;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Counter {
+        schema_version: u32,
+        count: u64,
+        unit: String,
+    }
+
+    // Version 1 renamed `total` to `count`
+    fn rename_total(item: &mut Item) {
+        if let Some(total) = item.inner_mut().remove("total") {
+            item.inner_mut().insert(String::from("count"), total);
+        }
+    }
+
+    // Version 2 added `unit`
+    fn add_unit(item: &mut Item) {
+        item.inner_mut().insert(
+            String::from("unit"),
+            AttributeValue::S(String::from("each")),
+        );
+    }
+
+    #[test]
+    fn migrates_from_each_version() {
+        let migrations = Migrations::new()
+            .migration(rename_total)
+            .migration(add_unit);
+        assert_eq!(migrations.latest_version(), 2);
+
+        let expected = Counter {
+            schema_version: 2,
+            count: 3,
+            unit: String::from("each"),
+        };
+        for (item, from_version) in [
+            (dynamo_item! { "total": 3 }, 0),
+            (dynamo_item! { "schema_version": 1, "count": 3 }, 1),
+            (
+                dynamo_item! { "schema_version": 2, "count": 3, "unit": "each" },
+                2,
+            ),
+        ] {
+            let migrated = from_item_migrated::<_, Counter>(item, &migrations).unwrap();
+            assert_eq!(migrated.value, expected);
+            assert_eq!(migrated.from_version, from_version);
+            assert_eq!(migrated.version, 2);
+            assert_eq!(migrated.was_migrated(), from_version != 2);
+        }
+    }
+
+    #[test]
+    fn rejects_bad_versions() {
+        let migrations = Migrations::new()
+            .version_attribute("v")
+            .migration(rename_total);
+
+        let mut item = dynamo_item! { "v": 2, "count": 3 };
+        assert_eq!(
+            migrations.migrate(&mut item).unwrap_err().to_string(),
+            "Attribute `v` is schema version 2, but migrations only reach version 1"
+        );
+
+        let mut item = dynamo_item! { "v": "one", "count": 3 };
+        assert!(migrations.migrate(&mut item).is_err());
+    }
+}
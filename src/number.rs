@@ -0,0 +1,262 @@
+//! Formatting and checking of DynamoDB number strings
+
+use crate::{error::ErrorImpl, Result};
+use std::cmp::Ordering;
+
+/// The most significant digits a DynamoDB number can have
+pub(crate) const MAX_SIGNIFICANT_DIGITS: usize = 38;
+
+/// The smallest and largest powers of ten a nonzero DynamoDB number can have in scientific
+/// notation, from `1E-130` up to `9.9999999999999999999999999999999999999E+125`
+const EXPONENT_RANGE: std::ops::RangeInclusive<i64> = -130..=125;
+
+/// A decimal number broken into its sign, its significant digits without leading or trailing
+/// zeros, and the power of ten of its first digit
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub(crate) struct Decimal {
+    pub(crate) negative: bool,
+    pub(crate) digits: String,
+    pub(crate) exponent: i64,
+}
+
+impl Decimal {
+    /// Parse a number in plain or scientific notation, such as `-0.0012` or `1.2E-3`
+    ///
+    /// Zero has no digits. Returns `None` if `n` isn't a number.
+    pub(crate) fn parse(n: &str) -> Option<Self> {
+        let (negative, n) = match n.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, n.strip_prefix('+').unwrap_or(n)),
+        };
+        let (mantissa, exponent) = match n.split_once(['e', 'E']) {
+            Some((mantissa, exponent)) => (mantissa, exponent.parse::<i64>().ok()?),
+            None => (n, 0),
+        };
+        let (int, frac) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        if int.is_empty() && frac.is_empty()
+            || !int.chars().chain(frac.chars()).all(|c| c.is_ascii_digit())
+        {
+            return None;
+        }
+
+        let all = format!("{int}{frac}");
+        let leading = all.len() - all.trim_start_matches('0').len();
+        let digits = all.trim_matches('0').to_string();
+        // Exponents near the limits of `i64` are far outside DynamoDB's range anyway
+        let exponent = (int.len() as i64 - leading as i64 - 1).checked_add(exponent)?;
+        Some(Self {
+            negative: negative && !digits.is_empty(),
+            digits,
+            exponent,
+        })
+    }
+
+    /// Compare the values of two numbers
+    pub(crate) fn cmp_value(&self, other: &Self) -> Ordering {
+        let sign = |decimal: &Self| match (decimal.negative, decimal.digits.is_empty()) {
+            (_, true) => 0,
+            (true, false) => -1,
+            (false, false) => 1,
+        };
+        let magnitude = || {
+            self.exponent
+                .cmp(&other.exponent)
+                .then_with(|| self.digits.cmp(&other.digits))
+        };
+        match sign(self).cmp(&sign(other)) {
+            Ordering::Equal if self.digits.is_empty() => Ordering::Equal,
+            Ordering::Equal if self.negative => magnitude().reverse(),
+            Ordering::Equal => magnitude(),
+            unequal => unequal,
+        }
+    }
+
+    /// Write the number as a plain decimal
+    pub(crate) fn to_plain(&self) -> String {
+        if self.digits.is_empty() {
+            return String::from("0");
+        }
+        let mut out = String::with_capacity(self.digits.len() + 4);
+        if self.negative {
+            out.push('-');
+        }
+        let point = self.exponent + 1;
+        if point <= 0 {
+            out.push_str("0.");
+            out.extend(std::iter::repeat_n('0', point.unsigned_abs() as usize));
+            out.push_str(&self.digits);
+        } else if point as usize >= self.digits.len() {
+            out.push_str(&self.digits);
+            out.extend(std::iter::repeat_n('0', point as usize - self.digits.len()));
+        } else {
+            let (int, frac) = self.digits.split_at(point as usize);
+            out.push_str(int);
+            out.push('.');
+            out.push_str(frac);
+        }
+        out
+    }
+}
+
+/// Rewrite a stored number into a form Rust's number parsers accept, if it needs it
+///
+/// Negative zero, such as `-0` or `-0.0`, always becomes `0`, so it reads the same into signed,
+/// unsigned and float targets. With `lenient`, surrounding whitespace and a leading `+` are
+/// removed, leading zeros are dropped, and every zero becomes `0`. Returns `None` if `n` is
+/// unchanged, or isn't a number even after trimming.
+pub(crate) fn normalize(n: &str, lenient: bool) -> Option<String> {
+    let trimmed = if lenient {
+        let trimmed = n.trim();
+        trimmed.strip_prefix('+').unwrap_or(trimmed)
+    } else {
+        n
+    };
+    let decimal = Decimal::parse(trimmed)?;
+
+    let normalized = if decimal.digits.is_empty() && (lenient || trimmed.starts_with('-')) {
+        String::from("0")
+    } else if lenient {
+        let (sign, unsigned) = match trimmed.strip_prefix('-') {
+            Some(unsigned) => ("-", unsigned),
+            None => ("", trimmed),
+        };
+        let without_zeros = unsigned.trim_start_matches('0');
+        if without_zeros.starts_with(|c: char| c.is_ascii_digit()) {
+            format!("{sign}{without_zeros}")
+        } else {
+            format!("{sign}0{without_zeros}")
+        }
+    } else {
+        return None;
+    };
+    (normalized != n).then_some(normalized)
+}
+
+/// How numbers that DynamoDB can't store precisely are serialized
+///
+/// DynamoDB numbers have at most 38 significant digits, and DynamoDB rejects the whole write if
+/// any number has more. Such numbers usually come from `i128` or `u128` values.
+///
+/// Numbers outside DynamoDB's range, from `1E-130` to just under `1E+126` in magnitude, and
+/// non-finite floats are rejected whatever this is set to.
+///
+/// Set with [`SerializerConfig::number_precision`](crate::SerializerConfig::number_precision).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum NumberPrecision {
+    /// Fail with an error naming the number
+    #[default]
+    Reject,
+    /// Keep the first 38 significant digits, rounding toward zero
+    Truncate,
+}
+
+/// Check that DynamoDB can store `n`, truncating it first if `precision` allows
+pub(crate) fn check(n: String, precision: NumberPrecision) -> Result<String> {
+    let unrepresentable = |reason| Err(ErrorImpl::UnrepresentableNumber(n.clone(), reason).into());
+
+    let Some(mut decimal) = Decimal::parse(&n) else {
+        return unrepresentable("it is not a finite decimal number");
+    };
+    if decimal.digits.is_empty() {
+        return Ok(n);
+    }
+
+    let truncated = decimal.digits.len() > MAX_SIGNIFICANT_DIGITS;
+    if truncated {
+        if precision == NumberPrecision::Reject {
+            return unrepresentable("it has more than 38 significant digits");
+        }
+        decimal.digits.truncate(MAX_SIGNIFICANT_DIGITS);
+        decimal
+            .digits
+            .truncate(decimal.digits.trim_end_matches('0').len());
+    }
+    if !EXPONENT_RANGE.contains(&decimal.exponent) {
+        return unrepresentable("its magnitude is outside 1E-130 to 1E+126");
+    }
+
+    Ok(if truncated { decimal.to_plain() } else { n })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_plain_decimals() {
+        for (n, plain) in [
+            ("0.0", "0"),
+            ("-1.50", "-1.5"),
+            ("1E-7", "0.0000001"),
+            ("1.2E+3", "1200"),
+            ("12.345", "12.345"),
+        ] {
+            assert_eq!(Decimal::parse(n).unwrap().to_plain(), plain, "{n}");
+        }
+    }
+
+    #[test]
+    fn check_precision_and_range() {
+        let check = |n: &str, precision| check(n.to_string(), precision).map_err(|e| e.to_string());
+
+        let max = "9".repeat(38);
+        for n in [
+            "0", "-0.000", &max, "1E-130", "-1e-130", "9.9E+125", "0.00100",
+        ] {
+            assert_eq!(check(n, NumberPrecision::Reject).as_deref(), Ok(n));
+        }
+
+        let long = u128::MAX.to_string();
+        assert_eq!(
+            check(&long, NumberPrecision::Reject),
+            Err(format!("Number `{long}` can't be stored in DynamoDB: it has more than 38 significant digits"))
+        );
+        assert_eq!(
+            check(&long, NumberPrecision::Truncate).as_deref(),
+            Ok("340282366920938463463374607431768211450")
+        );
+        assert_eq!(
+            check(
+                "-1.000000000000000000000000000000000000009",
+                NumberPrecision::Truncate
+            )
+            .as_deref(),
+            Ok("-1")
+        );
+
+        for n in ["1E-131", "1E+126", "0.99E-130"] {
+            assert_eq!(
+                check(n, NumberPrecision::Truncate),
+                Err(format!("Number `{n}` can't be stored in DynamoDB: its magnitude is outside 1E-130 to 1E+126"))
+            );
+        }
+        for n in ["NaN", "inf", "", "1..2", "0x10"] {
+            assert!(check(n, NumberPrecision::Truncate).is_err(), "{n}");
+        }
+    }
+
+    #[test]
+    fn normalize_stored_numbers() {
+        for (n, strict, lenient) in [
+            ("42", None, None),
+            ("-0", Some("0"), Some("0")),
+            ("-0.000", Some("0"), Some("0")),
+            ("0.000", None, Some("0")),
+            ("007", None, Some("7")),
+            ("-007.50", None, Some("-7.50")),
+            ("000.5", None, Some("0.5")),
+            (" 42\t", None, Some("42")),
+            ("+42", None, Some("42")),
+            (" -0 ", None, Some("0")),
+            ("1E+3", None, None),
+            ("4 2", None, None),
+            ("abc", None, None),
+            ("-10E9223372036854775807", None, None),
+            ("-0.01E-9223372036854775808", None, None),
+        ] {
+            assert_eq!(normalize(n, false).as_deref(), strict, "{n:?}");
+            assert_eq!(normalize(n, true).as_deref(), lenient, "{n:?}");
+        }
+    }
+}
@@ -8,10 +8,28 @@
 //! be beneficial to additionally annotate the field with `#[serde(default)]`
 //! and `#[serde(skip_serializing_if = "<empty check>")]`. This will make sure
 //! that the field is omitted when empty.
+//! For an `Option` field, the [`optional`] codec does both: it leaves out a
+//! set that is `None` or empty.
 //!
-//! This serializer does not check for duplicate values or an empty set.
-//! If the set contains duplicate values or is empty, DynamoDB will return a
-//! validation error when the attribute value is used.
+//! In release builds, this serializer does not check for duplicate values or
+//! an empty set. If the set contains duplicate values or is empty, DynamoDB
+//! will return a validation error when the attribute value is used. Builds
+//! with debug assertions fail with
+//! [`ErrorCode::InvalidSet`](crate::ErrorCode::InvalidSet) instead, where the
+//! set is serialized.
+//!
+//! # Containers
+//!
+//! The element order and membership are taken from whatever the value serializes as a sequence:
+//! `Vec`, `VecDeque`, `BinaryHeap`, `HashSet`, `BTreeSet`, arrays, and slices all work, for the
+//! field codec as well as the [`NumberSet`] wrapper. An iterator can be collected straight into a
+//! wrapper, as in `iter.collect::<NumberSet<Vec<_>>>()`.
+//!
+//! Nothing here deduplicates. A `HashSet<u64>` or `BTreeSet<i32>` can't hold duplicates in the
+//! first place, so it's the simplest way to always produce a valid set; a `Vec` or `VecDeque` must
+//! be kept unique by the caller. DynamoDB compares numbers by value, so `"1"` and `"1.0"` in a
+//! `BTreeSet<String>` are still duplicates to it, though the Rust set keeps both. Reading a set
+//! back into a `HashSet` or `BTreeSet` can't produce duplicates either.
 //!
 //! # Errors
 //!
@@ -48,7 +66,7 @@ pub(super) static NEWTYPE_SYMBOL: &str = "\u{037E}NUMBERSET\u{037E}";
 
 #[inline]
 pub(crate) fn should_serialize_as_numbers_set(name: &str) -> bool {
-    std::ptr::eq(name, NEWTYPE_SYMBOL)
+    std::ptr::eq(name, NEWTYPE_SYMBOL) || optional::should_serialize_as_optional_set(name)
 }
 
 /// Serializes the given value as a number set
@@ -114,6 +132,12 @@ where
     }
 }
 
+impl<A> FromIterator<A> for NumberSet<Vec<A>> {
+    fn from_iter<I: IntoIterator<Item = A>>(iter: I) -> Self {
+        NumberSet(iter.into_iter().collect())
+    }
+}
+
 #[inline(never)]
 pub(crate) fn convert_to_set(value: crate::AttributeValue) -> crate::Result<crate::AttributeValue> {
     let vals = match value {
@@ -132,7 +156,9 @@ pub(crate) fn convert_to_set(value: crate::AttributeValue) -> crate::Result<crat
         })
         .collect::<Result<_, _>>()?;
 
-    Ok(crate::AttributeValue::Ns(set))
+    let set = crate::AttributeValue::Ns(set);
+    set.debug_check_valid()?;
+    Ok(set)
 }
 
 #[cfg(test)]
@@ -167,4 +193,104 @@ mod tests {
             crate::AttributeValue::Ns(vec!["85".to_string(), "99".to_string(),])
         );
     }
+
+    #[test]
+    fn std_containers_and_iterators() {
+        use std::collections::{BTreeSet, BinaryHeap, HashSet, VecDeque};
+
+        fn members<T: serde::Serialize>(set: NumberSet<T>) -> Vec<String> {
+            match crate::to_attribute_value(set).unwrap() {
+                crate::AttributeValue::Ns(mut members) => {
+                    members.sort();
+                    members
+                }
+                other => panic!("expected a number set, got {other:?}"),
+            }
+        }
+
+        let expected = vec![String::from("1"), String::from("2")];
+        assert_eq!(members(NumberSet(VecDeque::from([2, 1]))), expected);
+        assert_eq!(members(NumberSet(BinaryHeap::from([2, 1]))), expected);
+        assert_eq!(members(NumberSet(HashSet::from([2, 1]))), expected);
+        assert_eq!(members(NumberSet(BTreeSet::from([2, 1]))), expected);
+        assert_eq!(members(NumberSet([2, 1])), expected);
+        assert_eq!(members((1..=2).collect::<NumberSet<Vec<_>>>()), expected);
+
+        #[derive(Debug, Serialize, Deserialize)]
+        struct Struct {
+            #[serde(with = "crate::number_set")]
+            heap: BinaryHeap<u8>,
+            #[serde(with = "crate::number_set")]
+            set: HashSet<u8>,
+        }
+
+        let item: crate::Item = crate::to_item(Struct {
+            heap: BinaryHeap::from([1, 2]),
+            set: HashSet::from([1, 2]),
+        })
+        .unwrap();
+        let value: Struct = crate::from_item(item).unwrap();
+        assert_eq!(value.heap.into_sorted_vec(), vec![1, 2]);
+        assert_eq!(value.set, HashSet::from([1, 2]));
+    }
+}
+
+/// Serializer codec for an optional number set that may be empty
+///
+/// To use, annotate an `Option` field with
+/// `#[serde(default, with = "serde_dynamo::number_set::optional")]`.
+///
+/// DynamoDB rejects empty sets, so `None` and an empty set are both left out of the item, or
+/// written as `NULL` with [`EmptySetPolicy::Null`](crate::EmptySetPolicy::Null). Both read back as
+/// `None`, as does a missing attribute thanks to `#[serde(default)]`. Other serializers see `None`
+/// as an empty sequence.
+///
+/// ```
+/// use serde_derive::{Serialize, Deserialize};
+/// use serde_dynamo::{AttributeValue, Item};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct MyStruct {
+///     #[serde(default, with = "serde_dynamo::number_set::optional")]
+///     scores: Option<Vec<u64>>,
+/// }
+///
+/// let item: Item = serde_dynamo::to_item(MyStruct { scores: None }).unwrap();
+/// assert!(item.is_empty());
+/// let my_struct: MyStruct = serde_dynamo::from_item(item).unwrap();
+/// assert_eq!(my_struct, MyStruct { scores: None });
+///
+/// let item: Item = serde_dynamo::to_item(MyStruct { scores: Some(vec![7]) }).unwrap();
+/// assert_eq!(item["scores"], AttributeValue::Ns(vec![String::from("7")]));
+/// ```
+pub mod optional {
+    pub(super) static NEWTYPE_SYMBOL: &str = "\u{037E}OPTIONALNUMBERSET\u{037E}";
+
+    #[inline]
+    pub(crate) fn should_serialize_as_optional_set(name: &str) -> bool {
+        std::ptr::eq(name, NEWTYPE_SYMBOL)
+    }
+
+    /// Serializes the given value as a number set, or leaves it out if it is `None` or empty
+    ///
+    /// See the [module documentation][crate::number_set::optional] for additional usage information.
+    pub fn serialize<T, S>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: serde::Serialize,
+        S: serde::Serializer,
+    {
+        match value {
+            Some(value) => serializer.serialize_newtype_struct(NEWTYPE_SYMBOL, value),
+            None => serializer.serialize_newtype_struct(NEWTYPE_SYMBOL, &[(); 0]),
+        }
+    }
+
+    /// Deserializes the given value as an optional set
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        T: serde::Deserialize<'de>,
+        D: serde::Deserializer<'de>,
+    {
+        <Option<T> as serde::Deserialize>::deserialize(deserializer)
+    }
 }
@@ -0,0 +1,145 @@
+use serde::{ser, Deserialize, Deserializer, Serialize, Serializer};
+
+/// A list attribute that remembers whether it was missing, `NULL`, or a list
+///
+/// `Option<Vec<T>>` reads a missing attribute and a `NULL` attribute the same way, and a
+/// `Vec<T>` with `#[serde(default)]` also folds in the empty list. When those mean different
+/// things, `OptionalList` keeps them apart, and writes each back the way it was stored.
+///
+/// Fields must be annotated with `#[serde(default, skip_serializing_if = "OptionalList::is_absent")]`,
+/// so that a missing attribute reads as [`Absent`](OptionalList::Absent) and is not written.
+/// Serializing an `Absent` value any other way is an error.
+///
+/// ```
+/// use serde_derive::{Deserialize, Serialize};
+/// use serde_dynamo::{dynamo_item, Item, OptionalList};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct User {
+///     #[serde(default, skip_serializing_if = "OptionalList::is_absent")]
+///     nicknames: OptionalList<String>,
+/// }
+///
+/// let missing: User = serde_dynamo::from_item(dynamo_item! {})?;
+/// let null: User = serde_dynamo::from_item(dynamo_item! { "nicknames": null })?;
+/// let empty: User = serde_dynamo::from_item(dynamo_item! { "nicknames": [] })?;
+///
+/// assert_eq!(missing.nicknames, OptionalList::Absent);
+/// assert_eq!(null.nicknames, OptionalList::Null);
+/// assert_eq!(empty.nicknames, OptionalList::List(vec![]));
+///
+/// let item: Item = serde_dynamo::to_item(&missing)?;
+/// assert!(item.is_empty());
+/// # Ok::<(), serde_dynamo::Error>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub enum OptionalList<T> {
+    /// The attribute is missing
+    #[default]
+    Absent,
+    /// The attribute is `NULL`
+    Null,
+    /// The attribute is a list, possibly empty
+    List(Vec<T>),
+}
+
+impl<T> OptionalList<T> {
+    /// Whether the attribute is missing
+    pub fn is_absent(&self) -> bool {
+        matches!(self, OptionalList::Absent)
+    }
+
+    /// Whether the attribute is `NULL`
+    pub fn is_null(&self) -> bool {
+        matches!(self, OptionalList::Null)
+    }
+
+    /// The elements, if the attribute is a list
+    pub fn as_slice(&self) -> Option<&[T]> {
+        match self {
+            OptionalList::List(list) => Some(list),
+            _ => None,
+        }
+    }
+
+    /// The elements if the attribute is a list, dropping the difference between missing and
+    /// `NULL`
+    pub fn into_option(self) -> Option<Vec<T>> {
+        match self {
+            OptionalList::List(list) => Some(list),
+            _ => None,
+        }
+    }
+}
+
+impl<T> From<Option<Vec<T>>> for OptionalList<T> {
+    /// `None` becomes [`Null`](OptionalList::Null)
+    fn from(option: Option<Vec<T>>) -> Self {
+        match option {
+            Some(list) => OptionalList::List(list),
+            None => OptionalList::Null,
+        }
+    }
+}
+
+impl<T> Serialize for OptionalList<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            OptionalList::Absent => Err(ser::Error::custom(
+                "OptionalList::Absent must be skipped with `skip_serializing_if = \"OptionalList::is_absent\"`",
+            )),
+            OptionalList::Null => serializer.serialize_none(),
+            OptionalList::List(list) => serializer.serialize_some(list),
+        }
+    }
+}
+
+impl<'de, T> Deserialize<'de> for OptionalList<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<Vec<T>>::deserialize(deserializer).map(OptionalList::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OptionalList;
+    use crate::{dynamo_item, from_item, to_item, Item};
+    use serde_derive::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Subject {
+        #[serde(default, skip_serializing_if = "OptionalList::is_absent")]
+        list: OptionalList<u8>,
+    }
+
+    #[test]
+    fn round_trips_keep_the_stored_representation() {
+        for item in [
+            dynamo_item! {},
+            dynamo_item! { "list": null },
+            dynamo_item! { "list": [] },
+            dynamo_item! { "list": [1, 2] },
+        ] {
+            let subject: Subject = from_item(item.clone()).unwrap();
+            assert_eq!(to_item::<_, Item>(&subject).unwrap(), item);
+        }
+    }
+
+    #[test]
+    fn absent_lists_must_be_skipped() {
+        let err = to_item::<_, Item>(OptionalList::<u8>::Absent).unwrap_err();
+        assert!(err.to_string().contains("must be skipped"), "{err}");
+    }
+}
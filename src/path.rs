@@ -0,0 +1,338 @@
+//! Attribute paths, such as `address.lines[0]`
+//!
+//! A path is a list of map keys separated by `.`, where each key may be followed by any number of
+//! `[index]` list indexes. The path must start with a key. Within a key, a `\` makes the next
+//! character part of the key, so an attribute named `a.b` is written `a\.b`. [`render`] escapes
+//! every `\`, `.`, `[`, and `]` in a key.
+//!
+//! Errors name the attribute they occurred in with such a path, available from
+//! [`Error::attribute_path`](crate::Error::attribute_path), and [`parse`] maps it back to the
+//! keys and indexes of the document.
+//!
+//! ```
+//! use serde_dynamo::path::{self, Segment};
+//!
+//! let segments = path::parse(r"orders[3].line\.items").unwrap();
+//! assert_eq!(
+//!     segments,
+//!     vec![
+//!         Segment::Key("orders".into()),
+//!         Segment::Index(3),
+//!         Segment::Key("line.items".into()),
+//!     ]
+//! );
+//! assert_eq!(path::render(&segments), r"orders[3].line\.items");
+//! ```
+
+use crate::{chunking::MAX_ITEM_SIZE, error::ErrorImpl, AttributeValue, Result};
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// One step of an attribute path
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Segment<'a> {
+    /// A map key, such as `b` in `a.b`, with any escapes removed
+    Key(Cow<'a, str>),
+    /// A list index, such as `1` in `a[1]`
+    Index(usize),
+}
+
+/// Parse a document path such as `a.b[1].c`
+///
+/// See the [module documentation](self) for the syntax.
+///
+/// # Errors
+///
+/// Fails if the path is empty, starts with an index, has an empty key, has an index that isn't a
+/// number, or ends in an unfinished escape.
+pub fn parse(path: &str) -> Result<Vec<Segment<'_>>> {
+    parse_separated(path, ".")
+}
+
+/// Parse a path whose keys are separated by `separator` instead of `.`
+pub(crate) fn parse_separated<'a>(path: &'a str, separator: &str) -> Result<Vec<Segment<'a>>> {
+    let invalid = || ErrorImpl::InvalidPath(path.to_string()).into();
+
+    let mut segments = Vec::new();
+    let mut rest = path;
+    loop {
+        let end = key_end(rest, separator).ok_or_else(invalid)?;
+        let (key, mut indexes) = rest.split_at(end);
+        if key.is_empty() {
+            return Err(invalid());
+        }
+        segments.push(Segment::Key(unescape(key)));
+
+        while let Some(index) = indexes.strip_prefix('[') {
+            let (index, after) = index.split_once(']').ok_or_else(invalid)?;
+            segments.push(Segment::Index(index.parse().map_err(|_| invalid())?));
+            indexes = after;
+        }
+        if indexes.is_empty() {
+            return Ok(segments);
+        }
+        rest = indexes.strip_prefix(separator).ok_or_else(invalid)?;
+    }
+}
+
+/// Where the key at the start of `path` ends, or `None` if it ends in a lone `\`
+fn key_end(path: &str, separator: &str) -> Option<usize> {
+    let mut chars = path.char_indices();
+    while let Some((i, c)) = chars.next() {
+        if c == '\\' {
+            chars.next()?;
+        } else if c == '[' || path[i..].starts_with(separator) {
+            return Some(i);
+        }
+    }
+    Some(path.len())
+}
+
+fn unescape(key: &str) -> Cow<'_, str> {
+    if !key.contains('\\') {
+        return Cow::Borrowed(key);
+    }
+    let mut unescaped = String::with_capacity(key.len());
+    let mut chars = key.chars();
+    while let Some(c) = chars.next() {
+        // `key_end` has already checked that every escape is followed by a character
+        unescaped.extend(if c == '\\' { chars.next() } else { Some(c) });
+    }
+    Cow::Owned(unescaped)
+}
+
+/// Render segments back into a path, such as `a.b[1]`
+///
+/// Keys are escaped so that [`parse`] gives back the same segments.
+pub fn render(segments: &[Segment<'_>]) -> String {
+    let mut path = String::new();
+    for segment in segments {
+        match segment {
+            Segment::Key(key) => push_key(&mut path, key),
+            Segment::Index(index) => path.push_str(&format!("[{index}]")),
+        }
+    }
+    path
+}
+
+/// Append `key` to a rendered path, escaped and after a `.` unless the path is empty
+pub(crate) fn push_key(path: &mut String, key: &str) {
+    if !path.is_empty() {
+        path.push('.');
+    }
+    for c in key.chars() {
+        if matches!(c, '\\' | '.' | '[' | ']') {
+            path.push('\\');
+        }
+        path.push(c);
+    }
+}
+
+/// Find the value at `segments` within an item
+pub(crate) fn lookup<'a>(
+    item: &'a HashMap<String, AttributeValue>,
+    segments: &[Segment<'_>],
+) -> Option<&'a AttributeValue> {
+    let (first, rest) = match segments.split_first() {
+        Some((Segment::Key(key), rest)) => (item.get(key.as_ref())?, rest),
+        _ => return None,
+    };
+    rest.iter()
+        .try_fold(first, |value, segment| match (value, segment) {
+            (AttributeValue::M(m), Segment::Key(key)) => m.get(key.as_ref()),
+            (AttributeValue::L(l), Segment::Index(index)) => l.get(*index),
+            _ => None,
+        })
+}
+
+/// Store `value` at `segments` within an item, returning the value it replaced
+///
+/// With `create`, missing maps are created and lists are extended with `NULL`s up to the index.
+/// Without it, every map and list along the way, and the list element being replaced, must
+/// already exist. Fails if the path goes through a value of the wrong type.
+pub(crate) fn insert(
+    item: &mut HashMap<String, AttributeValue>,
+    segments: &[Segment<'_>],
+    value: AttributeValue,
+    create: bool,
+) -> Result<Option<AttributeValue>> {
+    let (key, rest) = match segments.split_first() {
+        Some((Segment::Key(key), rest)) => (key, rest),
+        _ => return Err(ErrorImpl::InvalidPath(render(segments)).into()),
+    };
+    let Some((last, parents)) = rest.split_last() else {
+        return Ok(item.insert(key.to_string(), value));
+    };
+
+    let missing = |depth: usize| ErrorImpl::MissingAttribute(render(&segments[..depth])).into();
+    let mut target = if create {
+        item.entry(key.to_string())
+            .or_insert_with(|| empty_container(&rest[0]))
+    } else {
+        item.get_mut(key.as_ref()).ok_or_else(|| missing(1))?
+    };
+    for (depth, (segment, next)) in parents.iter().zip(rest.iter().skip(1)).enumerate() {
+        target = match child(target, segment, next, create)? {
+            Some(child) => child,
+            None => return Err(missing(depth + 2)),
+        };
+    }
+    match (target, last) {
+        (AttributeValue::M(m), Segment::Key(key)) => Ok(m.insert(key.to_string(), value)),
+        (AttributeValue::L(l), Segment::Index(index)) => {
+            if l.len() <= *index {
+                if !create {
+                    return Err(missing(segments.len()));
+                }
+                pad(l, *index)?;
+            }
+            Ok(Some(std::mem::replace(&mut l[*index], value)))
+        }
+        (target, _) => Err(ErrorImpl::PathTypeMismatch(target.type_descriptor()).into()),
+    }
+}
+
+/// The child at `segment`, created as a container for `next` if it is missing and `create` is set
+fn child<'a>(
+    value: &'a mut AttributeValue,
+    segment: &Segment<'_>,
+    next: &Segment<'_>,
+    create: bool,
+) -> Result<Option<&'a mut AttributeValue>> {
+    match (value, segment) {
+        (AttributeValue::M(m), Segment::Key(key)) => {
+            if create {
+                Ok(Some(
+                    m.entry(key.to_string())
+                        .or_insert_with(|| empty_container(next)),
+                ))
+            } else {
+                Ok(m.get_mut(key.as_ref()))
+            }
+        }
+        (AttributeValue::L(l), Segment::Index(index)) => {
+            if !create {
+                return Ok(l.get_mut(*index));
+            }
+            if l.len() <= *index {
+                pad(l, *index)?;
+            }
+            let child = &mut l[*index];
+            if let AttributeValue::Null(_) = child {
+                *child = empty_container(next);
+            }
+            Ok(Some(child))
+        }
+        (value, _) => Err(ErrorImpl::PathTypeMismatch(value.type_descriptor()).into()),
+    }
+}
+
+/// Extend `list` with `NULL`s so that it has an element at `index`
+///
+/// Each `NULL` takes at least a byte, so an index past the item size limit can never be stored,
+/// and is refused before allocating the list.
+fn pad(list: &mut Vec<AttributeValue>, index: usize) -> Result<()> {
+    if index >= MAX_ITEM_SIZE {
+        return Err(ErrorImpl::ItemTooLarge(index.saturating_add(1), MAX_ITEM_SIZE).into());
+    }
+    list.resize(index + 1, AttributeValue::Null(true));
+    Ok(())
+}
+
+fn empty_container(next: &Segment<'_>) -> AttributeValue {
+    match next {
+        Segment::Key(_) => AttributeValue::M(HashMap::new()),
+        Segment::Index(_) => AttributeValue::L(Vec::new()),
+    }
+}
+
+/// Render segments as an expression path, with a `#path0`, `#path1`, … placeholder for each key
+///
+/// Returns the path and the placeholders' `ExpressionAttributeNames`.
+pub(crate) fn placeholders(segments: &[Segment<'_>]) -> (String, HashMap<String, String>) {
+    let mut path = String::new();
+    let mut names = HashMap::new();
+    for segment in segments {
+        match segment {
+            Segment::Key(key) => {
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                let placeholder = format!("#path{}", names.len());
+                path.push_str(&placeholder);
+                names.insert(placeholder, key.to_string());
+            }
+            Segment::Index(index) => path.push_str(&format!("[{index}]")),
+        }
+    }
+    (path, names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_paths() {
+        assert_eq!(parse("a").unwrap(), vec![Segment::Key("a".into())]);
+        assert_eq!(
+            parse("a.b[1][2].c").unwrap(),
+            vec![
+                Segment::Key("a".into()),
+                Segment::Key("b".into()),
+                Segment::Index(1),
+                Segment::Index(2),
+                Segment::Key("c".into()),
+            ]
+        );
+
+        for invalid in ["", "a..b", "[1]", "a[", "a[x]", "a[1]b", "a.[1]"] {
+            assert_eq!(
+                parse(invalid).unwrap_err(),
+                ErrorImpl::InvalidPath(invalid.to_string()).into(),
+                "{invalid}"
+            );
+        }
+    }
+
+    #[test]
+    fn escaped_keys() {
+        let segments = vec![
+            Segment::Key("a.b".into()),
+            Segment::Index(3),
+            Segment::Key(r"c[0]\d".into()),
+        ];
+        let path = render(&segments);
+        assert_eq!(path, r"a\.b[3].c\[0\]\\d");
+        assert_eq!(parse(&path).unwrap(), segments);
+
+        // Escaping characters that don't need it is harmless
+        assert_eq!(parse(r"\a").unwrap(), vec![Segment::Key("a".into())]);
+        assert!(matches!(
+            parse("a").unwrap()[0],
+            Segment::Key(Cow::Borrowed(_))
+        ));
+
+        for invalid in [r"a\", r"a.\", r"\"] {
+            assert_eq!(
+                parse(invalid).unwrap_err(),
+                ErrorImpl::InvalidPath(invalid.to_string()).into(),
+                "{invalid}"
+            );
+        }
+    }
+
+    #[test]
+    fn placeholder_paths() {
+        let (path, names) = placeholders(&parse("a.b[1][2].a").unwrap());
+        assert_eq!(path, "#path0.#path1[1][2].#path2");
+        assert_eq!(
+            names,
+            HashMap::from([
+                (String::from("#path0"), String::from("a")),
+                (String::from("#path1"), String::from("b")),
+                (String::from("#path2"), String::from("a")),
+            ])
+        );
+    }
+}
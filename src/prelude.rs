@@ -0,0 +1,86 @@
+//! The types and functions most code needs, with concrete types
+//!
+//! The crate-root [`to_item`](crate::to_item) and [`to_attribute_value`](crate::to_attribute_value)
+//! can return the attribute values of any supported SDK, so a call whose result isn't annotated
+//! fails with a type inference error. The functions in this module return this crate's [`Item`]
+//! and [`AttributeValue`] instead, and the `from_*` functions accept them as well as SDK items.
+//!
+//! ```
+//! use serde_dynamo::prelude::*;
+//! # use serde_derive::{Serialize, Deserialize};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct User {
+//!     id: String,
+//!     age: u8,
+//! }
+//!
+//! let user = User { id: String::from("u-1"), age: 42 };
+//!
+//! let item = to_item(&user)?;
+//! assert_eq!(item, dynamo_item! { "id": "u-1", "age": 42 });
+//!
+//! let read: User = from_item(item)?;
+//! assert_eq!(read, user);
+//! # Ok::<(), serde_dynamo::Error>(())
+//! ```
+//!
+//! Code that talks to DynamoDB through an SDK should use that SDK module's prelude instead, such
+//! as `serde_dynamo::sdk::prelude` for the newest enabled aws-sdk-dynamodb, whose functions take
+//! and return the SDK's types.
+
+pub use crate::{dynamo_item, dynamo_value};
+pub use crate::{
+    from_attribute_value, from_item, from_item_ref, from_items, from_items_iter, from_items_ref,
+    from_optional_item,
+};
+pub use crate::{
+    AttributeValue, DeserializerConfig, Error, ErrorCode, Item, Items, SerdeDynamo,
+    SerializerConfig,
+};
+
+use crate::Result;
+use serde::Serialize;
+
+/// Convert a `T` into an [`AttributeValue`]
+///
+/// See [`to_attribute_value`](crate::to_attribute_value).
+pub fn to_attribute_value<T>(value: T) -> Result<AttributeValue>
+where
+    T: Serialize,
+{
+    crate::to_attribute_value(value)
+}
+
+/// Convert a `T` into an [`Item`]
+///
+/// See [`to_item`](crate::to_item).
+pub fn to_item<T>(value: T) -> Result<Item>
+where
+    T: Serialize,
+{
+    crate::to_item(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_derive::Serialize;
+
+    #[derive(Serialize)]
+    struct Key<'a> {
+        pk: &'a str,
+        sk: u32,
+    }
+
+    #[test]
+    fn results_need_no_annotations() {
+        let key = Key { pk: "a", sk: 1 };
+        let item = to_item(&key).unwrap();
+        assert_eq!(item, dynamo_item! { "pk": "a", "sk": 1 });
+        assert_eq!(
+            to_attribute_value(&key).unwrap(),
+            AttributeValue::M(item.into_inner())
+        );
+    }
+}
@@ -0,0 +1,286 @@
+//! Typed projections for reading a subset of an item's attributes
+//!
+//! Reading from a sparse global secondary index, or only fetching the attributes a code path
+//! needs, means writing a `ProjectionExpression` that matches the struct the results are
+//! deserialized into. [`Projection`] ties a smaller struct to the full item type it is a
+//! projection of, [`projection_expression`] derives the expression from the struct's serde field
+//! names, and [`from_projected_item`] checks that a returned item really was projected.
+//!
+//! # Examples
+//!
+//! ```
+//! # use serde_derive::Deserialize;
+//! use serde_dynamo::projection::{projection_expression, Projection};
+//!
+//! #[derive(Deserialize)]
+//! struct User {
+//!     id: String,
+//!     name: String,
+//!     email: String,
+//!     age: u8,
+//! }
+//!
+//! #[derive(Deserialize)]
+//! struct UserSummary {
+//!     id: String,
+//!     name: String,
+//! }
+//!
+//! impl Projection for UserSummary {
+//!     type Of = User;
+//! }
+//!
+//! let projection = projection_expression::<UserSummary>()?;
+//! assert_eq!(projection.expression(), "#__sd_id, #__sd_name");
+//! assert_eq!(projection.names()["#__sd_name"], "name");
+//! # Ok::<(), serde_dynamo::Error>(())
+//! ```
+
+use crate::{error::ErrorImpl, expression::Expression, Item, Result};
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+
+/// A struct that holds a subset of the attributes of `Of`
+pub trait Projection: DeserializeOwned {
+    /// The full item type
+    type Of: DeserializeOwned;
+}
+
+/// Build the `ProjectionExpression` for `P`
+///
+/// Every field name goes through its [`placeholder`](crate::expression::placeholder), so names
+/// that are reserved words or contain characters such as `-` or `.` are projected as the
+/// top-level attribute they name. The placeholders don't clash with ones chosen by callers, so
+/// the names can be merged with those of a key condition or filter expression.
+///
+/// Fails if `P` has no named fields, such as when it is a tuple struct or uses
+/// `#[serde(flatten)]`.
+///
+/// Every field of `P` must also be an attribute of `P::Of`. The check is skipped when `P::Of`
+/// doesn't expose its fields, such as when it uses `#[serde(flatten)]`.
+pub fn projection_expression<P>() -> Result<Expression>
+where
+    P: Projection,
+{
+    let fields = crate::expression::field_names::<P>();
+    if fields.is_empty() {
+        return Err(ErrorImpl::ProjectionWithoutFields(std::any::type_name::<P>()).into());
+    }
+
+    let of_fields = crate::expression::field_names::<P::Of>();
+    if !of_fields.is_empty() {
+        if let Some(field) = fields.iter().find(|field| !of_fields.contains(field)) {
+            return Err(ErrorImpl::ProjectedAttributeNotInItem(field.to_string()).into());
+        }
+    }
+
+    let mut placeholders = Vec::with_capacity(fields.len());
+    let mut names = HashMap::with_capacity(fields.len());
+    for field in fields {
+        let placeholder = crate::expression::placeholder(field);
+        placeholders.push(placeholder.clone());
+        names.insert(placeholder, field.to_string());
+    }
+
+    Ok(Expression::from_parts(
+        placeholders.join(", "),
+        names,
+        HashMap::new(),
+    ))
+}
+
+/// Interpret a projected [`Item`] as an instance of `P`
+///
+/// Unlike [`from_item`](crate::from_item), any attribute that isn't a field of `P` is an error,
+/// which catches reads that forgot to set the projection expression.
+///
+/// ```
+/// # use serde_derive::Deserialize;
+/// # use serde_dynamo::{AttributeValue, Item};
+/// # use std::collections::HashMap;
+/// use serde_dynamo::projection::{from_projected_item, Projection};
+///
+/// # #[derive(Deserialize)]
+/// # struct User { id: String, name: String }
+/// #[derive(Debug, Deserialize)]
+/// struct UserId {
+///     id: String,
+/// }
+///
+/// impl Projection for UserId {
+///     type Of = User;
+/// }
+///
+/// let item: Item = HashMap::from([
+///     (String::from("id"), AttributeValue::S(String::from("one"))),
+///     (String::from("name"), AttributeValue::S(String::from("Arthur"))),
+/// ]).into();
+///
+/// let err = from_projected_item::<_, UserId>(item).unwrap_err();
+/// assert_eq!(err.to_string(), "Attribute `name` is not part of the projection");
+/// ```
+pub fn from_projected_item<I, P>(item: I) -> Result<P>
+where
+    I: Into<Item>,
+    P: Projection,
+{
+    let item: Item = item.into();
+    let fields = crate::expression::field_names::<P>();
+    if let Some(name) = item
+        .as_ref()
+        .keys()
+        .find(|name| !fields.contains(&name.as_str()))
+    {
+        return Err(ErrorImpl::AttributeNotInProjection(name.clone()).into());
+    }
+    crate::from_item(item)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AttributeValue;
+    use serde_derive::Deserialize;
+    use std::collections::HashMap;
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    #[serde(rename_all = "camelCase")]
+    struct Order {
+        order_id: String,
+        status: String,
+        total_cents: u64,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct OrderStatus {
+        order_id: String,
+        status: String,
+    }
+
+    impl Projection for OrderStatus {
+        type Of = Order;
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Mistyped {
+        order_id: String,
+    }
+
+    impl Projection for Mistyped {
+        type Of = Order;
+    }
+
+    #[test]
+    fn expression() {
+        let projection = projection_expression::<OrderStatus>().unwrap();
+        assert_eq!(projection.expression(), "#__sd_orderId, #__sd_status");
+        assert_eq!(
+            projection.names(),
+            &HashMap::from([
+                (String::from("#__sd_orderId"), String::from("orderId")),
+                (String::from("#__sd_status"), String::from("status")),
+            ])
+        );
+
+        let err = projection_expression::<Mistyped>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Projected attribute `order_id` is not an attribute of the full item"
+        );
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Unusual {
+        #[serde(rename = "first-name")]
+        first_name: String,
+        #[serde(rename = "1st")]
+        first: String,
+        #[serde(rename = "a.b")]
+        a_b: String,
+        status: String,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct UnusualSummary {
+        #[serde(rename = "first-name")]
+        first_name: String,
+        #[serde(rename = "1st")]
+        first: String,
+        #[serde(rename = "a.b")]
+        a_b: String,
+    }
+
+    impl Projection for UnusualSummary {
+        type Of = Unusual;
+    }
+
+    #[test]
+    fn expression_with_unusual_names() {
+        let projection = projection_expression::<UnusualSummary>().unwrap();
+        assert_eq!(
+            projection.expression(),
+            "#__sd_first_2dname, #__sd_1st, #__sd_a_2eb"
+        );
+        assert_eq!(
+            projection.names(),
+            &HashMap::from([
+                (
+                    String::from("#__sd_first_2dname"),
+                    String::from("first-name")
+                ),
+                (String::from("#__sd_1st"), String::from("1st")),
+                (String::from("#__sd_a_2eb"), String::from("a.b")),
+            ])
+        );
+        assert!(projection.values().is_empty());
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Wrapped(String);
+
+    impl Projection for Wrapped {
+        type Of = Order;
+    }
+
+    #[test]
+    fn projection_without_fields() {
+        let err = projection_expression::<Wrapped>().unwrap_err();
+        assert_eq!(err.code(), crate::ErrorCode::ProjectionWithoutFields);
+        assert!(err.to_string().ends_with("::Wrapped` has no named fields"));
+    }
+
+    #[test]
+    fn projected_item() {
+        let item = HashMap::from([
+            (
+                String::from("orderId"),
+                AttributeValue::S(String::from("1")),
+            ),
+            (
+                String::from("status"),
+                AttributeValue::S(String::from("paid")),
+            ),
+        ]);
+        let status: OrderStatus = from_projected_item(item.clone()).unwrap();
+        assert_eq!(
+            status,
+            OrderStatus {
+                order_id: String::from("1"),
+                status: String::from("paid"),
+            }
+        );
+
+        let mut item = item;
+        item.insert(
+            String::from("totalCents"),
+            AttributeValue::N(String::from("100")),
+        );
+        assert!(from_projected_item::<_, OrderStatus>(item).is_err());
+    }
+}
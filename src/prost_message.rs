@@ -0,0 +1,330 @@
+//! Serializer codec for storing protocol buffers messages as binary
+//!
+//! Services that already exchange [prost] messages can store them in an item as-is, next to the
+//! top-level attributes that queries and indexes need. This module encodes the message with
+//! protobuf's wire format into a single `B` attribute, and decodes it on read.
+//!
+//! # Usage
+//!
+//! To use, annotate the field with `#[serde(with = "serde_dynamo::prost_message")]`. The field may
+//! be any `prost::Message` that implements `Default`.
+//!
+//! To record which message type the bytes hold, wrap the message in a [`TypedMessage`] and
+//! [flatten] it into the item instead. That adds a `type_url` attribute beside a `message`
+//! attribute, and reading checks the stored type URL against the message type.
+//!
+//! # Errors
+//!
+//! The deserializer in this module will return an error if:
+//!
+//! * the attribute is not binary
+//! * the binary is not a valid encoding of the message
+//! * for a [`TypedMessage`], the stored type URL doesn't match the message type
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate __prost as prost;
+//! use serde_derive::{Serialize, Deserialize};
+//! use serde_dynamo::{prost_message::TypedMessage, AttributeValue, Item};
+//!
+//! #[derive(Clone, PartialEq, prost::Message)]
+//! struct Shipment {
+//!     #[prost(string, tag = "1")]
+//!     carrier: String,
+//!     #[prost(uint32, tag = "2")]
+//!     parcels: u32,
+//! }
+//!
+//! impl prost::Name for Shipment {
+//!     const NAME: &'static str = "Shipment";
+//!     const PACKAGE: &'static str = "orders.v1";
+//! }
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Order {
+//!     id: String,
+//!     #[serde(with = "serde_dynamo::prost_message")]
+//!     shipment: Shipment,
+//! }
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct TaggedOrder {
+//!     id: String,
+//!     #[serde(flatten)]
+//!     shipment: TypedMessage<Shipment>,
+//! }
+//!
+//! let shipment = Shipment { carrier: String::from("ups"), parcels: 2 };
+//!
+//! let order = Order { id: String::from("o-1"), shipment: shipment.clone() };
+//! let item: Item = serde_dynamo::to_item(&order).unwrap();
+//! assert!(matches!(&item["shipment"], AttributeValue::B(_)));
+//! assert_eq!(serde_dynamo::from_item::<_, Order>(item).unwrap(), order);
+//!
+//! let order = TaggedOrder { id: String::from("o-1"), shipment: TypedMessage(shipment) };
+//! let item: Item = serde_dynamo::to_item(&order).unwrap();
+//! assert_eq!(item["type_url"], AttributeValue::S(String::from("/orders.v1.Shipment")));
+//! assert_eq!(serde_dynamo::from_item::<_, TaggedOrder>(item).unwrap(), order);
+//! ```
+//!
+//! [prost]: https://docs.rs/prost
+//! [flatten]: https://serde.rs/attr-flatten.html
+
+use prost::{Message, Name};
+use serde::{
+    de::{self, Deserialize, Deserializer},
+    ser::{Serialize, SerializeStruct, Serializer},
+};
+use std::fmt;
+
+/// Serializes the given message as protobuf-encoded binary
+///
+/// See the [module documentation][crate::prost_message] for additional usage information.
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Message,
+    S: Serializer,
+{
+    serializer.serialize_bytes(&value.encode_to_vec())
+}
+
+/// Deserializes protobuf-encoded binary as a message
+///
+/// See the [module documentation][crate::prost_message] for additional usage information.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: Message + Default,
+    D: Deserializer<'de>,
+{
+    let bytes = deserialize_bytes(deserializer)?;
+    decode(&bytes)
+}
+
+fn decode<T, E>(bytes: &[u8]) -> Result<T, E>
+where
+    T: Message + Default,
+    E: de::Error,
+{
+    T::decode(bytes).map_err(|err| E::custom(format!("invalid protobuf message: {err}")))
+}
+
+fn deserialize_bytes<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_bytes(BytesVisitor)
+}
+
+struct BytesVisitor;
+
+impl de::Visitor<'_> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a protobuf-encoded message")
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(v.to_vec())
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(v)
+    }
+}
+
+/// A message stored along with its type URL
+///
+/// Serializes as a `type_url` string and a `message` binary. [Flatten] it into a struct to store
+/// those as attributes of the item. Deserializing fails if the stored type URL isn't the one
+/// `T` reports through [`prost::Name`].
+///
+/// See the [module documentation][crate::prost_message] for more information.
+///
+/// [Flatten]: https://serde.rs/attr-flatten.html
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TypedMessage<T>(pub T);
+
+impl<T> Serialize for TypedMessage<T>
+where
+    T: Message + Name,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        struct Encoded<'a, T>(&'a T);
+
+        impl<T: Message> Serialize for Encoded<'_, T> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serialize(self.0, serializer)
+            }
+        }
+
+        let mut state = serializer.serialize_struct("TypedMessage", 2)?;
+        state.serialize_field("type_url", &T::type_url())?;
+        state.serialize_field("message", &Encoded(&self.0))?;
+        state.end()
+    }
+}
+
+impl<'de, T> Deserialize<'de> for TypedMessage<T>
+where
+    T: Message + Name + Default,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct Stored {
+            type_url: String,
+            message: Vec<u8>,
+        }
+
+        struct Encoded(Vec<u8>);
+
+        impl<'de> Deserialize<'de> for Encoded {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserialize_bytes(deserializer).map(Encoded)
+            }
+        }
+
+        struct StoredVisitor;
+
+        impl<'de> de::Visitor<'de> for StoredVisitor {
+            type Value = Stored;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a map with `type_url` and `message` fields")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::MapAccess<'de>,
+            {
+                let mut type_url = None;
+                let mut message = None;
+                while let Some(field) = map.next_key::<String>()? {
+                    match field.as_str() {
+                        "type_url" => type_url = Some(map.next_value()?),
+                        "message" => message = Some(map.next_value::<Encoded>()?.0),
+                        _ => {
+                            map.next_value::<de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(Stored {
+                    type_url: type_url.ok_or_else(|| de::Error::missing_field("type_url"))?,
+                    message: message.ok_or_else(|| de::Error::missing_field("message"))?,
+                })
+            }
+        }
+
+        let stored = deserializer.deserialize_struct(
+            "TypedMessage",
+            &["type_url", "message"],
+            StoredVisitor,
+        )?;
+        let expected = T::type_url();
+        if stored.type_url != expected {
+            return Err(de::Error::custom(format!(
+                "expected a message of type `{expected}`, found `{}`",
+                stored.type_url
+            )));
+        }
+        decode(&stored.message).map(TypedMessage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TypedMessage;
+    use crate::{from_item, to_item, AttributeValue, Item};
+    use serde_derive::{Deserialize, Serialize};
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    struct Point {
+        #[prost(sint32, tag = "1")]
+        x: i32,
+        #[prost(sint32, tag = "2")]
+        y: i32,
+    }
+
+    impl prost::Name for Point {
+        const NAME: &'static str = "Point";
+        const PACKAGE: &'static str = "geo";
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    struct Label {
+        #[prost(string, tag = "1")]
+        text: String,
+    }
+
+    impl prost::Name for Label {
+        const NAME: &'static str = "Label";
+        const PACKAGE: &'static str = "geo";
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Shape {
+        #[serde(with = "crate::prost_message")]
+        origin: Point,
+        #[serde(flatten)]
+        tagged: TypedMessage<Point>,
+    }
+
+    #[test]
+    fn round_trip() {
+        let shape = Shape {
+            origin: Point { x: -1, y: 2 },
+            tagged: TypedMessage(Point { x: 3, y: 0 }),
+        };
+
+        let item: Item = to_item(&shape).unwrap();
+        assert_eq!(
+            item["origin"],
+            AttributeValue::B(vec![0x08, 0x01, 0x10, 0x04])
+        );
+        assert_eq!(
+            item["type_url"],
+            AttributeValue::S(String::from("/geo.Point"))
+        );
+        assert_eq!(item["message"], AttributeValue::B(vec![0x08, 0x06]));
+
+        let round_tripped: Shape = from_item(item).unwrap();
+        assert_eq!(round_tripped, shape);
+    }
+
+    #[test]
+    fn reject_mismatched_messages() {
+        let item: Item = to_item(TypedMessage(Label {
+            text: String::from("x"),
+        }))
+        .unwrap();
+        let err = from_item::<_, TypedMessage<Point>>(item).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "expected a message of type `/geo.Point`, found `/geo.Label`"
+        );
+
+        let mut item: Item = to_item(&Shape {
+            origin: Point { x: 0, y: 0 },
+            tagged: TypedMessage(Point { x: 0, y: 0 }),
+        })
+        .unwrap();
+        item.insert(String::from("origin"), AttributeValue::B(vec![0x08]));
+        let err = from_item::<_, Shape>(item).unwrap_err();
+        assert!(
+            err.to_string().starts_with("invalid protobuf message"),
+            "{err}"
+        );
+    }
+}
@@ -0,0 +1,373 @@
+//! Storing types from other crates through a local mirror
+//!
+//! Serde's [remote derive] lets a local copy of a foreign type's definition serialize it, but it
+//! only works when every field is public and, for enums, when every variant can be matched. SDK
+//! types are usually `#[non_exhaustive]`, so a mirror of them can't be derived. It also only
+//! covers fields of exactly the foreign type, not an `Option` or `Vec` of it.
+//!
+//! Instead, define the shape to store as an ordinary local type, derive `Serialize` and
+//! `Deserialize` for it, and implement [`Mirror`] to convert to and from the foreign type. The
+//! conversion can fall back on a catch-all variant for variants added after the mirror was
+//! written. Then:
+//!
+//! - [`remote_with!`](crate::remote_with) declares a module for `#[serde(with = "...")]` on fields
+//!   of the foreign type
+//! - [`Remote`] wraps the foreign type wherever else it's stored, such as in an `Option` or a
+//!   `Vec`
+//!
+//! # Examples
+//!
+//! ```
+//! use serde_derive::{Serialize, Deserialize};
+//! use serde_dynamo::remote::{Mirror, Remote};
+//! use serde_dynamo::{dynamo_item, Item};
+//! use std::io::ErrorKind;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! #[serde(rename_all = "snake_case")]
+//! enum ErrorKindDef {
+//!     NotFound,
+//!     TimedOut,
+//!     #[serde(other)]
+//!     Other,
+//! }
+//!
+//! impl Mirror<ErrorKind> for ErrorKindDef {
+//!     fn from_remote(kind: &ErrorKind) -> Self {
+//!         match kind {
+//!             ErrorKind::NotFound => ErrorKindDef::NotFound,
+//!             ErrorKind::TimedOut => ErrorKindDef::TimedOut,
+//!             // `ErrorKind` is `#[non_exhaustive]`
+//!             _ => ErrorKindDef::Other,
+//!         }
+//!     }
+//!
+//!     fn into_remote(self) -> ErrorKind {
+//!         match self {
+//!             ErrorKindDef::NotFound => ErrorKind::NotFound,
+//!             ErrorKindDef::TimedOut => ErrorKind::TimedOut,
+//!             ErrorKindDef::Other => ErrorKind::Other,
+//!         }
+//!     }
+//! }
+//!
+//! serde_dynamo::remote_with! {
+//!     mod error_kind: ErrorKind as ErrorKindDef;
+//! }
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Failure {
+//!     #[serde(with = "error_kind")]
+//!     kind: ErrorKind,
+//!     retries: Vec<Remote<ErrorKind, ErrorKindDef>>,
+//! }
+//!
+//! # fn main() {
+//! let failure = Failure {
+//!     kind: ErrorKind::NotFound,
+//!     retries: vec![Remote::new(ErrorKind::TimedOut), Remote::new(ErrorKind::Interrupted)],
+//! };
+//!
+//! let item: Item = serde_dynamo::to_item(&failure).unwrap();
+//! assert_eq!(
+//!     item,
+//!     dynamo_item! { "kind": "not_found", "retries": ["timed_out", "other"] }
+//! );
+//!
+//! let read: Failure = serde_dynamo::from_item(item).unwrap();
+//! assert_eq!(read.kind, ErrorKind::NotFound);
+//! assert_eq!(*read.retries[1], ErrorKind::Other);
+//! # }
+//! ```
+//!
+//! [remote derive]: https://serde.rs/remote-derive.html
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+
+/// A local type that stores the foreign type `T`
+///
+/// The mirror decides how `T` is stored: it's serialized in place of `T`, and deserialized and
+/// converted back.
+pub trait Mirror<T> {
+    /// Convert the value to store
+    fn from_remote(value: &T) -> Self;
+
+    /// Convert the value read back
+    fn into_remote(self) -> T;
+}
+
+/// Declare a module for `#[serde(with = "...")]` that stores a foreign type through a [`Mirror`]
+///
+/// `remote_with! { mod name: Foreign as Local; }` declares the module `name`, whose `serialize`
+/// and `deserialize` functions store a `Foreign` as `Local`. `Local` must implement
+/// [`Mirror<Foreign>`](Mirror) as well as `Serialize` and `Deserialize`.
+///
+/// Paths in the module are resolved from the parent module, so the macro must be used at module
+/// level rather than inside a function.
+///
+/// See the [module documentation](crate::remote) for an example.
+#[macro_export]
+macro_rules! remote_with {
+    (
+        $(#[$meta:meta])*
+        $vis:vis mod $module:ident : $remote:ty as $mirror:ty;
+    ) => {
+        $(#[$meta])*
+        $vis mod $module {
+            #[allow(unused_imports)]
+            use super::*;
+
+            /// Serializes the value through its mirror
+            pub fn serialize<S>(
+                value: &$remote,
+                serializer: S,
+            ) -> ::std::result::Result<S::Ok, S::Error>
+            where
+                S: $crate::__serde::Serializer,
+            {
+                $crate::remote::serialize::<$remote, $mirror, S>(value, serializer)
+            }
+
+            /// Deserializes the value through its mirror
+            pub fn deserialize<'de, D>(deserializer: D) -> ::std::result::Result<$remote, D::Error>
+            where
+                D: $crate::__serde::Deserializer<'de>,
+            {
+                $crate::remote::deserialize::<$remote, $mirror, D>(deserializer)
+            }
+        }
+    };
+}
+
+/// Serializes `value` as its mirror `M`
+///
+/// The functions of a [`remote_with!`](crate::remote_with) module call this.
+pub fn serialize<T, M, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    M: Mirror<T> + Serialize,
+    S: Serializer,
+{
+    M::from_remote(value).serialize(serializer)
+}
+
+/// Deserializes a mirror `M` and converts it to a `T`
+///
+/// The functions of a [`remote_with!`](crate::remote_with) module call this.
+pub fn deserialize<'de, T, M, D>(deserializer: D) -> Result<T, D::Error>
+where
+    M: Mirror<T> + Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    M::deserialize(deserializer).map(M::into_remote)
+}
+
+/// A foreign `T` that is serialized and deserialized through its mirror `M`
+///
+/// Use it where a `with` module doesn't reach, such as `Option<Remote<T, M>>` or
+/// `HashMap<String, Remote<T, M>>`. It dereferences to the wrapped value.
+pub struct Remote<T, M> {
+    value: T,
+    mirror: PhantomData<fn() -> M>,
+}
+
+impl<T, M> Remote<T, M> {
+    /// Wrap `value`
+    pub fn new(value: T) -> Self {
+        Remote {
+            value,
+            mirror: PhantomData,
+        }
+    }
+
+    /// Unwrap the value
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T, M> From<T> for Remote<T, M> {
+    fn from(value: T) -> Self {
+        Remote::new(value)
+    }
+}
+
+impl<T, M> Deref for Remote<T, M> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T, M> DerefMut for Remote<T, M> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl<T, M> fmt::Debug for Remote<T, M>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Remote").field(&self.value).finish()
+    }
+}
+
+impl<T, M> Clone for Remote<T, M>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        Remote::new(self.value.clone())
+    }
+}
+
+impl<T, M> PartialEq for Remote<T, M>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T, M> Eq for Remote<T, M> where T: Eq {}
+
+impl<T, M> Serialize for Remote<T, M>
+where
+    M: Mirror<T> + Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize::<T, M, S>(&self.value, serializer)
+    }
+}
+
+impl<'de, T, M> Deserialize<'de> for Remote<T, M>
+where
+    M: Mirror<T> + Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize::<T, M, D>(deserializer).map(Remote::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Mirror, Remote};
+    use crate::{dynamo_item, from_item, to_item, Item};
+    use serde_derive::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+
+    mod sdk {
+        /// Stands in for an SDK struct with private fields
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct Endpoint {
+            host: String,
+            port: u16,
+        }
+
+        impl Endpoint {
+            pub fn new(host: &str, port: u16) -> Self {
+                Endpoint {
+                    host: host.to_string(),
+                    port,
+                }
+            }
+
+            pub fn host(&self) -> &str {
+                &self.host
+            }
+
+            pub fn port(&self) -> u16 {
+                self.port
+            }
+        }
+    }
+
+    use sdk::Endpoint;
+
+    #[derive(Serialize, Deserialize)]
+    struct EndpointDef {
+        host: String,
+        #[serde(default)]
+        port: Option<u16>,
+    }
+
+    impl Mirror<Endpoint> for EndpointDef {
+        fn from_remote(endpoint: &Endpoint) -> Self {
+            EndpointDef {
+                host: endpoint.host().to_string(),
+                port: Some(endpoint.port()).filter(|&port| port != 443),
+            }
+        }
+
+        fn into_remote(self) -> Endpoint {
+            Endpoint::new(&self.host, self.port.unwrap_or(443))
+        }
+    }
+
+    crate::remote_with! {
+        mod endpoint: Endpoint as EndpointDef;
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Service {
+        #[serde(with = "endpoint")]
+        primary: Endpoint,
+        fallback: Option<Remote<Endpoint, EndpointDef>>,
+        regions: BTreeMap<String, Remote<Endpoint, EndpointDef>>,
+    }
+
+    #[test]
+    fn stores_the_mirror() {
+        let service = Service {
+            primary: Endpoint::new("a.example", 443),
+            fallback: Some(Remote::new(Endpoint::new("b.example", 8443))),
+            regions: BTreeMap::from([(
+                String::from("eu"),
+                Endpoint::new("eu.example", 443).into(),
+            )]),
+        };
+        let item: Item = to_item(&service).unwrap();
+        assert_eq!(
+            item,
+            dynamo_item! {
+                "primary": { "host": "a.example", "port": null },
+                "fallback": { "host": "b.example", "port": 8443 },
+                "regions": { "eu": { "host": "eu.example", "port": null } },
+            }
+        );
+        assert_eq!(from_item::<_, Service>(item).unwrap(), service);
+
+        let item = dynamo_item! {
+            "primary": { "host": "a.example" },
+            "fallback": null,
+            "regions": {},
+        };
+        let read: Service = from_item(item).unwrap();
+        assert_eq!(read.primary.port(), 443);
+        assert_eq!(read.fallback, None);
+    }
+
+    #[test]
+    fn wraps_the_value() {
+        let mut remote: Remote<Endpoint, EndpointDef> = Remote::new(Endpoint::new("a", 1));
+        assert_eq!(remote.host(), "a");
+        *remote = Endpoint::new("b", 2);
+        assert_eq!(
+            format!("{remote:?}"),
+            r#"Remote(Endpoint { host: "b", port: 2 })"#
+        );
+        assert_eq!(remote.clone().into_inner(), Endpoint::new("b", 2));
+    }
+}
@@ -0,0 +1,172 @@
+use serde::{
+    ser::{Error, SerializeSeq},
+    Serialize, Serializer,
+};
+use std::{cell::Cell, fmt};
+
+/// Serializes the items of an iterator as a list, without collecting them first
+///
+/// Builders often compute a list lazily, such as `ids.iter().map(|id| format!("user#{id}"))`.
+/// Wrapping the iterator in `SeqOf` serializes each item straight into an `L`, instead of
+/// collecting it into a `Vec` just to serialize it.
+///
+/// Serialization takes `&self`, so the wrapped value is cloned and the clone is iterated. Iterator
+/// adapters over borrowed collections are cheap to clone. If the iterator reports an exact size
+/// hint, it is used to preallocate the list. For iterators that can't be cloned, such as
+/// `vec.into_iter().map(..)` over owned values, use [`SeqOnce`].
+///
+/// ```
+/// use serde_dynamo::{AttributeValue, SeqOf};
+///
+/// let ids = [3, 1, 2];
+/// let value: AttributeValue =
+///     serde_dynamo::to_attribute_value(SeqOf(ids.iter().map(|id| format!("user#{}", id)))).unwrap();
+///
+/// assert_eq!(
+///     value,
+///     AttributeValue::L(vec![
+///         AttributeValue::S(String::from("user#3")),
+///         AttributeValue::S(String::from("user#1")),
+///         AttributeValue::S(String::from("user#2")),
+///     ])
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct SeqOf<I>(pub I);
+
+impl<I> Serialize for SeqOf<I>
+where
+    I: IntoIterator + Clone,
+    I::Item: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_iter(self.0.clone(), serializer)
+    }
+}
+
+/// Serializes the items of an iterator as a list, consuming the iterator
+///
+/// Like [`SeqOf`], but takes the iterator by value instead of cloning it, so it works with
+/// iterators that move out of a collection or can't be cloned. Because the iterator is used up,
+/// a `SeqOnce` can only be serialized once; serializing it again fails.
+///
+/// ```
+/// use serde_dynamo::{AttributeValue, SeqOnce};
+///
+/// let names = vec![String::from("Ford"), String::from("Arthur")];
+/// let seq = SeqOnce::new(names.into_iter().map(|name| name.to_uppercase()));
+/// let value: AttributeValue = serde_dynamo::to_attribute_value(&seq).unwrap();
+///
+/// assert_eq!(
+///     value,
+///     AttributeValue::L(vec![
+///         AttributeValue::S(String::from("FORD")),
+///         AttributeValue::S(String::from("ARTHUR")),
+///     ])
+/// );
+/// assert!(serde_dynamo::to_attribute_value::<_, AttributeValue>(&seq).is_err());
+/// ```
+pub struct SeqOnce<I>(Cell<Option<I>>);
+
+impl<I> SeqOnce<I> {
+    /// Wrap an iterator, or anything else that can be turned into one
+    pub fn new(iter: I) -> Self {
+        Self(Cell::new(Some(iter)))
+    }
+}
+
+impl<I> fmt::Debug for SeqOnce<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SeqOnce").finish_non_exhaustive()
+    }
+}
+
+impl<I> Serialize for SeqOnce<I>
+where
+    I: IntoIterator,
+    I::Item: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let iter = self
+            .0
+            .take()
+            .ok_or_else(|| S::Error::custom("SeqOnce can only be serialized once"))?;
+        serialize_iter(iter, serializer)
+    }
+}
+
+fn serialize_iter<I, S>(iter: I, serializer: S) -> Result<S::Ok, S::Error>
+where
+    I: IntoIterator,
+    I::Item: Serialize,
+    S: Serializer,
+{
+    let iter = iter.into_iter();
+    let len = match iter.size_hint() {
+        (lower, Some(upper)) if lower == upper => Some(lower),
+        _ => None,
+    };
+    let mut seq = serializer.serialize_seq(len)?;
+    for item in iter {
+        seq.serialize_element(&item)?;
+    }
+    seq.end()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SeqOf, SeqOnce};
+    use crate::{to_attribute_value, AttributeValue};
+    use serde_derive::Serialize;
+
+    #[derive(Serialize)]
+    #[serde(bound = "SeqOf<I>: serde::Serialize")]
+    struct Query<I> {
+        keys: SeqOf<I>,
+    }
+
+    #[test]
+    fn serializes_lazily_computed_lists() {
+        let query = Query {
+            keys: SeqOf((1..=3).filter(|n| n % 2 == 1).map(|n| n * 10)),
+        };
+        let value: AttributeValue = to_attribute_value(&query).unwrap();
+        let AttributeValue::M(map) = value else {
+            panic!("expected a map");
+        };
+        assert_eq!(
+            map["keys"],
+            AttributeValue::L(vec![
+                AttributeValue::N(String::from("10")),
+                AttributeValue::N(String::from("30")),
+            ])
+        );
+
+        let empty: AttributeValue = to_attribute_value(SeqOf(std::iter::empty::<u8>())).unwrap();
+        assert_eq!(empty, AttributeValue::L(vec![]));
+    }
+
+    #[test]
+    fn serializes_owned_iterators_once() {
+        let values = vec![String::from("a"), String::from("b")];
+        let once = SeqOnce::new(values.into_iter().rev());
+
+        let value: AttributeValue = to_attribute_value(&once).unwrap();
+        assert_eq!(
+            value,
+            AttributeValue::L(vec![
+                AttributeValue::S(String::from("b")),
+                AttributeValue::S(String::from("a")),
+            ])
+        );
+        let err = to_attribute_value::<_, AttributeValue>(&once).unwrap_err();
+        assert_eq!(err.to_string(), "SeqOnce can only be serialized once");
+        assert_eq!(format!("{once:?}"), "SeqOnce { .. }");
+    }
+}
@@ -0,0 +1,173 @@
+//! Condition expressions generated from serialized items
+//!
+//! Tables with a version attribute guard writes with `version = :expected`. For tables without
+//! one, [`item_unchanged`] guards a write with the item as it was read instead: the write only
+//! succeeds if every attribute still holds the value it was read with.
+//!
+//! ```
+//! use serde_dynamo::{condition, dynamo_item};
+//!
+//! let read = dynamo_item! { "id": "u-1", "name": "Arthur", "age": 42 };
+//! let condition = condition::item_unchanged(&read)?;
+//!
+//! assert_eq!(
+//!     condition.expression(),
+//!     "#__sd_age = :__sd_read_age AND #__sd_id = :__sd_read_id AND #__sd_name = :__sd_read_name"
+//! );
+//! assert_eq!(condition.names()["#__sd_id"], "id");
+//! # Ok::<(), serde_dynamo::Error>(())
+//! ```
+//!
+//! The expression, names and values can be passed to `put_item`, `update_item` or
+//! `delete_item` with [`Expression::into_parts`].
+
+use crate::error::ErrorImpl;
+use crate::expression::Expression;
+use crate::{AttributeValue, Item, Result};
+use std::collections::HashMap;
+
+/// The maximum length in bytes of a DynamoDB expression
+pub const MAX_EXPRESSION_LENGTH: usize = 4 * 1024;
+
+/// The maximum length in bytes of an expression's attribute names and values together
+pub const MAX_SUBSTITUTIONS_LENGTH: usize = 2 * 1024 * 1024;
+
+/// A condition that holds while every attribute of `item` keeps its current value
+///
+/// Attributes are compared with `=`, except `NULL` attributes, which are checked with
+/// `attribute_type`. Every attribute name and value goes through a placeholder, so names don't
+/// need escaping. The names use [`placeholder`](crate::expression::placeholder), and the values
+/// `:__sd_read_` followed by the same escaped name, so they don't clash with the placeholders of
+/// an update expression the condition is combined with. Clauses are in the order of the sorted
+/// attribute names, so the same item always gives the same expression.
+///
+/// Attributes added to the stored item since it was read are not checked, so pass the full item
+/// as read rather than a projection.
+///
+/// Fails if `item` has no attributes, or if the expression would be longer than
+/// [`MAX_EXPRESSION_LENGTH`] or its names and values longer than [`MAX_SUBSTITUTIONS_LENGTH`],
+/// which DynamoDB would reject. Each attribute takes about twenty bytes of the expression plus
+/// twice the length of its name, so items with more than a hundred or so attributes, or fewer
+/// with long names, need a version attribute instead.
+pub fn item_unchanged(item: &Item) -> Result<Expression> {
+    if item.is_empty() {
+        return Err(ErrorImpl::InvalidCondition(String::from("the item has no attributes")).into());
+    }
+
+    let mut attributes = item.iter().collect::<Vec<_>>();
+    attributes.sort_unstable_by_key(|(name, _)| *name);
+
+    let mut clauses = Vec::with_capacity(attributes.len());
+    let mut names = HashMap::with_capacity(attributes.len());
+    let mut values = HashMap::with_capacity(attributes.len());
+    let mut substitutions = 0;
+    for (name, value) in attributes {
+        let name_placeholder = crate::expression::placeholder(name);
+        let value_placeholder = crate::expression::value_placeholder("read", name);
+        let value = match value {
+            AttributeValue::Null(_) => {
+                clauses.push(format!(
+                    "attribute_type({name_placeholder}, {value_placeholder})"
+                ));
+                AttributeValue::S(String::from("NULL"))
+            }
+            value => {
+                clauses.push(format!("{name_placeholder} = {value_placeholder}"));
+                value.clone()
+            }
+        };
+        substitutions +=
+            name_placeholder.len() + name.len() + value_placeholder.len() + value.size();
+        names.insert(name_placeholder, name.clone());
+        values.insert(value_placeholder, value);
+    }
+
+    let expression = clauses.join(" AND ");
+    if expression.len() > MAX_EXPRESSION_LENGTH {
+        return Err(ErrorImpl::InvalidCondition(format!(
+            "the expression is {} bytes, more than the maximum of {MAX_EXPRESSION_LENGTH}",
+            expression.len()
+        ))
+        .into());
+    }
+    if substitutions > MAX_SUBSTITUTIONS_LENGTH {
+        return Err(ErrorImpl::InvalidCondition(format!(
+            "the attribute names and values are {substitutions} bytes, more than the maximum of \
+             {MAX_SUBSTITUTIONS_LENGTH}"
+        ))
+        .into());
+    }
+
+    Ok(Expression::from_parts(expression, names, values))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dynamo_item, ErrorCode};
+
+    #[test]
+    fn compares_every_attribute() {
+        let item = dynamo_item! {
+            "status": "active",
+            "tags": ss["a", "b"],
+            "deleted": null,
+        };
+        let condition = item_unchanged(&item).unwrap();
+        assert_eq!(
+            condition.expression(),
+            "attribute_type(#__sd_deleted, :__sd_read_deleted) \
+             AND #__sd_status = :__sd_read_status AND #__sd_tags = :__sd_read_tags"
+        );
+        assert_eq!(
+            condition.names(),
+            &HashMap::from([
+                (String::from("#__sd_deleted"), String::from("deleted")),
+                (String::from("#__sd_status"), String::from("status")),
+                (String::from("#__sd_tags"), String::from("tags")),
+            ])
+        );
+        assert_eq!(
+            condition.values()[":__sd_read_deleted"],
+            AttributeValue::S(String::from("NULL"))
+        );
+        assert_eq!(condition.values()[":__sd_read_tags"], item["tags"]);
+    }
+
+    #[test]
+    fn escapes_unusual_names() {
+        let item = dynamo_item! { "first-name": "Ann", "1st": true };
+        let condition = item_unchanged(&item).unwrap();
+        assert_eq!(
+            condition.expression(),
+            "#__sd_1st = :__sd_read_1st AND #__sd_first_2dname = :__sd_read_first_2dname"
+        );
+        assert_eq!(condition.names()["#__sd_first_2dname"], "first-name");
+        assert_eq!(
+            condition.values()[":__sd_read_1st"],
+            AttributeValue::Bool(true)
+        );
+    }
+
+    #[test]
+    fn rejects_conditions_dynamodb_would() {
+        let err = item_unchanged(&Item::new()).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::InvalidCondition);
+        assert_eq!(
+            err.to_string(),
+            "Invalid condition: the item has no attributes"
+        );
+
+        let wide = (0..300)
+            .map(|i| (format!("attribute{i}"), AttributeValue::Bool(true)))
+            .collect::<HashMap<_, _>>();
+        let err = item_unchanged(&Item::from(wide)).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::InvalidCondition);
+
+        let large = dynamo_item! { "body": "x".repeat(MAX_SUBSTITUTIONS_LENGTH) };
+        let err = item_unchanged(&large).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Invalid condition: the attribute names and values are "));
+    }
+}
@@ -0,0 +1,696 @@
+use super::{ErrorImpl, FieldOverride, Result};
+use crate::number::{self, NumberPrecision};
+use crate::{AttributeValue, Warnings};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Options that change how a [`Serializer`](super::Serializer) writes its output
+///
+/// The default configuration writes values exactly as they are given.
+///
+/// ```
+/// use serde_dynamo::{AttributeValue, SerializerConfig};
+///
+/// let config = SerializerConfig::new().reject_lossy_strings(true);
+///
+/// let lossy = String::from_utf8_lossy(b"caf\xE9");
+/// assert!(serde_dynamo::to_attribute_value_with_config::<_, AttributeValue>(lossy, config).is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SerializerConfig {
+    #[cfg(feature = "unicode-normalization")]
+    pub(crate) normalize_strings: bool,
+    pub(crate) reject_lossy_strings: bool,
+    pub(crate) ordered_sets: bool,
+    pub(crate) number_precision: NumberPrecision,
+    pub(crate) reject_floats: bool,
+    pub(crate) reject_empty_items: bool,
+    pub(crate) reject_duplicate_attributes: bool,
+    pub(crate) flattened_adjacent_content: Option<(&'static str, &'static str)>,
+    pub(crate) newtype_structs: NewtypeStructs,
+    pub(crate) empty_sets: EmptySetPolicy,
+    pub(crate) none_values: NonePolicy,
+    pub(crate) warnings: Option<Warnings>,
+    #[cfg(feature = "stats")]
+    pub(crate) stats: Option<crate::Stats>,
+    pub(crate) overrides: BTreeMap<String, FieldOverride>,
+    pub(crate) newtype_extensions: BTreeMap<&'static str, NewtypeExtension>,
+    pub(crate) limits: SerializerLimits,
+}
+
+impl SerializerConfig {
+    /// Create the default configuration
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Convert every `S` value to Unicode Normalization Form C
+    ///
+    /// The same text can be encoded with different code point sequences, such as `é` as a single
+    /// code point or as `e` followed by a combining accent. DynamoDB compares strings byte by
+    /// byte, so keys written in different forms create duplicate "identical" items. Disabled by
+    /// default.
+    #[cfg(feature = "unicode-normalization")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "unicode-normalization")))]
+    pub fn normalize_strings(mut self, normalize_strings: bool) -> Self {
+        self.normalize_strings = normalize_strings;
+        self
+    }
+
+    /// Reject `S` values that contain U+FFFD REPLACEMENT CHARACTER
+    ///
+    /// Rust strings are always valid UTF-8, but lossy conversions such as
+    /// [`String::from_utf8_lossy`] and [`OsStr::to_string_lossy`](std::ffi::OsStr::to_string_lossy)
+    /// replace invalid bytes and unpaired surrogates with U+FFFD. Storing the result silently
+    /// loses data. Disabled by default.
+    pub fn reject_lossy_strings(mut self, reject_lossy_strings: bool) -> Self {
+        self.reject_lossy_strings = reject_lossy_strings;
+        self
+    }
+
+    /// Sort the elements of string, number and binary sets
+    ///
+    /// Sets written with [`string_set`](crate::string_set), [`number_set`](crate::number_set) or
+    /// [`binary_set`](crate::binary_set) keep the iteration order of the collection, which for a
+    /// `HashSet` changes from run to run. With this option, `SS` and `BS` elements are sorted
+    /// lexicographically and `NS` elements by numeric value, so the same set always serializes
+    /// the same way. Disabled by default.
+    ///
+    /// Serde serializes `BTreeSet` and `HashSet` exactly like `Vec`, so a field still needs one
+    /// of the set codecs to be written as a set at all.
+    ///
+    /// ```
+    /// use serde_dynamo::{AttributeValue, SerializerConfig};
+    /// use serde_dynamo::number_set::NumberSet;
+    /// use std::collections::HashSet;
+    ///
+    /// let config = SerializerConfig::new().ordered_sets(true);
+    /// let set = HashSet::from([10u64, 9, 100]);
+    ///
+    /// let value: AttributeValue =
+    ///     serde_dynamo::to_attribute_value_with_config(NumberSet(set), config).unwrap();
+    /// assert_eq!(
+    ///     value,
+    ///     AttributeValue::Ns(vec![String::from("9"), String::from("10"), String::from("100")])
+    /// );
+    /// ```
+    pub fn ordered_sets(mut self, ordered_sets: bool) -> Self {
+        self.ordered_sets = ordered_sets;
+        self
+    }
+
+    /// How to write numbers with more significant digits than DynamoDB stores
+    ///
+    /// See [`NumberPrecision`]. Defaults to [`NumberPrecision::Reject`].
+    ///
+    /// ```
+    /// use serde_dynamo::{AttributeValue, NumberPrecision, SerializerConfig};
+    ///
+    /// let big = 123456789012345678901234567890123456789u128;
+    /// assert!(serde_dynamo::to_attribute_value::<_, AttributeValue>(big).is_err());
+    ///
+    /// let config = SerializerConfig::new().number_precision(NumberPrecision::Truncate);
+    /// let value: AttributeValue = serde_dynamo::to_attribute_value_with_config(big, config).unwrap();
+    /// assert_eq!(
+    ///     value,
+    ///     AttributeValue::N(String::from("123456789012345678901234567890123456780"))
+    /// );
+    /// ```
+    pub fn number_precision(mut self, number_precision: NumberPrecision) -> Self {
+        self.number_precision = number_precision;
+        self
+    }
+
+    /// Fail when an `f32` or `f64` is written
+    ///
+    /// Floats are binary fractions, so amounts such as `0.1` are stored as the nearest float's
+    /// shortest decimal form and sums of them drift. For tables that must hold exact values, this
+    /// option turns an accidental float field into an error naming its attribute, leaving
+    /// integers, decimal strings, or a decimal type as the ways to write fractional numbers.
+    /// Disabled by default.
+    ///
+    /// ```
+    /// use serde_dynamo::{Item, SerializerConfig};
+    /// # use serde_derive::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Invoice {
+    ///     id: String,
+    ///     total: f64,
+    /// }
+    ///
+    /// let invoice = Invoice {
+    ///     id: String::from("inv-1"),
+    ///     total: 10.1,
+    /// };
+    ///
+    /// let config = SerializerConfig::new().reject_floats(true);
+    /// let err = serde_dynamo::to_item_with_config::<_, Item>(&invoice, config).unwrap_err();
+    /// assert_eq!(err.attribute_path(), Some("total"));
+    /// ```
+    pub fn reject_floats(mut self, reject_floats: bool) -> Self {
+        self.reject_floats = reject_floats;
+        self
+    }
+
+    /// Make [`to_item_with_config`](crate::to_item_with_config) fail when the item has no
+    /// attributes
+    ///
+    /// DynamoDB rejects `PutItem` with an empty item, but a struct whose fields are all skipped,
+    /// such as with `skip_serializing_if = "Option::is_none"`, serializes to one without
+    /// complaint. With this option the mistake is caught when the item is built, with an error
+    /// naming the type. Disabled by default.
+    ///
+    /// ```
+    /// use serde_dynamo::{Item, SerializerConfig};
+    /// # use serde_derive::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Patch {
+    ///     #[serde(skip_serializing_if = "Option::is_none")]
+    ///     name: Option<String>,
+    /// }
+    ///
+    /// let config = SerializerConfig::new().reject_empty_items(true);
+    /// let err = serde_dynamo::to_item_with_config::<_, Item>(Patch { name: None }, config)
+    ///     .unwrap_err();
+    /// assert!(err
+    ///     .to_string()
+    ///     .ends_with("::Patch` serialized to an empty item, which DynamoDB rejects"));
+    /// ```
+    pub fn reject_empty_items(mut self, reject_empty_items: bool) -> Self {
+        self.reject_empty_items = reject_empty_items;
+        self
+    }
+
+    /// Fail when a map or struct writes the same attribute name twice
+    ///
+    /// With `#[serde(flatten)]`, a struct's own fields and the fields of a flattened struct or
+    /// map are written to the same item, and when two of them share a name the last one silently
+    /// wins. With this option, serializing fails with an error naming the attribute instead.
+    /// Disabled by default.
+    ///
+    /// ```
+    /// use serde_dynamo::{Item, SerializerConfig};
+    /// # use serde_derive::Serialize;
+    /// # use std::collections::HashMap;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Event {
+    ///     id: String,
+    ///     #[serde(flatten)]
+    ///     extra: HashMap<String, String>,
+    /// }
+    ///
+    /// let event = Event {
+    ///     id: String::from("e-1"),
+    ///     extra: HashMap::from([(String::from("id"), String::from("oops"))]),
+    /// };
+    ///
+    /// let config = SerializerConfig::new().reject_duplicate_attributes(true);
+    /// let err = serde_dynamo::to_item_with_config::<_, Item>(&event, config).unwrap_err();
+    /// assert_eq!(err.to_string(), "Attribute `id` is written more than once");
+    /// ```
+    pub fn reject_duplicate_attributes(mut self, reject_duplicate_attributes: bool) -> Self {
+        self.reject_duplicate_attributes = reject_duplicate_attributes;
+        self
+    }
+
+    /// Write the content of adjacently tagged enums as attributes beside the tag
+    ///
+    /// An enum with `#[serde(tag = "type", content = "data")]` is written as a `type` attribute
+    /// and a `data` map. Filter and condition expressions can reach into the map only with
+    /// document paths such as `data.amount`, and indexes can't use its fields at all. With this
+    /// option, the fields of the content map are written next to the tag instead. Read them back
+    /// with [`DeserializerConfig::flattened_adjacent_content`](crate::DeserializerConfig::flattened_adjacent_content)
+    /// and the same names.
+    ///
+    /// Any struct whose only fields are `tag` and a map-valued `content` is flattened, because it
+    /// serializes the same way as such an enum. Content that isn't a map, such as the value of a
+    /// newtype variant holding a string, is left in place. Serializing fails if a content field
+    /// has the same name as the tag.
+    ///
+    /// ```
+    /// use serde_dynamo::{AttributeValue, DeserializerConfig, Item, SerializerConfig};
+    /// # use serde_derive::{Deserialize, Serialize};
+    ///
+    /// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    /// #[serde(tag = "type", content = "data")]
+    /// enum Payment {
+    ///     Card { last4: String, amount: u32 },
+    ///     Cash(u32),
+    /// }
+    ///
+    /// let payment = Payment::Card { last4: String::from("4242"), amount: 500 };
+    ///
+    /// let config = SerializerConfig::new().flattened_adjacent_content("type", "data");
+    /// let item: Item = serde_dynamo::to_item_with_config(&payment, config).unwrap();
+    /// assert_eq!(item["type"], AttributeValue::S(String::from("Card")));
+    /// assert_eq!(item["amount"], AttributeValue::N(String::from("500")));
+    ///
+    /// let config = DeserializerConfig::new().flattened_adjacent_content("type", "data");
+    /// let round_tripped: Payment = serde_dynamo::from_item_with_config(item, config).unwrap();
+    /// assert_eq!(round_tripped, payment);
+    /// ```
+    pub fn flattened_adjacent_content(mut self, tag: &'static str, content: &'static str) -> Self {
+        self.flattened_adjacent_content = Some((tag, content));
+        self
+    }
+
+    /// How to write newtype structs, such as `struct UserId(String)`
+    ///
+    /// See [`NewtypeStructs`]. Defaults to [`NewtypeStructs::Transparent`]. Read them back with
+    /// [`DeserializerConfig::newtype_structs`](crate::DeserializerConfig::newtype_structs) and the
+    /// same setting.
+    pub fn newtype_structs(mut self, newtype_structs: NewtypeStructs) -> Self {
+        self.newtype_structs = newtype_structs;
+        self
+    }
+
+    /// How to write an empty or missing set through one of the `optional` set codecs
+    ///
+    /// See [`EmptySetPolicy`]. Defaults to [`EmptySetPolicy::Omit`].
+    pub fn empty_sets(mut self, empty_sets: EmptySetPolicy) -> Self {
+        self.empty_sets = empty_sets;
+        self
+    }
+
+    /// How to write `None`
+    ///
+    /// See [`NonePolicy`]. Defaults to [`NonePolicy::Null`]. Read the values back with
+    /// [`DeserializerConfig::none_values`](crate::DeserializerConfig::none_values) and the same
+    /// setting.
+    pub fn none_values(mut self, none_values: NonePolicy) -> Self {
+        self.none_values = none_values;
+        self
+    }
+
+    /// Record each string changed by [`normalize_strings`](Self::normalize_strings) in
+    /// `warnings`
+    pub fn warnings(mut self, warnings: Warnings) -> Self {
+        self.warnings = Some(warnings);
+        self
+    }
+
+    /// Measure every value written in `stats`
+    ///
+    /// See [`Stats`](crate::Stats).
+    #[cfg(feature = "stats")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "stats")))]
+    pub fn stats(mut self, stats: crate::Stats) -> Self {
+        self.stats = Some(stats);
+        self
+    }
+
+    /// Stop serializing once the value grows past `limits`
+    ///
+    /// See [`SerializerLimits`]. Without limits, a buggy `Serialize` impl that emits an endless
+    /// sequence allocates until the process runs out of memory.
+    pub fn limits(mut self, limits: SerializerLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Write the value at `path` as described by `field_override`
+    ///
+    /// `path` is an [attribute path](crate::path) starting from the top-level value, such as
+    /// `profile.tags`, `orders[1].items`, or `line\.items` for an attribute named `line.items`.
+    /// This changes how a field is written without touching its type's serde attributes, which
+    /// isn't possible for types defined in another crate. Paths that aren't present in a value,
+    /// such as a skipped `None` field, are ignored, and a path that doesn't parse fails
+    /// serialization with [`ErrorCode::InvalidPath`](crate::ErrorCode::InvalidPath).
+    ///
+    /// Overrides are applied by [`to_item_with_config`](crate::to_item_with_config) and
+    /// [`to_attribute_value_with_config`](crate::to_attribute_value_with_config) once the whole
+    /// value has been serialized. A serializer from
+    /// [`Serializer::with_config`](crate::Serializer::with_config) ignores them.
+    ///
+    /// ```
+    /// use serde_dynamo::{AttributeValue, FieldOverride, Item, SerializerConfig};
+    /// # use serde_derive::Serialize;
+    /// # use std::collections::BTreeMap;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Profile {
+    ///     tags: Vec<String>,
+    /// }
+    ///
+    /// #[derive(Serialize)]
+    /// struct Event {
+    ///     profile: Profile,
+    ///     payload: BTreeMap<String, u32>,
+    /// }
+    ///
+    /// let config = SerializerConfig::new()
+    ///     .field_override("profile.tags", FieldOverride::StringSet)
+    ///     .field_override("payload", FieldOverride::JsonString);
+    ///
+    /// let event = Event {
+    ///     profile: Profile { tags: vec![String::from("new")] },
+    ///     payload: BTreeMap::from([(String::from("retries"), 3)]),
+    /// };
+    /// let item: Item = serde_dynamo::to_item_with_config(event, config).unwrap();
+    ///
+    /// assert_eq!(item["payload"], AttributeValue::S(String::from(r#"{"retries":3}"#)));
+    /// let AttributeValue::M(profile) = &item["profile"] else { panic!() };
+    /// assert_eq!(profile["tags"], AttributeValue::Ss(vec![String::from("new")]));
+    /// ```
+    pub fn field_override(
+        mut self,
+        path: impl Into<String>,
+        field_override: FieldOverride,
+    ) -> Self {
+        self.overrides.insert(path.into(), field_override);
+        self
+    }
+
+    /// Write every newtype struct named `name` with `convert`
+    ///
+    /// `convert` receives the value the newtype struct wraps, already serialized, and returns what
+    /// to write in its place. This lets a wrapper type be special-cased the way the set wrappers
+    /// in [`string_set`](crate::string_set) are: its `Serialize` impl calls
+    /// [`serialize_newtype_struct`](serde::Serializer::serialize_newtype_struct) with `name`, and
+    /// every config that registers `name` writes it as `convert` says. Other serializers see a
+    /// plain newtype struct. The value `convert` returns is written as-is, regardless of
+    /// [`newtype_structs`](Self::newtype_structs). Errors can be built with
+    /// [`serde::ser::Error::custom`].
+    ///
+    /// Names are compared as strings, so registering the name of an ordinary newtype struct, even
+    /// one from another crate, changes how it's written. A wrapper meant only for this purpose
+    /// should use a name that no real type has. The built-in wrappers put an upper-case name
+    /// between two `U+037E GREEK QUESTION MARK`s, and following that convention, as in
+    /// `"\u{037E}GEOPOINT\u{037E}"`, keeps an extension apart from real type names. The built-in
+    /// wrappers' own names can't be overridden.
+    ///
+    /// Only serializing is affected; the type's `Deserialize` impl reads whatever `convert`
+    /// wrote.
+    ///
+    /// ```
+    /// use serde_dynamo::{AttributeValue, Item, SerializerConfig};
+    /// # use serde_derive::Serialize;
+    /// # use std::collections::HashMap;
+    ///
+    /// const GEO_POINT: &str = "\u{037E}GEOPOINT\u{037E}";
+    ///
+    /// /// A latitude and longitude, written as a map with a geohash cell next to the coordinates
+    /// struct GeoPoint(f64, f64);
+    ///
+    /// impl serde::Serialize for GeoPoint {
+    ///     fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    ///         serializer.serialize_newtype_struct(GEO_POINT, &(self.0, self.1))
+    ///     }
+    /// }
+    ///
+    /// fn geo_point(value: AttributeValue) -> serde_dynamo::Result<AttributeValue> {
+    ///     let AttributeValue::L(coordinates) = value else {
+    ///         return Err(serde::ser::Error::custom("expected a coordinate pair"));
+    ///     };
+    ///     let [AttributeValue::N(lat), AttributeValue::N(lon)] = coordinates.as_slice() else {
+    ///         return Err(serde::ser::Error::custom("expected a coordinate pair"));
+    ///     };
+    ///     let cell = format!("{:.0}:{:.0}", lat.parse::<f64>().unwrap(), lon.parse::<f64>().unwrap());
+    ///     Ok(AttributeValue::M(HashMap::from([
+    ///         (String::from("cell"), AttributeValue::S(cell)),
+    ///         (String::from("lat"), AttributeValue::N(lat.clone())),
+    ///         (String::from("lon"), AttributeValue::N(lon.clone())),
+    ///     ])))
+    /// }
+    ///
+    /// #[derive(Serialize)]
+    /// struct Store {
+    ///     location: GeoPoint,
+    /// }
+    ///
+    /// let config = SerializerConfig::new().newtype_extension(GEO_POINT, geo_point);
+    /// let store = Store { location: GeoPoint(52.5, 13.4) };
+    /// let item: Item = serde_dynamo::to_item_with_config(store, config).unwrap();
+    ///
+    /// let AttributeValue::M(location) = &item["location"] else { panic!() };
+    /// assert_eq!(location["cell"], AttributeValue::S(String::from("52:13")));
+    /// assert_eq!(location["lat"], AttributeValue::N(String::from("52.5")));
+    /// ```
+    pub fn newtype_extension(
+        mut self,
+        name: &'static str,
+        convert: fn(AttributeValue) -> Result<AttributeValue>,
+    ) -> Self {
+        self.newtype_extensions
+            .insert(name, NewtypeExtension(convert));
+        self
+    }
+
+    /// Check that a value about to be written as an `N` fits in a DynamoDB number, applying the
+    /// [`NumberPrecision`]
+    pub(crate) fn number(&self, n: String) -> Result<String> {
+        number::check(n, self.number_precision)
+    }
+
+    /// Apply the number options to a float about to be written as an `N`
+    pub(crate) fn float(&self, n: String) -> Result<String> {
+        if self.reject_floats {
+            return Err(ErrorImpl::RejectedFloat.into());
+        }
+        self.number(n)
+    }
+
+    /// Apply the string options to a value about to be written as an `S`
+    pub(crate) fn string(&self, s: &str) -> Result<String> {
+        if self.reject_lossy_strings && s.contains(char::REPLACEMENT_CHARACTER) {
+            return Err(ErrorImpl::LossyString.into());
+        }
+
+        #[cfg(feature = "unicode-normalization")]
+        if self.normalize_strings {
+            use __unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+            if is_nfc_quick(s.chars()) != IsNormalized::Yes {
+                let normalized: String = s.nfc().collect();
+                if normalized != s {
+                    if let Some(warnings) = &self.warnings {
+                        warnings.push(crate::Warning::NormalizedString {
+                            original: s.to_string(),
+                        });
+                    }
+                }
+                return Ok(normalized);
+            }
+        }
+
+        Ok(s.to_string())
+    }
+}
+
+/// A conversion registered with [`SerializerConfig::newtype_extension`]
+#[derive(Clone, Copy)]
+pub(crate) struct NewtypeExtension(pub(crate) fn(AttributeValue) -> Result<AttributeValue>);
+
+impl fmt::Debug for NewtypeExtension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("NewtypeExtension")
+    }
+}
+
+impl PartialEq for NewtypeExtension {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::fn_addr_eq(self.0, other.0)
+    }
+}
+
+impl Eq for NewtypeExtension {}
+
+/// Limits on the size of a serialized value
+///
+/// Serializing fails with an error naming the limit as soon as one is exceeded, instead of after
+/// the whole value has been built. Limits are counted across everything one
+/// [`Serializer`](super::Serializer) and its clones write, which for
+/// [`to_item_with_config`](crate::to_item_with_config) and
+/// [`to_attribute_value_with_config`](crate::to_attribute_value_with_config) is one value. No
+/// limits are set by default.
+///
+/// Set with [`SerializerConfig::limits`].
+///
+/// ```
+/// use serde_dynamo::{AttributeValue, SerializerConfig, SerializerLimits};
+///
+/// let config = SerializerConfig::new().limits(SerializerLimits::new().max_list_len(100));
+///
+/// let endless = serde_dynamo::SeqOf(std::iter::repeat(0));
+/// let err =
+///     serde_dynamo::to_attribute_value_with_config::<_, AttributeValue>(endless, config).unwrap_err();
+/// assert_eq!(err.to_string(), "Serialized value exceeds the limit of 100 list elements");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SerializerLimits {
+    pub(crate) max_attributes: Option<usize>,
+    pub(crate) max_bytes: Option<usize>,
+    pub(crate) max_list_len: Option<usize>,
+}
+
+impl SerializerLimits {
+    /// Create limits that allow everything
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The most values nested anywhere in the value, counting every map entry, struct field and
+    /// list element
+    pub fn max_attributes(mut self, max_attributes: usize) -> Self {
+        self.max_attributes = Some(max_attributes);
+        self
+    }
+
+    /// The most bytes of strings, binary and attribute names in the value
+    pub fn max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// The most elements in any one list
+    pub fn max_list_len(mut self, max_list_len: usize) -> Self {
+        self.max_list_len = Some(max_list_len);
+        self
+    }
+}
+
+/// How newtype structs, such as `struct UserId(String)`, are stored
+///
+/// By default a newtype struct is stored as the value it wraps, so a `UserId` and an `OrderId`
+/// that both wrap a string can't be told apart. When one attribute holds values of several such
+/// types, the other settings keep the wrapper, optionally with the type's name.
+///
+/// Set the same value with [`SerializerConfig::newtype_structs`] and
+/// [`DeserializerConfig::newtype_structs`](crate::DeserializerConfig::newtype_structs). It
+/// applies to every newtype struct, except the set wrappers in [`string_set`](crate::string_set),
+/// [`number_set`](crate::number_set) and [`binary_set`](crate::binary_set).
+///
+/// ```
+/// use serde_dynamo::{dynamo_value, AttributeValue, DeserializerConfig, NewtypeStructs, SerializerConfig};
+/// # use serde_derive::{Deserialize, Serialize};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct UserId(String);
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct OrderId(String);
+///
+/// let config = SerializerConfig::new().newtype_structs(NewtypeStructs::Named("type"));
+/// let value: AttributeValue =
+///     serde_dynamo::to_attribute_value_with_config(UserId(String::from("u1")), config).unwrap();
+/// assert_eq!(value, dynamo_value!({ "type": "UserId", "0": "u1" }));
+///
+/// let config = || DeserializerConfig::new().newtype_structs(NewtypeStructs::Named("type"));
+/// let id: UserId = serde_dynamo::from_attribute_value_with_config(value.clone(), config()).unwrap();
+/// assert_eq!(id, UserId(String::from("u1")));
+/// assert!(serde_dynamo::from_attribute_value_with_config::<_, OrderId>(value, config()).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum NewtypeStructs {
+    /// Store the wrapped value alone
+    #[default]
+    Transparent,
+    /// Store a map with the wrapped value under `"0"`, like a one-field tuple
+    Indexed,
+    /// Store a map with the wrapped value under `"0"` and the struct's name under this attribute
+    ///
+    /// Reading fails unless the stored name matches the target type's.
+    Named(&'static str),
+}
+
+/// How the `optional` set codecs write a set that is `None` or empty
+///
+/// DynamoDB rejects empty sets, so [`string_set::optional`](crate::string_set::optional),
+/// [`number_set::optional`](crate::number_set::optional) and
+/// [`binary_set::optional`](crate::binary_set::optional) never write one. This policy decides
+/// what they write instead. Both settings read back as `None`.
+///
+/// ```
+/// use serde_dynamo::{dynamo_item, EmptySetPolicy, Item, SerializerConfig};
+/// # use serde_derive::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Post {
+///     id: String,
+///     #[serde(with = "serde_dynamo::string_set::optional")]
+///     tags: Option<Vec<String>>,
+/// }
+///
+/// let post = Post { id: String::from("p1"), tags: Some(vec![]) };
+///
+/// let item: Item = serde_dynamo::to_item(&post).unwrap();
+/// assert_eq!(item, dynamo_item! { "id": "p1" });
+///
+/// let config = SerializerConfig::new().empty_sets(EmptySetPolicy::Null);
+/// let item: Item = serde_dynamo::to_item_with_config(&post, config).unwrap();
+/// assert_eq!(item, dynamo_item! { "id": "p1", "tags": null });
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EmptySetPolicy {
+    /// Leave the attribute out of the item
+    ///
+    /// Only struct fields and map entries can be left out. Anywhere else, such as in a list,
+    /// serializing fails.
+    #[default]
+    Omit,
+    /// Write `NULL`
+    Null,
+}
+
+/// How `None` is written and how `NULL` is read into an `Option`
+///
+/// By default, `None` is written as `NULL`, and `NULL` is read back as `None`. That loses
+/// information when the `Some` value is itself written as `NULL`: `Some(serde_json::Value::Null)`
+/// and `Some(())` come back as `None`. [`Omit`](Self::Omit) keeps the two apart, so JSON
+/// documents in `Option<serde_json::Value>` fields round-trip with the same shape.
+///
+/// In either setting, `serde_json::Value::Null` outside an `Option`, such as in a JSON object or
+/// array, is written as `NULL` and read back as `Value::Null`, and a missing struct field is
+/// read as `None`.
+///
+/// Set the same value with [`SerializerConfig::none_values`] and
+/// [`DeserializerConfig::none_values`](crate::DeserializerConfig::none_values).
+///
+/// ```
+/// use serde_dynamo::{dynamo_item, DeserializerConfig, Item, NonePolicy, SerializerConfig};
+/// # use serde_derive::{Deserialize, Serialize};
+/// use serde_json::Value;
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Event {
+///     id: String,
+///     payload: Option<Value>,
+/// }
+///
+/// let event = Event { id: String::from("e1"), payload: Some(Value::Null) };
+///
+/// let item: Item = serde_dynamo::to_item(&event).unwrap();
+/// let read: Event = serde_dynamo::from_item(item).unwrap();
+/// assert_eq!(read.payload, None);
+///
+/// let config = SerializerConfig::new().none_values(NonePolicy::Omit);
+/// let item: Item = serde_dynamo::to_item_with_config(&event, config.clone()).unwrap();
+/// assert_eq!(item, dynamo_item! { "id": "e1", "payload": null });
+///
+/// let read: Event = serde_dynamo::from_item_with_config(
+///     item,
+///     DeserializerConfig::new().none_values(NonePolicy::Omit),
+/// )
+/// .unwrap();
+/// assert_eq!(read, event);
+///
+/// let empty = Event { id: String::from("e2"), payload: None };
+/// let item: Item = serde_dynamo::to_item_with_config(&empty, config).unwrap();
+/// assert_eq!(item, dynamo_item! { "id": "e2" });
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum NonePolicy {
+    /// Write `None` as `NULL`, and read `NULL` as `None`
+    #[default]
+    Null,
+    /// Leave `None` out of the item, and read `NULL` as `Some`
+    ///
+    /// Only struct fields and map entries can be left out, so a map entry whose value is `None`
+    /// is left out too. Anywhere else, such as in a list, serializing `None` fails.
+    Omit,
+}
@@ -1,23 +1,38 @@
 use super::AttributeValue;
 use crate::{error::ErrorImpl, Error, Item, Result};
 use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
 
+pub mod condition;
+mod config;
+mod overrides;
 mod serializer;
 mod serializer_map;
 mod serializer_seq;
 mod serializer_struct;
 mod serializer_struct_variant;
 mod serializer_tuple_variant;
+#[cfg(feature = "validator")]
+mod validated;
 
 #[cfg(test)]
 mod tests;
 
-pub use serializer::Serializer;
+pub use crate::number::NumberPrecision;
+pub use config::{EmptySetPolicy, NewtypeStructs, NonePolicy, SerializerConfig, SerializerLimits};
+#[cfg(any(feature = "arrow", feature = "csv"))]
+pub(crate) use overrides::to_json;
+pub use overrides::FieldOverride;
+pub use serializer::{ConfiguredSerializer, Serializer};
 use serializer_map::SerializerMap;
 use serializer_seq::SerializerSeq;
 use serializer_struct::SerializerStruct;
 use serializer_struct_variant::SerializerStructVariant;
 use serializer_tuple_variant::SerializerTupleVariant;
+#[cfg(feature = "validator")]
+#[cfg_attr(docsrs, doc(cfg(feature = "validator")))]
+pub use validated::{to_item_validated, to_item_validated_with_config};
 
 /// Convert a `T` into an [`AttributeValue`].
 ///
@@ -84,8 +99,30 @@ where
     T: Serialize,
     AV: From<AttributeValue>,
 {
-    let serializer = Serializer;
-    let attribute_value = value.serialize(serializer)?;
+    to_attribute_value_with_config(value, SerializerConfig::default())
+}
+
+/// Convert a `T` into an [`AttributeValue`], using the given configuration.
+///
+/// See [`to_attribute_value`] and [`SerializerConfig`].
+pub fn to_attribute_value_with_config<T, AV>(value: T, config: SerializerConfig) -> Result<AV>
+where
+    T: Serialize,
+    AV: From<AttributeValue>,
+{
+    to_attribute_value_shared(value, Arc::new(config))
+}
+
+/// Convert a `T` into an [`AttributeValue`], using a configuration shared between calls
+pub(crate) fn to_attribute_value_shared<T, AV>(
+    value: T,
+    config: Arc<SerializerConfig>,
+) -> Result<AV>
+where
+    T: Serialize,
+    AV: From<AttributeValue>,
+{
+    let attribute_value = serialize_with_overrides(value, config)?;
     Ok(AV::from(attribute_value))
 }
 
@@ -126,12 +163,142 @@ where
     T: Serialize,
     I: From<Item>,
 {
-    let serializer = Serializer;
-    let attribute_value = value.serialize(serializer)?;
+    to_item_with_config(value, SerializerConfig::default())
+}
+
+/// Convert a `T` into an [`Item`], using the given configuration.
+///
+/// See [`to_item`] and [`SerializerConfig`].
+pub fn to_item_with_config<T, I>(value: T, config: SerializerConfig) -> Result<I>
+where
+    T: Serialize,
+    I: From<Item>,
+{
+    to_item_shared(value, Arc::new(config))
+}
+
+/// Convert a `T` into an [`Item`], using a configuration shared between calls
+pub(crate) fn to_item_shared<T, I>(value: T, config: Arc<SerializerConfig>) -> Result<I>
+where
+    T: Serialize,
+    I: From<Item>,
+{
+    let reject_empty_items = config.reject_empty_items;
+    let attribute_value = serialize_with_overrides(value, config)?;
     if let AttributeValue::M(item) = attribute_value {
+        if reject_empty_items && item.is_empty() {
+            let type_name = std::any::type_name::<T>().trim_start_matches('&');
+            return Err(ErrorImpl::EmptyItem(type_name).into());
+        }
         let item = Item::from(item);
         Ok(I::from(item))
     } else {
         Err(ErrorImpl::NotMaplike.into())
     }
 }
+
+/// Serialize a `T` and add its attributes to an existing [`Item`]
+///
+/// With a `key_prefix`, the attributes are added to the map in that attribute of `item`, which is
+/// created if it is missing. Without one, they are added to `item` itself. This builds one item
+/// out of several values, such as a header and a payload, without merging intermediate items.
+/// Attributes already in the target map are replaced, unless
+/// [`SerializerConfig::reject_duplicate_attributes`] is set, in which case nothing is added and
+/// the first duplicate is an error.
+///
+/// ```
+/// use serde_dynamo::{dynamo_item, Item};
+/// # use serde_derive::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Header {
+///     id: String,
+/// }
+///
+/// #[derive(Serialize)]
+/// struct Order {
+///     total: u32,
+/// }
+///
+/// let mut item = Item::new();
+/// serde_dynamo::serialize_into(&mut item, None, Header { id: String::from("o-1") })?;
+/// serde_dynamo::serialize_into(&mut item, Some("order"), Order { total: 30 })?;
+///
+/// assert_eq!(item, dynamo_item! { "id": "o-1", "order": { "total": 30 } });
+/// # Ok::<(), serde_dynamo::Error>(())
+/// ```
+///
+/// # Errors
+///
+/// Fails if `T` doesn't serialize as a map, or if the `key_prefix` attribute isn't a map.
+pub fn serialize_into<T>(item: &mut Item, key_prefix: Option<&str>, value: T) -> Result<()>
+where
+    T: Serialize,
+{
+    serialize_into_with_config(item, key_prefix, value, SerializerConfig::default())
+}
+
+/// Serialize a `T` and add its attributes to an existing [`Item`], using the given configuration.
+///
+/// See [`serialize_into`] and [`SerializerConfig`].
+pub fn serialize_into_with_config<T>(
+    item: &mut Item,
+    key_prefix: Option<&str>,
+    value: T,
+    config: SerializerConfig,
+) -> Result<()>
+where
+    T: Serialize,
+{
+    serialize_into_shared(item, key_prefix, value, Arc::new(config))
+}
+
+/// Serialize a `T` into an existing [`Item`], using a configuration shared between calls
+pub(crate) fn serialize_into_shared<T>(
+    item: &mut Item,
+    key_prefix: Option<&str>,
+    value: T,
+    config: Arc<SerializerConfig>,
+) -> Result<()>
+where
+    T: Serialize,
+{
+    let reject_duplicate_attributes = config.reject_duplicate_attributes;
+    let attributes: Item = to_item_shared(value, config)?;
+
+    let target = match key_prefix {
+        None => item.inner_mut(),
+        Some(key) => match item
+            .inner_mut()
+            .entry(key.to_string())
+            .or_insert_with(|| AttributeValue::M(HashMap::new()))
+        {
+            AttributeValue::M(m) => m,
+            other => {
+                let err: Error = ErrorImpl::PathTypeMismatch(other.type_descriptor()).into();
+                return Err(err.in_attribute(key));
+            }
+        },
+    };
+    if reject_duplicate_attributes {
+        if let Some(name) = attributes.keys().find(|name| target.contains_key(*name)) {
+            return Err(ErrorImpl::DuplicateAttribute(name.clone()).into());
+        }
+    }
+    target.extend(attributes.into_inner());
+    Ok(())
+}
+
+fn serialize_with_overrides<T>(value: T, config: Arc<SerializerConfig>) -> Result<AttributeValue>
+where
+    T: Serialize,
+{
+    let serializer = ConfiguredSerializer::with_shared_config(config.clone());
+    let mut attribute_value = value.serialize(serializer)?;
+    overrides::apply(&mut attribute_value, &config.overrides, config.ordered_sets)?;
+    #[cfg(feature = "stats")]
+    if let Some(stats) = &config.stats {
+        stats.push(crate::ItemStats::of(&attribute_value));
+    }
+    Ok(attribute_value)
+}
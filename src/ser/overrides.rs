@@ -0,0 +1,185 @@
+use super::serializer::sort_set;
+use crate::{error::ErrorImpl, path::Segment, AttributeValue, Result};
+use base64::Engine;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+/// How to write the value at an attribute path, in place of what its `Serialize` implementation
+/// produces
+///
+/// Register overrides with [`SerializerConfig::field_override`](super::SerializerConfig::field_override).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FieldOverride {
+    /// Write a list of strings as a string set (`SS`)
+    StringSet,
+    /// Write a list of numbers as a number set (`NS`)
+    NumberSet,
+    /// Write a list of binary values as a binary set (`BS`)
+    BinarySet,
+    /// Write the value as an `S` holding its JSON encoding
+    ///
+    /// Maps become objects, lists and sets become arrays, numbers are written as-is, and binary
+    /// values become base64 strings.
+    JsonString,
+}
+
+/// Apply `overrides` to a serialized value
+///
+/// Paths that don't exist in the value, such as skipped `None` fields, are ignored.
+pub(crate) fn apply(
+    value: &mut AttributeValue,
+    overrides: &BTreeMap<String, FieldOverride>,
+    ordered_sets: bool,
+) -> Result<()> {
+    for (path, field_override) in overrides {
+        if let Some(target) = lookup(value, &crate::path::parse(path)?) {
+            let taken = std::mem::replace(target, AttributeValue::Null(true));
+            *target = field_override.apply(taken, ordered_sets)?;
+        }
+    }
+    Ok(())
+}
+
+fn lookup<'a>(
+    value: &'a mut AttributeValue,
+    segments: &[Segment<'_>],
+) -> Option<&'a mut AttributeValue> {
+    segments
+        .iter()
+        .try_fold(value, |value, segment| match (value, segment) {
+            (AttributeValue::M(m), Segment::Key(key)) => m.get_mut(key.as_ref()),
+            (AttributeValue::L(l), Segment::Index(index)) => l.get_mut(*index),
+            _ => None,
+        })
+}
+
+impl FieldOverride {
+    fn apply(self, value: AttributeValue, ordered_sets: bool) -> Result<AttributeValue> {
+        let set = match self {
+            FieldOverride::JsonString => {
+                return Ok(AttributeValue::S(to_json(&value)));
+            }
+            FieldOverride::StringSet => AttributeValue::Ss(set_elements(
+                value,
+                |element| match element {
+                    AttributeValue::S(s) => Some(s),
+                    _ => None,
+                },
+                ErrorImpl::StringSetExpectedType,
+            )?),
+            FieldOverride::NumberSet => AttributeValue::Ns(set_elements(
+                value,
+                |element| match element {
+                    AttributeValue::N(n) => Some(n),
+                    _ => None,
+                },
+                ErrorImpl::NumberSetExpectedType,
+            )?),
+            FieldOverride::BinarySet => AttributeValue::Bs(set_elements(
+                value,
+                |element| match element {
+                    AttributeValue::B(b) => Some(b),
+                    _ => None,
+                },
+                ErrorImpl::BinarySetExpectedType,
+            )?),
+        };
+
+        set.debug_check_valid()?;
+        if ordered_sets {
+            Ok(sort_set(set))
+        } else {
+            Ok(set)
+        }
+    }
+}
+
+fn set_elements<T>(
+    value: AttributeValue,
+    element: impl Fn(AttributeValue) -> Option<T>,
+    error: ErrorImpl,
+) -> Result<Vec<T>> {
+    let elements = match value {
+        AttributeValue::L(l) => l,
+        _ => return Err(ErrorImpl::NotSetlike.into()),
+    };
+    elements
+        .into_iter()
+        .map(|e| element(e).ok_or_else(|| error.clone().into()))
+        .collect()
+}
+
+/// Render a value as JSON, with binary values as base64 strings and map keys in sorted order
+pub(crate) fn to_json(value: &AttributeValue) -> String {
+    let mut json = String::new();
+    write_json(&mut json, value);
+    json
+}
+
+fn write_json(out: &mut String, value: &AttributeValue) {
+    match value {
+        AttributeValue::Null(_) => out.push_str("null"),
+        AttributeValue::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        AttributeValue::N(n) => out.push_str(n),
+        AttributeValue::S(s) => write_json_string(out, s),
+        AttributeValue::B(b) => write_json_string(out, &base64_encode(b)),
+        AttributeValue::L(l) => write_json_array(out, l, write_json),
+        AttributeValue::Ss(ss) => write_json_array(out, ss, |out, s| write_json_string(out, s)),
+        AttributeValue::Ns(ns) => write_json_array(out, ns, |out, n| out.push_str(n)),
+        AttributeValue::Bs(bs) => {
+            write_json_array(out, bs, |out, b| write_json_string(out, &base64_encode(b)))
+        }
+        AttributeValue::M(m) => write_json_object(out, m),
+    }
+}
+
+fn write_json_array<T>(out: &mut String, elements: &[T], write: impl Fn(&mut String, &T)) {
+    out.push('[');
+    for (index, element) in elements.iter().enumerate() {
+        if index > 0 {
+            out.push(',');
+        }
+        write(out, element);
+    }
+    out.push(']');
+}
+
+fn write_json_object(out: &mut String, m: &HashMap<String, AttributeValue>) {
+    // Sort the keys so the same map always produces the same string
+    let mut entries: Vec<_> = m.iter().collect();
+    entries.sort_unstable_by_key(|(key, _)| *key);
+
+    out.push('{');
+    for (index, (key, value)) in entries.into_iter().enumerate() {
+        if index > 0 {
+            out.push(',');
+        }
+        write_json_string(out, key);
+        out.push(':');
+        write_json(out, value);
+    }
+    out.push('}');
+}
+
+fn write_json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c < ' ' => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn base64_encode(b: &[u8]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(b)
+}
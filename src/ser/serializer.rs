@@ -1,15 +1,115 @@
 use super::{
-    AttributeValue, Error, SerializerMap, SerializerSeq, SerializerStruct, SerializerStructVariant,
-    SerializerTupleVariant,
+    AttributeValue, Error, ErrorImpl, SerializerConfig, SerializerMap, SerializerSeq,
+    SerializerStruct, SerializerStructVariant, SerializerTupleVariant,
 };
+use super::{EmptySetPolicy, NewtypeStructs, NonePolicy};
 use serde::{ser, Serialize};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
 
 /// A structure for serializing Rust values into [`AttributeValue`]s.
+///
+/// `Serializer` uses the default configuration. [`Serializer::with_config`] gives a
+/// [`ConfiguredSerializer`] that applies a [`SerializerConfig`].
 #[derive(Copy, Clone, Debug, Default)]
 pub struct Serializer;
 
-impl ser::Serializer for Serializer {
+impl Serializer {
+    /// Create a serializer using the given configuration
+    ///
+    /// [Field overrides](SerializerConfig::field_override) and stats are applied by the
+    /// `*_with_config` functions, such as [`to_item_with_config`](crate::to_item_with_config),
+    /// after the value is serialized, so a serializer created here doesn't apply them.
+    pub fn with_config(config: SerializerConfig) -> ConfiguredSerializer {
+        ConfiguredSerializer::with_shared_config(Arc::new(config))
+    }
+}
+
+/// A structure for serializing Rust values into [`AttributeValue`]s with a [`SerializerConfig`]
+///
+/// Created with [`Serializer::with_config`]. Clones share the configuration, and count towards
+/// the same [limits](super::SerializerLimits).
+#[derive(Clone, Debug)]
+pub struct ConfiguredSerializer {
+    pub(super) config: Arc<SerializerConfig>,
+    usage: Arc<Usage>,
+}
+
+/// What a serializer and its clones have written so far, checked against the
+/// [limits](super::SerializerLimits)
+#[derive(Debug, Default)]
+struct Usage {
+    attributes: AtomicUsize,
+    bytes: AtomicUsize,
+}
+
+impl Default for ConfiguredSerializer {
+    /// A serializer with the default configuration, which is only built once
+    fn default() -> Self {
+        static DEFAULT_CONFIG: OnceLock<Arc<SerializerConfig>> = OnceLock::new();
+        Self::with_shared_config(DEFAULT_CONFIG.get_or_init(Arc::default).clone())
+    }
+}
+
+impl ConfiguredSerializer {
+    /// Create a serializer for a new value, using a configuration shared between calls
+    pub(crate) fn with_shared_config(config: Arc<SerializerConfig>) -> Self {
+        ConfiguredSerializer {
+            config,
+            usage: Arc::default(),
+        }
+    }
+
+    /// Count one more nested value, under a name of `name_len` bytes
+    pub(super) fn track_attribute(&self, name_len: usize) -> Result<(), Error> {
+        let attributes = self.usage.attributes.fetch_add(1, Ordering::Relaxed) + 1;
+        if let Some(max) = self.config.limits.max_attributes {
+            if attributes > max {
+                return Err(ErrorImpl::LimitExceeded(max, "attributes").into());
+            }
+        }
+        self.track_bytes(name_len)
+    }
+
+    /// Count one more element of a list that already has `len`
+    pub(super) fn track_element(&self, len: usize) -> Result<(), Error> {
+        if let Some(max) = self.config.limits.max_list_len {
+            if len >= max {
+                return Err(ErrorImpl::LimitExceeded(max, "list elements").into());
+            }
+        }
+        self.track_attribute(0)
+    }
+
+    /// Add `value` to `item` under `key`, failing if the key is already there and duplicates are
+    /// rejected
+    pub(super) fn insert_attribute(
+        &self,
+        item: &mut HashMap<String, AttributeValue>,
+        key: String,
+        value: AttributeValue,
+    ) -> Result<(), Error> {
+        if self.config.reject_duplicate_attributes && item.contains_key(&key) {
+            return Err(ErrorImpl::DuplicateAttribute(key).into());
+        }
+        item.insert(key, value);
+        Ok(())
+    }
+
+    /// Count `len` more bytes of strings, binary or names
+    fn track_bytes(&self, len: usize) -> Result<(), Error> {
+        let bytes = self.usage.bytes.fetch_add(len, Ordering::Relaxed) + len;
+        if let Some(max) = self.config.limits.max_bytes {
+            if bytes > max {
+                return Err(ErrorImpl::LimitExceeded(max, "bytes").into());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ser::Serializer for ConfiguredSerializer {
     type Ok = AttributeValue;
     type Error = Error;
 
@@ -45,35 +145,58 @@ impl ser::Serializer for Serializer {
     fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
         Ok(AttributeValue::N(v.to_string()))
     }
+    fn serialize_i128(self, v: i128) -> Result<Self::Ok, Self::Error> {
+        Ok(AttributeValue::N(self.config.number(v.to_string())?))
+    }
+    fn serialize_u128(self, v: u128) -> Result<Self::Ok, Self::Error> {
+        Ok(AttributeValue::N(self.config.number(v.to_string())?))
+    }
     fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
-        Ok(AttributeValue::N(v.to_string()))
+        // Display writes the shortest digits that read back as the same float, without an
+        // exponent, but keeps the sign of negative zero, which DynamoDB doesn't have
+        let n = if v == 0.0 {
+            String::from("0")
+        } else {
+            v.to_string()
+        };
+        Ok(AttributeValue::N(self.config.float(n)?))
     }
     fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
-        Ok(AttributeValue::N(v.to_string()))
+        let n = if v == 0.0 {
+            String::from("0")
+        } else {
+            v.to_string()
+        };
+        Ok(AttributeValue::N(self.config.float(n)?))
     }
     fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
-        Ok(AttributeValue::S(v.to_string()))
+        self.track_bytes(v.len())?;
+        Ok(AttributeValue::S(self.config.string(v)?))
     }
     fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
-        let serializer = SerializerSeq::new(len);
+        let serializer = SerializerSeq::new(self, len);
         Ok(serializer)
     }
     fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
-        let serializer = SerializerMap::new(len);
+        let serializer = SerializerMap::new(self, len);
         Ok(serializer)
     }
     fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
         Ok(AttributeValue::Bool(v))
     }
     fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+        self.track_bytes(v.len_utf8())?;
         Ok(AttributeValue::S(v.to_string()))
     }
     fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
-        Ok(AttributeValue::Null(true))
+        match self.config.none_values {
+            NonePolicy::Null => Ok(AttributeValue::Null(true)),
+            NonePolicy::Omit => Err(ErrorImpl::OmittedNone.into()),
+        }
     }
-    fn serialize_some<V: ?Sized>(self, value: &V) -> Result<Self::Ok, Self::Error>
+    fn serialize_some<V>(self, value: &V) -> Result<Self::Ok, Self::Error>
     where
-        V: Serialize,
+        V: ?Sized + Serialize,
     {
         value.serialize(self)
     }
@@ -81,10 +204,11 @@ impl ser::Serializer for Serializer {
         Ok(AttributeValue::Null(true))
     }
     fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        self.track_bytes(v.len())?;
         Ok(AttributeValue::B(v.to_vec()))
     }
     fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
-        let serializer = SerializerSeq::new(Some(len));
+        let serializer = SerializerSeq::new(self, Some(len));
         Ok(serializer)
     }
     fn serialize_struct(
@@ -92,7 +216,7 @@ impl ser::Serializer for Serializer {
         _name: &'static str,
         len: usize,
     ) -> Result<Self::SerializeStruct, Self::Error> {
-        let serializer = SerializerStruct::new(len);
+        let serializer = SerializerStruct::new(self, len);
         Ok(serializer)
     }
     fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
@@ -111,7 +235,7 @@ impl ser::Serializer for Serializer {
         _name: &'static str,
         len: usize,
     ) -> Result<Self::SerializeTupleStruct, Self::Error> {
-        let serializer = SerializerSeq::new(Some(len));
+        let serializer = SerializerSeq::new(self, Some(len));
         Ok(serializer)
     }
     fn serialize_tuple_variant(
@@ -121,27 +245,57 @@ impl ser::Serializer for Serializer {
         variant: &'static str,
         len: usize,
     ) -> Result<Self::SerializeTupleVariant, Self::Error> {
-        let serializer = SerializerTupleVariant::new(variant, len);
+        let serializer = SerializerTupleVariant::new(self, variant, len);
         Ok(serializer)
     }
-    fn serialize_newtype_struct<V: ?Sized>(
+    fn serialize_newtype_struct<V>(
         self,
         name: &'static str,
         value: &V,
     ) -> Result<Self::Ok, Self::Error>
     where
-        V: Serialize,
+        V: ?Sized + Serialize,
     {
+        let ordered_sets = self.config.ordered_sets;
+        let newtype_structs = self.config.newtype_structs;
+        let empty_sets = self.config.empty_sets;
+        let extension = self.config.newtype_extensions.get(name).copied();
         let av = value.serialize(self)?;
 
-        if crate::string_set::should_serialize_as_string_set(name) {
-            crate::string_set::convert_to_set(av)
+        if is_optional_set(name) && matches!(&av, AttributeValue::L(l) if l.is_empty()) {
+            return match empty_sets {
+                EmptySetPolicy::Omit => Err(ErrorImpl::OmittedEmptySet.into()),
+                EmptySetPolicy::Null => Ok(AttributeValue::Null(true)),
+            };
+        }
+
+        let set = if crate::string_set::should_serialize_as_string_set(name) {
+            crate::string_set::convert_to_set(av)?
         } else if crate::number_set::should_serialize_as_numbers_set(name) {
-            crate::number_set::convert_to_set(av)
+            crate::number_set::convert_to_set(av)?
         } else if crate::binary_set::should_serialize_as_binary_set(name) {
-            crate::binary_set::convert_to_set(av)
+            crate::binary_set::convert_to_set(av)?
+        } else if let Some(extension) = extension {
+            let av = (extension.0)(av)?;
+            av.debug_check_valid()?;
+            return Ok(av);
         } else {
-            Ok(av)
+            return Ok(match newtype_structs {
+                NewtypeStructs::Transparent => av,
+                NewtypeStructs::Indexed => {
+                    AttributeValue::M(HashMap::from([(String::from("0"), av)]))
+                }
+                NewtypeStructs::Named(tag) => AttributeValue::M(HashMap::from([
+                    (tag.to_string(), AttributeValue::S(name.to_string())),
+                    (String::from("0"), av),
+                ])),
+            });
+        };
+
+        if ordered_sets {
+            Ok(sort_set(set))
+        } else {
+            Ok(set)
         }
     }
     fn serialize_struct_variant(
@@ -151,10 +305,10 @@ impl ser::Serializer for Serializer {
         variant: &'static str,
         len: usize,
     ) -> Result<Self::SerializeStructVariant, Self::Error> {
-        let serializer = SerializerStructVariant::new(variant, len);
+        let serializer = SerializerStructVariant::new(self, variant, len);
         Ok(serializer)
     }
-    fn serialize_newtype_variant<V: ?Sized>(
+    fn serialize_newtype_variant<V>(
         self,
         _name: &'static str,
         _variant_index: u32,
@@ -162,12 +316,199 @@ impl ser::Serializer for Serializer {
         value: &V,
     ) -> Result<Self::Ok, Self::Error>
     where
-        V: Serialize,
+        V: ?Sized + Serialize,
     {
-        let serializer = Serializer;
-        let av = value.serialize(serializer)?;
+        let av = value.serialize(self)?;
         let mut item = HashMap::new();
         item.insert(variant.to_string(), av);
         Ok(AttributeValue::M(item))
     }
 }
+
+/// Serializes with the default configuration, as [`ConfiguredSerializer`] does
+impl ser::Serializer for Serializer {
+    type Ok = AttributeValue;
+    type Error = Error;
+
+    type SerializeSeq = SerializerSeq;
+    type SerializeTuple = SerializerSeq;
+    type SerializeTupleStruct = SerializerSeq;
+    type SerializeTupleVariant = SerializerTupleVariant;
+    type SerializeMap = SerializerMap;
+    type SerializeStruct = SerializerStruct;
+    type SerializeStructVariant = SerializerStructVariant;
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+        ConfiguredSerializer::default().serialize_i8(v)
+    }
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+        ConfiguredSerializer::default().serialize_u8(v)
+    }
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+        ConfiguredSerializer::default().serialize_i16(v)
+    }
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+        ConfiguredSerializer::default().serialize_i32(v)
+    }
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+        ConfiguredSerializer::default().serialize_i64(v)
+    }
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+        ConfiguredSerializer::default().serialize_u16(v)
+    }
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+        ConfiguredSerializer::default().serialize_u32(v)
+    }
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+        ConfiguredSerializer::default().serialize_u64(v)
+    }
+    fn serialize_i128(self, v: i128) -> Result<Self::Ok, Self::Error> {
+        ConfiguredSerializer::default().serialize_i128(v)
+    }
+    fn serialize_u128(self, v: u128) -> Result<Self::Ok, Self::Error> {
+        ConfiguredSerializer::default().serialize_u128(v)
+    }
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+        ConfiguredSerializer::default().serialize_f32(v)
+    }
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+        ConfiguredSerializer::default().serialize_f64(v)
+    }
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+        ConfiguredSerializer::default().serialize_str(v)
+    }
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        ConfiguredSerializer::default().serialize_seq(len)
+    }
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        ConfiguredSerializer::default().serialize_map(len)
+    }
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+        ConfiguredSerializer::default().serialize_bool(v)
+    }
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+        ConfiguredSerializer::default().serialize_char(v)
+    }
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        ConfiguredSerializer::default().serialize_none()
+    }
+    fn serialize_some<V>(self, value: &V) -> Result<Self::Ok, Self::Error>
+    where
+        V: ?Sized + Serialize,
+    {
+        ConfiguredSerializer::default().serialize_some(value)
+    }
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        ConfiguredSerializer::default().serialize_unit()
+    }
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        ConfiguredSerializer::default().serialize_bytes(v)
+    }
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        ConfiguredSerializer::default().serialize_tuple(len)
+    }
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        ConfiguredSerializer::default().serialize_struct(name, len)
+    }
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
+        ConfiguredSerializer::default().serialize_unit_struct(name)
+    }
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        ConfiguredSerializer::default().serialize_unit_variant(name, variant_index, variant)
+    }
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        ConfiguredSerializer::default().serialize_tuple_struct(name, len)
+    }
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        ConfiguredSerializer::default().serialize_tuple_variant(name, variant_index, variant, len)
+    }
+    fn serialize_newtype_struct<V>(
+        self,
+        name: &'static str,
+        value: &V,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        V: ?Sized + Serialize,
+    {
+        ConfiguredSerializer::default().serialize_newtype_struct(name, value)
+    }
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        ConfiguredSerializer::default().serialize_struct_variant(name, variant_index, variant, len)
+    }
+    fn serialize_newtype_variant<V>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &V,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        V: ?Sized + Serialize,
+    {
+        ConfiguredSerializer::default().serialize_newtype_variant(
+            name,
+            variant_index,
+            variant,
+            value,
+        )
+    }
+}
+
+/// Whether `name` marks a set written by one of the `optional` set codecs
+fn is_optional_set(name: &str) -> bool {
+    crate::string_set::optional::should_serialize_as_optional_set(name)
+        || crate::number_set::optional::should_serialize_as_optional_set(name)
+        || crate::binary_set::optional::should_serialize_as_optional_set(name)
+}
+
+/// Sort the elements of a set attribute value, comparing numbers by value
+pub(super) fn sort_set(set: AttributeValue) -> AttributeValue {
+    match set {
+        AttributeValue::Ss(mut ss) => {
+            ss.sort_unstable();
+            AttributeValue::Ss(ss)
+        }
+        AttributeValue::Ns(mut ns) => {
+            ns.sort_by(|a, b| {
+                let by_value = match (a.parse::<f64>(), b.parse::<f64>()) {
+                    (Ok(a), Ok(b)) => a.partial_cmp(&b),
+                    _ => None,
+                };
+                by_value
+                    .filter(|ordering| ordering.is_ne())
+                    .unwrap_or_else(|| a.cmp(b))
+            });
+            AttributeValue::Ns(ns)
+        }
+        AttributeValue::Bs(mut bs) => {
+            bs.sort_unstable();
+            AttributeValue::Bs(bs)
+        }
+        other => other,
+    }
+}
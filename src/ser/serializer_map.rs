@@ -1,33 +1,48 @@
-use super::{AttributeValue, Error, ErrorImpl, Result, Serializer};
-use serde::{ser, serde_if_integer128, Serialize};
+use super::{AttributeValue, ConfiguredSerializer, Error, ErrorImpl, Result};
+use serde::{ser, Serialize};
 use std::collections::HashMap;
 
 pub struct SerializerMap {
+    serializer: ConfiguredSerializer,
     item: HashMap<String, AttributeValue>,
     next_key: Option<String>,
 }
 
 impl SerializerMap {
-    pub fn new(len: Option<usize>) -> Self {
+    pub fn new(serializer: ConfiguredSerializer, len: Option<usize>) -> Self {
         let item = if let Some(len) = len {
             HashMap::with_capacity(len)
         } else {
             HashMap::new()
         };
         SerializerMap {
+            serializer,
             item,
             next_key: None,
         }
     }
+
+    /// Serialize `value` under `key`, leaving out empty optional sets and omitted `None`s
+    fn insert<V>(&mut self, key: String, value: &V) -> Result<()>
+    where
+        V: ?Sized + Serialize,
+    {
+        self.serializer.track_attribute(key.len())?;
+        match value.serialize(self.serializer.clone()) {
+            Ok(value) => self.serializer.insert_attribute(&mut self.item, key, value),
+            Err(err) if err.is_omitted() => Ok(()),
+            Err(err) => Err(err.float_in_attribute(&key)),
+        }
+    }
 }
 
 impl ser::SerializeMap for SerializerMap {
     type Ok = AttributeValue;
     type Error = Error;
 
-    fn serialize_key<K: ?Sized>(&mut self, key: &K) -> Result<(), Self::Error>
+    fn serialize_key<K>(&mut self, key: &K) -> Result<(), Self::Error>
     where
-        K: Serialize,
+        K: ?Sized + Serialize,
     {
         if self.next_key.is_some() {
             return Err(ErrorImpl::SerializeMapKeyCalledTwice.into());
@@ -38,33 +53,25 @@ impl ser::SerializeMap for SerializerMap {
         Ok(())
     }
 
-    fn serialize_value<V: ?Sized>(&mut self, value: &V) -> Result<(), Self::Error>
+    fn serialize_value<V>(&mut self, value: &V) -> Result<(), Self::Error>
     where
-        V: Serialize,
+        V: ?Sized + Serialize,
     {
         let key = self
             .next_key
             .take()
             .ok_or_else(|| ErrorImpl::SerializeMapValueBeforeKey.into())?;
 
-        let value = value.serialize(Serializer)?;
-        self.item.insert(key, value);
-        Ok(())
+        self.insert(key, value)
     }
 
-    fn serialize_entry<K: ?Sized, V: ?Sized>(
-        &mut self,
-        key: &K,
-        value: &V,
-    ) -> Result<(), Self::Error>
+    fn serialize_entry<K, V>(&mut self, key: &K, value: &V) -> Result<(), Self::Error>
     where
-        K: Serialize,
-        V: Serialize,
+        K: ?Sized + Serialize,
+        V: ?Sized + Serialize,
     {
         let key = key.serialize(MapKeySerializer)?;
-        let value = value.serialize(Serializer)?;
-        self.item.insert(key, value);
-        Ok(())
+        self.insert(key, value)
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
@@ -101,10 +108,8 @@ impl ser::Serializer for MapKeySerializer {
     fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
         Ok(v.to_string())
     }
-    serde_if_integer128! {
-        fn serialize_i128(self, v: i128) -> Result<Self::Ok, Self::Error> {
-            Ok(v.to_string())
-        }
+    fn serialize_i128(self, v: i128) -> Result<Self::Ok, Self::Error> {
+        Ok(v.to_string())
     }
     fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
         Ok(v.to_string())
@@ -115,10 +120,8 @@ impl ser::Serializer for MapKeySerializer {
     fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
         Ok(v.to_string())
     }
-    serde_if_integer128! {
-        fn serialize_u128(self, v: u128) -> Result<Self::Ok, Self::Error> {
-            Ok(v.to_string())
-        }
+    fn serialize_u128(self, v: u128) -> Result<Self::Ok, Self::Error> {
+        Ok(v.to_string())
     }
     fn serialize_f32(self, _v: f32) -> Result<Self::Ok, Self::Error> {
         Err(ErrorImpl::KeyMustBeAString.into())
@@ -147,9 +150,9 @@ impl ser::Serializer for MapKeySerializer {
     fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
         Err(ErrorImpl::KeyMustBeAString.into())
     }
-    fn serialize_some<T: ?Sized>(self, _value: &T) -> Result<Self::Ok, Self::Error>
+    fn serialize_some<T>(self, _value: &T) -> Result<Self::Ok, Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         Err(ErrorImpl::KeyMustBeAString.into())
     }
@@ -196,13 +199,13 @@ impl ser::Serializer for MapKeySerializer {
     ) -> Result<Self::SerializeTupleVariant, Self::Error> {
         Err(ErrorImpl::KeyMustBeAString.into())
     }
-    fn serialize_newtype_struct<T: ?Sized>(
+    fn serialize_newtype_struct<T>(
         self,
         _name: &'static str,
         value: &T,
     ) -> Result<Self::Ok, Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         value.serialize(self)
     }
@@ -215,7 +218,7 @@ impl ser::Serializer for MapKeySerializer {
     ) -> Result<Self::SerializeStructVariant, Self::Error> {
         Err(ErrorImpl::KeyMustBeAString.into())
     }
-    fn serialize_newtype_variant<T: ?Sized>(
+    fn serialize_newtype_variant<T>(
         self,
         _name: &'static str,
         _variant_index: u32,
@@ -223,7 +226,7 @@ impl ser::Serializer for MapKeySerializer {
         _value: &T,
     ) -> Result<Self::Ok, Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         Err(ErrorImpl::KeyMustBeAString.into())
     }
@@ -233,9 +236,9 @@ impl ser::SerializeSeq for MapKeySerializer {
     type Ok = String;
     type Error = Error;
 
-    fn serialize_element<T: ?Sized>(&mut self, _value: &T) -> Result<(), Self::Error>
+    fn serialize_element<T>(&mut self, _value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         unreachable!()
     }
@@ -248,9 +251,9 @@ impl ser::SerializeTuple for MapKeySerializer {
     type Ok = String;
     type Error = Error;
 
-    fn serialize_element<T: ?Sized>(&mut self, _value: &T) -> Result<(), Self::Error>
+    fn serialize_element<T>(&mut self, _value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         unreachable!()
     }
@@ -263,9 +266,9 @@ impl ser::SerializeTupleStruct for MapKeySerializer {
     type Ok = String;
     type Error = Error;
 
-    fn serialize_field<T: ?Sized>(&mut self, _value: &T) -> Result<(), Self::Error>
+    fn serialize_field<T>(&mut self, _value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         unreachable!()
     }
@@ -278,9 +281,9 @@ impl ser::SerializeTupleVariant for MapKeySerializer {
     type Ok = String;
     type Error = Error;
 
-    fn serialize_field<T: ?Sized>(&mut self, _value: &T) -> Result<(), Self::Error>
+    fn serialize_field<T>(&mut self, _value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         unreachable!()
     }
@@ -294,13 +297,9 @@ impl ser::SerializeStructVariant for MapKeySerializer {
     type Ok = String;
     type Error = Error;
 
-    fn serialize_field<T: ?Sized>(
-        &mut self,
-        _key: &'static str,
-        _value: &T,
-    ) -> Result<(), Self::Error>
+    fn serialize_field<T>(&mut self, _key: &'static str, _value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         unreachable!()
     }
@@ -314,28 +313,24 @@ impl ser::SerializeMap for MapKeySerializer {
     type Ok = String;
     type Error = Error;
 
-    fn serialize_key<T: ?Sized>(&mut self, _key: &T) -> Result<(), Self::Error>
+    fn serialize_key<T>(&mut self, _key: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         unreachable!()
     }
 
-    fn serialize_value<T: ?Sized>(&mut self, _value: &T) -> Result<(), Self::Error>
+    fn serialize_value<T>(&mut self, _value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         unreachable!()
     }
 
-    fn serialize_entry<K: ?Sized, V: ?Sized>(
-        &mut self,
-        _key: &K,
-        _value: &V,
-    ) -> Result<(), Self::Error>
+    fn serialize_entry<K, V>(&mut self, _key: &K, _value: &V) -> Result<(), Self::Error>
     where
-        K: Serialize,
-        V: Serialize,
+        K: ?Sized + Serialize,
+        V: ?Sized + Serialize,
     {
         unreachable!()
     }
@@ -349,13 +344,9 @@ impl ser::SerializeStruct for MapKeySerializer {
     type Ok = String;
     type Error = Error;
 
-    fn serialize_field<T: ?Sized>(
-        &mut self,
-        _key: &'static str,
-        _value: &T,
-    ) -> Result<(), Self::Error>
+    fn serialize_field<T>(&mut self, _key: &'static str, _value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         unreachable!()
     }
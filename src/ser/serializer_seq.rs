@@ -1,19 +1,36 @@
-use super::{AttributeValue, Error, Result, Serializer};
+use super::{AttributeValue, ConfiguredSerializer, Error, Result};
 use serde::{ser, Serialize};
 
 pub struct SerializerSeq {
+    serializer: ConfiguredSerializer,
     vec: Vec<AttributeValue>,
 }
 
 impl SerializerSeq {
-    pub fn new(len: Option<usize>) -> Self {
+    pub fn new(serializer: ConfiguredSerializer, len: Option<usize>) -> Self {
+        // A length hint past the limit fails at the first element past it, so don't allocate
+        // for more than that
+        let max_list_len = serializer.config.limits.max_list_len;
         let vec = if let Some(len) = len {
-            Vec::with_capacity(len)
+            Vec::with_capacity(max_list_len.map_or(len, |max| len.min(max)))
         } else {
             Vec::new()
         };
 
-        SerializerSeq { vec }
+        SerializerSeq { serializer, vec }
+    }
+
+    fn push<E>(&mut self, value: &E) -> Result<()>
+    where
+        E: ?Sized + Serialize,
+    {
+        let index = self.vec.len();
+        self.serializer.track_element(index)?;
+        let value = value
+            .serialize(self.serializer.clone())
+            .map_err(|err| err.float_in_element(index))?;
+        self.vec.push(value);
+        Ok(())
     }
 }
 
@@ -26,10 +43,7 @@ impl ser::SerializeSeq for SerializerSeq {
     where
         E: ?Sized + Serialize,
     {
-        let serializer = Serializer;
-        let value = value.serialize(serializer)?;
-        self.vec.push(value);
-        Ok(())
+        self.push(value)
     }
 
     // Close the sequence.
@@ -46,10 +60,7 @@ impl ser::SerializeTupleStruct for SerializerSeq {
     where
         F: ?Sized + Serialize,
     {
-        let serializer = Serializer;
-        let value = value.serialize(serializer)?;
-        self.vec.push(value);
-        Ok(())
+        self.push(value)
     }
 
     // Close the sequence.
@@ -66,10 +77,7 @@ impl ser::SerializeTuple for SerializerSeq {
     where
         E: ?Sized + Serialize,
     {
-        let serializer = Serializer;
-        let value = value.serialize(serializer)?;
-        self.vec.push(value);
-        Ok(())
+        self.push(value)
     }
 
     // Close the sequence.
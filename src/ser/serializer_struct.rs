@@ -1,14 +1,16 @@
-use super::{AttributeValue, Error, Result, Serializer};
+use super::{AttributeValue, ConfiguredSerializer, Error, ErrorImpl, Result};
 use serde::{ser, Serialize};
 use std::collections::HashMap;
 
 pub struct SerializerStruct {
+    serializer: ConfiguredSerializer,
     item: HashMap<String, AttributeValue>,
 }
 
 impl SerializerStruct {
-    pub fn new(len: usize) -> Self {
+    pub fn new(serializer: ConfiguredSerializer, len: usize) -> Self {
         SerializerStruct {
+            serializer,
             item: HashMap::with_capacity(len),
         }
     }
@@ -18,21 +20,53 @@ impl ser::SerializeStruct for SerializerStruct {
     type Ok = AttributeValue;
     type Error = Error;
 
-    fn serialize_field<F: ?Sized>(
-        &mut self,
-        key: &'static str,
-        value: &F,
-    ) -> Result<(), Self::Error>
+    fn serialize_field<F>(&mut self, key: &'static str, value: &F) -> Result<(), Self::Error>
     where
-        F: Serialize,
+        F: ?Sized + Serialize,
     {
-        let serializer = Serializer;
-        let value = value.serialize(serializer)?;
-        self.item.insert(key.to_string(), value);
-        Ok(())
+        self.serializer.track_attribute(key.len())?;
+        match value.serialize(self.serializer.clone()) {
+            Ok(value) => self
+                .serializer
+                .insert_attribute(&mut self.item, key.to_string(), value),
+            Err(err) if err.is_omitted() => Ok(()),
+            Err(err) => Err(err.float_in_attribute(key)),
+        }
     }
 
-    fn end(self) -> Result<Self::Ok, Self::Error> {
+    fn end(mut self) -> Result<Self::Ok, Self::Error> {
+        if let Some((tag, content)) = self.serializer.config.flattened_adjacent_content {
+            flatten_adjacent_content(&mut self.item, tag, content)?;
+        }
         Ok(AttributeValue::M(self.item))
     }
 }
+
+/// Move the fields of an adjacently tagged enum's content map up beside its tag
+///
+/// Serde serializes an adjacently tagged enum as a struct with only the tag and content fields.
+/// Content that isn't a map, or is an empty map, stays where it is, so that reading it back
+/// doesn't depend on the option.
+fn flatten_adjacent_content(
+    item: &mut HashMap<String, AttributeValue>,
+    tag: &str,
+    content: &str,
+) -> Result<()> {
+    let adjacently_tagged =
+        item.contains_key(tag) && item.keys().all(|key| key == tag || key == content);
+    let nonempty_map = matches!(item.get(content), Some(AttributeValue::M(m)) if !m.is_empty());
+    if !adjacently_tagged || !nonempty_map {
+        return Ok(());
+    }
+
+    let Some(AttributeValue::M(fields)) = item.remove(content) else {
+        unreachable!("content was checked to be a map")
+    };
+    for (key, value) in fields {
+        if key == tag {
+            return Err(ErrorImpl::FlattenedContentCollision(key).into());
+        }
+        item.insert(key, value);
+    }
+    Ok(())
+}
@@ -1,15 +1,17 @@
-use super::{AttributeValue, Error, Result, Serializer};
+use super::{AttributeValue, ConfiguredSerializer, Error, Result};
 use serde::{ser, Serialize};
 use std::collections::HashMap;
 
 pub struct SerializerStructVariant {
+    serializer: ConfiguredSerializer,
     key: &'static str,
     item: HashMap<String, AttributeValue>,
 }
 
 impl SerializerStructVariant {
-    pub fn new(key: &'static str, len: usize) -> Self {
+    pub fn new(serializer: ConfiguredSerializer, key: &'static str, len: usize) -> Self {
         Self {
+            serializer,
             key,
             item: HashMap::with_capacity(len),
         }
@@ -20,18 +22,18 @@ impl ser::SerializeStructVariant for SerializerStructVariant {
     type Ok = AttributeValue;
     type Error = Error;
 
-    fn serialize_field<F: ?Sized>(
-        &mut self,
-        key: &'static str,
-        value: &F,
-    ) -> Result<(), Self::Error>
+    fn serialize_field<F>(&mut self, key: &'static str, value: &F) -> Result<(), Self::Error>
     where
-        F: Serialize,
+        F: ?Sized + Serialize,
     {
-        let serializer = Serializer;
-        let value = value.serialize(serializer)?;
-        self.item.insert(key.to_string(), value);
-        Ok(())
+        self.serializer.track_attribute(key.len())?;
+        match value.serialize(self.serializer.clone()) {
+            Ok(value) => self
+                .serializer
+                .insert_attribute(&mut self.item, key.to_string(), value),
+            Err(err) if err.is_omitted() => Ok(()),
+            Err(err) => Err(err.float_in_attribute(key).float_in_attribute(self.key)),
+        }
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
@@ -1,15 +1,17 @@
-use super::{AttributeValue, Error, Result, Serializer};
+use super::{AttributeValue, ConfiguredSerializer, Error, Result};
 use serde::{ser, Serialize};
 use std::collections::HashMap;
 
 pub struct SerializerTupleVariant {
+    serializer: ConfiguredSerializer,
     key: &'static str,
     vec: Vec<AttributeValue>,
 }
 
 impl SerializerTupleVariant {
-    pub fn new(key: &'static str, len: usize) -> Self {
+    pub fn new(serializer: ConfiguredSerializer, key: &'static str, len: usize) -> Self {
         Self {
+            serializer,
             key,
             vec: Vec::with_capacity(len),
         }
@@ -20,12 +22,15 @@ impl ser::SerializeTupleVariant for SerializerTupleVariant {
     type Ok = AttributeValue;
     type Error = Error;
 
-    fn serialize_field<F: ?Sized>(&mut self, value: &F) -> Result<(), Self::Error>
+    fn serialize_field<F>(&mut self, value: &F) -> Result<(), Self::Error>
     where
-        F: Serialize,
+        F: ?Sized + Serialize,
     {
-        let serializer = Serializer;
-        let value = value.serialize(serializer)?;
+        let index = self.vec.len();
+        self.serializer.track_element(index)?;
+        let value = value
+            .serialize(self.serializer.clone())
+            .map_err(|err| err.float_in_element(index).float_in_attribute(self.key))?;
         self.vec.push(value);
         Ok(())
     }
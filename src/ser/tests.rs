@@ -78,6 +78,56 @@ fn serialize_num() {
     serialize_num!(f64, 1.1);
 }
 
+#[test]
+fn serialize_floats_like_boto3() {
+    // The digits of a number, without its sign, decimal point, exponent, or padding zeros
+    fn digits(n: &str) -> String {
+        let mantissa = n.split(['e', 'E']).next().unwrap();
+        let digits: String = mantissa.chars().filter(char::is_ascii_digit).collect();
+        digits.trim_matches('0').to_string()
+    }
+
+    // boto3 1.43 `TypeSerializer().serialize(Decimal(repr(value)))` for each value. boto3 writes
+    // the digits Python's `repr` picks, in scientific notation for large and small values, and
+    // DynamoDB returns the same numbers as plain decimals, so the digits and values must match.
+    let boto3 = [
+        (0.0, "0.0"),
+        (-0.0, "-0.0"),
+        (1.0, "1.0"),
+        (-1.5, "-1.5"),
+        (0.1, "0.1"),
+        (1.1, "1.1"),
+        (0.1 + 0.2, "0.30000000000000004"),
+        (100.0, "100.0"),
+        (123.456, "123.456"),
+        (1e-5, "0.00001"),
+        (1e-7, "1E-7"),
+        (-2.5e-10, "-2.5E-10"),
+        (1e16, "1E+16"),
+        (1e22, "1E+22"),
+        (1.2345678901234568e20, "1.2345678901234568E+20"),
+        (std::f64::consts::PI, "3.141592653589793"),
+        (9007199254740992.0, "9007199254740992.0"),
+    ];
+    for (value, expected) in boto3 {
+        let AttributeValue::N(n) = to_attribute_value(value).unwrap() else {
+            panic!("{value:?} wasn't written as a number");
+        };
+        assert!(!n.contains(['e', 'E']), "{n}");
+        assert_eq!(digits(&n), digits(expected), "{value:?}");
+        assert_eq!(n.parse::<f64>().unwrap(), value, "{value:?}");
+    }
+
+    let result: AttributeValue = to_attribute_value(-0.0f64).unwrap();
+    assert_eq!(result, AttributeValue::N(String::from("0")));
+    let result: AttributeValue = to_attribute_value(1e-7f64).unwrap();
+    assert_eq!(result, AttributeValue::N(String::from("0.0000001")));
+    let result: AttributeValue = to_attribute_value(0.1f32).unwrap();
+    assert_eq!(result, AttributeValue::N(String::from("0.1")));
+    let result: AttributeValue = to_attribute_value(-0.0f32).unwrap();
+    assert_eq!(result, AttributeValue::N(String::from("0")));
+}
+
 #[test]
 fn serialize_bool() {
     let result = to_attribute_value::<_, AttributeValue>(true).unwrap();
@@ -527,3 +577,856 @@ fn issue_27() {
         data: Data::Boolean(true),
     });
 }
+
+#[test]
+fn serialize_lossy_strings() {
+    use crate::{to_attribute_value_with_config, SerializerConfig};
+
+    let lossy = String::from_utf8_lossy(b"caf\xE9").into_owned();
+    let config = SerializerConfig::new().reject_lossy_strings(true);
+
+    let result: AttributeValue = to_attribute_value(&lossy).unwrap();
+    assert_eq!(result, AttributeValue::S(lossy.clone()));
+
+    let err = to_attribute_value_with_config::<_, AttributeValue>(vec![lossy], config.clone())
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "String contains U+FFFD replacement characters from a lossy conversion"
+    );
+
+    let result: AttributeValue = to_attribute_value_with_config("café", config).unwrap();
+    assert_eq!(result, AttributeValue::S(String::from("café")));
+}
+
+#[test]
+fn serializer_instances() {
+    use crate::{ConfiguredSerializer, Serializer, SerializerConfig};
+    use serde::Serialize;
+
+    let serializer = Serializer;
+    let copy = serializer;
+    assert_eq!(
+        "x".serialize(serializer).unwrap(),
+        AttributeValue::S(String::from("x"))
+    );
+    assert_eq!(
+        Some(1).serialize(copy).unwrap(),
+        AttributeValue::N(String::from("1"))
+    );
+
+    let lossy = String::from_utf8_lossy(b"caf\xE9").into_owned();
+    let configured = Serializer::with_config(SerializerConfig::new().reject_lossy_strings(true));
+    assert!(lossy.serialize(configured.clone()).is_err());
+    assert_eq!(
+        "café".serialize(configured).unwrap(),
+        AttributeValue::S(String::from("café"))
+    );
+
+    // The unit serializer reuses one default configuration rather than building one per value
+    let (a, b) = (
+        ConfiguredSerializer::default(),
+        ConfiguredSerializer::default(),
+    );
+    assert!(std::sync::Arc::ptr_eq(&a.config, &b.config));
+}
+
+#[test]
+fn serialize_rejected_floats() {
+    use crate::{to_attribute_value_with_config, ErrorCode, SerializerConfig};
+
+    #[derive(Serialize)]
+    struct Line {
+        sku: String,
+        prices: Vec<f64>,
+    }
+
+    #[derive(Serialize)]
+    enum Discount {
+        Percent { rate: f32 },
+        Fixed(u32, f64),
+    }
+
+    #[derive(Serialize)]
+    struct Order {
+        id: u32,
+        lines: Vec<Line>,
+        totals: HashMap<String, f64>,
+        discount: Discount,
+    }
+
+    let config = SerializerConfig::new().reject_floats(true);
+    let path = |order: &Order| {
+        to_attribute_value_with_config::<_, AttributeValue>(order, config.clone())
+            .unwrap_err()
+            .attribute_path()
+            .map(String::from)
+    };
+
+    let mut order = Order {
+        id: 1,
+        lines: vec![
+            Line {
+                sku: String::from("a"),
+                prices: vec![],
+            },
+            Line {
+                sku: String::from("b"),
+                prices: vec![9.99],
+            },
+        ],
+        totals: HashMap::new(),
+        discount: Discount::Fixed(1, 0.5),
+    };
+    let err =
+        to_attribute_value_with_config::<_, AttributeValue>(&order, config.clone()).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::RejectedFloat);
+    assert_eq!(
+        err.to_string(),
+        "Attribute `lines[1].prices[0]`: Found a float where floats are rejected; write exact \
+         numbers as integers or decimal strings"
+    );
+
+    order.lines.pop();
+    order.totals.insert(String::from("net.usd"), 1.0);
+    assert_eq!(path(&order).as_deref(), Some(r"totals.net\.usd"));
+
+    order.totals.clear();
+    assert_eq!(path(&order).as_deref(), Some("discount.Fixed[1]"));
+
+    order.discount = Discount::Percent { rate: 0.1 };
+    assert_eq!(path(&order).as_deref(), Some("discount.Percent.rate"));
+
+    // Floats are still written without the option, and integers with it
+    let result: AttributeValue = to_attribute_value(1.5f64).unwrap();
+    assert_eq!(result, AttributeValue::N(String::from("1.5")));
+    let result: AttributeValue = to_attribute_value_with_config(15u64, config).unwrap();
+    assert_eq!(result, AttributeValue::N(String::from("15")));
+}
+
+#[cfg(feature = "unicode-normalization")]
+#[test]
+fn serialize_normalized_strings() {
+    use crate::{to_item_with_config, Item, SerializerConfig};
+
+    #[derive(Serialize)]
+    struct Subject {
+        id: String,
+    }
+
+    let decomposed = String::from("cafe\u{301}");
+    let warnings = crate::Warnings::new();
+    let config = SerializerConfig::new()
+        .normalize_strings(true)
+        .warnings(warnings.clone());
+
+    let item: Item = to_item_with_config(
+        Subject {
+            id: decomposed.clone(),
+        },
+        config,
+    )
+    .unwrap();
+    assert_eq!(item["id"], AttributeValue::S(String::from("caf\u{e9}")));
+    assert_eq!(
+        warnings.take(),
+        vec![crate::Warning::NormalizedString {
+            original: decomposed
+        }]
+    );
+}
+
+#[test]
+fn serialize_ordered_sets() {
+    use crate::{to_item_with_config, Item, SerializerConfig};
+    use std::collections::{BTreeSet, HashSet};
+
+    #[derive(Serialize)]
+    struct Subject {
+        #[serde(with = "crate::string_set")]
+        tags: HashSet<&'static str>,
+        #[serde(with = "crate::number_set")]
+        scores: BTreeSet<i64>,
+        #[serde(with = "crate::binary_set")]
+        blobs: Vec<serde_bytes::ByteBuf>,
+    }
+
+    let subject = Subject {
+        tags: HashSet::from(["b", "c", "a"]),
+        scores: BTreeSet::from([-5, 3, 20]),
+        blobs: vec![
+            serde_bytes::ByteBuf::from(vec![2]),
+            serde_bytes::ByteBuf::from(vec![1, 9]),
+        ],
+    };
+    let config = SerializerConfig::new().ordered_sets(true);
+    let item: Item = to_item_with_config(&subject, config).unwrap();
+
+    assert_eq!(
+        item["tags"],
+        AttributeValue::Ss(vec![
+            String::from("a"),
+            String::from("b"),
+            String::from("c")
+        ])
+    );
+    assert_eq!(
+        item["scores"],
+        AttributeValue::Ns(vec![
+            String::from("-5"),
+            String::from("3"),
+            String::from("20")
+        ])
+    );
+    assert_eq!(item["blobs"], AttributeValue::Bs(vec![vec![1, 9], vec![2]]));
+}
+
+#[test]
+fn serialize_field_overrides() {
+    use crate::{to_item_with_config, FieldOverride, SerializerConfig};
+
+    #[derive(Serialize)]
+    struct Inner {
+        scores: Vec<u32>,
+        note: Option<String>,
+    }
+
+    #[derive(Serialize)]
+    struct Subject {
+        inner: Inner,
+        names: Vec<&'static str>,
+        payload: (bool, &'static str, Option<u8>),
+    }
+
+    let subject = Subject {
+        inner: Inner {
+            scores: vec![20, 3],
+            note: None,
+        },
+        names: vec!["b", "a"],
+        payload: (true, "say \"hi\"\n", None),
+    };
+    let config = SerializerConfig::new()
+        .ordered_sets(true)
+        .field_override("inner.scores", FieldOverride::NumberSet)
+        .field_override("inner.missing", FieldOverride::StringSet)
+        .field_override("names", FieldOverride::StringSet)
+        .field_override("payload", FieldOverride::JsonString);
+    let item: Item = to_item_with_config(&subject, config).unwrap();
+
+    let AttributeValue::M(inner) = &item["inner"] else {
+        panic!("expected a map, found {:?}", item["inner"]);
+    };
+    assert_eq!(
+        inner["scores"],
+        AttributeValue::Ns(vec![String::from("3"), String::from("20")])
+    );
+    assert_eq!(
+        item["names"],
+        AttributeValue::Ss(vec![String::from("a"), String::from("b")])
+    );
+    assert_eq!(
+        item["payload"],
+        AttributeValue::S(String::from(r#"[true,"say \"hi\"\n",null]"#))
+    );
+
+    let config = SerializerConfig::new().field_override("inner.scores", FieldOverride::StringSet);
+    let err = to_item_with_config::<_, Item>(&subject, config).unwrap_err();
+    assert_eq!(err, crate::error::ErrorImpl::StringSetExpectedType.into());
+
+    let config = SerializerConfig::new().field_override("inner..scores", FieldOverride::StringSet);
+    let err = to_item_with_config::<_, Item>(&subject, config).unwrap_err();
+    assert_eq!(err.to_string(), "Invalid attribute path `inner..scores`");
+}
+
+#[test]
+fn serialize_field_overrides_at_paths() {
+    use crate::{to_item_with_config, FieldOverride, SerializerConfig};
+    use std::collections::BTreeMap;
+
+    #[derive(Serialize)]
+    struct Line {
+        tags: Vec<&'static str>,
+    }
+
+    #[derive(Serialize)]
+    struct Order {
+        lines: Vec<Line>,
+        totals: BTreeMap<&'static str, Vec<u32>>,
+    }
+
+    let order = Order {
+        lines: vec![Line { tags: vec!["a"] }, Line { tags: vec!["b"] }],
+        totals: BTreeMap::from([("by.day", vec![1, 2]), ("by", vec![3])]),
+    };
+    let config = SerializerConfig::new()
+        .field_override("lines[1].tags", FieldOverride::StringSet)
+        .field_override("lines[5].tags", FieldOverride::StringSet)
+        .field_override(r"totals.by\.day", FieldOverride::NumberSet);
+    let item: Item = to_item_with_config(&order, config).unwrap();
+
+    assert_eq!(
+        item["lines"],
+        AttributeValue::L(vec![
+            AttributeValue::M(HashMap::from([(
+                String::from("tags"),
+                AttributeValue::L(vec![AttributeValue::S(String::from("a"))])
+            )])),
+            AttributeValue::M(HashMap::from([(
+                String::from("tags"),
+                AttributeValue::Ss(vec![String::from("b")])
+            )])),
+        ])
+    );
+    let AttributeValue::M(totals) = &item["totals"] else {
+        panic!("expected a map, found {:?}", item["totals"]);
+    };
+    assert!(matches!(totals["by.day"], AttributeValue::Ns(_)));
+    assert!(matches!(totals["by"], AttributeValue::L(_)));
+}
+
+#[cfg(feature = "validator")]
+#[test]
+fn serialize_validated_items() {
+    use crate::{to_item_validated, Error};
+    use validator::Validate;
+
+    #[derive(Serialize, Validate)]
+    #[serde(rename_all = "PascalCase")]
+    struct Address {
+        #[validate(length(min = 5, message = "too short"))]
+        post_code: String,
+    }
+
+    #[derive(Serialize, Validate)]
+    struct User {
+        #[serde(rename = "userName")]
+        #[validate(length(min = 1))]
+        user_name: String,
+        #[validate(nested)]
+        addresses: Vec<Address>,
+    }
+
+    let user = User {
+        user_name: String::new(),
+        addresses: vec![
+            Address {
+                post_code: String::from("12345"),
+            },
+            Address {
+                post_code: String::from("123"),
+            },
+        ],
+    };
+    let err: Error = to_item_validated::<_, Item>(&user).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Validation failed: `addresses[1].PostCode`: too short, `userName`: length"
+    );
+
+    let user = User {
+        user_name: String::from("ann"),
+        addresses: vec![],
+    };
+    let item: Item = to_item_validated(&user).unwrap();
+    assert_eq!(item["userName"], AttributeValue::S(String::from("ann")));
+
+    // Names no `rename_all` convention produces can't be found, so the Rust name is reported
+    #[derive(Serialize, Validate)]
+    struct Account {
+        #[serde(rename = "login")]
+        #[validate(length(min = 1))]
+        user_name: String,
+    }
+
+    let account = Account {
+        user_name: String::new(),
+    };
+    let err: Error = to_item_validated::<_, Item>(&account).unwrap_err();
+    assert_eq!(err.to_string(), "Validation failed: `user_name`: length");
+}
+
+#[test]
+fn serialize_numbers_dynamodb_can_store() {
+    #[derive(Serialize)]
+    struct Reading {
+        id: i128,
+        value: f64,
+    }
+
+    let item: Item = to_item(Reading {
+        id: -170141183460469231731687303715884105,
+        value: 0.5,
+    })
+    .unwrap();
+    assert_eq!(
+        item["id"],
+        AttributeValue::N(String::from("-170141183460469231731687303715884105"))
+    );
+
+    for value in [f64::NAN, f64::INFINITY, 1e200, 1e-200] {
+        let err = to_item::<_, Item>(Reading { id: 1, value }).unwrap_err();
+        assert!(err.to_string().starts_with("Number `"), "{value}: {err}");
+    }
+}
+
+#[test]
+fn serialize_empty_items() {
+    use crate::{to_item_with_config, SerializerConfig};
+
+    #[derive(Serialize)]
+    struct Patch {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+    }
+
+    let empty = Patch { name: None };
+    let item: Item = to_item(&empty).unwrap();
+    assert!(item.is_empty());
+
+    let strict = SerializerConfig::new().reject_empty_items(true);
+    let err = to_item_with_config::<_, Item>(&empty, strict.clone()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`serde_dynamo::ser::tests::serialize_empty_items::Patch` serialized to an empty item, \
+         which DynamoDB rejects"
+    );
+
+    let patch = Patch {
+        name: Some(String::from("x")),
+    };
+    let item: Item = to_item_with_config(&patch, strict).unwrap();
+    assert_eq!(item.len(), 1);
+}
+
+#[test]
+fn serialize_zero_as_none() {
+    use std::num::NonZeroU64;
+
+    #[derive(Serialize)]
+    struct Session {
+        #[serde(with = "crate::zero_as_none")]
+        expires_at: Option<NonZeroU64>,
+        #[serde(with = "crate::zero_as_none", skip_serializing_if = "Option::is_none")]
+        renewed_at: Option<NonZeroU64>,
+    }
+
+    let item: Item = to_item(Session {
+        expires_at: None,
+        renewed_at: None,
+    })
+    .unwrap();
+    assert_eq!(
+        item,
+        Item::from(HashMap::from([(
+            String::from("expires_at"),
+            AttributeValue::N(String::from("0"))
+        )]))
+    );
+
+    let item: Item = to_item(Session {
+        expires_at: NonZeroU64::new(1_700_000_000),
+        renewed_at: NonZeroU64::new(3),
+    })
+    .unwrap();
+    assert_eq!(
+        item["expires_at"],
+        AttributeValue::N(String::from("1700000000"))
+    );
+    assert_eq!(item["renewed_at"], AttributeValue::N(String::from("3")));
+}
+
+#[test]
+fn serialize_optional_sets() {
+    use crate::{dynamo_item, from_item, to_item_with_config, EmptySetPolicy, SerializerConfig};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Sets {
+        #[serde(default, with = "crate::string_set::optional")]
+        tags: Option<Vec<String>>,
+        #[serde(default, with = "crate::number_set::optional")]
+        scores: Option<Vec<u32>>,
+        #[serde(default, with = "crate::binary_set::optional")]
+        hashes: Option<Vec<serde_bytes::ByteBuf>>,
+    }
+
+    let empty = Sets {
+        tags: None,
+        scores: Some(vec![]),
+        hashes: None,
+    };
+    let item: Item = to_item(&empty).unwrap();
+    assert_eq!(item, Item::default());
+    let read: Sets = from_item(item).unwrap();
+    assert_eq!(read.scores, None);
+
+    let config = SerializerConfig::new().empty_sets(EmptySetPolicy::Null);
+    let item: Item = to_item_with_config(&empty, config).unwrap();
+    assert_eq!(
+        item,
+        dynamo_item! { "tags": null, "scores": null, "hashes": null }
+    );
+    let read: Sets = from_item(item).unwrap();
+    assert_eq!(read.scores, None);
+
+    let full = Sets {
+        tags: Some(vec![String::from("b"), String::from("a")]),
+        scores: Some(vec![3]),
+        hashes: Some(vec![serde_bytes::ByteBuf::from(vec![1])]),
+    };
+    let config = SerializerConfig::new().ordered_sets(true);
+    let item: Item = to_item_with_config(&full, config).unwrap();
+    assert_eq!(
+        item["tags"],
+        AttributeValue::Ss(vec![String::from("a"), String::from("b")])
+    );
+    assert_eq!(item["scores"], AttributeValue::Ns(vec![String::from("3")]));
+    assert_eq!(item["hashes"], AttributeValue::Bs(vec![vec![1]]));
+    let read: Sets = from_item(item).unwrap();
+    assert_eq!(read.scores, Some(vec![3]));
+
+    #[derive(Serialize)]
+    struct Wrapper(#[serde(with = "crate::string_set::optional")] Option<Vec<String>>);
+
+    // Map entries are left out like struct fields
+    let map = HashMap::from([
+        ("a", Wrapper(Some(vec![String::from("x")]))),
+        ("b", Wrapper(None)),
+    ]);
+    let value: AttributeValue = to_attribute_value(map).unwrap();
+    assert_eq!(
+        value,
+        AttributeValue::M(HashMap::from([(
+            String::from("a"),
+            AttributeValue::Ss(vec![String::from("x")])
+        )]))
+    );
+
+    // Anywhere else, an empty set can't be left out
+    assert_eq!(
+        to_attribute_value::<_, AttributeValue>(Wrapper(None))
+            .unwrap_err()
+            .to_string(),
+        "An empty optional set can only be omitted from a struct field or map entry"
+    );
+    assert!(to_attribute_value::<_, AttributeValue>(vec![Wrapper(None)]).is_err());
+}
+
+#[test]
+fn serialize_duplicate_attributes() {
+    use crate::{to_item_with_config, SerializerConfig};
+
+    #[derive(Serialize)]
+    struct Audit {
+        updated_by: String,
+    }
+
+    #[derive(Serialize)]
+    struct Document {
+        id: String,
+        updated_by: String,
+        #[serde(flatten)]
+        audit: Audit,
+    }
+
+    let document = Document {
+        id: String::from("d-1"),
+        updated_by: String::from("ann"),
+        audit: Audit {
+            updated_by: String::from("bob"),
+        },
+    };
+
+    // By default the flattened field silently wins
+    let item: Item = to_item(&document).unwrap();
+    assert_eq!(item["updated_by"], AttributeValue::S(String::from("bob")));
+
+    let strict = SerializerConfig::new().reject_duplicate_attributes(true);
+    let err = to_item_with_config::<_, Item>(&document, strict.clone()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Attribute `updated_by` is written more than once"
+    );
+
+    #[derive(Serialize)]
+    struct Distinct {
+        id: String,
+        #[serde(flatten)]
+        audit: Audit,
+    }
+
+    let distinct = Distinct {
+        id: String::from("d-2"),
+        audit: Audit {
+            updated_by: String::from("bob"),
+        },
+    };
+    let item: Item = to_item_with_config(&distinct, strict).unwrap();
+    assert_eq!(item.len(), 2);
+}
+
+#[test]
+fn serialize_within_limits() {
+    use crate::{to_attribute_value_with_config, SeqOf, SerializerConfig, SerializerLimits};
+
+    #[derive(Serialize)]
+    struct Order {
+        id: String,
+        lines: Vec<u32>,
+        notes: HashMap<String, String>,
+    }
+
+    let order = Order {
+        id: String::from("o-1"),
+        lines: vec![1, 2, 3],
+        notes: HashMap::from([(String::from("gift"), String::from("yes"))]),
+    };
+    let serialize = |limits: SerializerLimits| {
+        let config = SerializerConfig::new().limits(limits);
+        to_attribute_value_with_config::<_, AttributeValue>(&order, config)
+            .map_err(|err| err.to_string())
+    };
+
+    // id, lines, notes, three lines and one note
+    assert!(serialize(SerializerLimits::new().max_attributes(7)).is_ok());
+    assert_eq!(
+        serialize(SerializerLimits::new().max_attributes(6)).unwrap_err(),
+        "Serialized value exceeds the limit of 6 attributes"
+    );
+
+    // "id" "o-1" "lines" "notes" "gift" "yes"
+    let bytes = 2 + 3 + 5 + 5 + 4 + 3;
+    assert!(serialize(SerializerLimits::new().max_bytes(bytes)).is_ok());
+    assert_eq!(
+        serialize(SerializerLimits::new().max_bytes(bytes - 1)).unwrap_err(),
+        format!("Serialized value exceeds the limit of {} bytes", bytes - 1)
+    );
+
+    assert!(serialize(SerializerLimits::new().max_list_len(3)).is_ok());
+    assert_eq!(
+        serialize(SerializerLimits::new().max_list_len(2)).unwrap_err(),
+        "Serialized value exceeds the limit of 2 list elements"
+    );
+
+    // A producer that never stops is cut off
+    let config = SerializerConfig::new().limits(SerializerLimits::new().max_attributes(1000));
+    let endless = SeqOf(std::iter::repeat("spam"));
+    assert!(to_attribute_value_with_config::<_, AttributeValue>(endless, config).is_err());
+}
+
+#[test]
+fn serialize_newtype_extensions() {
+    use crate::{to_attribute_value_with_config, NewtypeStructs, SerializerConfig};
+
+    const UPPERCASE: &str = "\u{037E}UPPERCASE\u{037E}";
+
+    struct Uppercase(&'static str);
+
+    impl serde::Serialize for Uppercase {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_newtype_struct(UPPERCASE, self.0)
+        }
+    }
+
+    fn uppercase(value: AttributeValue) -> crate::Result<AttributeValue> {
+        match value {
+            AttributeValue::S(s) => Ok(AttributeValue::S(s.to_uppercase())),
+            _ => Err(serde::ser::Error::custom("expected a string")),
+        }
+    }
+
+    #[derive(Serialize)]
+    struct Plain(&'static str);
+
+    let config = SerializerConfig::new()
+        .newtype_structs(NewtypeStructs::Indexed)
+        .newtype_extension(UPPERCASE, uppercase);
+
+    let value = (Uppercase("abc"), vec![Uppercase("x")], Plain("abc"));
+    assert_eq!(
+        to_attribute_value_with_config::<_, AttributeValue>(value, config.clone()).unwrap(),
+        AttributeValue::L(vec![
+            AttributeValue::S(String::from("ABC")),
+            AttributeValue::L(vec![AttributeValue::S(String::from("X"))]),
+            AttributeValue::M(HashMap::from([(
+                String::from("0"),
+                AttributeValue::S(String::from("abc"))
+            )])),
+        ])
+    );
+
+    // Without the extension the wrapper is an ordinary newtype struct
+    assert_eq!(
+        to_attribute_value::<_, AttributeValue>(Uppercase("abc")).unwrap(),
+        AttributeValue::S(String::from("abc"))
+    );
+
+    // Ordinary newtype structs can be registered by name too
+    let config = SerializerConfig::new().newtype_extension("Plain", uppercase);
+    assert_eq!(
+        to_attribute_value_with_config::<_, AttributeValue>(Plain("abc"), config).unwrap(),
+        AttributeValue::S(String::from("ABC"))
+    );
+
+    #[derive(Serialize)]
+    struct NotAString(u8);
+    let config = SerializerConfig::new().newtype_extension("NotAString", uppercase);
+    let err =
+        to_attribute_value_with_config::<_, AttributeValue>(NotAString(1), config).unwrap_err();
+    assert_eq!(err.to_string(), "expected a string");
+}
+
+#[test]
+fn serialize_into_existing_items() {
+    use crate::{dynamo_item, serialize_into, serialize_into_with_config, SerializerConfig};
+
+    #[derive(Serialize)]
+    struct Header {
+        id: &'static str,
+        version: u8,
+    }
+
+    #[derive(Serialize)]
+    struct Payload {
+        size: u8,
+    }
+
+    let mut item: Item = dynamo_item! { "payload": { "kind": "box" } };
+    serialize_into(
+        &mut item,
+        None,
+        Header {
+            id: "a",
+            version: 1,
+        },
+    )
+    .unwrap();
+    serialize_into(&mut item, Some("payload"), Payload { size: 3 }).unwrap();
+    assert_eq!(
+        item,
+        dynamo_item! { "id": "a", "version": 1, "payload": { "kind": "box", "size": 3 } }
+    );
+
+    // Later values replace earlier attributes, unless duplicates are rejected
+    serialize_into(
+        &mut item,
+        None,
+        Header {
+            id: "b",
+            version: 2,
+        },
+    )
+    .unwrap();
+    assert_eq!(item["id"], AttributeValue::S(String::from("b")));
+    let strict = SerializerConfig::new().reject_duplicate_attributes(true);
+    let before = item.clone();
+    let err = serialize_into_with_config(
+        &mut item,
+        None,
+        Header {
+            id: "c",
+            version: 3,
+        },
+        strict,
+    )
+    .unwrap_err();
+    assert!(
+        err.to_string().contains("is written more than once"),
+        "{err}"
+    );
+    assert_eq!(item, before);
+
+    assert_eq!(
+        serialize_into(&mut item, Some("id"), Payload { size: 1 })
+            .unwrap_err()
+            .attribute_path(),
+        Some("id")
+    );
+    assert!(serialize_into(&mut item, None, 7).is_err());
+}
+
+#[cfg(debug_assertions)]
+mod invalid_sets {
+    use super::*;
+    use crate::{binary_set::BinarySet, number_set::NumberSet, string_set::StringSet};
+    use crate::{to_attribute_value_with_config, ErrorCode, SerializerConfig};
+
+    fn invalid<T: serde::Serialize>(value: T) -> String {
+        let err = to_attribute_value::<_, AttributeValue>(value).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::InvalidSet);
+        err.to_string()
+    }
+
+    #[test]
+    fn empty_string_set() {
+        assert_eq!(
+            invalid(StringSet(Vec::<String>::new())),
+            "Invalid SS attribute value: the set is empty"
+        );
+    }
+
+    #[test]
+    fn duplicate_numbers() {
+        assert_eq!(
+            invalid(NumberSet(vec![1.0, 1.0])),
+            "Invalid NS attribute value: member 1 is a duplicate"
+        );
+    }
+
+    #[test]
+    fn duplicate_binary() {
+        assert_eq!(
+            invalid(BinarySet(vec![
+                serde_bytes::ByteBuf::from(vec![1]),
+                serde_bytes::ByteBuf::from(vec![2]),
+                serde_bytes::ByteBuf::from(vec![1]),
+            ])),
+            "Invalid BS attribute value: member 2 is a duplicate"
+        );
+    }
+
+    #[test]
+    fn invalid_extension_numbers() {
+        #[derive(Serialize)]
+        struct Price(&'static str);
+
+        fn price(value: AttributeValue) -> crate::Result<AttributeValue> {
+            match value {
+                AttributeValue::S(s) => Ok(AttributeValue::N(s)),
+                other => Ok(other),
+            }
+        }
+
+        let config = SerializerConfig::new().newtype_extension("Price", price);
+        let result: AttributeValue =
+            to_attribute_value_with_config(Price("9.99"), config.clone()).unwrap();
+        assert_eq!(result, AttributeValue::N(String::from("9.99")));
+
+        let err = to_attribute_value_with_config::<_, AttributeValue>(Price("cheap"), config)
+            .unwrap_err();
+        assert_eq!(err.code(), ErrorCode::UnrepresentableNumber);
+    }
+
+    #[test]
+    fn valid_sets() {
+        assert_eq!(
+            to_attribute_value::<_, AttributeValue>(NumberSet(vec![1, 10])).unwrap(),
+            AttributeValue::Ns(vec![String::from("1"), String::from("10")])
+        );
+    }
+}
+
+#[test]
+#[cfg(not(debug_assertions))]
+fn release_builds_leave_sets_unchecked() {
+    use crate::{number_set::NumberSet, string_set::StringSet};
+
+    assert_eq!(
+        to_attribute_value::<_, AttributeValue>(StringSet(Vec::<String>::new())).unwrap(),
+        AttributeValue::Ss(vec![])
+    );
+    assert_eq!(
+        to_attribute_value::<_, AttributeValue>(NumberSet(vec![1, 1])).unwrap(),
+        AttributeValue::Ns(vec![String::from("1"), String::from("1")])
+    );
+}
@@ -0,0 +1,163 @@
+use super::{to_item, SerializerConfig};
+use crate::{error::ErrorImpl, AttributeValue, Item, Result};
+use __validator::{Validate, ValidationErrors, ValidationErrorsKind};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Validate a `T` with the [validator] crate, then convert it into an [`Item`].
+///
+/// Validation errors are returned as a [`crate::Error`] listing every failure. Each failure is
+/// reported at its attribute path, such as `addresses[1].postCode`, using the attribute names
+/// that serde writes rather than the Rust field names, so renamed fields point at the attribute
+/// that would have been stored.
+///
+/// validator reports Rust field names, and serde has no way to map those to the names it writes,
+/// so the attribute names are found by matching each field name, under every `rename_all`
+/// convention, against the attributes the value actually serializes to. This finds fields renamed
+/// with `rename_all`, but not fields given some other name with `#[serde(rename = "...")]`, nor
+/// fields inside `#[serde(flatten)]` structs renamed differently from their parent. Those
+/// failures, and failures in values that don't serialize to a map, are reported at the Rust field
+/// name instead.
+///
+/// ```
+/// # extern crate __validator as validator;
+/// use serde_derive::Serialize;
+/// use serde_dynamo::Item;
+/// use validator::Validate;
+///
+/// #[derive(Serialize, Validate)]
+/// #[serde(rename_all = "camelCase")]
+/// struct User {
+///     #[validate(email)]
+///     email_address: String,
+/// }
+///
+/// let user = User { email_address: String::from("not an email") };
+///
+/// let err = serde_dynamo::to_item_validated::<_, Item>(&user).unwrap_err();
+/// assert_eq!(err.to_string(), "Validation failed: `emailAddress`: email");
+/// ```
+///
+/// [validator]: https://docs.rs/validator
+pub fn to_item_validated<T, I>(value: T) -> Result<I>
+where
+    T: Serialize + Validate,
+    I: From<Item>,
+{
+    to_item_validated_with_config(value, SerializerConfig::default())
+}
+
+/// Validate a `T` with the [validator] crate, then convert it into an [`Item`], using the given
+/// configuration.
+///
+/// See [`to_item_validated`] and [`SerializerConfig`].
+///
+/// [validator]: https://docs.rs/validator
+pub fn to_item_validated_with_config<T, I>(value: T, config: SerializerConfig) -> Result<I>
+where
+    T: Serialize + Validate,
+    I: From<Item>,
+{
+    if let Err(errors) = value.validate() {
+        // The serialized item is only used to find the attribute names, so fall back to the Rust
+        // field names if the value doesn't serialize to a map
+        let item = to_item::<_, Item>(&value).ok();
+        let mut failures = Vec::new();
+        collect_failures(&errors, item.as_deref(), "", &mut failures);
+        return Err(ErrorImpl::ValidationFailed(failures).into());
+    }
+    super::to_item_with_config(value, config)
+}
+
+fn collect_failures(
+    errors: &ValidationErrors,
+    item: Option<&HashMap<String, AttributeValue>>,
+    prefix: &str,
+    failures: &mut Vec<String>,
+) {
+    let mut fields: Vec<_> = errors.errors().iter().collect();
+    fields.sort_unstable_by_key(|(field, _)| *field);
+
+    for (field, kind) in fields {
+        let (name, value) = attribute(field, item);
+        let mut path = prefix.to_string();
+        crate::path::push_key(&mut path, &name);
+        match kind {
+            ValidationErrorsKind::Field(errors) => {
+                for error in errors {
+                    let message = error.message.as_ref().unwrap_or(&error.code);
+                    failures.push(format!("`{path}`: {message}"));
+                }
+            }
+            ValidationErrorsKind::Struct(errors) => {
+                collect_failures(errors, as_map(value), &path, failures);
+            }
+            ValidationErrorsKind::List(elements) => {
+                for (index, errors) in elements {
+                    let element = match value {
+                        Some(AttributeValue::L(l)) => l.get(*index),
+                        _ => None,
+                    };
+                    collect_failures(
+                        errors,
+                        as_map(element),
+                        &format!("{path}[{index}]"),
+                        failures,
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn as_map(value: Option<&AttributeValue>) -> Option<&HashMap<String, AttributeValue>> {
+    match value {
+        Some(AttributeValue::M(m)) => Some(m),
+        _ => None,
+    }
+}
+
+/// Find the attribute a Rust field was serialized to, trying the `rename_all` conventions
+///
+/// This is a heuristic: the first convention that names an attribute of `item` wins, so a field
+/// can be matched to another field's attribute if, say, one struct has both `user_id` and
+/// `userId` fields, and a field renamed to a name no convention produces isn't matched at all. An unmatched field
+/// keeps its Rust name and has no value, so errors nested inside it keep their Rust names too.
+fn attribute<'a>(
+    field: &str,
+    item: Option<&'a HashMap<String, AttributeValue>>,
+) -> (String, Option<&'a AttributeValue>) {
+    let Some(item) = item else {
+        return (field.to_string(), None);
+    };
+
+    let words: Vec<&str> = field.split('_').filter(|word| !word.is_empty()).collect();
+    let capitalized = || words.iter().map(|word| capitalize(word));
+    let candidates = [
+        field.to_string(),
+        words.concat(),
+        capitalized().collect::<String>(),
+        words
+            .first()
+            .map(|first| first.to_string() + &capitalized().skip(1).collect::<String>())
+            .unwrap_or_default(),
+        words.join("-"),
+        field.to_uppercase(),
+        words.join("-").to_uppercase(),
+        words.concat().to_uppercase(),
+    ];
+
+    candidates
+        .into_iter()
+        .find_map(|candidate| item.get_key_value(&candidate))
+        .map(|(name, value)| (name.clone(), Some(value)))
+        .unwrap_or_else(|| (field.to_string(), None))
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
@@ -0,0 +1,282 @@
+use crate::de::BorrowedAttributeValue;
+use crate::AttributeValue;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// The size and shape of one serialized or deserialized value
+///
+/// See [`Stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ItemStats {
+    /// The values nested anywhere in the value, counting every map entry and list element
+    pub attributes: usize,
+    /// The `S` values and `SS` elements
+    pub strings: usize,
+    /// The bytes of strings, numbers, binary and attribute names
+    pub bytes: usize,
+    /// How deeply maps and lists are nested, where the attributes of an item are at depth 1
+    pub max_depth: usize,
+}
+
+impl ItemStats {
+    /// Measure `value`
+    ///
+    /// ```
+    /// use serde_dynamo::{dynamo_value, ItemStats};
+    ///
+    /// let stats = ItemStats::of(&dynamo_value!({ "id": "u-1", "tags": ["a", "b"] }));
+    /// assert_eq!(stats.attributes, 4);
+    /// assert_eq!(stats.strings, 3);
+    /// assert_eq!(stats.bytes, 11);
+    /// assert_eq!(stats.max_depth, 2);
+    /// ```
+    pub fn of(value: &AttributeValue) -> Self {
+        Self::of_borrowed(value)
+    }
+
+    pub(crate) fn of_borrowed<A>(value: &A) -> Self
+    where
+        A: BorrowedAttributeValue,
+    {
+        let mut stats = ItemStats::default();
+        stats.add(value, 0);
+        stats
+    }
+
+    pub(crate) fn of_borrowed_item<A>(item: &HashMap<String, A>) -> Self
+    where
+        A: BorrowedAttributeValue,
+    {
+        let mut stats = ItemStats::default();
+        stats.add_map(item, 0);
+        stats
+    }
+
+    fn add<A>(&mut self, value: &A, depth: usize)
+    where
+        A: BorrowedAttributeValue,
+    {
+        self.max_depth = self.max_depth.max(depth);
+        if let Some(map) = value.map() {
+            self.add_map(map, depth);
+        } else if let Some(list) = value.list() {
+            for value in list {
+                self.attributes += 1;
+                self.add(value, depth + 1);
+            }
+        } else {
+            value.measure_scalar(self);
+        }
+    }
+
+    fn add_map<A>(&mut self, map: &HashMap<String, A>, depth: usize)
+    where
+        A: BorrowedAttributeValue,
+    {
+        for (name, value) in map {
+            self.attributes += 1;
+            self.bytes += name.len();
+            self.add(value, depth + 1);
+        }
+    }
+
+    /// Add the size of a value that isn't a map or list
+    pub(crate) fn add_scalar(&mut self, value: &AttributeValue) {
+        match value {
+            AttributeValue::S(s) => {
+                self.strings += 1;
+                self.bytes += s.len();
+            }
+            AttributeValue::N(n) => self.bytes += n.len(),
+            AttributeValue::B(b) => self.bytes += b.len(),
+            AttributeValue::Ss(ss) => {
+                self.strings += ss.len();
+                self.bytes += ss.iter().map(String::len).sum::<usize>();
+            }
+            AttributeValue::Ns(ns) => self.bytes += ns.iter().map(String::len).sum::<usize>(),
+            AttributeValue::Bs(bs) => self.bytes += bs.iter().map(Vec::len).sum::<usize>(),
+            AttributeValue::Bool(_)
+            | AttributeValue::Null(_)
+            | AttributeValue::M(_)
+            | AttributeValue::L(_) => {}
+        }
+    }
+}
+
+/// A sink for [`ItemStats`]
+///
+/// Giving a [`SerializerConfig`](crate::SerializerConfig) or
+/// [`DeserializerConfig`](crate::DeserializerConfig) a `Stats` measures every value written or
+/// read with it: each item, or each top-level attribute value. The measurements help with
+/// capacity planning and with finding pathologically large or deep items in production, without
+/// an external profiler. Measuring walks the whole value once more, so it is only available with
+/// the `stats` feature.
+///
+/// `Stats` is a cheap handle to shared storage: clones record into, and read from, the same
+/// list.
+///
+/// ```
+/// use serde_dynamo::{dynamo_item, DeserializerConfig, Item, Stats};
+/// # use serde_derive::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct User {
+///     id: String,
+/// }
+///
+/// let stats = Stats::new();
+/// let config = DeserializerConfig::new().stats(stats.clone());
+///
+/// let items = vec![dynamo_item! { "id": "u-1" }, dynamo_item! { "id": "u-22" }];
+/// let users: Vec<User> = serde_dynamo::from_items_with_config(items, config).unwrap();
+///
+/// let bytes: Vec<usize> = stats.take().iter().map(|item| item.bytes).collect();
+/// assert_eq!(bytes, vec![5, 6]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Stats(Arc<Mutex<Vec<ItemStats>>>);
+
+impl Stats {
+    /// Create an empty sink
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remove and return the recorded measurements, oldest first
+    pub fn take(&self) -> Vec<ItemStats> {
+        std::mem::take(&mut *self.lock())
+    }
+
+    /// The number of recorded measurements
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Whether nothing has been recorded
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    pub(crate) fn push(&self, stats: ItemStats) {
+        self.lock().push(stats);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<ItemStats>> {
+        // A panic while holding the lock can't leave the list in an invalid state
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Two handles are equal when they share the same storage
+impl PartialEq for Stats {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for Stats {}
+
+#[cfg(test)]
+mod tests {
+    use super::{ItemStats, Stats};
+    use crate::{
+        dynamo_item, from_item_ref_with_config, from_item_with_config, from_items_iter_with_config,
+        from_items_ref_with_config, to_item_with_config, AttributeValue, DeserializerConfig, Item,
+        SerializerConfig,
+    };
+    use serde_derive::{Deserialize, Serialize};
+    use std::collections::HashMap;
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Order {
+        id: String,
+        lines: Vec<Line>,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Line {
+        sku: String,
+        qty: u32,
+    }
+
+    fn stats(attributes: usize, strings: usize, bytes: usize, max_depth: usize) -> ItemStats {
+        ItemStats {
+            attributes,
+            strings,
+            bytes,
+            max_depth,
+        }
+    }
+
+    #[test]
+    fn measures_values() {
+        assert_eq!(
+            ItemStats::of(&AttributeValue::N(String::from("12"))),
+            stats(0, 0, 2, 0)
+        );
+        let binary = AttributeValue::M(HashMap::from([(
+            String::from("x"),
+            AttributeValue::B(vec![0]),
+        )]));
+        let deep = AttributeValue::L(vec![AttributeValue::L(vec![AttributeValue::L(vec![
+            binary,
+        ])])]);
+        let value = AttributeValue::M(HashMap::from([
+            (
+                String::from("ss"),
+                AttributeValue::Ss(vec![String::from("a"), String::from("bc")]),
+            ),
+            (
+                String::from("bs"),
+                AttributeValue::Bs(vec![vec![1, 2], vec![3]]),
+            ),
+            (String::from("flag"), AttributeValue::Bool(true)),
+            (String::from("none"), AttributeValue::Null(true)),
+            (String::from("deep"), deep),
+        ]));
+        assert_eq!(ItemStats::of(&value), stats(9, 2, 24, 5));
+    }
+
+    #[test]
+    fn records_every_item() {
+        let recorded = Stats::new();
+        let order = Order {
+            id: String::from("o-1"),
+            lines: vec![
+                Line {
+                    sku: String::from("a"),
+                    qty: 2,
+                },
+                Line {
+                    sku: String::from("bb"),
+                    qty: 10,
+                },
+            ],
+        };
+        let expected = stats(8, 3, 28, 3);
+
+        let config = SerializerConfig::new().stats(recorded.clone());
+        let item: Item = to_item_with_config(&order, config).unwrap();
+        assert_eq!(recorded.take(), vec![expected]);
+
+        let config = DeserializerConfig::new().stats(recorded.clone());
+        let _: Order = from_item_with_config(item.clone(), config.clone()).unwrap();
+        let _: Order = from_item_ref_with_config(&item, config.clone()).unwrap();
+        assert_eq!(recorded.len(), 2);
+
+        let small = dynamo_item! { "id": "o-2", "lines": [] };
+        let items = vec![item, small];
+        let _: Vec<Order> = from_items_ref_with_config(&items, config.clone()).unwrap();
+        let _: Vec<Order> = crate::from_items_with_config(items.clone(), config.clone()).unwrap();
+        let _: Vec<Order> = from_items_iter_with_config(items, config).unwrap();
+        let small = stats(2, 1, 10, 1);
+        assert_eq!(
+            recorded.take(),
+            vec![expected, expected, expected, small, expected, small, expected, small]
+        );
+        assert!(recorded.is_empty());
+    }
+}
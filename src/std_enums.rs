@@ -0,0 +1,501 @@
+//! Serializer codecs for standard library enums with filter-friendly shapes
+//!
+//! Serde writes enums externally tagged, so `Ok(5)` is stored as `{ "Ok": 5 }` and
+//! `Bound::Included(3)` as `{ "Included": 3 }`. Filter and condition expressions can only test
+//! such values by checking which key exists, and indexes can't use them at all. The codecs in
+//! this module store the variant in its own string attribute beside the value instead:
+//!
+//! | Type | Codec | DynamoDB |
+//! |------|-------|----------|
+//! | `Result<T, E>` | [`result`] | `{ "status": "ok", "value": T }` or `{ "status": "err", "error": E }` |
+//! | `Bound<T>` | [`bound`] | `{ "bound": "included" \| "excluded", "value": T }` or `{ "bound": "unbounded" }` |
+//! | `(Bound<T>, Bound<T>)` | [`bounds`] | `{ "start_bound": …, "start": T, "end_bound": …, "end": T }` |
+//!
+//! Values of unbounded ends are left out. [`Range`](std::ops::Range) and
+//! [`RangeInclusive`](std::ops::RangeInclusive) need no codec: serde already stores them as a map
+//! with `start` and `end` attributes.
+//!
+//! # Usage
+//!
+//! Annotate a field with, for example, `#[serde(with = "serde_dynamo::std_enums::result")]` to
+//! store it as a map in one attribute. Add `flatten` as well to store the attributes directly in
+//! the item, where a filter such as `status = :err` can reach them.
+//!
+//! # Examples
+//!
+//! ```
+//! use serde_derive::{Serialize, Deserialize};
+//! use serde_dynamo::{dynamo_item, Item};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct JobFinished {
+//!     job_id: String,
+//!     #[serde(flatten, with = "serde_dynamo::std_enums::result")]
+//!     outcome: Result<u32, String>,
+//! }
+//!
+//! let event = JobFinished {
+//!     job_id: String::from("j-1"),
+//!     outcome: Err(String::from("timed out")),
+//! };
+//!
+//! let item: Item = serde_dynamo::to_item(&event).unwrap();
+//! assert_eq!(
+//!     item,
+//!     dynamo_item! { "job_id": "j-1", "status": "err", "error": "timed out" }
+//! );
+//!
+//! let read: JobFinished = serde_dynamo::from_item(item).unwrap();
+//! assert_eq!(read, event);
+//! ```
+
+/// Codec for `Result<T, E>`, stored as a `status` of `"ok"` or `"err"` beside a `value` or an
+/// `error`
+///
+/// See the [module documentation](super::std_enums).
+pub mod result {
+    use serde::de::{self, MapAccess, Visitor};
+    use serde::ser::SerializeStruct;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::fmt;
+    use std::marker::PhantomData;
+
+    const NAME: &str = "Result";
+    const STATUS: &str = "status";
+    const VALUE: &str = "value";
+    const ERROR: &str = "error";
+    const FIELDS: &[&str] = &[STATUS, VALUE, ERROR];
+    const OK: &str = "ok";
+    const ERR: &str = "err";
+    const VARIANTS: &[&str] = &[OK, ERR];
+
+    /// Serializes a result as its status and its value or error
+    pub fn serialize<T, E, S>(value: &Result<T, E>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        E: Serialize,
+        S: Serializer,
+    {
+        let mut result = serializer.serialize_struct(NAME, 2)?;
+        match value {
+            Ok(value) => {
+                result.serialize_field(STATUS, OK)?;
+                result.serialize_field(VALUE, value)?;
+            }
+            Err(error) => {
+                result.serialize_field(STATUS, ERR)?;
+                result.serialize_field(ERROR, error)?;
+            }
+        }
+        result.end()
+    }
+
+    /// Deserializes a result from its status and its value or error
+    pub fn deserialize<'de, T, E, D>(deserializer: D) -> Result<Result<T, E>, D::Error>
+    where
+        T: Deserialize<'de>,
+        E: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_struct(NAME, FIELDS, ResultVisitor(PhantomData))
+    }
+
+    struct ResultVisitor<T, E>(PhantomData<(T, E)>);
+
+    impl<'de, T, E> Visitor<'de> for ResultVisitor<T, E>
+    where
+        T: Deserialize<'de>,
+        E: Deserialize<'de>,
+    {
+        type Value = Result<T, E>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a map with a `status` and a `value` or an `error`")
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            let mut status = None;
+            let mut value = None;
+            let mut error = None;
+            while let Some(field) = map.next_key::<String>()? {
+                match field.as_str() {
+                    STATUS => status = Some(map.next_value::<String>()?),
+                    VALUE => value = Some(map.next_value()?),
+                    ERROR => error = Some(map.next_value()?),
+                    _ => {
+                        map.next_value::<de::IgnoredAny>()?;
+                    }
+                }
+            }
+
+            match status.as_deref() {
+                Some(OK) => value.map(Ok).ok_or_else(|| de::Error::missing_field(VALUE)),
+                Some(ERR) => error
+                    .map(Err)
+                    .ok_or_else(|| de::Error::missing_field(ERROR)),
+                Some(other) => Err(de::Error::unknown_variant(other, VARIANTS)),
+                None => Err(de::Error::missing_field(STATUS)),
+            }
+        }
+    }
+}
+
+/// Codec for `Bound<T>`, stored as a `bound` of `"included"`, `"excluded"` or `"unbounded"`
+/// beside its `value`
+///
+/// See the [module documentation](super::std_enums).
+pub mod bound {
+    use super::kind::{self, Kind};
+    use serde::de::{self, MapAccess, Visitor};
+    use serde::ser::SerializeStruct;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::fmt;
+    use std::marker::PhantomData;
+    use std::ops::Bound;
+
+    const NAME: &str = "Bound";
+    const BOUND: &str = "bound";
+    const VALUE: &str = "value";
+    const FIELDS: &[&str] = &[BOUND, VALUE];
+
+    /// Serializes a bound as its kind and its value
+    pub fn serialize<T, S>(value: &Bound<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: Serializer,
+    {
+        let (bound, value) = kind::split(value);
+        let mut state = serializer.serialize_struct(NAME, 1 + usize::from(value.is_some()))?;
+        state.serialize_field(BOUND, &bound)?;
+        if let Some(value) = value {
+            state.serialize_field(VALUE, value)?;
+        }
+        state.end()
+    }
+
+    /// Deserializes a bound from its kind and its value
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Bound<T>, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_struct(NAME, FIELDS, BoundVisitor(PhantomData))
+    }
+
+    struct BoundVisitor<T>(PhantomData<T>);
+
+    impl<'de, T> Visitor<'de> for BoundVisitor<T>
+    where
+        T: Deserialize<'de>,
+    {
+        type Value = Bound<T>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a map with a `bound` and a `value`")
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            let mut bound = None;
+            let mut value = None;
+            while let Some(field) = map.next_key::<String>()? {
+                match field.as_str() {
+                    BOUND => bound = Some(map.next_value::<Kind>()?),
+                    VALUE => value = Some(map.next_value()?),
+                    _ => {
+                        map.next_value::<de::IgnoredAny>()?;
+                    }
+                }
+            }
+
+            let bound = bound.ok_or_else(|| de::Error::missing_field(BOUND))?;
+            kind::join(bound, value, VALUE)
+        }
+    }
+}
+
+/// Codec for a `(Bound<T>, Bound<T>)` range, stored as `start_bound` and `end_bound` kinds beside
+/// `start` and `end` values
+///
+/// This is the shape of [`RangeBounds`](std::ops::RangeBounds) with either end open, closed or
+/// missing, such as a price band or a validity period. The value of an unbounded end is left
+/// out, so `attribute_not_exists(end)` finds open-ended ranges.
+///
+/// ```
+/// use serde_derive::{Serialize, Deserialize};
+/// use serde_dynamo::{dynamo_item, Item};
+/// use std::ops::Bound;
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Discount {
+///     #[serde(flatten, with = "serde_dynamo::std_enums::bounds")]
+///     valid: (Bound<u64>, Bound<u64>),
+/// }
+///
+/// let discount = Discount { valid: (Bound::Included(1700000000), Bound::Unbounded) };
+///
+/// let item: Item = serde_dynamo::to_item(&discount).unwrap();
+/// assert_eq!(
+///     item,
+///     dynamo_item! { "start_bound": "included", "start": 1700000000, "end_bound": "unbounded" }
+/// );
+///
+/// let read: Discount = serde_dynamo::from_item(item).unwrap();
+/// assert_eq!(read, discount);
+/// ```
+pub mod bounds {
+    use super::kind::{self, Kind};
+    use serde::de::{self, MapAccess, Visitor};
+    use serde::ser::SerializeStruct;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::fmt;
+    use std::marker::PhantomData;
+    use std::ops::Bound;
+
+    const NAME: &str = "Bounds";
+    const START_BOUND: &str = "start_bound";
+    const START: &str = "start";
+    const END_BOUND: &str = "end_bound";
+    const END: &str = "end";
+    const FIELDS: &[&str] = &[START_BOUND, START, END_BOUND, END];
+
+    /// Serializes a pair of bounds as the kind and value of each end
+    pub fn serialize<T, S>(value: &(Bound<T>, Bound<T>), serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: Serializer,
+    {
+        let (start_bound, start) = kind::split(&value.0);
+        let (end_bound, end) = kind::split(&value.1);
+        let len = 2 + usize::from(start.is_some()) + usize::from(end.is_some());
+        let mut state = serializer.serialize_struct(NAME, len)?;
+        state.serialize_field(START_BOUND, &start_bound)?;
+        if let Some(start) = start {
+            state.serialize_field(START, start)?;
+        }
+        state.serialize_field(END_BOUND, &end_bound)?;
+        if let Some(end) = end {
+            state.serialize_field(END, end)?;
+        }
+        state.end()
+    }
+
+    /// Deserializes a pair of bounds from the kind and value of each end
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<(Bound<T>, Bound<T>), D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_struct(NAME, FIELDS, BoundsVisitor(PhantomData))
+    }
+
+    struct BoundsVisitor<T>(PhantomData<T>);
+
+    impl<'de, T> Visitor<'de> for BoundsVisitor<T>
+    where
+        T: Deserialize<'de>,
+    {
+        type Value = (Bound<T>, Bound<T>);
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a map with `start_bound` and `end_bound` fields")
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            let mut start_bound = None;
+            let mut start = None;
+            let mut end_bound = None;
+            let mut end = None;
+            while let Some(field) = map.next_key::<String>()? {
+                match field.as_str() {
+                    START_BOUND => start_bound = Some(map.next_value::<Kind>()?),
+                    START => start = map.next_value()?,
+                    END_BOUND => end_bound = Some(map.next_value::<Kind>()?),
+                    END => end = map.next_value()?,
+                    _ => {
+                        map.next_value::<de::IgnoredAny>()?;
+                    }
+                }
+            }
+
+            let start_bound = start_bound.ok_or_else(|| de::Error::missing_field(START_BOUND))?;
+            let end_bound = end_bound.ok_or_else(|| de::Error::missing_field(END_BOUND))?;
+            Ok((
+                kind::join(start_bound, start, START)?,
+                kind::join(end_bound, end, END)?,
+            ))
+        }
+    }
+}
+
+/// The kind of a `Bound`, stored as a snake case string
+mod kind {
+    use serde::de::{self, Error, Visitor};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::fmt;
+    use std::ops::Bound;
+
+    const INCLUDED: &str = "included";
+    const EXCLUDED: &str = "excluded";
+    const UNBOUNDED: &str = "unbounded";
+    const VARIANTS: &[&str] = &[INCLUDED, EXCLUDED, UNBOUNDED];
+
+    pub(super) enum Kind {
+        Included,
+        Excluded,
+        Unbounded,
+    }
+
+    impl Serialize for Kind {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.serialize_str(match self {
+                Kind::Included => INCLUDED,
+                Kind::Excluded => EXCLUDED,
+                Kind::Unbounded => UNBOUNDED,
+            })
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Kind {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_str(KindVisitor)
+        }
+    }
+
+    struct KindVisitor;
+
+    impl Visitor<'_> for KindVisitor {
+        type Value = Kind;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("`included`, `excluded` or `unbounded`")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            match v {
+                INCLUDED => Ok(Kind::Included),
+                EXCLUDED => Ok(Kind::Excluded),
+                UNBOUNDED => Ok(Kind::Unbounded),
+                _ => Err(E::unknown_variant(v, VARIANTS)),
+            }
+        }
+    }
+
+    pub(super) fn split<T>(bound: &Bound<T>) -> (Kind, Option<&T>) {
+        match bound {
+            Bound::Included(value) => (Kind::Included, Some(value)),
+            Bound::Excluded(value) => (Kind::Excluded, Some(value)),
+            Bound::Unbounded => (Kind::Unbounded, None),
+        }
+    }
+
+    pub(super) fn join<T, E>(
+        kind: Kind,
+        value: Option<T>,
+        field: &'static str,
+    ) -> Result<Bound<T>, E>
+    where
+        E: Error,
+    {
+        match (kind, value) {
+            (Kind::Included, Some(value)) => Ok(Bound::Included(value)),
+            (Kind::Excluded, Some(value)) => Ok(Bound::Excluded(value)),
+            (Kind::Unbounded, _) => Ok(Bound::Unbounded),
+            (_, None) => Err(E::missing_field(field)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{dynamo_item, dynamo_value, from_item, to_item, Item};
+    use serde_derive::{Deserialize, Serialize};
+    use std::ops::Bound;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Nested {
+        #[serde(with = "super::result")]
+        result: Result<Vec<u8>, String>,
+        #[serde(with = "super::bound")]
+        bound: Bound<String>,
+        #[serde(with = "super::bounds")]
+        bounds: (Bound<i32>, Bound<i32>),
+    }
+
+    #[test]
+    fn nested_shapes() {
+        let value = Nested {
+            result: Ok(vec![1, 2]),
+            bound: Bound::Excluded(String::from("m")),
+            bounds: (Bound::Unbounded, Bound::Excluded(-1)),
+        };
+        let item: Item = to_item(&value).unwrap();
+        assert_eq!(
+            item,
+            dynamo_item! {
+                "result": { "status": "ok", "value": [1, 2] },
+                "bound": { "bound": "excluded", "value": "m" },
+                "bounds": { "start_bound": "unbounded", "end_bound": "excluded", "end": -1 },
+            }
+        );
+        assert_eq!(from_item::<_, Nested>(item).unwrap(), value);
+
+        let value = Nested {
+            result: Err(String::from("nope")),
+            bound: Bound::Unbounded,
+            bounds: (Bound::Included(0), Bound::Included(9)),
+        };
+        let item: Item = to_item(&value).unwrap();
+        assert_eq!(
+            item["result"],
+            dynamo_value!({ "status": "err", "error": "nope" })
+        );
+        assert_eq!(item["bound"], dynamo_value!({ "bound": "unbounded" }));
+        assert_eq!(from_item::<_, Nested>(item).unwrap(), value);
+    }
+
+    #[test]
+    fn invalid_shapes() {
+        let item = dynamo_item! {
+            "result": { "status": "maybe", "value": [] },
+            "bound": { "bound": "unbounded" },
+            "bounds": { "start_bound": "unbounded", "end_bound": "unbounded" },
+        };
+        assert!(from_item::<_, Nested>(item).is_err());
+
+        let item = dynamo_item! {
+            "result": { "status": "ok", "value": [] },
+            "bound": { "bound": "unbounded" },
+            "bounds": { "start_bound": "included", "end_bound": "unbounded" },
+        };
+        let err = from_item::<_, Nested>(item).unwrap_err();
+        assert_eq!(err.to_string(), "missing field `start`");
+
+        let item = dynamo_item! {
+            "result": { "status": "ok", "value": [] },
+            "bound": { "bound": "unbounded" },
+            "bounds": { "start_bound": "unbounded", "end_bound": "unbounded" },
+        };
+        let read: Nested = from_item(item).unwrap();
+        assert_eq!(read.bounds, (Bound::Unbounded, Bound::Unbounded));
+        assert_eq!(read.result, Ok(vec![]));
+        assert_eq!(read.bound, Bound::Unbounded);
+    }
+}
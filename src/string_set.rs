@@ -8,10 +8,27 @@
 //! be beneficial to additionally annotate the field with `#[serde(default)]`
 //! and `#[serde(skip_serializing_if = "<empty check>")]`. This will make sure
 //! that the field is omitted when empty.
+//! For an `Option` field, the [`optional`] codec does both: it leaves out a
+//! set that is `None` or empty.
 //!
-//! This serializer does not check for duplicate values or an empty set.
-//! If the set contains duplicate values or is empty, DynamoDB will return a
-//! validation error when the attribute value is used.
+//! In release builds, this serializer does not check for duplicate values or
+//! an empty set. If the set contains duplicate values or is empty, DynamoDB
+//! will return a validation error when the attribute value is used. Builds
+//! with debug assertions fail with
+//! [`ErrorCode::InvalidSet`](crate::ErrorCode::InvalidSet) instead, where the
+//! set is serialized.
+//!
+//! # Containers
+//!
+//! The element order and membership are taken from whatever the value serializes as a sequence:
+//! `Vec`, `VecDeque`, `BinaryHeap`, `HashSet`, `BTreeSet`, arrays, and slices all work, for the
+//! field codec as well as the [`StringSet`] wrapper. An iterator can be collected straight into a
+//! wrapper, as in `iter.collect::<StringSet<Vec<_>>>()`.
+//!
+//! Nothing here deduplicates. A `HashSet<String>` or `BTreeSet<&str>` can't hold duplicates in the
+//! first place, so it's the simplest way to always produce a valid set; a `Vec` or `VecDeque` must
+//! be kept unique by the caller. Two strings are the same member only if they are byte-for-byte
+//! equal. Reading a set back into a `HashSet` or `BTreeSet` can't produce duplicates either.
 //!
 //! # Errors
 //!
@@ -48,7 +65,7 @@ pub(super) static NEWTYPE_SYMBOL: &str = "\u{037E}STRINGSET\u{037E}";
 
 #[inline]
 pub(crate) fn should_serialize_as_string_set(name: &str) -> bool {
-    std::ptr::eq(name, NEWTYPE_SYMBOL)
+    std::ptr::eq(name, NEWTYPE_SYMBOL) || optional::should_serialize_as_optional_set(name)
 }
 
 /// Serializes the given value as a string set
@@ -114,6 +131,12 @@ where
     }
 }
 
+impl<A> FromIterator<A> for StringSet<Vec<A>> {
+    fn from_iter<I: IntoIterator<Item = A>>(iter: I) -> Self {
+        StringSet(iter.into_iter().collect())
+    }
+}
+
 #[inline(never)]
 pub(crate) fn convert_to_set(value: crate::AttributeValue) -> crate::Result<crate::AttributeValue> {
     let vals = match value {
@@ -132,7 +155,9 @@ pub(crate) fn convert_to_set(value: crate::AttributeValue) -> crate::Result<crat
         })
         .collect::<Result<_, _>>()?;
 
-    Ok(crate::AttributeValue::Ss(set))
+    let set = crate::AttributeValue::Ss(set);
+    set.debug_check_valid()?;
+    Ok(set)
 }
 
 #[cfg(test)]
@@ -167,4 +192,106 @@ mod tests {
             crate::AttributeValue::Ss(vec!["test".to_string(), "test2".to_string(),])
         );
     }
+
+    #[test]
+    fn std_containers_and_iterators() {
+        use std::collections::{BTreeSet, BinaryHeap, HashSet, VecDeque};
+
+        fn members<T: serde::Serialize>(set: StringSet<T>) -> Vec<String> {
+            match crate::to_attribute_value(set).unwrap() {
+                crate::AttributeValue::Ss(mut members) => {
+                    members.sort();
+                    members
+                }
+                other => panic!("expected a string set, got {other:?}"),
+            }
+        }
+
+        let expected = vec![String::from("a"), String::from("b")];
+        assert_eq!(members(StringSet(VecDeque::from(["b", "a"]))), expected);
+        assert_eq!(members(StringSet(BinaryHeap::from(["b", "a"]))), expected);
+        assert_eq!(members(StringSet(HashSet::from(["b", "a"]))), expected);
+        assert_eq!(members(StringSet(BTreeSet::from(["b", "a"]))), expected);
+        assert_eq!(members(StringSet(["b", "a"])), expected);
+
+        let collected: StringSet<Vec<_>> = ["b", "a"].into_iter().map(String::from).collect();
+        assert_eq!(members(collected), expected);
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Struct {
+            #[serde(with = "crate::string_set")]
+            queue: VecDeque<String>,
+            #[serde(with = "crate::string_set")]
+            set: BTreeSet<String>,
+        }
+
+        let value = Struct {
+            queue: VecDeque::from(expected.clone()),
+            set: BTreeSet::from_iter(expected.clone()),
+        };
+        let item: crate::Item = crate::to_item(&value).unwrap();
+        assert_eq!(item["queue"], crate::AttributeValue::Ss(expected.clone()));
+        assert_eq!(item["set"], crate::AttributeValue::Ss(expected));
+        assert_eq!(crate::from_item::<_, Struct>(item).unwrap(), value);
+    }
+}
+
+/// Serializer codec for an optional string set that may be empty
+///
+/// To use, annotate an `Option` field with
+/// `#[serde(default, with = "serde_dynamo::string_set::optional")]`.
+///
+/// DynamoDB rejects empty sets, so `None` and an empty set are both left out of the item, or
+/// written as `NULL` with [`EmptySetPolicy::Null`](crate::EmptySetPolicy::Null). Both read back as
+/// `None`, as does a missing attribute thanks to `#[serde(default)]`. Other serializers see `None`
+/// as an empty sequence.
+///
+/// ```
+/// use serde_derive::{Serialize, Deserialize};
+/// use serde_dynamo::{AttributeValue, Item};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct MyStruct {
+///     #[serde(default, with = "serde_dynamo::string_set::optional")]
+///     tags: Option<Vec<String>>,
+/// }
+///
+/// let item: Item = serde_dynamo::to_item(MyStruct { tags: None }).unwrap();
+/// assert!(item.is_empty());
+/// let my_struct: MyStruct = serde_dynamo::from_item(item).unwrap();
+/// assert_eq!(my_struct, MyStruct { tags: None });
+///
+/// let item: Item = serde_dynamo::to_item(MyStruct { tags: Some(vec![String::from("new")]) }).unwrap();
+/// assert_eq!(item["tags"], AttributeValue::Ss(vec![String::from("new")]));
+/// ```
+pub mod optional {
+    pub(super) static NEWTYPE_SYMBOL: &str = "\u{037E}OPTIONALSTRINGSET\u{037E}";
+
+    #[inline]
+    pub(crate) fn should_serialize_as_optional_set(name: &str) -> bool {
+        std::ptr::eq(name, NEWTYPE_SYMBOL)
+    }
+
+    /// Serializes the given value as a string set, or leaves it out if it is `None` or empty
+    ///
+    /// See the [module documentation][crate::string_set::optional] for additional usage information.
+    pub fn serialize<T, S>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: serde::Serialize,
+        S: serde::Serializer,
+    {
+        match value {
+            Some(value) => serializer.serialize_newtype_struct(NEWTYPE_SYMBOL, value),
+            None => serializer.serialize_newtype_struct(NEWTYPE_SYMBOL, &[(); 0]),
+        }
+    }
+
+    /// Deserializes the given value as an optional set
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        T: serde::Deserialize<'de>,
+        D: serde::Deserializer<'de>,
+    {
+        <Option<T> as serde::Deserialize>::deserialize(deserializer)
+    }
 }
@@ -0,0 +1,260 @@
+use serde::de::{self, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::ser::{self, SerializeMap, SerializeTuple, Serializer};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::marker::PhantomData;
+
+/// A tuple whose elements are each stored under a tag naming their type
+///
+/// A tuple is stored as an `L`, which says nothing about what each element is. `TaggedTuple`
+/// stores each element as a map with a single key, the element's tag, so records such as audit
+/// log entries describe themselves. Reading it back returns the stored tags along with the
+/// values.
+///
+/// Tuples of up to 12 elements are supported. Serializing fails if the number of tags doesn't
+/// match the number of elements, and deserializing fails if an element isn't a map with exactly
+/// one key.
+///
+/// ```
+/// use serde_dynamo::{dynamo_value, AttributeValue, TaggedTuple};
+///
+/// let entry = TaggedTuple::new(["user", "action"], ("u-42", 7u8));
+///
+/// let value: AttributeValue = serde_dynamo::to_attribute_value(&entry).unwrap();
+/// assert_eq!(value, dynamo_value!([{ "user": "u-42" }, { "action": 7 }]));
+///
+/// let read: TaggedTuple<(String, u8)> = serde_dynamo::from_attribute_value(value).unwrap();
+/// assert_eq!(read.tags, ["user", "action"]);
+/// assert_eq!(read.value, (String::from("u-42"), 7));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaggedTuple<T> {
+    /// The tag of each element, in order
+    pub tags: Vec<String>,
+    /// The tuple
+    pub value: T,
+}
+
+impl<T> TaggedTuple<T> {
+    /// Tag the elements of `value` with `tags`
+    pub fn new<I>(tags: I, value: T) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        Self {
+            tags: tags.into_iter().map(Into::into).collect(),
+            value,
+        }
+    }
+}
+
+/// One element, serialized as a map from its tag to its value
+struct TaggedElement<'a, T>(&'a str, &'a T);
+
+impl<T> Serialize for TaggedElement<'_, T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_entry(self.0, self.1)?;
+        map.end()
+    }
+}
+
+/// Reads one element as a map with a single key, returning the key and the value
+struct TaggedElementSeed<T>(PhantomData<T>);
+
+impl<'de, T> DeserializeSeed<'de> for TaggedElementSeed<T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = (String, T);
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, T> Visitor<'de> for TaggedElementSeed<T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = (String, T);
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map with a single key")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let Some(tag) = map.next_key::<String>()? else {
+            return Err(de::Error::invalid_length(0, &self));
+        };
+        let value = map.next_value()?;
+        let mut len = 1;
+        while map.next_entry::<IgnoredAny, IgnoredAny>()?.is_some() {
+            len += 1;
+        }
+        if len > 1 {
+            return Err(de::Error::invalid_length(len, &self));
+        }
+        Ok((tag, value))
+    }
+}
+
+macro_rules! impl_tagged_tuple {
+    ($($len:literal => ($($index:tt $name:ident)+),)+) => {
+        $(
+            impl<$($name),+> Serialize for TaggedTuple<($($name,)+)>
+            where
+                $($name: Serialize,)+
+            {
+                fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: Serializer,
+                {
+                    if self.tags.len() != $len {
+                        return Err(ser::Error::custom(format_args!(
+                            "expected {} tags for a tuple of {} elements, found {}",
+                            $len,
+                            $len,
+                            self.tags.len()
+                        )));
+                    }
+                    let mut tuple = serializer.serialize_tuple($len)?;
+                    $(
+                        tuple.serialize_element(&TaggedElement(
+                            &self.tags[$index],
+                            &self.value.$index,
+                        ))?;
+                    )+
+                    tuple.end()
+                }
+            }
+
+            impl<'de, $($name),+> Deserialize<'de> for TaggedTuple<($($name,)+)>
+            where
+                $($name: Deserialize<'de>,)+
+            {
+                fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                where
+                    D: Deserializer<'de>,
+                {
+                    struct TupleVisitor<$($name),+>(PhantomData<($($name,)+)>);
+
+                    impl<'de, $($name),+> Visitor<'de> for TupleVisitor<$($name),+>
+                    where
+                        $($name: Deserialize<'de>,)+
+                    {
+                        type Value = TaggedTuple<($($name,)+)>;
+
+                        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                            write!(formatter, "a tagged tuple of {} elements", $len)
+                        }
+
+                        fn visit_seq<S>(self, mut seq: S) -> Result<Self::Value, S::Error>
+                        where
+                            S: SeqAccess<'de>,
+                        {
+                            let mut tags = Vec::with_capacity($len);
+                            let value = ($(
+                                {
+                                    let (tag, element) = seq
+                                        .next_element_seed(TaggedElementSeed::<$name>(PhantomData))?
+                                        .ok_or_else(|| de::Error::invalid_length($index, &self))?;
+                                    tags.push(tag);
+                                    element
+                                },
+                            )+);
+                            Ok(TaggedTuple { tags, value })
+                        }
+                    }
+
+                    deserializer.deserialize_tuple($len, TupleVisitor(PhantomData))
+                }
+            }
+        )+
+    };
+}
+
+impl_tagged_tuple! {
+    1 => (0 T0),
+    2 => (0 T0 1 T1),
+    3 => (0 T0 1 T1 2 T2),
+    4 => (0 T0 1 T1 2 T2 3 T3),
+    5 => (0 T0 1 T1 2 T2 3 T3 4 T4),
+    6 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5),
+    7 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6),
+    8 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7),
+    9 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8),
+    10 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9),
+    11 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10),
+    12 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TaggedTuple;
+    use crate::{dynamo_value, from_attribute_value, to_attribute_value, AttributeValue};
+    use serde_derive::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Change {
+        field: String,
+    }
+
+    #[test]
+    fn round_trips_heterogeneous_tuples() {
+        let entry = TaggedTuple::new(
+            ["at", "change", "note"],
+            (
+                1700000000u64,
+                Change {
+                    field: String::from("email"),
+                },
+                None::<String>,
+            ),
+        );
+        let value: AttributeValue = to_attribute_value(&entry).unwrap();
+        assert_eq!(
+            value,
+            dynamo_value!([
+                { "at": 1700000000 },
+                { "change": { "field": "email" } },
+                { "note": null },
+            ])
+        );
+
+        let read: TaggedTuple<(u64, Change, Option<String>)> = from_attribute_value(value).unwrap();
+        assert_eq!(read, entry);
+    }
+
+    #[test]
+    fn rejects_mismatched_shapes() {
+        let err =
+            to_attribute_value::<_, AttributeValue>(TaggedTuple::new(["a"], (1, 2))).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "expected 2 tags for a tuple of 2 elements, found 1"
+        );
+
+        for value in [
+            dynamo_value!([{ "a": 1, "b": 2 }]),
+            dynamo_value!([{}]),
+            dynamo_value!([1]),
+            dynamo_value!([{ "a": 1 }, { "b": 2 }]),
+        ] {
+            assert!(from_attribute_value::<_, TaggedTuple<(u8,)>>(value).is_err());
+        }
+    }
+}
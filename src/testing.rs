@@ -0,0 +1,166 @@
+//! Helpers for unit testing code that reads DynamoDB
+//!
+//! Pagination code follows `LastEvaluatedKey` from one query or scan response to the next. A
+//! [`Page`] bundles the items of one response with its last evaluated key, serialized from a
+//! typed key, and converts into the `QueryOutput` and `ScanOutput` of aws-sdk-dynamodb 0.35 and
+//! later and of rusoto. That way a mocked client can hand back realistic pages without a DynamoDB
+//! to talk to.
+//!
+//! ```
+//! use serde_derive::Serialize;
+//! use serde_dynamo::testing::Page;
+//! use serde_dynamo::dynamo_item;
+//!
+//! #[derive(Serialize)]
+//! struct UserKey<'a> {
+//!     id: &'a str,
+//! }
+//!
+//! let first = Page::new(vec![dynamo_item! { "id": "u-1", "name": "Arthur" }])
+//!     .with_last_evaluated_key(UserKey { id: "u-1" })?;
+//! let last = Page::new(vec![dynamo_item! { "id": "u-2", "name": "Ford" }]);
+//!
+//! assert_eq!(first.last_evaluated_key(), Some(&dynamo_item! { "id": "u-1" }));
+//! assert_eq!(last.last_evaluated_key(), None);
+//! # Ok::<(), serde_dynamo::Error>(())
+//! ```
+//!
+//! [`Page::paginate`] splits a list of items into pages the way DynamoDB would, taking each
+//! page's last evaluated key from its last item.
+//!
+//! Like [`tokens`](crate::tokens), this module requires the `testing` feature, which belongs in
+//! `[dev-dependencies]`.
+
+use crate::error::ErrorImpl;
+use crate::{Item, Items, Result};
+use serde::Serialize;
+
+/// The items of one query or scan response, and the key to continue from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page {
+    items: Items,
+    last_evaluated_key: Option<Item>,
+}
+
+impl Page {
+    /// Create the last page of a response, holding `items`
+    pub fn new<I>(items: I) -> Self
+    where
+        I: Into<Items>,
+    {
+        Page {
+            items: items.into(),
+            last_evaluated_key: None,
+        }
+    }
+
+    /// Serialize `key` as the last evaluated key, so that more pages follow this one
+    pub fn with_last_evaluated_key<K>(mut self, key: K) -> Result<Self>
+    where
+        K: Serialize,
+    {
+        self.last_evaluated_key = Some(crate::to_item(key)?);
+        Ok(self)
+    }
+
+    /// Split `items` into pages of `page_size` items
+    ///
+    /// Every page but the last has a last evaluated key made of the `key_attributes` of its last
+    /// item, such as the partition and sort key of the table or index. No items give a single
+    /// empty page. Fails if an item is missing one of the key attributes.
+    ///
+    /// ```
+    /// use serde_dynamo::testing::Page;
+    /// use serde_dynamo::dynamo_item;
+    ///
+    /// let items = (1..=5)
+    ///     .map(|n| dynamo_item! { "pk": "a", "sk": n, "body": "..." })
+    ///     .collect::<Vec<_>>();
+    /// let pages = Page::paginate(items, 2, &["pk", "sk"])?;
+    ///
+    /// assert_eq!(pages.len(), 3);
+    /// assert_eq!(pages[0].last_evaluated_key(), Some(&dynamo_item! { "pk": "a", "sk": 2 }));
+    /// assert_eq!(pages[2].items().len(), 1);
+    /// assert_eq!(pages[2].last_evaluated_key(), None);
+    /// # Ok::<(), serde_dynamo::Error>(())
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `page_size` is zero.
+    pub fn paginate<I>(items: I, page_size: usize, key_attributes: &[&str]) -> Result<Vec<Page>>
+    where
+        I: Into<Items>,
+    {
+        assert!(page_size > 0, "page_size must be greater than zero");
+        let items = Vec::<Item>::from(items.into());
+        if items.is_empty() {
+            return Ok(vec![Page::new(items)]);
+        }
+
+        let page_count = items.len().div_ceil(page_size);
+        let mut pages = Vec::with_capacity(page_count);
+        for (index, chunk) in items.chunks(page_size).enumerate() {
+            let mut page = Page::new(chunk.to_vec());
+            if index + 1 < page_count {
+                let last = &chunk[chunk.len() - 1];
+                let key = key_attributes
+                    .iter()
+                    .map(|&name| match last.get(name) {
+                        Some(value) => Ok((name.to_string(), value.clone())),
+                        None => Err(ErrorImpl::MissingAttribute(name.to_string()).into()),
+                    })
+                    .collect::<Result<std::collections::HashMap<_, _>>>()?;
+                page.last_evaluated_key = Some(Item::from(key));
+            }
+            pages.push(page);
+        }
+        Ok(pages)
+    }
+
+    /// The items
+    pub fn items(&self) -> &Items {
+        &self.items
+    }
+
+    /// The last evaluated key, unless this is the last page
+    pub fn last_evaluated_key(&self) -> Option<&Item> {
+        self.last_evaluated_key.as_ref()
+    }
+
+    /// Split the page into its items and last evaluated key
+    pub fn into_parts(self) -> (Items, Option<Item>) {
+        (self.items, self.last_evaluated_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Page;
+    use crate::{dynamo_item, ErrorCode, Item, Items};
+
+    #[test]
+    fn paginates_by_key() {
+        let items = vec![
+            dynamo_item! { "pk": "a", "sk": 1 },
+            dynamo_item! { "pk": "a", "sk": 2 },
+        ];
+
+        let pages = Page::paginate(items.clone(), 2, &["pk", "sk"]).unwrap();
+        assert_eq!(pages, [Page::new(items.clone())]);
+
+        let pages = Page::paginate(items.clone(), 1, &["pk"]).unwrap();
+        let (first, key) = pages[0].clone().into_parts();
+        assert_eq!(first, Items::from(vec![items[0].clone()]));
+        assert_eq!(key, Some(dynamo_item! { "pk": "a" }));
+        assert_eq!(pages[1], Page::new(vec![items[1].clone()]));
+
+        assert_eq!(
+            Page::paginate(Vec::<Item>::new(), 10, &["pk"]).unwrap(),
+            [Page::new(Vec::<Item>::new())]
+        );
+
+        let err = Page::paginate(items, 1, &["id"]).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::MissingAttribute);
+    }
+}
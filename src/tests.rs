@@ -36,6 +36,95 @@ fn adjacently_tagged_enum() {
     round_trip(Subject::Two { two: 2 });
 }
 
+#[test]
+fn adjacently_tagged_enum_with_flattened_content() {
+    use crate::{
+        from_item_ref_with_config, from_item_with_config, to_item_with_config, AttributeValue,
+        DeserializerConfig, SerializerConfig,
+    };
+
+    #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+    #[serde(tag = "type", content = "value", rename_all = "snake_case")]
+    enum Subject {
+        Struct { one: u8, two: String },
+        Newtype(String),
+        Empty {},
+        Unit,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+    struct Outer {
+        subject: Subject,
+    }
+
+    let ser = SerializerConfig::new().flattened_adjacent_content("type", "value");
+    let de = DeserializerConfig::new().flattened_adjacent_content("type", "value");
+    let round_trip = |subject: Subject| {
+        let item: Item = to_item_with_config(&subject, ser.clone()).unwrap();
+        assert_eq!(
+            from_item_ref_with_config::<Subject>(&item, de.clone()).unwrap(),
+            subject
+        );
+        assert_eq!(
+            from_item_with_config::<_, Subject>(item.clone(), de.clone()).unwrap(),
+            subject
+        );
+        item
+    };
+
+    let item = round_trip(Subject::Struct {
+        one: 1,
+        two: String::from("2"),
+    });
+    assert_eq!(item.len(), 3);
+    assert_eq!(item["one"], AttributeValue::N(String::from("1")));
+    let item = round_trip(Subject::Newtype(String::from("x")));
+    assert_eq!(item["value"], AttributeValue::S(String::from("x")));
+    round_trip(Subject::Empty {});
+    round_trip(Subject::Unit);
+
+    // Nested enums are flattened into their own map
+    let outer = Outer {
+        subject: Subject::Struct {
+            one: 1,
+            two: String::from("2"),
+        },
+    };
+    let item: Item = to_item_with_config(&outer, ser.clone()).unwrap();
+    let AttributeValue::M(subject) = &item["subject"] else {
+        panic!("expected a map: {item:?}");
+    };
+    assert!(subject.contains_key("one"));
+    assert_eq!(
+        from_item_with_config::<_, Outer>(item.clone(), de.clone()).unwrap(),
+        outer
+    );
+    assert_eq!(
+        from_item_ref_with_config::<Outer>(&item, de).unwrap(),
+        outer
+    );
+
+    // Items written without the option still read
+    let item: Item = to_item(&outer).unwrap();
+    let de = DeserializerConfig::new().flattened_adjacent_content("type", "value");
+    assert_eq!(from_item_with_config::<_, Outer>(item, de).unwrap(), outer);
+
+    #[derive(Serialize)]
+    #[serde(tag = "type", content = "value")]
+    enum Colliding {
+        Variant {
+            #[serde(rename = "type")]
+            kind: u8,
+        },
+    }
+    assert_eq!(
+        to_item_with_config::<_, Item>(Colliding::Variant { kind: 1 }, ser)
+            .unwrap_err()
+            .to_string(),
+        "Content field `type` can't be flattened beside the tag of the same name"
+    );
+}
+
 #[test]
 fn untagged_enum() {
     #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
@@ -101,6 +190,71 @@ fn error_eq() {
     );
 }
 
+#[test]
+fn error_codes() {
+    use crate::{error::ErrorImpl, Error, ErrorCode};
+
+    let err: Error = ErrorImpl::ExpectedNum.into();
+    assert_eq!(err.code(), ErrorCode::ExpectedNum);
+    assert_eq!(err.to_code(), 2006);
+
+    let nested = err.in_attribute("inner").in_attribute("outer");
+    assert_eq!(nested.code(), ErrorCode::ExpectedNum);
+
+    let err: Error = ErrorImpl::Message(String::from("one")).into();
+    assert_eq!(err.to_code(), ErrorCode::Message.to_code());
+}
+
+#[test]
+fn error_zero_for_nonzero() {
+    use crate::{Error, ErrorCode};
+    use serde::de::{Error as _, Unexpected};
+
+    let err = Error::invalid_value(Unexpected::Signed(0), &"a nonzero i32");
+    assert_eq!(err.code(), ErrorCode::ZeroForNonZero);
+    assert!(err
+        .to_string()
+        .starts_with("Found `0` where a nonzero i32 "));
+
+    // Other zeros, and other values for nonzero integers, are ordinary invalid values
+    for (unexpected, expected) in [
+        (Unexpected::Unsigned(0), "a nonzero number of bytes"),
+        (Unexpected::Unsigned(0), "a u64 in 1..10"),
+        (Unexpected::Unsigned(300), "a nonzero u8"),
+    ] {
+        let err = Error::invalid_value(unexpected, &expected);
+        assert_eq!(err.code(), ErrorCode::Message, "{expected}");
+    }
+}
+
+#[test]
+fn error_attribute_paths() {
+    use crate::{error::ErrorImpl, path, Error};
+
+    let err: Error = ErrorImpl::ExpectedNum.into();
+    assert_eq!(err.attribute_path(), None);
+
+    let err = err
+        .in_attribute("key2")
+        .in_path("[3]")
+        .in_attribute("list")
+        .in_attribute("a.b");
+    assert_eq!(err.attribute_path(), Some(r"a\.b.list[3].key2"));
+    assert_eq!(
+        err.to_string(),
+        r"Attribute `a\.b.list[3].key2`: Expected num"
+    );
+    assert_eq!(
+        path::parse(err.attribute_path().unwrap()).unwrap(),
+        vec![
+            path::Segment::Key("a.b".into()),
+            path::Segment::Key("list".into()),
+            path::Segment::Index(3),
+            path::Segment::Key("key2".into()),
+        ]
+    );
+}
+
 #[cfg(test)]
 mod from_items {
     use crate::{error::ErrorImpl, from_items, to_attribute_value, AttributeValue, Error, Items};
@@ -171,6 +325,188 @@ mod from_items {
     }
 }
 
+#[cfg(test)]
+mod from_optional_item {
+    use crate::{from_optional_item, to_attribute_value, AttributeValue};
+    use serde_derive::Deserialize;
+    use std::collections::HashMap;
+
+    #[derive(Debug, PartialEq, Eq, Deserialize)]
+    struct User {
+        id: String,
+    }
+
+    #[test]
+    fn some_and_none() {
+        let item: HashMap<String, AttributeValue> =
+            HashMap::from([(String::from("id"), to_attribute_value("one").unwrap())]);
+        let user: Option<User> = from_optional_item(Some(item)).unwrap();
+        assert_eq!(
+            user,
+            Some(User {
+                id: String::from("one")
+            })
+        );
+
+        let user: Option<User> =
+            from_optional_item(None::<HashMap<String, AttributeValue>>).unwrap();
+        assert_eq!(user, None);
+
+        let item = HashMap::from([(String::from("id"), AttributeValue::Bool(true))]);
+        assert!(from_optional_item::<_, User>(Some(item)).is_err());
+    }
+}
+
+#[cfg(feature = "aws-sdk-dynamodb+1")]
+mod from_unprocessed_items {
+    use crate::aws_sdk_dynamodb_1::{from_unprocessed_items, to_item, UnprocessedWrite};
+    use __aws_sdk_dynamodb_1::types::{DeleteRequest, PutRequest, WriteRequest};
+    use serde_derive::{Deserialize, Serialize};
+    use std::collections::HashMap;
+
+    #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+    struct User {
+        id: String,
+        name: String,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+    struct UserKey {
+        id: String,
+    }
+
+    #[test]
+    fn puts_and_deletes() {
+        let user = User {
+            id: String::from("one"),
+            name: String::from("Arthur"),
+        };
+        let key = UserKey {
+            id: String::from("two"),
+        };
+        let requests = vec![
+            WriteRequest::builder()
+                .put_request(
+                    PutRequest::builder()
+                        .set_item(Some(to_item(&user).unwrap()))
+                        .build()
+                        .unwrap(),
+                )
+                .build(),
+            WriteRequest::builder()
+                .delete_request(
+                    DeleteRequest::builder()
+                        .set_key(Some(to_item(&key).unwrap()))
+                        .build()
+                        .unwrap(),
+                )
+                .build(),
+        ];
+
+        let unprocessed = from_unprocessed_items::<User, UserKey>(Some(HashMap::from([(
+            String::from("users"),
+            requests,
+        )])))
+        .unwrap();
+        assert_eq!(
+            unprocessed,
+            HashMap::from([(
+                String::from("users"),
+                vec![UnprocessedWrite::Put(user), UnprocessedWrite::Delete(key)],
+            )])
+        );
+
+        let unprocessed = from_unprocessed_items::<User, UserKey>(None).unwrap();
+        assert!(unprocessed.is_empty());
+    }
+
+    #[test]
+    fn request_without_a_write() {
+        let requests = vec![WriteRequest::builder().build()];
+        let err = from_unprocessed_items::<User, UserKey>(Some(HashMap::from([(
+            String::from("users"),
+            requests,
+        )])))
+        .unwrap_err();
+        assert_eq!(err.code(), crate::ErrorCode::InvalidWriteRequest);
+        assert_eq!(
+            err.to_string(),
+            "Invalid write request for table `users`: it has neither a put nor a delete request"
+        );
+    }
+}
+
+#[cfg(feature = "aws-sdk-dynamodb+1")]
+mod sdk_table {
+    use crate::aws_sdk_dynamodb_1::table_key;
+    use __aws_sdk_dynamodb_1::types::AttributeValue;
+    use serde_derive::Serialize;
+    use std::collections::HashMap;
+
+    #[derive(Serialize)]
+    struct MessageKey {
+        channel: String,
+        sent_at: u64,
+    }
+
+    fn s(value: &str) -> AttributeValue {
+        AttributeValue::S(String::from(value))
+    }
+
+    #[test]
+    fn map_key() {
+        let key = MessageKey {
+            channel: String::from("general"),
+            sent_at: 1700000000,
+        };
+        assert_eq!(
+            table_key("channel", Some("sent_at"), &key).unwrap(),
+            HashMap::from([
+                (String::from("channel"), s("general")),
+                (
+                    String::from("sent_at"),
+                    AttributeValue::N(String::from("1700000000"))
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn tuple_key() {
+        assert_eq!(
+            table_key("channel", Some("sent_at"), ("general", 1700000000)).unwrap(),
+            HashMap::from([
+                (String::from("channel"), s("general")),
+                (
+                    String::from("sent_at"),
+                    AttributeValue::N(String::from("1700000000"))
+                ),
+            ])
+        );
+
+        // Without a sort key, the tuple is the partition key value
+        assert_eq!(
+            table_key("channel", None, ("general", "random")).unwrap(),
+            HashMap::from([(
+                String::from("channel"),
+                AttributeValue::L(vec![s("general"), s("random")])
+            )])
+        );
+    }
+
+    #[test]
+    fn scalar_key() {
+        assert_eq!(
+            table_key("channel", None, "general").unwrap(),
+            HashMap::from([(String::from("channel"), s("general"))])
+        );
+        assert_eq!(
+            table_key("channel", Some("sent_at"), "general").unwrap(),
+            HashMap::from([(String::from("channel"), s("general"))])
+        );
+    }
+}
+
 // Tests for various types being used as map keys
 #[cfg(test)]
 mod map_key {
@@ -646,3 +982,314 @@ mod sets {
         ));
     }
 }
+
+#[test]
+fn newtype_structs_with_wrappers() {
+    use crate::{
+        dynamo_item, from_item_ref_with_config, from_item_with_config, to_item_with_config,
+        DeserializerConfig, NewtypeStructs, SerializerConfig,
+    };
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct UserId(String);
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct OrderId(String);
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Row {
+        owner: UserId,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct OrderRow {
+        owner: OrderId,
+    }
+
+    let row = Row {
+        owner: UserId(String::from("u1")),
+    };
+    for (setting, expected) in [
+        (NewtypeStructs::Transparent, dynamo_item! { "owner": "u1" }),
+        (
+            NewtypeStructs::Indexed,
+            dynamo_item! { "owner": { "0": "u1" } },
+        ),
+        (
+            NewtypeStructs::Named("kind"),
+            dynamo_item! { "owner": { "kind": "UserId", "0": "u1" } },
+        ),
+    ] {
+        let ser = SerializerConfig::new().newtype_structs(setting);
+        let de = || DeserializerConfig::new().newtype_structs(setting);
+
+        let item: Item = to_item_with_config(&row, ser).unwrap();
+        assert_eq!(item, expected, "{setting:?}");
+        assert_eq!(
+            from_item_with_config::<_, Row>(item.clone(), de()).unwrap(),
+            row
+        );
+        assert_eq!(from_item_ref_with_config::<Row>(&item, de()).unwrap(), row);
+    }
+
+    let de = || DeserializerConfig::new().newtype_structs(NewtypeStructs::Named("kind"));
+    let item = dynamo_item! { "owner": { "kind": "UserId", "0": "u1" } };
+    assert_eq!(
+        from_item_with_config::<_, OrderRow>(item.clone(), de())
+            .unwrap_err()
+            .to_string(),
+        "Expected a `OrderId` value, found a `UserId` value"
+    );
+    assert_eq!(
+        from_item_ref_with_config::<OrderRow>(&item, de())
+            .unwrap_err()
+            .to_string(),
+        "Expected a `OrderId` value, found a `UserId` value"
+    );
+    assert!(from_item_with_config::<_, Row>(dynamo_item! { "owner": "u1" }, de()).is_err());
+}
+
+#[cfg(feature = "aws-sdk-dynamodb+1")]
+mod sdk_from_item_ref {
+    use crate::aws_sdk_dynamodb_1::{from_item_ref, from_items_ref, to_item};
+    use serde_derive::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Post {
+        id: String,
+        tags: Vec<String>,
+        author: Author,
+        draft: Option<bool>,
+        body: Vec<u8>,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Author {
+        name: String,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct PostId {
+        id: String,
+    }
+
+    #[test]
+    fn reads_borrowed_sdk_items() {
+        let post = Post {
+            id: String::from("p-1"),
+            tags: vec![String::from("rust"), String::from("serde")],
+            author: Author {
+                name: String::from("Arthur"),
+            },
+            draft: None,
+            body: vec![1, 2, 3],
+        };
+        let item = to_item(&post).unwrap();
+
+        let read: Post = from_item_ref(&item).unwrap();
+        assert_eq!(read, post);
+
+        let page = vec![item.clone(), item];
+        let ids: Vec<PostId> = from_items_ref(&page).unwrap();
+        assert_eq!(
+            ids,
+            [
+                PostId {
+                    id: String::from("p-1")
+                },
+                PostId {
+                    id: String::from("p-1")
+                },
+            ]
+        );
+        assert!(from_item_ref::<Author>(&page[0]).is_err());
+    }
+}
+
+#[test]
+fn none_values() {
+    use crate::{
+        dynamo_item, dynamo_value, from_item_ref, from_item_with_config,
+        to_attribute_value_with_config, to_item_with_config, AttributeValue, DeserializerConfig,
+        ErrorCode, NonePolicy, SerdeDynamo, SerializerConfig,
+    };
+    use serde_json::{json, Value};
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Document {
+        id: String,
+        body: Option<Value>,
+        marker: Option<()>,
+        counts: BTreeMap<String, Option<u32>>,
+    }
+
+    let document = Document {
+        id: String::from("d1"),
+        body: Some(Value::Null),
+        marker: Some(()),
+        counts: BTreeMap::from([(String::from("a"), Some(1)), (String::from("b"), None)]),
+    };
+
+    // By default, `Some` of a null-like value collapses into `None`
+    let item: Item = to_item(&document).unwrap();
+    assert_eq!(
+        item,
+        dynamo_item! { "id": "d1", "body": null, "marker": null, "counts": { "a": 1, "b": null } }
+    );
+    let read: Document = from_item(item).unwrap();
+    assert_eq!(read.body, None);
+    assert_eq!(read.marker, None);
+    assert_eq!(read.counts, document.counts);
+
+    // With `Omit`, `None` is left out and every document keeps its shape
+    let dynamo = SerdeDynamo::new(
+        SerializerConfig::new().none_values(NonePolicy::Omit),
+        DeserializerConfig::new().none_values(NonePolicy::Omit),
+    );
+    let item: Item = dynamo.to_item(&document).unwrap();
+    assert_eq!(
+        item,
+        dynamo_item! { "id": "d1", "body": null, "marker": null, "counts": { "a": 1 } }
+    );
+    let read: Document = dynamo.from_item(item.clone()).unwrap();
+    assert_eq!(read.body, Some(Value::Null));
+    assert_eq!(read.marker, Some(()));
+    assert_eq!(read.counts, BTreeMap::from([(String::from("a"), Some(1))]));
+    let read: Document = dynamo.from_item_ref(&item).unwrap();
+    assert_eq!(read.body, Some(Value::Null));
+
+    for body in [
+        None,
+        Some(Value::Null),
+        Some(json!({ "a": null, "b": [null, 1] })),
+    ] {
+        let document = Document {
+            id: String::from("d2"),
+            body,
+            marker: None,
+            counts: BTreeMap::new(),
+        };
+        let item: Item = dynamo.to_item(&document).unwrap();
+        assert_eq!(dynamo.from_item::<_, Document>(item).unwrap(), document);
+    }
+
+    // Nulls inside JSON values are the same in both settings
+    let value = json!({ "a": null, "b": [null] });
+    let expected = dynamo_value!({ "a": null, "b": [null] });
+    let written: AttributeValue = crate::to_attribute_value(&value).unwrap();
+    assert_eq!(written, expected);
+    let written: AttributeValue = dynamo.to_attribute_value(&value).unwrap();
+    assert_eq!(written, expected);
+    assert_eq!(
+        dynamo.from_attribute_value::<_, Value>(written).unwrap(),
+        value
+    );
+
+    // `None` can't be left out of a list
+    let config = SerializerConfig::new().none_values(NonePolicy::Omit);
+    let err = to_attribute_value_with_config::<_, AttributeValue>(vec![Some(1), None], config)
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::OmittedNone);
+
+    // A missing field is `None` with either setting, and `NULL` is `None` by default
+    let item = dynamo_item! { "id": "d3", "marker": null, "counts": {} };
+    let config = DeserializerConfig::new().none_values(NonePolicy::Omit);
+    let read: Document = from_item_with_config(item.clone(), config).unwrap();
+    assert_eq!((read.body, read.marker), (None, Some(())));
+    let read: Document = from_item_ref(&item).unwrap();
+    assert_eq!((&read.body, read.marker), (&None, None));
+
+    let config = SerializerConfig::new().none_values(NonePolicy::Null);
+    let item: Item = to_item_with_config(&read, config).unwrap();
+    assert_eq!(item["body"], AttributeValue::Null(true));
+}
+
+#[cfg(feature = "aws-sdk-dynamodb+1")]
+mod sdk_prelude {
+    use crate::sdk::prelude::*;
+    use serde_derive::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Session {
+        id: String,
+        ttl: u64,
+    }
+
+    #[test]
+    fn functions_take_and_return_sdk_types() {
+        let session = Session {
+            id: String::from("s-1"),
+            ttl: 1700000000,
+        };
+        let item = to_item(&session).unwrap();
+        assert_eq!(item["id"], AttributeValue::S(String::from("s-1")));
+        assert_eq!(
+            to_attribute_value(session.ttl).unwrap(),
+            AttributeValue::N(String::from("1700000000"))
+        );
+
+        let read: Session = from_item_ref(&item).unwrap();
+        assert_eq!(read, session);
+        let read: Vec<Session> = from_items(vec![item]).unwrap();
+        assert_eq!(read, vec![session]);
+    }
+}
+
+#[cfg(all(feature = "aws-sdk-dynamodb+1", feature = "testing"))]
+#[test]
+fn testing_pages_become_sdk_outputs() {
+    use crate::testing::Page;
+    use __aws_sdk_dynamodb_1::operation::query::QueryOutput;
+    use __aws_sdk_dynamodb_1::types::AttributeValue as SdkAttributeValue;
+
+    let items = vec![
+        crate::dynamo_item! { "pk": "a", "sk": 1 },
+        crate::dynamo_item! { "pk": "a", "sk": 2 },
+    ];
+    let pages = Page::paginate(items, 1, &["pk", "sk"]).unwrap();
+
+    let first = QueryOutput::from(pages[0].clone());
+    assert_eq!(first.count(), 1);
+    assert_eq!(first.items().len(), 1);
+    assert_eq!(
+        first.last_evaluated_key().unwrap()["sk"],
+        SdkAttributeValue::N(String::from("1"))
+    );
+    let last = QueryOutput::from(pages[1].clone());
+    assert_eq!(last.last_evaluated_key(), None);
+}
+
+#[cfg(feature = "rusoto_dynamodb+0_48")]
+#[test]
+fn untyped_rusoto_values_fail_owned_reads() {
+    use crate::rusoto_dynamodb_0_48::{from_attribute_value, from_item, from_items};
+    use crate::ErrorCode;
+    use __rusoto_dynamodb_0_48::AttributeValue;
+    use std::collections::HashMap;
+
+    #[derive(Debug, serde_derive::Deserialize)]
+    struct User {
+        #[allow(dead_code)]
+        id: String,
+    }
+
+    // A value with no field set, as `Default` builds it, holds none of DynamoDB's types
+    let item = HashMap::from([
+        (
+            String::from("id"),
+            AttributeValue {
+                s: Some(String::from("u-1")),
+                ..AttributeValue::default()
+            },
+        ),
+        (String::from("name"), AttributeValue::default()),
+    ]);
+
+    let err = from_item::<User>(item.clone()).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::UnsupportedType);
+    let err = from_items::<User>(vec![item]).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::UnsupportedType);
+    let err = from_attribute_value::<String>(AttributeValue::default()).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::UnsupportedType);
+}
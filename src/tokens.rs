@@ -0,0 +1,534 @@
+//! Assertions on the serializer calls a `Serialize` implementation makes
+//!
+//! When writing a `with` module or a hand-written `Serialize` implementation, checking the final
+//! [`AttributeValue`] doesn't show *how* it was produced: a `Vec<String>` and a string set wrapper
+//! can both end up as a list of strings, but only one of them goes through the set path.
+//! [`assert_ser_tokens_dynamo`] checks the exact sequence of serializer calls, in the style of
+//! [serde_test], and returns the resulting [`AttributeValue`] for further assertions.
+//!
+//! # Examples
+//!
+//! ```
+//! # use serde_derive::Serialize;
+//! use serde_dynamo::tokens::{assert_ser_tokens_dynamo, Token};
+//! use serde_dynamo::AttributeValue;
+//! use std::collections::HashMap;
+//!
+//! #[derive(Serialize)]
+//! struct User {
+//!     #[serde(with = "serde_dynamo::string_set")]
+//!     roles: Vec<String>,
+//! }
+//!
+//! let user = User {
+//!     roles: vec![String::from("admin")],
+//! };
+//!
+//! let value = assert_ser_tokens_dynamo(
+//!     &user,
+//!     &[
+//!         Token::Struct { name: "User", len: 1 },
+//!         Token::Str("roles"),
+//!         Token::NewtypeStruct { name: "\u{037E}STRINGSET\u{037E}" },
+//!         Token::Seq { len: Some(1) },
+//!         Token::Str("admin"),
+//!         Token::SeqEnd,
+//!         Token::StructEnd,
+//!     ],
+//! );
+//!
+//! assert_eq!(
+//!     value,
+//!     AttributeValue::M(HashMap::from([(
+//!         String::from("roles"),
+//!         AttributeValue::Ss(vec![String::from("admin")]),
+//!     )]))
+//! );
+//! ```
+//!
+//! Struct field names are checked as [`Token::Str`] tokens directly before the field's value.
+//!
+//! This module requires the `testing` feature:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! serde_dynamo = { version = "4", features = ["testing"] }
+//! ```
+//!
+//! [serde_test]: https://docs.rs/serde_test
+
+use crate::{AttributeValue, Error, Result};
+use serde::{ser, Serialize};
+use std::cell::Cell;
+use std::fmt;
+
+/// A single serializer call
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Token {
+    /// `serialize_bool`
+    Bool(bool),
+    /// `serialize_i8`
+    I8(i8),
+    /// `serialize_i16`
+    I16(i16),
+    /// `serialize_i32`
+    I32(i32),
+    /// `serialize_i64`
+    I64(i64),
+    /// `serialize_i128`
+    I128(i128),
+    /// `serialize_u8`
+    U8(u8),
+    /// `serialize_u16`
+    U16(u16),
+    /// `serialize_u32`
+    U32(u32),
+    /// `serialize_u64`
+    U64(u64),
+    /// `serialize_u128`
+    U128(u128),
+    /// `serialize_f32`
+    F32(f32),
+    /// `serialize_f64`
+    F64(f64),
+    /// `serialize_char`
+    Char(char),
+    /// `serialize_str`, or a struct field name
+    Str(&'static str),
+    /// `serialize_bytes`
+    Bytes(&'static [u8]),
+    /// `serialize_none`
+    None,
+    /// `serialize_some`, followed by the value
+    Some,
+    /// `serialize_unit`
+    Unit,
+    /// `serialize_unit_struct`
+    UnitStruct {
+        /// The struct name
+        name: &'static str,
+    },
+    /// `serialize_unit_variant`
+    UnitVariant {
+        /// The enum name
+        name: &'static str,
+        /// The variant name
+        variant: &'static str,
+    },
+    /// `serialize_newtype_struct`, followed by the value
+    NewtypeStruct {
+        /// The struct name
+        name: &'static str,
+    },
+    /// `serialize_newtype_variant`, followed by the value
+    NewtypeVariant {
+        /// The enum name
+        name: &'static str,
+        /// The variant name
+        variant: &'static str,
+    },
+    /// `serialize_seq`, followed by the elements and [`Token::SeqEnd`]
+    Seq {
+        /// The length hint
+        len: Option<usize>,
+    },
+    /// The end of a sequence
+    SeqEnd,
+    /// `serialize_tuple`, followed by the elements and [`Token::TupleEnd`]
+    Tuple {
+        /// The tuple length
+        len: usize,
+    },
+    /// The end of a tuple
+    TupleEnd,
+    /// `serialize_tuple_struct`, followed by the fields and [`Token::TupleStructEnd`]
+    TupleStruct {
+        /// The struct name
+        name: &'static str,
+        /// The number of fields
+        len: usize,
+    },
+    /// The end of a tuple struct
+    TupleStructEnd,
+    /// `serialize_tuple_variant`, followed by the fields and [`Token::TupleVariantEnd`]
+    TupleVariant {
+        /// The enum name
+        name: &'static str,
+        /// The variant name
+        variant: &'static str,
+        /// The number of fields
+        len: usize,
+    },
+    /// The end of a tuple variant
+    TupleVariantEnd,
+    /// `serialize_map`, followed by the keys and values and [`Token::MapEnd`]
+    Map {
+        /// The length hint
+        len: Option<usize>,
+    },
+    /// The end of a map
+    MapEnd,
+    /// `serialize_struct`, followed by the fields and [`Token::StructEnd`]
+    Struct {
+        /// The struct name
+        name: &'static str,
+        /// The number of fields
+        len: usize,
+    },
+    /// The end of a struct
+    StructEnd,
+    /// `serialize_struct_variant`, followed by the fields and [`Token::StructVariantEnd`]
+    StructVariant {
+        /// The enum name
+        name: &'static str,
+        /// The variant name
+        variant: &'static str,
+        /// The number of fields
+        len: usize,
+    },
+    /// The end of a struct variant
+    StructVariantEnd,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// Assert that serializing `value` makes exactly the serializer calls in `tokens`, and return the
+/// [`AttributeValue`] it serializes to.
+///
+/// # Panics
+///
+/// Panics if the calls differ from `tokens`, or if `value` fails to serialize.
+#[track_caller]
+pub fn assert_ser_tokens_dynamo<T>(value: &T, tokens: &[Token]) -> AttributeValue
+where
+    T: ?Sized + Serialize,
+{
+    let remaining = Cell::new(tokens);
+    if let Err(err) = value.serialize(TokenSerializer {
+        remaining: &remaining,
+    }) {
+        panic!("{}", err);
+    }
+    let remaining = remaining.get();
+    if !remaining.is_empty() {
+        panic!(
+            "{} expected tokens were not serialized: {:?}",
+            remaining.len(),
+            remaining
+        );
+    }
+
+    match crate::to_attribute_value(value) {
+        Ok(attribute_value) => attribute_value,
+        Err(err) => panic!("{}", err),
+    }
+}
+
+/// A serializer that checks each call against the next expected token
+#[derive(Clone, Copy)]
+struct TokenSerializer<'a> {
+    remaining: &'a Cell<&'a [Token]>,
+}
+
+impl TokenSerializer<'_> {
+    fn next(&self, actual: Token) -> Result<()> {
+        match self.remaining.get().split_first() {
+            Some((expected, rest)) if *expected == actual => {
+                self.remaining.set(rest);
+                Ok(())
+            }
+            Some((expected, _)) => Err(ser::Error::custom(format_args!(
+                "expected {expected} but serialized {actual}"
+            ))),
+            None => Err(ser::Error::custom(format_args!(
+                "serialized {actual} after the last expected token"
+            ))),
+        }
+    }
+}
+
+macro_rules! serialize_token {
+    ($($method:ident($ty:ty) => $token:ident,)*) => {
+        $(
+            fn $method(self, v: $ty) -> Result<()> {
+                self.next(Token::$token(v))
+            }
+        )*
+    };
+}
+
+impl<'a> ser::Serializer for TokenSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    serialize_token! {
+        serialize_bool(bool) => Bool,
+        serialize_i8(i8) => I8,
+        serialize_i16(i16) => I16,
+        serialize_i32(i32) => I32,
+        serialize_i64(i64) => I64,
+        serialize_i128(i128) => I128,
+        serialize_u8(u8) => U8,
+        serialize_u16(u16) => U16,
+        serialize_u32(u32) => U32,
+        serialize_u64(u64) => U64,
+        serialize_u128(u128) => U128,
+        serialize_f32(f32) => F32,
+        serialize_f64(f64) => F64,
+        serialize_char(char) => Char,
+    }
+
+    fn serialize_str(self, v: &str) -> Result<()> {
+        match self.remaining.get().first() {
+            Some(Token::Str(expected)) if *expected == v => self.next(Token::Str(expected)),
+            _ => self.next(Token::Str(leak(v))),
+        }
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<()> {
+        match self.remaining.get().first() {
+            Some(Token::Bytes(expected)) if *expected == v => self.next(Token::Bytes(expected)),
+            _ => self.next(Token::Bytes(leak(v))),
+        }
+    }
+
+    fn serialize_none(self) -> Result<()> {
+        self.next(Token::None)
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.next(Token::Some)?;
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<()> {
+        self.next(Token::Unit)
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<()> {
+        self.next(Token::UnitStruct { name })
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<()> {
+        self.next(Token::UnitVariant { name, variant })
+    }
+
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.next(Token::NewtypeStruct { name })?;
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.next(Token::NewtypeVariant { name, variant })?;
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self> {
+        self.next(Token::Seq { len })?;
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self> {
+        self.next(Token::Tuple { len })?;
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(self, name: &'static str, len: usize) -> Result<Self> {
+        self.next(Token::TupleStruct { name, len })?;
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self> {
+        self.next(Token::TupleVariant { name, variant, len })?;
+        Ok(self)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self> {
+        self.next(Token::Map { len })?;
+        Ok(self)
+    }
+
+    fn serialize_struct(self, name: &'static str, len: usize) -> Result<Self> {
+        self.next(Token::Struct { name, len })?;
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self> {
+        self.next(Token::StructVariant { name, variant, len })?;
+        Ok(self)
+    }
+}
+
+/// Leak a mismatched value so it can be shown in a [`Token`]. Only reached on the way to a panic.
+fn leak<T>(value: &T) -> &'static T
+where
+    T: ?Sized + ToOwned,
+    T::Owned: Into<Box<T>>,
+{
+    Box::leak(value.to_owned().into())
+}
+
+macro_rules! serialize_compound {
+    ($trait:ident, $method:ident, $end:ident) => {
+        impl ser::$trait for TokenSerializer<'_> {
+            type Ok = ();
+            type Error = Error;
+
+            fn $method<T>(&mut self, value: &T) -> Result<()>
+            where
+                T: ?Sized + Serialize,
+            {
+                value.serialize(*self)
+            }
+
+            fn end(self) -> Result<()> {
+                self.next(Token::$end)
+            }
+        }
+    };
+}
+
+serialize_compound!(SerializeSeq, serialize_element, SeqEnd);
+serialize_compound!(SerializeTuple, serialize_element, TupleEnd);
+serialize_compound!(SerializeTupleStruct, serialize_field, TupleStructEnd);
+serialize_compound!(SerializeTupleVariant, serialize_field, TupleVariantEnd);
+
+impl ser::SerializeMap for TokenSerializer<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        key.serialize(*self)
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(*self)
+    }
+
+    fn end(self) -> Result<()> {
+        self.next(Token::MapEnd)
+    }
+}
+
+macro_rules! serialize_fields {
+    ($trait:ident, $end:ident) => {
+        impl ser::$trait for TokenSerializer<'_> {
+            type Ok = ();
+            type Error = Error;
+
+            fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+            where
+                T: ?Sized + Serialize,
+            {
+                self.next(Token::Str(key))?;
+                value.serialize(*self)
+            }
+
+            fn end(self) -> Result<()> {
+                self.next(Token::$end)
+            }
+        }
+    };
+}
+
+serialize_fields!(SerializeStruct, StructEnd);
+serialize_fields!(SerializeStructVariant, StructVariantEnd);
+
+#[cfg(test)]
+mod tests {
+    use super::{assert_ser_tokens_dynamo, Token};
+    use crate::AttributeValue;
+    use serde_derive::Serialize;
+
+    #[derive(Serialize)]
+    enum Shape {
+        Circle { radius: u8 },
+        Point,
+    }
+
+    #[test]
+    fn enums() {
+        let value = assert_ser_tokens_dynamo(
+            &vec![Shape::Circle { radius: 2 }, Shape::Point],
+            &[
+                Token::Seq { len: Some(2) },
+                Token::StructVariant {
+                    name: "Shape",
+                    variant: "Circle",
+                    len: 1,
+                },
+                Token::Str("radius"),
+                Token::U8(2),
+                Token::StructVariantEnd,
+                Token::UnitVariant {
+                    name: "Shape",
+                    variant: "Point",
+                },
+                Token::SeqEnd,
+            ],
+        );
+        assert!(matches!(value, AttributeValue::L(l) if l.len() == 2));
+    }
+
+    #[test]
+    #[should_panic(expected = "expected U8(3) but serialized U8(2)")]
+    fn mismatch() {
+        assert_ser_tokens_dynamo(&Some(2u8), &[Token::Some, Token::U8(3)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "1 expected tokens were not serialized")]
+    fn leftover_tokens() {
+        assert_ser_tokens_dynamo(&(), &[Token::Unit, Token::Unit]);
+    }
+}
@@ -0,0 +1,137 @@
+use crate::{de::from_item_ref, error::ErrorImpl, Item, Result};
+use serde::Deserialize;
+
+/// Try deserializing `item` as each of several candidate types, in order
+///
+/// Tables that hold several versions or kinds of record need to find out which type an item is.
+/// Add each candidate with [`or`](TryChain::or), along with a function that wraps it in a
+/// common type, such as a variant of an enum, and call [`finish`](TryChain::finish). The first
+/// candidate that deserializes wins, and later ones aren't tried. If none does, the error lists
+/// why each one failed.
+///
+/// The item is borrowed, so it isn't cloned for each attempt. See [`from_item_ref`].
+///
+/// ```
+/// use serde_dynamo::{dynamo_item, try_chain};
+/// # use serde_derive::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct UserV2 {
+///     id: String,
+///     emails: Vec<String>,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct UserV1 {
+///     id: String,
+///     email: String,
+/// }
+///
+/// enum User {
+///     V2(UserV2),
+///     V1(UserV1),
+/// }
+///
+/// let item = dynamo_item! { "id": "u-1", "email": "ann@example.com" };
+/// let user = try_chain(&item).or(User::V2).or(User::V1).finish()?;
+/// assert!(matches!(user, User::V1(v1) if v1.email == "ann@example.com"));
+///
+/// let item = dynamo_item! { "id": "u-2" };
+/// let result = try_chain(&item).or(User::V2).or(User::V1).finish();
+/// assert!(result.is_err());
+/// # Ok::<(), serde_dynamo::Error>(())
+/// ```
+pub fn try_chain<R>(item: &Item) -> TryChain<'_, R> {
+    TryChain {
+        item,
+        parsed: None,
+        errors: Vec::new(),
+    }
+}
+
+/// Candidate types to deserialize an item as, built with [`try_chain`]
+#[derive(Debug)]
+#[must_use = "call `finish` to get the result"]
+pub struct TryChain<'a, R> {
+    item: &'a Item,
+    parsed: Option<R>,
+    errors: Vec<String>,
+}
+
+impl<R> TryChain<'_, R> {
+    /// Try the item as a `T`, wrapped with `wrap`, unless an earlier candidate matched
+    pub fn or<'de, T, F>(mut self, wrap: F) -> Self
+    where
+        T: Deserialize<'de>,
+        F: FnOnce(T) -> R,
+    {
+        if self.parsed.is_none() {
+            match from_item_ref::<T>(self.item) {
+                Ok(value) => self.parsed = Some(wrap(value)),
+                Err(err) => self
+                    .errors
+                    .push(format!("`{}`: {err}", std::any::type_name::<T>())),
+            }
+        }
+        self
+    }
+
+    /// The first candidate that matched, or an error listing why each one didn't
+    pub fn finish(self) -> Result<R> {
+        self.parsed
+            .ok_or_else(|| ErrorImpl::NoCandidateMatched(self.errors).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::try_chain;
+    use crate::dynamo_item;
+    use serde_derive::Deserialize;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Circle {
+        radius: u32,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Square {
+        side: u32,
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Shape {
+        Circle(Circle),
+        Square(Square),
+    }
+
+    #[test]
+    fn stops_at_the_first_match() {
+        let item = dynamo_item! { "side": 2 };
+        let shape = try_chain(&item)
+            .or(Shape::Circle)
+            .or(Shape::Square)
+            .or(|_: Square| panic!("tried after a match"))
+            .finish()
+            .unwrap();
+        assert_eq!(shape, Shape::Square(Square { side: 2 }));
+    }
+
+    #[test]
+    fn reports_every_failure() {
+        let item = dynamo_item! { "radius": "big" };
+        let err = try_chain(&item)
+            .or(Shape::Circle)
+            .or(Shape::Square)
+            .finish()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Item matched none of the candidate types: \
+             `serde_dynamo::try_chain::tests::Circle`: Expected num; \
+             `serde_dynamo::try_chain::tests::Square`: missing field `side`"
+        );
+
+        assert!(try_chain::<Shape>(&item).finish().is_err());
+    }
+}
@@ -0,0 +1,148 @@
+//! Relative expiry times for DynamoDB's [time to live]
+//!
+//! DynamoDB expires an item once the time in its TTL attribute, in whole seconds since the Unix
+//! epoch, has passed. Application code usually thinks in relative terms instead: a session lasts
+//! an hour, a lock is held for thirty seconds. [`from_now`] turns such a [`Duration`] into the
+//! attribute value to write.
+//!
+//! # Usage
+//!
+//! To store a `Duration` field as an absolute expiry time, annotate it with
+//! `#[serde(with = "serde_dynamo::ttl")]`. Writing stores the current time plus the duration,
+//! and reading gives back the time that remains, or zero if the expiry has already passed.
+//! DynamoDB deletes expired items in the background, so reads can return items that have
+//! already expired.
+//!
+//! Both directions work in whole seconds, dropping any fraction of a second.
+//!
+//! # Examples
+//!
+//! ```
+//! use serde_derive::{Serialize, Deserialize};
+//! use serde_dynamo::{AttributeValue, Item};
+//! use std::time::Duration;
+//!
+//! #[derive(Debug, Serialize, Deserialize)]
+//! struct Lock {
+//!     owner: String,
+//!     #[serde(with = "serde_dynamo::ttl")]
+//!     expires_in: Duration,
+//! }
+//!
+//! let lock = Lock {
+//!     owner: String::from("worker-1"),
+//!     expires_in: Duration::from_secs(30),
+//! };
+//!
+//! let item: Item = serde_dynamo::to_item(&lock).unwrap();
+//! let AttributeValue::N(expires_at) = &item["expires_in"] else { panic!() };
+//! assert!(expires_at.parse::<u64>().unwrap() > 1_600_000_000);
+//!
+//! let lock: Lock = serde_dynamo::from_item(item).unwrap();
+//! assert!(lock.expires_in <= Duration::from_secs(30));
+//! ```
+//!
+//! [time to live]: https://docs.aws.amazon.com/amazondynamodb/latest/developerguide/TTL.html
+
+use crate::AttributeValue;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The TTL attribute value for an item that should expire `duration` from now
+///
+/// ```
+/// use serde_dynamo::{ttl, AttributeValue};
+/// use std::time::Duration;
+///
+/// let AttributeValue::N(expires_at) = ttl::from_now(Duration::from_secs(60 * 60)) else {
+///     panic!()
+/// };
+/// assert!(expires_at.parse::<u64>().unwrap() > 1_600_000_000);
+/// ```
+pub fn from_now(duration: Duration) -> AttributeValue {
+    AttributeValue::N(expires_at(duration).to_string())
+}
+
+/// Serializes a duration as the time it expires, in seconds since the Unix epoch
+pub fn serialize<S>(value: &Duration, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    expires_at(*value).serialize(serializer)
+}
+
+/// Deserializes an expiry time, in seconds since the Unix epoch, as the time until it passes
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let expires_at = u64::deserialize(deserializer)?;
+    Ok(Duration::from_secs(expires_at.saturating_sub(now())))
+}
+
+fn expires_at(duration: Duration) -> u64 {
+    now().saturating_add(duration.as_secs())
+}
+
+/// The current time, in whole seconds since the Unix epoch
+pub(crate) fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::now;
+    use crate::{from_item, to_item, AttributeValue, Item};
+    use serde_derive::{Deserialize, Serialize};
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Session {
+        #[serde(with = "crate::ttl")]
+        expires_in: Duration,
+    }
+
+    fn epoch_seconds(value: &AttributeValue) -> u64 {
+        match value {
+            AttributeValue::N(n) => n.parse().unwrap(),
+            other => panic!("expected a number, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn from_now_adds_to_the_current_time() {
+        let before = now();
+        let expires_at = epoch_seconds(&super::from_now(Duration::from_millis(90_500)));
+        assert!((before + 90..=now() + 90).contains(&expires_at));
+
+        let far = epoch_seconds(&super::from_now(Duration::MAX));
+        assert_eq!(far, u64::MAX);
+    }
+
+    #[test]
+    fn durations_round_trip_as_expiry_times() {
+        let before = now();
+        let item: Item = to_item(Session {
+            expires_in: Duration::from_secs(60),
+        })
+        .unwrap();
+        let expires_at = epoch_seconds(&item["expires_in"]);
+        assert!((before + 60..=now() + 60).contains(&expires_at));
+
+        let session: Session = from_item(item).unwrap();
+        assert!(session.expires_in <= Duration::from_secs(60));
+        assert!(session.expires_in >= Duration::from_secs(55));
+
+        // An expiry in the past leaves no time
+        let item: Item = HashMap::from([(
+            String::from("expires_in"),
+            AttributeValue::N(String::from("1000")),
+        )])
+        .into();
+        let session: Session = from_item(item).unwrap();
+        assert_eq!(session.expires_in, Duration::ZERO);
+    }
+}
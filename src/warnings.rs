@@ -0,0 +1,113 @@
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// A non-fatal event recorded while serializing or deserializing
+///
+/// See [`Warnings`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Warning {
+    /// A value was read as a different type than it is stored as
+    CoercedType {
+        /// The stored DynamoDB type, such as `N`
+        from: &'static str,
+        /// The Rust type it was read as, such as `bool`
+        to: &'static str,
+    },
+    /// A string was changed by Unicode normalization before being written
+    NormalizedString {
+        /// The string as it was given
+        original: String,
+    },
+    /// A number was trimmed or had leading zeros removed before being read
+    NormalizedNumber {
+        /// The number as it is stored
+        original: String,
+    },
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::CoercedType { from, to } => write!(f, "Coerced {from} value to {to}"),
+            Warning::NormalizedString { original } => {
+                write!(f, "Normalized string {original:?}")
+            }
+            Warning::NormalizedNumber { original } => {
+                write!(f, "Normalized number {original:?}")
+            }
+        }
+    }
+}
+
+/// A sink for [`Warning`]s
+///
+/// The lenient options of [`SerializerConfig`](crate::SerializerConfig) and
+/// [`DeserializerConfig`](crate::DeserializerConfig) change data instead of failing. Giving the
+/// config a `Warnings` records each time that happens, so a pipeline can monitor how much of its
+/// data is being quietly fixed up.
+///
+/// `Warnings` is a cheap handle to shared storage: clones record into, and read from, the same
+/// list.
+///
+/// ```
+/// use serde_dynamo::{AttributeValue, DeserializerConfig, Warning, Warnings};
+///
+/// let warnings = Warnings::new();
+/// let config = DeserializerConfig::new()
+///     .coerce_bools(true)
+///     .warnings(warnings.clone());
+///
+/// let flag: bool =
+///     serde_dynamo::from_attribute_value_with_config(AttributeValue::N(String::from("1")), config)
+///         .unwrap();
+///
+/// assert_eq!(
+///     warnings.take(),
+///     vec![Warning::CoercedType { from: "N", to: "bool" }]
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Warnings(Arc<Mutex<Vec<Warning>>>);
+
+impl Warnings {
+    /// Create an empty sink
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remove and return the recorded warnings
+    pub fn take(&self) -> Vec<Warning> {
+        std::mem::take(&mut *self.lock())
+    }
+
+    /// The number of recorded warnings
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Whether no warnings have been recorded
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    pub(crate) fn push(&self, warning: Warning) {
+        self.lock().push(warning);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Warning>> {
+        // A panic while holding the lock can't leave the list in an invalid state
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Two handles are equal when they share the same storage
+impl PartialEq for Warnings {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for Warnings {}
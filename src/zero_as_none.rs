@@ -0,0 +1,109 @@
+//! Serializer codec for reading a zero as `None` in an optional nonzero integer
+//!
+//! # Usage
+//!
+//! To use, annotate an `Option<NonZeroU64>` field, or an option of any other
+//! [`NonZero`](std::num::NonZero) integer, with `#[serde(with = "serde_dynamo::zero_as_none")]`.
+//!
+//! Attributes such as TTLs and counters often use `0` to mean "not set". A nonzero integer can't
+//! hold that value, so reading it normally fails. With this codec, `N("0")` reads as `None`, as do
+//! `NULL` and, with `#[serde(default)]`, a missing attribute. `None` is written back as `N("0")`,
+//! unless the field also has `#[serde(skip_serializing_if = "Option::is_none")]`.
+//!
+//! # Examples
+//!
+//! ```
+//! use serde_derive::{Serialize, Deserialize};
+//! use serde_dynamo::{dynamo_item, Item};
+//! use std::num::NonZeroU64;
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Session {
+//!     id: String,
+//!     #[serde(default, with = "serde_dynamo::zero_as_none")]
+//!     expires_at: Option<NonZeroU64>,
+//! }
+//!
+//! let session: Session = serde_dynamo::from_item(dynamo_item! {
+//!     "id": "abc",
+//!     "expires_at": 0,
+//! })?;
+//! assert_eq!(session.expires_at, None);
+//!
+//! let item: Item = serde_dynamo::to_item(&session)?;
+//! assert_eq!(item, dynamo_item! { "id": "abc", "expires_at": 0 });
+//! # Ok::<(), serde_dynamo::Error>(())
+//! ```
+
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+use std::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+    NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize,
+};
+
+/// Serializes an optional nonzero integer, writing `None` as zero
+pub fn serialize<T, S>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: NonZeroInteger,
+    S: Serializer,
+{
+    match value {
+        Some(value) => value.serialize(serializer),
+        None => T::Integer::default().serialize(serializer),
+    }
+}
+
+/// Deserializes an optional nonzero integer, reading zero as `None`
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: NonZeroInteger,
+    D: Deserializer<'de>,
+{
+    Ok(Option::<T::Integer>::deserialize(deserializer)?.and_then(T::new))
+}
+
+/// A [`NonZero`](std::num::NonZero) integer type
+///
+/// This trait is sealed and implemented for all the standard nonzero integers.
+pub trait NonZeroInteger: Serialize + Sized + sealed::Sealed {
+    /// The integer type that may be zero
+    type Integer: Serialize + DeserializeOwned + Default;
+
+    /// The nonzero integer, or `None` if `integer` is zero
+    fn new(integer: Self::Integer) -> Option<Self>;
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+macro_rules! impl_nonzero_integer {
+    ($($nonzero:ty => $integer:ty,)*) => {
+        $(
+            impl sealed::Sealed for $nonzero {}
+
+            impl NonZeroInteger for $nonzero {
+                type Integer = $integer;
+
+                fn new(integer: $integer) -> Option<Self> {
+                    <$nonzero>::new(integer)
+                }
+            }
+        )*
+    };
+}
+
+impl_nonzero_integer! {
+    NonZeroU8 => u8,
+    NonZeroU16 => u16,
+    NonZeroU32 => u32,
+    NonZeroU64 => u64,
+    NonZeroU128 => u128,
+    NonZeroUsize => usize,
+    NonZeroI8 => i8,
+    NonZeroI16 => i16,
+    NonZeroI32 => i32,
+    NonZeroI64 => i64,
+    NonZeroI128 => i128,
+    NonZeroIsize => isize,
+}